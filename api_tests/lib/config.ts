@@ -8,6 +8,12 @@ export interface CndConfigFile {
     data?: { dir: string };
     network: { listen: string[] };
     logging: { level: string };
+    expiry_margins: ExpiryMargins;
+}
+
+export interface ExpiryMargins {
+    min_gap_secs: number;
+    min_time_to_expiry_secs: number;
 }
 
 export interface HttpApi {
@@ -50,6 +56,12 @@ export class E2ETestActorConfig {
             logging: {
                 level: "Trace",
             },
+            // The tests use expiries that are only seconds apart to not
+            // wait for long when refunding.
+            expiry_margins: {
+                min_gap_secs: 0,
+                min_time_to_expiry_secs: 0,
+            },
             ...createLedgerConnectors(ledgerConfig),
         };
     }
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+
+DROP INDEX rfc003_swap_events_swap_id;
+DROP TABLE rfc003_swap_events;
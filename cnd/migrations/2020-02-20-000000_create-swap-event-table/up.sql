@@ -0,0 +1,10 @@
+CREATE TABLE rfc003_swap_events
+(
+    id INTEGER          NOT NULL PRIMARY KEY,
+    swap_id TEXT        NOT NULL,
+    event TEXT          NOT NULL,
+    at DATETIME         NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- The events of a swap are loaded whenever it is resumed.
+CREATE INDEX rfc003_swap_events_swap_id ON rfc003_swap_events (swap_id);
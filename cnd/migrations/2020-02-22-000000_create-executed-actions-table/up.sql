@@ -0,0 +1,8 @@
+CREATE TABLE rfc003_executed_actions
+(
+    id INTEGER     	NOT NULL PRIMARY KEY,
+    swap_id 	NOT NULL,
+    action 		NOT NULL,
+    outcome 	NOT NULL,
+    at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+
+DROP TABLE rfc003_lightning_ethereum_bitcoin_ether_request_messages;
+DROP TABLE rfc003_ethereum_lightning_ether_bitcoin_request_messages;
+DROP TABLE rfc003_lightning_ethereum_bitcoin_erc20_request_messages;
+DROP TABLE rfc003_ethereum_lightning_erc20_bitcoin_request_messages;
+DROP TABLE rfc003_ethereum_lightning_accept_messages;
+DROP TABLE rfc003_lightning_ethereum_accept_messages;
@@ -0,0 +1,83 @@
+CREATE TABLE rfc003_lightning_ethereum_bitcoin_ether_request_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    lightning_network         NOT NULL,
+    ethereum_chain_id         NOT NULL,
+    bitcoin_amount            NOT NULL,
+    ether_amount              NOT NULL,
+    hash_function             NOT NULL,
+    lightning_refund_identity NOT NULL,
+    ethereum_redeem_identity  NOT NULL,
+    lightning_expiry          NOT NULL,
+    ethereum_expiry           NOT NULL,
+    secret_hash               NOT NULL
+);
+
+CREATE TABLE rfc003_ethereum_lightning_ether_bitcoin_request_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    ethereum_chain_id         NOT NULL,
+    lightning_network         NOT NULL,
+    ether_amount              NOT NULL,
+    bitcoin_amount            NOT NULL,
+    hash_function             NOT NULL,
+    ethereum_refund_identity  NOT NULL,
+    lightning_redeem_identity NOT NULL,
+    ethereum_expiry           NOT NULL,
+    lightning_expiry          NOT NULL,
+    secret_hash               NOT NULL
+);
+
+CREATE TABLE rfc003_lightning_ethereum_bitcoin_erc20_request_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    lightning_network         NOT NULL,
+    ethereum_chain_id         NOT NULL,
+    bitcoin_amount            NOT NULL,
+    erc20_amount              NOT NULL,
+    erc20_token_contract      NOT NULL,
+    hash_function             NOT NULL,
+    lightning_refund_identity NOT NULL,
+    ethereum_redeem_identity  NOT NULL,
+    lightning_expiry          NOT NULL,
+    ethereum_expiry           NOT NULL,
+    secret_hash               NOT NULL
+);
+
+CREATE TABLE rfc003_ethereum_lightning_erc20_bitcoin_request_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    ethereum_chain_id         NOT NULL,
+    lightning_network         NOT NULL,
+    erc20_amount              NOT NULL,
+    erc20_token_contract      NOT NULL,
+    bitcoin_amount            NOT NULL,
+    hash_function             NOT NULL,
+    ethereum_refund_identity  NOT NULL,
+    lightning_redeem_identity NOT NULL,
+    ethereum_expiry           NOT NULL,
+    lightning_expiry          NOT NULL,
+    secret_hash               NOT NULL
+);
+
+CREATE TABLE rfc003_ethereum_lightning_accept_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    ethereum_redeem_identity  NOT NULL,
+    lightning_refund_identity NOT NULL,
+    at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE rfc003_lightning_ethereum_accept_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    lightning_redeem_identity NOT NULL,
+    ethereum_refund_identity  NOT NULL,
+    at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+
+DROP TABLE rfc003_litecoin_ethereum_bitcoin_ether_request_messages;
+DROP TABLE rfc003_ethereum_litecoin_ether_bitcoin_request_messages;
+DROP TABLE rfc003_litecoin_ethereum_bitcoin_erc20_request_messages;
+DROP TABLE rfc003_ethereum_litecoin_erc20_bitcoin_request_messages;
@@ -0,0 +1,65 @@
+CREATE TABLE rfc003_litecoin_ethereum_bitcoin_ether_request_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    litecoin_network          NOT NULL,
+    ethereum_chain_id         NOT NULL,
+    bitcoin_amount            NOT NULL,
+    ether_amount              NOT NULL,
+    hash_function             NOT NULL,
+    litecoin_refund_identity  NOT NULL,
+    ethereum_redeem_identity  NOT NULL,
+    litecoin_expiry           NOT NULL,
+    ethereum_expiry           NOT NULL,
+    secret_hash               NOT NULL
+);
+
+CREATE TABLE rfc003_ethereum_litecoin_ether_bitcoin_request_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    ethereum_chain_id         NOT NULL,
+    litecoin_network          NOT NULL,
+    ether_amount              NOT NULL,
+    bitcoin_amount            NOT NULL,
+    hash_function             NOT NULL,
+    ethereum_refund_identity  NOT NULL,
+    litecoin_redeem_identity  NOT NULL,
+    ethereum_expiry           NOT NULL,
+    litecoin_expiry           NOT NULL,
+    secret_hash               NOT NULL
+);
+
+CREATE TABLE rfc003_litecoin_ethereum_bitcoin_erc20_request_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    litecoin_network          NOT NULL,
+    ethereum_chain_id         NOT NULL,
+    bitcoin_amount            NOT NULL,
+    erc20_amount              NOT NULL,
+    erc20_token_contract      NOT NULL,
+    hash_function             NOT NULL,
+    litecoin_refund_identity  NOT NULL,
+    ethereum_redeem_identity  NOT NULL,
+    litecoin_expiry           NOT NULL,
+    ethereum_expiry           NOT NULL,
+    secret_hash               NOT NULL
+);
+
+CREATE TABLE rfc003_ethereum_litecoin_erc20_bitcoin_request_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    ethereum_chain_id         NOT NULL,
+    litecoin_network          NOT NULL,
+    erc20_amount              NOT NULL,
+    erc20_token_contract      NOT NULL,
+    bitcoin_amount            NOT NULL,
+    hash_function             NOT NULL,
+    ethereum_refund_identity  NOT NULL,
+    litecoin_redeem_identity  NOT NULL,
+    ethereum_expiry           NOT NULL,
+    litecoin_expiry           NOT NULL,
+    secret_hash               NOT NULL
+);
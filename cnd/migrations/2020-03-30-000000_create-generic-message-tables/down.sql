@@ -0,0 +1,784 @@
+-- This file should undo anything in `up.sql`
+
+CREATE TABLE rfc003_bitcoin_ethereum_bitcoin_ether_request_messages
+(
+    id INTEGER               NOT NULL PRIMARY KEY,
+    swap_id UNIQUE           NOT NULL,
+    bitcoin_network          NOT NULL,
+    ethereum_chain_id        NOT NULL,
+    bitcoin_amount           NOT NULL,
+    ether_amount             NOT NULL,
+    hash_function            NOT NULL,
+    bitcoin_refund_identity  NOT NULL,
+    ethereum_redeem_identity NOT NULL,
+    bitcoin_expiry           NOT NULL,
+    ethereum_expiry          NOT NULL,
+    secret_hash              NOT NULL
+);
+
+CREATE TABLE rfc003_ethereum_bitcoin_ether_bitcoin_request_messages
+(
+    id INTEGER               NOT NULL PRIMARY KEY,
+    swap_id UNIQUE           NOT NULL,
+    bitcoin_network          NOT NULL,
+    ethereum_chain_id        NOT NULL,
+    bitcoin_amount           NOT NULL,
+    ether_amount             NOT NULL,
+    hash_function            NOT NULL,
+    bitcoin_redeem_identity  NOT NULL,
+    ethereum_refund_identity NOT NULL,
+    bitcoin_expiry           NOT NULL,
+    ethereum_expiry          NOT NULL,
+    secret_hash              NOT NULL
+);
+
+CREATE TABLE rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages
+(
+    id INTEGER               NOT NULL PRIMARY KEY,
+    swap_id UNIQUE           NOT NULL,
+    bitcoin_network          NOT NULL,
+    ethereum_chain_id        NOT NULL,
+    bitcoin_amount           NOT NULL,
+    erc20_amount             NOT NULL,
+    erc20_token_contract     NOT NULL,
+    hash_function            NOT NULL,
+    bitcoin_refund_identity  NOT NULL,
+    ethereum_redeem_identity NOT NULL,
+    bitcoin_expiry           NOT NULL,
+    ethereum_expiry          NOT NULL,
+    secret_hash              NOT NULL
+);
+
+CREATE TABLE rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages
+(
+    id INTEGER               NOT NULL PRIMARY KEY,
+    swap_id UNIQUE           NOT NULL,
+    bitcoin_network          NOT NULL,
+    ethereum_chain_id        NOT NULL,
+    bitcoin_amount           NOT NULL,
+    erc20_amount             NOT NULL,
+    erc20_token_contract     NOT NULL,
+    hash_function            NOT NULL,
+    bitcoin_redeem_identity  NOT NULL,
+    ethereum_refund_identity NOT NULL,
+    bitcoin_expiry           NOT NULL,
+    ethereum_expiry          NOT NULL,
+    secret_hash              NOT NULL
+);
+
+CREATE TABLE rfc003_ethereum_bitcoin_accept_messages
+(
+    id INTEGER               NOT NULL PRIMARY KEY,
+    swap_id UNIQUE           NOT NULL,
+    bitcoin_refund_identity  NOT NULL,
+    ethereum_redeem_identity NOT NULL,
+    at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE rfc003_bitcoin_ethereum_accept_messages
+(
+    id INTEGER               NOT NULL PRIMARY KEY,
+    swap_id UNIQUE           NOT NULL,
+    bitcoin_redeem_identity  NOT NULL,
+    ethereum_refund_identity NOT NULL,
+    at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE rfc003_lightning_ethereum_bitcoin_ether_request_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    lightning_network         NOT NULL,
+    ethereum_chain_id         NOT NULL,
+    bitcoin_amount            NOT NULL,
+    ether_amount              NOT NULL,
+    hash_function             NOT NULL,
+    lightning_refund_identity NOT NULL,
+    ethereum_redeem_identity  NOT NULL,
+    lightning_expiry          NOT NULL,
+    ethereum_expiry           NOT NULL,
+    secret_hash               NOT NULL
+);
+
+CREATE TABLE rfc003_ethereum_lightning_ether_bitcoin_request_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    ethereum_chain_id         NOT NULL,
+    lightning_network         NOT NULL,
+    ether_amount              NOT NULL,
+    bitcoin_amount            NOT NULL,
+    hash_function             NOT NULL,
+    ethereum_refund_identity  NOT NULL,
+    lightning_redeem_identity NOT NULL,
+    ethereum_expiry           NOT NULL,
+    lightning_expiry          NOT NULL,
+    secret_hash               NOT NULL
+);
+
+CREATE TABLE rfc003_lightning_ethereum_bitcoin_erc20_request_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    lightning_network         NOT NULL,
+    ethereum_chain_id         NOT NULL,
+    bitcoin_amount            NOT NULL,
+    erc20_amount              NOT NULL,
+    erc20_token_contract      NOT NULL,
+    hash_function             NOT NULL,
+    lightning_refund_identity NOT NULL,
+    ethereum_redeem_identity  NOT NULL,
+    lightning_expiry          NOT NULL,
+    ethereum_expiry           NOT NULL,
+    secret_hash               NOT NULL
+);
+
+CREATE TABLE rfc003_ethereum_lightning_erc20_bitcoin_request_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    ethereum_chain_id         NOT NULL,
+    lightning_network         NOT NULL,
+    erc20_amount              NOT NULL,
+    erc20_token_contract      NOT NULL,
+    bitcoin_amount            NOT NULL,
+    hash_function             NOT NULL,
+    ethereum_refund_identity  NOT NULL,
+    lightning_redeem_identity NOT NULL,
+    ethereum_expiry           NOT NULL,
+    lightning_expiry          NOT NULL,
+    secret_hash               NOT NULL
+);
+
+CREATE TABLE rfc003_ethereum_lightning_accept_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    ethereum_redeem_identity  NOT NULL,
+    lightning_refund_identity NOT NULL,
+    at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE rfc003_lightning_ethereum_accept_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    lightning_redeem_identity NOT NULL,
+    ethereum_refund_identity  NOT NULL,
+    at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE rfc003_ethereum_ethereum_ether_erc20_request_messages
+(
+    id INTEGER                     NOT NULL PRIMARY KEY,
+    swap_id UNIQUE                 NOT NULL,
+    alpha_ethereum_chain_id        NOT NULL,
+    beta_ethereum_chain_id         NOT NULL,
+    ether_amount                   NOT NULL,
+    erc20_amount                   NOT NULL,
+    erc20_token_contract           NOT NULL,
+    hash_function                  NOT NULL,
+    alpha_ethereum_refund_identity NOT NULL,
+    beta_ethereum_redeem_identity  NOT NULL,
+    alpha_expiry                   NOT NULL,
+    beta_expiry                    NOT NULL,
+    secret_hash                    NOT NULL
+);
+
+CREATE TABLE rfc003_ethereum_ethereum_erc20_ether_request_messages
+(
+    id INTEGER                     NOT NULL PRIMARY KEY,
+    swap_id UNIQUE                 NOT NULL,
+    alpha_ethereum_chain_id        NOT NULL,
+    beta_ethereum_chain_id         NOT NULL,
+    erc20_amount                   NOT NULL,
+    erc20_token_contract           NOT NULL,
+    ether_amount                   NOT NULL,
+    hash_function                  NOT NULL,
+    alpha_ethereum_refund_identity NOT NULL,
+    beta_ethereum_redeem_identity  NOT NULL,
+    alpha_expiry                   NOT NULL,
+    beta_expiry                    NOT NULL,
+    secret_hash                    NOT NULL
+);
+
+CREATE TABLE rfc003_ethereum_ethereum_erc20_erc20_request_messages
+(
+    id INTEGER                     NOT NULL PRIMARY KEY,
+    swap_id UNIQUE                 NOT NULL,
+    alpha_ethereum_chain_id        NOT NULL,
+    beta_ethereum_chain_id         NOT NULL,
+    alpha_erc20_amount             NOT NULL,
+    alpha_erc20_token_contract     NOT NULL,
+    beta_erc20_amount              NOT NULL,
+    beta_erc20_token_contract      NOT NULL,
+    hash_function                  NOT NULL,
+    alpha_ethereum_refund_identity NOT NULL,
+    beta_ethereum_redeem_identity  NOT NULL,
+    alpha_expiry                   NOT NULL,
+    beta_expiry                    NOT NULL,
+    secret_hash                    NOT NULL
+);
+
+CREATE TABLE rfc003_ethereum_ethereum_accept_messages
+(
+    id INTEGER                     NOT NULL PRIMARY KEY,
+    swap_id UNIQUE                 NOT NULL,
+    alpha_ethereum_redeem_identity NOT NULL,
+    beta_ethereum_refund_identity  NOT NULL,
+    at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE rfc003_litecoin_ethereum_bitcoin_ether_request_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    litecoin_network          NOT NULL,
+    ethereum_chain_id         NOT NULL,
+    bitcoin_amount            NOT NULL,
+    ether_amount              NOT NULL,
+    hash_function             NOT NULL,
+    litecoin_refund_identity  NOT NULL,
+    ethereum_redeem_identity  NOT NULL,
+    litecoin_expiry           NOT NULL,
+    ethereum_expiry           NOT NULL,
+    secret_hash               NOT NULL
+);
+
+CREATE TABLE rfc003_ethereum_litecoin_ether_bitcoin_request_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    ethereum_chain_id         NOT NULL,
+    litecoin_network          NOT NULL,
+    ether_amount              NOT NULL,
+    bitcoin_amount            NOT NULL,
+    hash_function             NOT NULL,
+    ethereum_refund_identity  NOT NULL,
+    litecoin_redeem_identity  NOT NULL,
+    ethereum_expiry           NOT NULL,
+    litecoin_expiry           NOT NULL,
+    secret_hash               NOT NULL
+);
+
+CREATE TABLE rfc003_litecoin_ethereum_bitcoin_erc20_request_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    litecoin_network          NOT NULL,
+    ethereum_chain_id         NOT NULL,
+    bitcoin_amount            NOT NULL,
+    erc20_amount              NOT NULL,
+    erc20_token_contract      NOT NULL,
+    hash_function             NOT NULL,
+    litecoin_refund_identity  NOT NULL,
+    ethereum_redeem_identity  NOT NULL,
+    litecoin_expiry           NOT NULL,
+    ethereum_expiry           NOT NULL,
+    secret_hash               NOT NULL
+);
+
+CREATE TABLE rfc003_ethereum_litecoin_erc20_bitcoin_request_messages
+(
+    id INTEGER                NOT NULL PRIMARY KEY,
+    swap_id UNIQUE            NOT NULL,
+    ethereum_chain_id         NOT NULL,
+    litecoin_network          NOT NULL,
+    erc20_amount              NOT NULL,
+    erc20_token_contract      NOT NULL,
+    bitcoin_amount            NOT NULL,
+    hash_function             NOT NULL,
+    ethereum_refund_identity  NOT NULL,
+    litecoin_redeem_identity  NOT NULL,
+    ethereum_expiry           NOT NULL,
+    litecoin_expiry           NOT NULL,
+    secret_hash               NOT NULL
+);
+
+INSERT INTO rfc003_bitcoin_ethereum_bitcoin_ether_request_messages
+(
+    swap_id,
+    bitcoin_network,
+    ethereum_chain_id,
+    bitcoin_amount,
+    ether_amount,
+    hash_function,
+    bitcoin_refund_identity,
+    ethereum_redeem_identity,
+    bitcoin_expiry,
+    ethereum_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    alpha_ledger_network,
+    CAST(beta_ledger_network AS INTEGER),
+    alpha_asset_quantity,
+    beta_asset_quantity,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+FROM rfc003_request_messages
+WHERE alpha_ledger = 'bitcoin' AND beta_ledger = 'ethereum' AND alpha_asset = 'bitcoin' AND beta_asset = 'ether';
+
+INSERT INTO rfc003_ethereum_bitcoin_ether_bitcoin_request_messages
+(
+    swap_id,
+    ethereum_chain_id,
+    bitcoin_network,
+    ether_amount,
+    bitcoin_amount,
+    hash_function,
+    ethereum_refund_identity,
+    bitcoin_redeem_identity,
+    ethereum_expiry,
+    bitcoin_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    CAST(alpha_ledger_network AS INTEGER),
+    beta_ledger_network,
+    alpha_asset_quantity,
+    beta_asset_quantity,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+FROM rfc003_request_messages
+WHERE alpha_ledger = 'ethereum' AND beta_ledger = 'bitcoin' AND alpha_asset = 'ether' AND beta_asset = 'bitcoin';
+
+INSERT INTO rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages
+(
+    swap_id,
+    bitcoin_network,
+    ethereum_chain_id,
+    bitcoin_amount,
+    erc20_amount,
+    erc20_token_contract,
+    hash_function,
+    bitcoin_refund_identity,
+    ethereum_redeem_identity,
+    bitcoin_expiry,
+    ethereum_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    alpha_ledger_network,
+    CAST(beta_ledger_network AS INTEGER),
+    alpha_asset_quantity,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+FROM rfc003_request_messages
+WHERE alpha_ledger = 'bitcoin' AND beta_ledger = 'ethereum' AND alpha_asset = 'bitcoin' AND beta_asset = 'erc20';
+
+INSERT INTO rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages
+(
+    swap_id,
+    ethereum_chain_id,
+    bitcoin_network,
+    erc20_amount,
+    erc20_token_contract,
+    bitcoin_amount,
+    hash_function,
+    ethereum_refund_identity,
+    bitcoin_redeem_identity,
+    ethereum_expiry,
+    bitcoin_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    CAST(alpha_ledger_network AS INTEGER),
+    beta_ledger_network,
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset_quantity,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+FROM rfc003_request_messages
+WHERE alpha_ledger = 'ethereum' AND beta_ledger = 'bitcoin' AND alpha_asset = 'erc20' AND beta_asset = 'bitcoin';
+
+INSERT INTO rfc003_lightning_ethereum_bitcoin_ether_request_messages
+(
+    swap_id,
+    lightning_network,
+    ethereum_chain_id,
+    bitcoin_amount,
+    ether_amount,
+    hash_function,
+    lightning_refund_identity,
+    ethereum_redeem_identity,
+    lightning_expiry,
+    ethereum_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    alpha_ledger_network,
+    CAST(beta_ledger_network AS INTEGER),
+    alpha_asset_quantity,
+    beta_asset_quantity,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+FROM rfc003_request_messages
+WHERE alpha_ledger = 'lightning' AND beta_ledger = 'ethereum' AND alpha_asset = 'bitcoin' AND beta_asset = 'ether';
+
+INSERT INTO rfc003_ethereum_lightning_ether_bitcoin_request_messages
+(
+    swap_id,
+    ethereum_chain_id,
+    lightning_network,
+    ether_amount,
+    bitcoin_amount,
+    hash_function,
+    ethereum_refund_identity,
+    lightning_redeem_identity,
+    ethereum_expiry,
+    lightning_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    CAST(alpha_ledger_network AS INTEGER),
+    beta_ledger_network,
+    alpha_asset_quantity,
+    beta_asset_quantity,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+FROM rfc003_request_messages
+WHERE alpha_ledger = 'ethereum' AND beta_ledger = 'lightning' AND alpha_asset = 'ether' AND beta_asset = 'bitcoin';
+
+INSERT INTO rfc003_lightning_ethereum_bitcoin_erc20_request_messages
+(
+    swap_id,
+    lightning_network,
+    ethereum_chain_id,
+    bitcoin_amount,
+    erc20_amount,
+    erc20_token_contract,
+    hash_function,
+    lightning_refund_identity,
+    ethereum_redeem_identity,
+    lightning_expiry,
+    ethereum_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    alpha_ledger_network,
+    CAST(beta_ledger_network AS INTEGER),
+    alpha_asset_quantity,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+FROM rfc003_request_messages
+WHERE alpha_ledger = 'lightning' AND beta_ledger = 'ethereum' AND alpha_asset = 'bitcoin' AND beta_asset = 'erc20';
+
+INSERT INTO rfc003_ethereum_lightning_erc20_bitcoin_request_messages
+(
+    swap_id,
+    ethereum_chain_id,
+    lightning_network,
+    erc20_amount,
+    erc20_token_contract,
+    bitcoin_amount,
+    hash_function,
+    ethereum_refund_identity,
+    lightning_redeem_identity,
+    ethereum_expiry,
+    lightning_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    CAST(alpha_ledger_network AS INTEGER),
+    beta_ledger_network,
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset_quantity,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+FROM rfc003_request_messages
+WHERE alpha_ledger = 'ethereum' AND beta_ledger = 'lightning' AND alpha_asset = 'erc20' AND beta_asset = 'bitcoin';
+
+INSERT INTO rfc003_ethereum_ethereum_ether_erc20_request_messages
+(
+    swap_id,
+    alpha_ethereum_chain_id,
+    beta_ethereum_chain_id,
+    ether_amount,
+    erc20_amount,
+    erc20_token_contract,
+    hash_function,
+    alpha_ethereum_refund_identity,
+    beta_ethereum_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    CAST(alpha_ledger_network AS INTEGER),
+    CAST(beta_ledger_network AS INTEGER),
+    alpha_asset_quantity,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+FROM rfc003_request_messages
+WHERE alpha_ledger = 'ethereum' AND beta_ledger = 'ethereum' AND alpha_asset = 'ether' AND beta_asset = 'erc20';
+
+INSERT INTO rfc003_ethereum_ethereum_erc20_ether_request_messages
+(
+    swap_id,
+    alpha_ethereum_chain_id,
+    beta_ethereum_chain_id,
+    erc20_amount,
+    erc20_token_contract,
+    ether_amount,
+    hash_function,
+    alpha_ethereum_refund_identity,
+    beta_ethereum_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    CAST(alpha_ledger_network AS INTEGER),
+    CAST(beta_ledger_network AS INTEGER),
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset_quantity,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+FROM rfc003_request_messages
+WHERE alpha_ledger = 'ethereum' AND beta_ledger = 'ethereum' AND alpha_asset = 'erc20' AND beta_asset = 'ether';
+
+INSERT INTO rfc003_ethereum_ethereum_erc20_erc20_request_messages
+(
+    swap_id,
+    alpha_ethereum_chain_id,
+    beta_ethereum_chain_id,
+    alpha_erc20_amount,
+    alpha_erc20_token_contract,
+    beta_erc20_amount,
+    beta_erc20_token_contract,
+    hash_function,
+    alpha_ethereum_refund_identity,
+    beta_ethereum_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    CAST(alpha_ledger_network AS INTEGER),
+    CAST(beta_ledger_network AS INTEGER),
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+FROM rfc003_request_messages
+WHERE alpha_ledger = 'ethereum' AND beta_ledger = 'ethereum' AND alpha_asset = 'erc20' AND beta_asset = 'erc20';
+
+INSERT INTO rfc003_litecoin_ethereum_bitcoin_ether_request_messages
+(
+    swap_id,
+    litecoin_network,
+    ethereum_chain_id,
+    bitcoin_amount,
+    ether_amount,
+    hash_function,
+    litecoin_refund_identity,
+    ethereum_redeem_identity,
+    litecoin_expiry,
+    ethereum_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    alpha_ledger_network,
+    CAST(beta_ledger_network AS INTEGER),
+    alpha_asset_quantity,
+    beta_asset_quantity,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+FROM rfc003_request_messages
+WHERE alpha_ledger = 'litecoin' AND beta_ledger = 'ethereum' AND alpha_asset = 'bitcoin' AND beta_asset = 'ether';
+
+INSERT INTO rfc003_ethereum_litecoin_ether_bitcoin_request_messages
+(
+    swap_id,
+    ethereum_chain_id,
+    litecoin_network,
+    ether_amount,
+    bitcoin_amount,
+    hash_function,
+    ethereum_refund_identity,
+    litecoin_redeem_identity,
+    ethereum_expiry,
+    litecoin_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    CAST(alpha_ledger_network AS INTEGER),
+    beta_ledger_network,
+    alpha_asset_quantity,
+    beta_asset_quantity,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+FROM rfc003_request_messages
+WHERE alpha_ledger = 'ethereum' AND beta_ledger = 'litecoin' AND alpha_asset = 'ether' AND beta_asset = 'bitcoin';
+
+INSERT INTO rfc003_litecoin_ethereum_bitcoin_erc20_request_messages
+(
+    swap_id,
+    litecoin_network,
+    ethereum_chain_id,
+    bitcoin_amount,
+    erc20_amount,
+    erc20_token_contract,
+    hash_function,
+    litecoin_refund_identity,
+    ethereum_redeem_identity,
+    litecoin_expiry,
+    ethereum_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    alpha_ledger_network,
+    CAST(beta_ledger_network AS INTEGER),
+    alpha_asset_quantity,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+FROM rfc003_request_messages
+WHERE alpha_ledger = 'litecoin' AND beta_ledger = 'ethereum' AND alpha_asset = 'bitcoin' AND beta_asset = 'erc20';
+
+INSERT INTO rfc003_ethereum_litecoin_erc20_bitcoin_request_messages
+(
+    swap_id,
+    ethereum_chain_id,
+    litecoin_network,
+    erc20_amount,
+    erc20_token_contract,
+    bitcoin_amount,
+    hash_function,
+    ethereum_refund_identity,
+    litecoin_redeem_identity,
+    ethereum_expiry,
+    litecoin_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    CAST(alpha_ledger_network AS INTEGER),
+    beta_ledger_network,
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset_quantity,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+FROM rfc003_request_messages
+WHERE alpha_ledger = 'ethereum' AND beta_ledger = 'litecoin' AND alpha_asset = 'erc20' AND beta_asset = 'bitcoin';
+
+INSERT INTO rfc003_bitcoin_ethereum_accept_messages (swap_id, bitcoin_redeem_identity, ethereum_refund_identity, at)
+SELECT accept_messages.swap_id, accept_messages.alpha_ledger_redeem_identity, accept_messages.beta_ledger_refund_identity, accept_messages.at
+FROM rfc003_accept_messages AS accept_messages
+INNER JOIN rfc003_request_messages AS request_messages ON request_messages.swap_id = accept_messages.swap_id
+WHERE request_messages.alpha_ledger IN ('bitcoin', 'litecoin') AND request_messages.beta_ledger IN ('ethereum');
+
+INSERT INTO rfc003_ethereum_bitcoin_accept_messages (swap_id, ethereum_redeem_identity, bitcoin_refund_identity, at)
+SELECT accept_messages.swap_id, accept_messages.alpha_ledger_redeem_identity, accept_messages.beta_ledger_refund_identity, accept_messages.at
+FROM rfc003_accept_messages AS accept_messages
+INNER JOIN rfc003_request_messages AS request_messages ON request_messages.swap_id = accept_messages.swap_id
+WHERE request_messages.alpha_ledger IN ('ethereum') AND request_messages.beta_ledger IN ('bitcoin', 'litecoin');
+
+INSERT INTO rfc003_lightning_ethereum_accept_messages (swap_id, lightning_redeem_identity, ethereum_refund_identity, at)
+SELECT accept_messages.swap_id, accept_messages.alpha_ledger_redeem_identity, accept_messages.beta_ledger_refund_identity, accept_messages.at
+FROM rfc003_accept_messages AS accept_messages
+INNER JOIN rfc003_request_messages AS request_messages ON request_messages.swap_id = accept_messages.swap_id
+WHERE request_messages.alpha_ledger IN ('lightning') AND request_messages.beta_ledger IN ('ethereum');
+
+INSERT INTO rfc003_ethereum_lightning_accept_messages (swap_id, ethereum_redeem_identity, lightning_refund_identity, at)
+SELECT accept_messages.swap_id, accept_messages.alpha_ledger_redeem_identity, accept_messages.beta_ledger_refund_identity, accept_messages.at
+FROM rfc003_accept_messages AS accept_messages
+INNER JOIN rfc003_request_messages AS request_messages ON request_messages.swap_id = accept_messages.swap_id
+WHERE request_messages.alpha_ledger IN ('ethereum') AND request_messages.beta_ledger IN ('lightning');
+
+INSERT INTO rfc003_ethereum_ethereum_accept_messages (swap_id, alpha_ethereum_redeem_identity, beta_ethereum_refund_identity, at)
+SELECT accept_messages.swap_id, accept_messages.alpha_ledger_redeem_identity, accept_messages.beta_ledger_refund_identity, accept_messages.at
+FROM rfc003_accept_messages AS accept_messages
+INNER JOIN rfc003_request_messages AS request_messages ON request_messages.swap_id = accept_messages.swap_id
+WHERE request_messages.alpha_ledger IN ('ethereum') AND request_messages.beta_ledger IN ('ethereum');
+
+DROP TABLE rfc003_request_messages;
+DROP TABLE rfc003_accept_messages;
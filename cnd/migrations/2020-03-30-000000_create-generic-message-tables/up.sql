@@ -0,0 +1,675 @@
+-- Store the messages of all combinations of ledgers and assets in the same
+-- tables. Ledgers are described by their name and network (the chain id for
+-- Ethereum), assets by their name, quantity and token contract.
+
+CREATE TABLE rfc003_request_messages
+(
+    id INTEGER                   NOT NULL PRIMARY KEY,
+    swap_id UNIQUE               NOT NULL,
+    alpha_ledger                 NOT NULL,
+    alpha_ledger_network         NOT NULL,
+    beta_ledger                  NOT NULL,
+    beta_ledger_network          NOT NULL,
+    alpha_asset                  NOT NULL,
+    alpha_asset_quantity         NOT NULL,
+    alpha_asset_token_contract,
+    beta_asset                   NOT NULL,
+    beta_asset_quantity          NOT NULL,
+    beta_asset_token_contract,
+    hash_function                NOT NULL,
+    alpha_ledger_refund_identity NOT NULL,
+    beta_ledger_redeem_identity  NOT NULL,
+    alpha_expiry                 NOT NULL,
+    beta_expiry                  NOT NULL,
+    secret_hash                  NOT NULL
+);
+
+CREATE TABLE rfc003_accept_messages
+(
+    id INTEGER                   NOT NULL PRIMARY KEY,
+    swap_id UNIQUE               NOT NULL,
+    alpha_ledger_redeem_identity NOT NULL,
+    beta_ledger_refund_identity  NOT NULL,
+    at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO rfc003_request_messages
+(
+    swap_id,
+    alpha_ledger,
+    alpha_ledger_network,
+    beta_ledger,
+    beta_ledger_network,
+    alpha_asset,
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    'bitcoin',
+    bitcoin_network,
+    'ethereum',
+    CAST(ethereum_chain_id AS TEXT),
+    'bitcoin',
+    bitcoin_amount,
+    NULL,
+    'ether',
+    ether_amount,
+    NULL,
+    hash_function,
+    bitcoin_refund_identity,
+    ethereum_redeem_identity,
+    bitcoin_expiry,
+    ethereum_expiry,
+    secret_hash
+FROM rfc003_bitcoin_ethereum_bitcoin_ether_request_messages;
+
+INSERT INTO rfc003_request_messages
+(
+    swap_id,
+    alpha_ledger,
+    alpha_ledger_network,
+    beta_ledger,
+    beta_ledger_network,
+    alpha_asset,
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    'ethereum',
+    CAST(ethereum_chain_id AS TEXT),
+    'bitcoin',
+    bitcoin_network,
+    'ether',
+    ether_amount,
+    NULL,
+    'bitcoin',
+    bitcoin_amount,
+    NULL,
+    hash_function,
+    ethereum_refund_identity,
+    bitcoin_redeem_identity,
+    ethereum_expiry,
+    bitcoin_expiry,
+    secret_hash
+FROM rfc003_ethereum_bitcoin_ether_bitcoin_request_messages;
+
+INSERT INTO rfc003_request_messages
+(
+    swap_id,
+    alpha_ledger,
+    alpha_ledger_network,
+    beta_ledger,
+    beta_ledger_network,
+    alpha_asset,
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    'bitcoin',
+    bitcoin_network,
+    'ethereum',
+    CAST(ethereum_chain_id AS TEXT),
+    'bitcoin',
+    bitcoin_amount,
+    NULL,
+    'erc20',
+    erc20_amount,
+    erc20_token_contract,
+    hash_function,
+    bitcoin_refund_identity,
+    ethereum_redeem_identity,
+    bitcoin_expiry,
+    ethereum_expiry,
+    secret_hash
+FROM rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages;
+
+INSERT INTO rfc003_request_messages
+(
+    swap_id,
+    alpha_ledger,
+    alpha_ledger_network,
+    beta_ledger,
+    beta_ledger_network,
+    alpha_asset,
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    'ethereum',
+    CAST(ethereum_chain_id AS TEXT),
+    'bitcoin',
+    bitcoin_network,
+    'erc20',
+    erc20_amount,
+    erc20_token_contract,
+    'bitcoin',
+    bitcoin_amount,
+    NULL,
+    hash_function,
+    ethereum_refund_identity,
+    bitcoin_redeem_identity,
+    ethereum_expiry,
+    bitcoin_expiry,
+    secret_hash
+FROM rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages;
+
+INSERT INTO rfc003_request_messages
+(
+    swap_id,
+    alpha_ledger,
+    alpha_ledger_network,
+    beta_ledger,
+    beta_ledger_network,
+    alpha_asset,
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    'lightning',
+    lightning_network,
+    'ethereum',
+    CAST(ethereum_chain_id AS TEXT),
+    'bitcoin',
+    bitcoin_amount,
+    NULL,
+    'ether',
+    ether_amount,
+    NULL,
+    hash_function,
+    lightning_refund_identity,
+    ethereum_redeem_identity,
+    lightning_expiry,
+    ethereum_expiry,
+    secret_hash
+FROM rfc003_lightning_ethereum_bitcoin_ether_request_messages;
+
+INSERT INTO rfc003_request_messages
+(
+    swap_id,
+    alpha_ledger,
+    alpha_ledger_network,
+    beta_ledger,
+    beta_ledger_network,
+    alpha_asset,
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    'ethereum',
+    CAST(ethereum_chain_id AS TEXT),
+    'lightning',
+    lightning_network,
+    'ether',
+    ether_amount,
+    NULL,
+    'bitcoin',
+    bitcoin_amount,
+    NULL,
+    hash_function,
+    ethereum_refund_identity,
+    lightning_redeem_identity,
+    ethereum_expiry,
+    lightning_expiry,
+    secret_hash
+FROM rfc003_ethereum_lightning_ether_bitcoin_request_messages;
+
+INSERT INTO rfc003_request_messages
+(
+    swap_id,
+    alpha_ledger,
+    alpha_ledger_network,
+    beta_ledger,
+    beta_ledger_network,
+    alpha_asset,
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    'lightning',
+    lightning_network,
+    'ethereum',
+    CAST(ethereum_chain_id AS TEXT),
+    'bitcoin',
+    bitcoin_amount,
+    NULL,
+    'erc20',
+    erc20_amount,
+    erc20_token_contract,
+    hash_function,
+    lightning_refund_identity,
+    ethereum_redeem_identity,
+    lightning_expiry,
+    ethereum_expiry,
+    secret_hash
+FROM rfc003_lightning_ethereum_bitcoin_erc20_request_messages;
+
+INSERT INTO rfc003_request_messages
+(
+    swap_id,
+    alpha_ledger,
+    alpha_ledger_network,
+    beta_ledger,
+    beta_ledger_network,
+    alpha_asset,
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    'ethereum',
+    CAST(ethereum_chain_id AS TEXT),
+    'lightning',
+    lightning_network,
+    'erc20',
+    erc20_amount,
+    erc20_token_contract,
+    'bitcoin',
+    bitcoin_amount,
+    NULL,
+    hash_function,
+    ethereum_refund_identity,
+    lightning_redeem_identity,
+    ethereum_expiry,
+    lightning_expiry,
+    secret_hash
+FROM rfc003_ethereum_lightning_erc20_bitcoin_request_messages;
+
+INSERT INTO rfc003_request_messages
+(
+    swap_id,
+    alpha_ledger,
+    alpha_ledger_network,
+    beta_ledger,
+    beta_ledger_network,
+    alpha_asset,
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    'ethereum',
+    CAST(alpha_ethereum_chain_id AS TEXT),
+    'ethereum',
+    CAST(beta_ethereum_chain_id AS TEXT),
+    'ether',
+    ether_amount,
+    NULL,
+    'erc20',
+    erc20_amount,
+    erc20_token_contract,
+    hash_function,
+    alpha_ethereum_refund_identity,
+    beta_ethereum_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+FROM rfc003_ethereum_ethereum_ether_erc20_request_messages;
+
+INSERT INTO rfc003_request_messages
+(
+    swap_id,
+    alpha_ledger,
+    alpha_ledger_network,
+    beta_ledger,
+    beta_ledger_network,
+    alpha_asset,
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    'ethereum',
+    CAST(alpha_ethereum_chain_id AS TEXT),
+    'ethereum',
+    CAST(beta_ethereum_chain_id AS TEXT),
+    'erc20',
+    erc20_amount,
+    erc20_token_contract,
+    'ether',
+    ether_amount,
+    NULL,
+    hash_function,
+    alpha_ethereum_refund_identity,
+    beta_ethereum_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+FROM rfc003_ethereum_ethereum_erc20_ether_request_messages;
+
+INSERT INTO rfc003_request_messages
+(
+    swap_id,
+    alpha_ledger,
+    alpha_ledger_network,
+    beta_ledger,
+    beta_ledger_network,
+    alpha_asset,
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    'ethereum',
+    CAST(alpha_ethereum_chain_id AS TEXT),
+    'ethereum',
+    CAST(beta_ethereum_chain_id AS TEXT),
+    'erc20',
+    alpha_erc20_amount,
+    alpha_erc20_token_contract,
+    'erc20',
+    beta_erc20_amount,
+    beta_erc20_token_contract,
+    hash_function,
+    alpha_ethereum_refund_identity,
+    beta_ethereum_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+FROM rfc003_ethereum_ethereum_erc20_erc20_request_messages;
+
+INSERT INTO rfc003_request_messages
+(
+    swap_id,
+    alpha_ledger,
+    alpha_ledger_network,
+    beta_ledger,
+    beta_ledger_network,
+    alpha_asset,
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    'litecoin',
+    litecoin_network,
+    'ethereum',
+    CAST(ethereum_chain_id AS TEXT),
+    'bitcoin',
+    bitcoin_amount,
+    NULL,
+    'ether',
+    ether_amount,
+    NULL,
+    hash_function,
+    litecoin_refund_identity,
+    ethereum_redeem_identity,
+    litecoin_expiry,
+    ethereum_expiry,
+    secret_hash
+FROM rfc003_litecoin_ethereum_bitcoin_ether_request_messages;
+
+INSERT INTO rfc003_request_messages
+(
+    swap_id,
+    alpha_ledger,
+    alpha_ledger_network,
+    beta_ledger,
+    beta_ledger_network,
+    alpha_asset,
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    'ethereum',
+    CAST(ethereum_chain_id AS TEXT),
+    'litecoin',
+    litecoin_network,
+    'ether',
+    ether_amount,
+    NULL,
+    'bitcoin',
+    bitcoin_amount,
+    NULL,
+    hash_function,
+    ethereum_refund_identity,
+    litecoin_redeem_identity,
+    ethereum_expiry,
+    litecoin_expiry,
+    secret_hash
+FROM rfc003_ethereum_litecoin_ether_bitcoin_request_messages;
+
+INSERT INTO rfc003_request_messages
+(
+    swap_id,
+    alpha_ledger,
+    alpha_ledger_network,
+    beta_ledger,
+    beta_ledger_network,
+    alpha_asset,
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    'litecoin',
+    litecoin_network,
+    'ethereum',
+    CAST(ethereum_chain_id AS TEXT),
+    'bitcoin',
+    bitcoin_amount,
+    NULL,
+    'erc20',
+    erc20_amount,
+    erc20_token_contract,
+    hash_function,
+    litecoin_refund_identity,
+    ethereum_redeem_identity,
+    litecoin_expiry,
+    ethereum_expiry,
+    secret_hash
+FROM rfc003_litecoin_ethereum_bitcoin_erc20_request_messages;
+
+INSERT INTO rfc003_request_messages
+(
+    swap_id,
+    alpha_ledger,
+    alpha_ledger_network,
+    beta_ledger,
+    beta_ledger_network,
+    alpha_asset,
+    alpha_asset_quantity,
+    alpha_asset_token_contract,
+    beta_asset,
+    beta_asset_quantity,
+    beta_asset_token_contract,
+    hash_function,
+    alpha_ledger_refund_identity,
+    beta_ledger_redeem_identity,
+    alpha_expiry,
+    beta_expiry,
+    secret_hash
+)
+SELECT
+    swap_id,
+    'ethereum',
+    CAST(ethereum_chain_id AS TEXT),
+    'litecoin',
+    litecoin_network,
+    'erc20',
+    erc20_amount,
+    erc20_token_contract,
+    'bitcoin',
+    bitcoin_amount,
+    NULL,
+    hash_function,
+    ethereum_refund_identity,
+    litecoin_redeem_identity,
+    ethereum_expiry,
+    litecoin_expiry,
+    secret_hash
+FROM rfc003_ethereum_litecoin_erc20_bitcoin_request_messages;
+
+INSERT INTO rfc003_accept_messages (swap_id, alpha_ledger_redeem_identity, beta_ledger_refund_identity, at)
+SELECT swap_id, bitcoin_redeem_identity, ethereum_refund_identity, at
+FROM rfc003_bitcoin_ethereum_accept_messages;
+
+INSERT INTO rfc003_accept_messages (swap_id, alpha_ledger_redeem_identity, beta_ledger_refund_identity, at)
+SELECT swap_id, ethereum_redeem_identity, bitcoin_refund_identity, at
+FROM rfc003_ethereum_bitcoin_accept_messages;
+
+INSERT INTO rfc003_accept_messages (swap_id, alpha_ledger_redeem_identity, beta_ledger_refund_identity, at)
+SELECT swap_id, lightning_redeem_identity, ethereum_refund_identity, at
+FROM rfc003_lightning_ethereum_accept_messages;
+
+INSERT INTO rfc003_accept_messages (swap_id, alpha_ledger_redeem_identity, beta_ledger_refund_identity, at)
+SELECT swap_id, ethereum_redeem_identity, lightning_refund_identity, at
+FROM rfc003_ethereum_lightning_accept_messages;
+
+INSERT INTO rfc003_accept_messages (swap_id, alpha_ledger_redeem_identity, beta_ledger_refund_identity, at)
+SELECT swap_id, alpha_ethereum_redeem_identity, beta_ethereum_refund_identity, at
+FROM rfc003_ethereum_ethereum_accept_messages;
+
+DROP TABLE rfc003_bitcoin_ethereum_bitcoin_ether_request_messages;
+DROP TABLE rfc003_ethereum_bitcoin_ether_bitcoin_request_messages;
+DROP TABLE rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages;
+DROP TABLE rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages;
+DROP TABLE rfc003_lightning_ethereum_bitcoin_ether_request_messages;
+DROP TABLE rfc003_ethereum_lightning_ether_bitcoin_request_messages;
+DROP TABLE rfc003_lightning_ethereum_bitcoin_erc20_request_messages;
+DROP TABLE rfc003_ethereum_lightning_erc20_bitcoin_request_messages;
+DROP TABLE rfc003_ethereum_ethereum_ether_erc20_request_messages;
+DROP TABLE rfc003_ethereum_ethereum_erc20_ether_request_messages;
+DROP TABLE rfc003_ethereum_ethereum_erc20_erc20_request_messages;
+DROP TABLE rfc003_litecoin_ethereum_bitcoin_ether_request_messages;
+DROP TABLE rfc003_ethereum_litecoin_ether_bitcoin_request_messages;
+DROP TABLE rfc003_litecoin_ethereum_bitcoin_erc20_request_messages;
+DROP TABLE rfc003_ethereum_litecoin_erc20_bitcoin_request_messages;
+DROP TABLE rfc003_bitcoin_ethereum_accept_messages;
+DROP TABLE rfc003_ethereum_bitcoin_accept_messages;
+DROP TABLE rfc003_lightning_ethereum_accept_messages;
+DROP TABLE rfc003_ethereum_lightning_accept_messages;
+DROP TABLE rfc003_ethereum_ethereum_accept_messages;
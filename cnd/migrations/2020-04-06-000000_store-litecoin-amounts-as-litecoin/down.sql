@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+
+UPDATE rfc003_request_messages
+SET alpha_asset = 'bitcoin'
+WHERE alpha_ledger = 'litecoin' AND alpha_asset = 'litecoin';
+
+UPDATE rfc003_request_messages
+SET beta_asset = 'bitcoin'
+WHERE beta_ledger = 'litecoin' AND beta_asset = 'litecoin';
@@ -0,0 +1,9 @@
+-- Litecoin amounts used to be stored as bitcoin amounts of the same value.
+
+UPDATE rfc003_request_messages
+SET alpha_asset = 'litecoin'
+WHERE alpha_ledger = 'litecoin' AND alpha_asset = 'bitcoin';
+
+UPDATE rfc003_request_messages
+SET beta_asset = 'litecoin'
+WHERE beta_ledger = 'litecoin' AND beta_asset = 'bitcoin';
@@ -1,4 +1,8 @@
 use bitcoin::{util::amount::Denomination, Amount};
+use serde::{
+    de::{self, Deserialize, Deserializer},
+    ser::{Serialize, Serializer},
+};
 use std::fmt;
 
 #[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
@@ -27,6 +31,42 @@ impl fmt::Display for Bitcoin {
     }
 }
 
+impl<'de> Deserialize<'de> for Bitcoin {
+    fn deserialize<D>(deserializer: D) -> Result<Self, <D as Deserializer<'de>>::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'vde> de::Visitor<'vde> for Visitor {
+            type Value = Bitcoin;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+                formatter.write_str("A string representing a satoshi quantity")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Bitcoin, E>
+            where
+                E: de::Error,
+            {
+                let sat = v.parse::<u64>().map_err(E::custom)?;
+                Ok(Bitcoin::from_sat(sat))
+            }
+        }
+
+        deserializer.deserialize_str(Visitor)
+    }
+}
+
+impl Serialize for Bitcoin {
+    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_sat().to_string().as_str())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::asset;
@@ -38,4 +78,15 @@ mod tests {
             "9000.00000000 BTC"
         );
     }
+
+    #[test]
+    fn bitcoin_serializes_as_satoshi_string() {
+        let bitcoin = asset::Bitcoin::from_sat(100_000_000);
+
+        let serialized = serde_json::to_string(&bitcoin).unwrap();
+        assert_eq!(serialized, r#""100000000""#);
+
+        let deserialized = serde_json::from_str::<asset::Bitcoin>(&serialized).unwrap();
+        assert_eq!(deserialized, bitcoin);
+    }
 }
@@ -0,0 +1,96 @@
+use bitcoin::{util::amount::Denomination, Amount};
+use serde::{
+    de::{self, Deserialize, Deserializer},
+    ser::{Serialize, Serializer},
+};
+use std::fmt;
+
+/// An amount of litecoin.
+///
+/// Litecoin has the same number of decimals as Bitcoin, its smallest unit is
+/// called a litoshi.
+#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
+pub struct Litecoin(Amount);
+
+impl Litecoin {
+    pub fn from_litoshi(litoshi: u64) -> Litecoin {
+        Litecoin(Amount::from_sat(litoshi))
+    }
+
+    pub fn as_litoshi(self) -> u64 {
+        Amount::as_sat(self.0)
+    }
+}
+
+impl From<Litecoin> for Amount {
+    fn from(litecoin: Litecoin) -> Self {
+        Amount::from_sat(litecoin.as_litoshi())
+    }
+}
+
+impl fmt::Display for Litecoin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let litecoin = self.0.to_string_in(Denomination::Bitcoin);
+        write!(f, "{} LTC", litecoin)
+    }
+}
+
+impl<'de> Deserialize<'de> for Litecoin {
+    fn deserialize<D>(deserializer: D) -> Result<Self, <D as Deserializer<'de>>::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'vde> de::Visitor<'vde> for Visitor {
+            type Value = Litecoin;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+                formatter.write_str("A string representing a litoshi quantity")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Litecoin, E>
+            where
+                E: de::Error,
+            {
+                let litoshi = v.parse::<u64>().map_err(E::custom)?;
+                Ok(Litecoin::from_litoshi(litoshi))
+            }
+        }
+
+        deserializer.deserialize_str(Visitor)
+    }
+}
+
+impl Serialize for Litecoin {
+    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_litoshi().to_string().as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::asset;
+
+    #[test]
+    fn display_litecoin() {
+        assert_eq!(
+            asset::Litecoin::from_litoshi(150_000_000).to_string(),
+            "1.50000000 LTC"
+        );
+    }
+
+    #[test]
+    fn litecoin_serializes_as_litoshi_string() {
+        let litecoin = asset::Litecoin::from_litoshi(100_000_000);
+
+        let serialized = serde_json::to_string(&litecoin).unwrap();
+        assert_eq!(serialized, r#""100000000""#);
+
+        let deserialized = serde_json::from_str::<asset::Litecoin>(&serialized).unwrap();
+        assert_eq!(deserialized, litecoin);
+    }
+}
@@ -1,33 +1,71 @@
 mod bitcoin;
 pub mod ethereum;
+mod litecoin;
 pub use self::{
     bitcoin::Bitcoin,
     ethereum::{Erc20, Erc20Quantity, Ether},
+    litecoin::Litecoin,
 };
 use crate::asset;
 use derivative::Derivative;
+use serde::{de::DeserializeOwned, Serialize};
 use std::{
     fmt::{Debug, Display},
     hash::Hash,
 };
 
 pub trait Asset:
-    Clone + Debug + Display + Send + Sync + 'static + PartialEq + Eq + Hash + Into<AssetKind> + Ord
+    Clone
+    + Debug
+    + Display
+    + Send
+    + Sync
+    + 'static
+    + PartialEq
+    + Eq
+    + Hash
+    + Into<AssetKind>
+    + Ord
+    + Serialize
+    + DeserializeOwned
 {
 }
 
+/// An asset that is held in the transaction outputs of a Bitcoin-like ledger,
+/// i.e. Bitcoin or litecoin.
+pub trait BitcoinLike: Asset + Copy {
+    /// The asset held by an output of `value` in the smallest unit, e.g.
+    /// satoshis.
+    fn from_output_value(value: u64) -> Self;
+}
+
 impl Asset for Bitcoin {}
 
 impl Asset for Ether {}
 
 impl Asset for Erc20 {}
 
+impl Asset for Litecoin {}
+
 #[derive(Clone, Derivative, PartialEq)]
 #[derivative(Debug = "transparent")]
 pub enum AssetKind {
     Bitcoin(Bitcoin),
     Ether(Ether),
     Erc20(Erc20),
+    Litecoin(Litecoin),
+}
+
+impl BitcoinLike for Bitcoin {
+    fn from_output_value(value: u64) -> Self {
+        Bitcoin::from_sat(value)
+    }
+}
+
+impl BitcoinLike for Litecoin {
+    fn from_output_value(value: u64) -> Self {
+        Litecoin::from_litoshi(value)
+    }
 }
 
 impl From<Bitcoin> for AssetKind {
@@ -47,3 +85,9 @@ impl From<asset::Erc20> for AssetKind {
         AssetKind::Erc20(quantity)
     }
 }
+
+impl From<asset::Litecoin> for AssetKind {
+    fn from(amount: asset::Litecoin) -> Self {
+        AssetKind::Litecoin(amount)
+    }
+}
@@ -0,0 +1,182 @@
+#![allow(clippy::type_repetition_in_bounds)]
+use crate::{
+    config::Autopilot,
+    db::{DetermineTypes, ExecutedAction, ExecutedActions, Retrieve},
+    http_api::action::{execute_once, ActionAlreadyExecuted, ExecuteAction},
+    swap_protocols::{
+        actions::Actions,
+        rfc003::{
+            actions::{Action, ActionKind},
+            state_store::StateStore,
+            ActorState,
+        },
+        Facade, SwapId,
+    },
+    wallet::ChainTimeNotReached,
+};
+use chrono::{NaiveDateTime, Utc};
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long we wait before retrying an action that failed once. The delay
+/// doubles with every further failure, up to `MAX_RETRY_DELAY_SECS`.
+const FIRST_RETRY_DELAY_SECS: i64 = 10;
+const MAX_RETRY_DELAY_SECS: i64 = 60 * 60;
+
+/// Periodically goes through all swaps and executes their redeem and refund
+/// actions with the built-in wallets, as allowed by `policy`.
+///
+/// Every action is executed until it succeeds once per swap. Executed actions
+/// are recorded in the database together with their outcome, failed attempts
+/// too. Those are retried with an increasing delay. Actions that no built-in
+/// wallet can execute, e.g. those on Litecoin, are skipped.
+///
+/// Refunds are only sent once the time of the chain, e.g. the median time past
+/// on Bitcoin, reached the expiry of the HTLC. Until then the wallets decline
+/// to send them and the autopilot tries again on the next round.
+pub async fn run(facade: Facade, policy: Autopilot) {
+    tracing::info!("autopilot started with {:?}", policy);
+
+    loop {
+        match Retrieve::all(&facade).await {
+            Ok(swaps) => {
+                for swap in swaps {
+                    if let Err(e) = advance_swap(&facade, policy, swap.swap_id).await {
+                        tracing::warn!(
+                            "autopilot could not advance swap {}: {:#}",
+                            swap.swap_id,
+                            e
+                        );
+                    }
+                }
+            }
+            Err(e) => tracing::error!("autopilot failed to load swaps: {:#}", e),
+        }
+
+        tokio::time::delay_for(POLL_INTERVAL).await;
+    }
+}
+
+#[allow(clippy::cognitive_complexity)]
+async fn advance_swap(facade: &Facade, policy: Autopilot, swap_id: SwapId) -> anyhow::Result<()> {
+    let types = facade.determine_types(&swap_id).await?;
+
+    with_swap_types!(types, {
+        let state = match StateStore::get::<ROLE>(facade, &swap_id)? {
+            Some(state) => state,
+            None => return Ok(()),
+        };
+
+        if state.swap_failed() || state.swap_outcome().is_some() {
+            return Ok(());
+        }
+
+        for action in state.actions() {
+            match action {
+                Action::Redeem(action) if policy.redeem => {
+                    execute(facade, swap_id, ActionKind::Redeem, action).await?
+                }
+                Action::Refund(action) if policy.refund => {
+                    execute(facade, swap_id, ActionKind::Refund, action).await?
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    })
+}
+
+async fn execute<A>(
+    facade: &Facade,
+    swap_id: SwapId,
+    action_kind: ActionKind,
+    action: A,
+) -> anyhow::Result<()>
+where
+    A: Send + 'static,
+    Facade: ExecuteAction<A>,
+{
+    if !facade.can_execute(&action) {
+        return Ok(());
+    }
+
+    let failed_attempts = facade
+        .load_executed_actions(&swap_id)
+        .await?
+        .into_iter()
+        .filter(|executed| executed.action == action_kind && executed.failed())
+        .collect::<Vec<_>>();
+    if !retry_due(&failed_attempts, Utc::now().naive_utc()) {
+        return Ok(());
+    }
+
+    match execute_once(facade, swap_id, action_kind, action).await {
+        Ok(_) => {
+            tracing::info!(
+                "autopilot executed {} action of swap {}",
+                action_kind,
+                swap_id
+            );
+
+            Ok(())
+        }
+        Err(e) if e.is::<ActionAlreadyExecuted>() || e.is::<ChainTimeNotReached>() => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Whether enough time passed since the last of the `failed_attempts` to
+/// execute the action again.
+fn retry_due(failed_attempts: &[ExecutedAction], now: NaiveDateTime) -> bool {
+    let last_attempt = match failed_attempts.iter().map(|attempt| attempt.at).max() {
+        Some(at) => at,
+        None => return true,
+    };
+
+    // Cap the exponent so that the delay cannot overflow.
+    let doublings = failed_attempts.len().saturating_sub(1).min(16) as u32;
+    let delay_secs = (FIRST_RETRY_DELAY_SECS * 2i64.pow(doublings)).min(MAX_RETRY_DELAY_SECS);
+
+    now >= last_attempt + chrono::Duration::seconds(delay_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spectral::prelude::*;
+
+    fn failed_at(secs: i64) -> ExecutedAction {
+        ExecutedAction {
+            action: ActionKind::Refund,
+            outcome: ExecutedAction::failure(&anyhow::anyhow!("node not reachable")),
+            at: at(secs),
+        }
+    }
+
+    fn at(secs: i64) -> NaiveDateTime {
+        NaiveDateTime::from_timestamp(secs, 0)
+    }
+
+    #[test]
+    fn action_without_failed_attempts_is_due() {
+        assert_that(&retry_due(&[], at(0))).is_true();
+    }
+
+    #[test]
+    fn retry_delay_doubles_with_every_failed_attempt() {
+        let attempts = vec![failed_at(0), failed_at(10), failed_at(30)];
+
+        assert_that(&retry_due(&attempts, at(69))).is_false();
+        assert_that(&retry_due(&attempts, at(70))).is_true();
+    }
+
+    #[test]
+    fn retry_delay_is_capped() {
+        let attempts = (0..100).map(|_| failed_at(0)).collect::<Vec<_>>();
+
+        assert_that(&retry_due(&attempts, at(MAX_RETRY_DELAY_SECS - 1))).is_false();
+        assert_that(&retry_due(&attempts, at(MAX_RETRY_DELAY_SECS))).is_true();
+    }
+}
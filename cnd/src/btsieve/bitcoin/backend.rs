@@ -0,0 +1,177 @@
+use crate::btsieve::{
+    bitcoin::{
+        BitcoindConnector, BitcoindRpcConnector, BlockFilterByHash, EsploraConnector,
+        MatchingTransactions, NewBlocks, TransactionPattern,
+    },
+    failover::NodeStatus,
+    BlockByHash, LatestBlock, PendingTransactions,
+};
+use bitcoin::{util::bip158::BlockFilter, BlockHash, BlockHeader, Network, Transaction, Txid};
+use futures::Future;
+
+/// Where we learn about the Bitcoin chain from, as configured in the
+/// `[bitcoin]` section: the REST or JSON-RPC interface of bitcoind or an
+/// Esplora instance.
+#[derive(Clone, Debug)]
+pub enum BitcoinBackend {
+    Rest(BitcoindConnector),
+    JsonRpc(BitcoindRpcConnector),
+    Esplora(EsploraConnector),
+}
+
+impl BitcoinBackend {
+    /// Takes the latest block from `new_blocks` while bitcoind sends
+    /// notifications, whatever interface of bitcoind we use otherwise.
+    pub fn with_new_blocks(self, new_blocks: NewBlocks) -> Self {
+        match self {
+            BitcoinBackend::Rest(connector) => {
+                BitcoinBackend::Rest(connector.with_new_blocks(new_blocks))
+            }
+            BitcoinBackend::JsonRpc(connector) => {
+                BitcoinBackend::JsonRpc(connector.with_new_blocks(new_blocks))
+            }
+            // Esplora does not send notifications, we keep polling it.
+            BitcoinBackend::Esplora(connector) => BitcoinBackend::Esplora(connector),
+        }
+    }
+}
+
+impl From<BitcoindConnector> for BitcoinBackend {
+    fn from(connector: BitcoindConnector) -> Self {
+        BitcoinBackend::Rest(connector)
+    }
+}
+
+impl From<BitcoindRpcConnector> for BitcoinBackend {
+    fn from(connector: BitcoindRpcConnector) -> Self {
+        BitcoinBackend::JsonRpc(connector)
+    }
+}
+
+impl From<EsploraConnector> for BitcoinBackend {
+    fn from(connector: EsploraConnector) -> Self {
+        BitcoinBackend::Esplora(connector)
+    }
+}
+
+impl LatestBlock for BitcoinBackend {
+    type Block = bitcoin::Block;
+    type BlockHash = bitcoin::BlockHash;
+
+    fn latest_block(
+        &mut self,
+    ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
+        match self {
+            BitcoinBackend::Rest(connector) => connector.latest_block(),
+            BitcoinBackend::JsonRpc(connector) => connector.latest_block(),
+            BitcoinBackend::Esplora(connector) => connector.latest_block(),
+        }
+    }
+
+    fn wait_for_new_block(
+        &self,
+    ) -> Box<dyn Future<Item = (), Error = anyhow::Error> + Send + 'static> {
+        match self {
+            BitcoinBackend::Rest(connector) => connector.wait_for_new_block(),
+            BitcoinBackend::JsonRpc(connector) => connector.wait_for_new_block(),
+            BitcoinBackend::Esplora(connector) => connector.wait_for_new_block(),
+        }
+    }
+}
+
+impl BlockByHash for BitcoinBackend {
+    type Block = bitcoin::Block;
+    type BlockHash = bitcoin::BlockHash;
+
+    fn block_by_hash(
+        &self,
+        block_hash: Self::BlockHash,
+    ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
+        match self {
+            BitcoinBackend::Rest(connector) => connector.block_by_hash(block_hash),
+            BitcoinBackend::JsonRpc(connector) => connector.block_by_hash(block_hash),
+            BitcoinBackend::Esplora(connector) => connector.block_by_hash(block_hash),
+        }
+    }
+}
+
+impl PendingTransactions for BitcoinBackend {
+    type Transaction = Transaction;
+    type TransactionId = Txid;
+
+    fn pending_transaction_ids(
+        &self,
+    ) -> Box<dyn Future<Item = Vec<Self::TransactionId>, Error = anyhow::Error> + Send + 'static>
+    {
+        match self {
+            BitcoinBackend::Rest(connector) => connector.pending_transaction_ids(),
+            BitcoinBackend::JsonRpc(connector) => connector.pending_transaction_ids(),
+            BitcoinBackend::Esplora(connector) => connector.pending_transaction_ids(),
+        }
+    }
+
+    fn pending_transaction(
+        &self,
+        txid: Self::TransactionId,
+    ) -> Box<dyn Future<Item = Option<Self::Transaction>, Error = anyhow::Error> + Send + 'static>
+    {
+        match self {
+            BitcoinBackend::Rest(connector) => connector.pending_transaction(txid),
+            BitcoinBackend::JsonRpc(connector) => connector.pending_transaction(txid),
+            BitcoinBackend::Esplora(connector) => connector.pending_transaction(txid),
+        }
+    }
+}
+
+impl MatchingTransactions for BitcoinBackend {
+    fn matching_transactions(
+        &self,
+        pattern: TransactionPattern,
+    ) -> Box<dyn Future<Item = Option<Vec<Transaction>>, Error = anyhow::Error> + Send + 'static>
+    {
+        match self {
+            BitcoinBackend::Rest(connector) => connector.matching_transactions(pattern),
+            BitcoinBackend::JsonRpc(connector) => connector.matching_transactions(pattern),
+            BitcoinBackend::Esplora(connector) => connector.matching_transactions(pattern),
+        }
+    }
+}
+
+impl BlockFilterByHash for BitcoinBackend {
+    fn block_filter_by_hash(
+        &self,
+        block_hash: BlockHash,
+    ) -> Box<
+        dyn Future<Item = Option<(BlockHeader, BlockFilter)>, Error = anyhow::Error>
+            + Send
+            + 'static,
+    > {
+        match self {
+            BitcoinBackend::Rest(connector) => connector.block_filter_by_hash(block_hash),
+            BitcoinBackend::JsonRpc(connector) => connector.block_filter_by_hash(block_hash),
+            BitcoinBackend::Esplora(connector) => connector.block_filter_by_hash(block_hash),
+        }
+    }
+}
+
+impl NodeStatus for BitcoinBackend {
+    type ChainId = Network;
+
+    fn chain_id(
+        &self,
+    ) -> Box<dyn Future<Item = Self::ChainId, Error = anyhow::Error> + Send + 'static> {
+        match self {
+            BitcoinBackend::Rest(connector) => connector.chain_id(),
+            BitcoinBackend::JsonRpc(connector) => connector.chain_id(),
+            BitcoinBackend::Esplora(connector) => connector.chain_id(),
+        }
+    }
+
+    fn block_height(&self) -> Box<dyn Future<Item = u64, Error = anyhow::Error> + Send + 'static> {
+        match self {
+            BitcoinBackend::Rest(connector) => connector.block_height(),
+            BitcoinBackend::JsonRpc(connector) => connector.block_height(),
+            BitcoinBackend::Esplora(connector) => connector.block_height(),
+        }
+    }
+}
@@ -1,22 +1,26 @@
 use crate::btsieve::{
-    bitcoin::bitcoin_http_request_for_hex_encoded_object, BlockByHash, LatestBlock,
+    bitcoin::{
+        bitcoin_http_request_for_hex_encoded_object, decode_response, BlockFilterByHash,
+        BlockNotification, ChainInfo, MatchingTransactions, NewBlocks, TransactionPattern,
+    },
+    failover::NodeStatus,
+    wait_for_new_block_notification, BlockByHash, LatestBlock, Notification, PendingTransactions,
 };
-use bitcoin::{BlockHash, Network};
+use bitcoin::{util::bip158::BlockFilter, BlockHash, BlockHeader, Network, Transaction, Txid};
 use futures::Future;
 use futures_core::{compat::Future01CompatExt, FutureExt, TryFutureExt};
-use reqwest::{Client, Url};
-use serde::Deserialize;
-
-#[derive(Deserialize)]
-struct ChainInfo {
-    bestblockhash: BlockHash,
-}
+use reqwest::{Client, StatusCode, Url};
+use serde::de::IgnoredAny;
+use std::collections::HashMap;
 
 #[derive(Clone, Debug)]
 pub struct BitcoindConnector {
     chaininfo_url: Url,
     raw_block_by_hash_url: Url,
+    mempool_url: Url,
+    raw_transaction_by_id_url: Url,
     client: Client,
+    new_blocks: Option<NewBlocks>,
 }
 
 impl BitcoindConnector {
@@ -24,15 +28,45 @@ impl BitcoindConnector {
         Ok(Self {
             chaininfo_url: base_url.join("rest/chaininfo.json")?,
             raw_block_by_hash_url: base_url.join("rest/block/")?,
+            mempool_url: base_url.join("rest/mempool/contents.json")?,
+            raw_transaction_by_id_url: base_url.join("rest/tx/")?,
             client: Client::new(),
+            new_blocks: None,
         })
     }
 
+    /// Takes the latest block from `new_blocks` instead of polling the REST
+    /// interface while bitcoind sends notifications, see `Notification::fresh`.
+    pub fn with_new_blocks(self, new_blocks: NewBlocks) -> Self {
+        Self {
+            new_blocks: Some(new_blocks),
+            ..self
+        }
+    }
+
     fn raw_block_by_hash_url(&self, block_hash: &BlockHash) -> Url {
         self.raw_block_by_hash_url
             .join(&format!("{}.hex", block_hash))
             .expect("building url should work")
     }
+
+    fn raw_transaction_by_id_url(&self, txid: &Txid) -> Url {
+        self.raw_transaction_by_id_url
+            .join(&format!("{}.hex", txid))
+            .expect("building url should work")
+    }
+
+    async fn chain_info(&self) -> anyhow::Result<ChainInfo> {
+        let chain_info = self
+            .client
+            .get(self.chaininfo_url.clone())
+            .send()
+            .await?
+            .json::<ChainInfo>()
+            .await?;
+
+        Ok(chain_info)
+    }
 }
 
 impl LatestBlock for BitcoindConnector {
@@ -42,17 +76,16 @@ impl LatestBlock for BitcoindConnector {
     fn latest_block(
         &mut self,
     ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
-        let chaininfo_url = self.chaininfo_url.clone();
+        match Notification::fresh(self.new_blocks.as_ref()) {
+            Some(BlockNotification::Raw(block)) => return Box::new(futures::future::ok(block)),
+            Some(BlockNotification::Hash(block_hash)) => return self.block_by_hash(block_hash),
+            None => {}
+        }
+
         let this = self.clone();
 
         let latest_block = async move {
-            let chain_info = this
-                .client
-                .get(chaininfo_url)
-                .send()
-                .await?
-                .json::<ChainInfo>()
-                .await?;
+            let chain_info = this.chain_info().await?;
 
             let block = this
                 .block_by_hash(chain_info.bestblockhash)
@@ -64,6 +97,14 @@ impl LatestBlock for BitcoindConnector {
 
         Box::new(latest_block.boxed().compat())
     }
+
+    fn wait_for_new_block(
+        &self,
+    ) -> Box<dyn Future<Item = (), Error = anyhow::Error> + Send + 'static> {
+        wait_for_new_block_notification(self.new_blocks.as_ref(), |notification| {
+            notification.block.block_hash()
+        })
+    }
 }
 
 impl BlockByHash for BitcoindConnector {
@@ -95,6 +136,112 @@ impl BlockByHash for BitcoindConnector {
     }
 }
 
+impl PendingTransactions for BitcoindConnector {
+    type Transaction = Transaction;
+    type TransactionId = Txid;
+
+    fn pending_transaction_ids(
+        &self,
+    ) -> Box<dyn Future<Item = Vec<Self::TransactionId>, Error = anyhow::Error> + Send + 'static>
+    {
+        let url = self.mempool_url.clone();
+        let client = self.client.clone();
+
+        let txids = async move {
+            let mempool = client
+                .get(url)
+                .send()
+                .await?
+                .json::<HashMap<Txid, IgnoredAny>>()
+                .await?;
+
+            Ok(mempool
+                .into_iter()
+                .map(|(txid, _)| txid)
+                .collect::<Vec<_>>())
+        }
+        .boxed()
+        .compat();
+
+        Box::new(txids)
+    }
+
+    fn pending_transaction(
+        &self,
+        txid: Self::TransactionId,
+    ) -> Box<dyn Future<Item = Option<Self::Transaction>, Error = anyhow::Error> + Send + 'static>
+    {
+        let url = self.raw_transaction_by_id_url(&txid);
+        let client = self.client.clone();
+
+        let transaction = async move {
+            let response = client.get(url).send().await?;
+            if response.status() == StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+
+            let transaction: Transaction = decode_response(response.text().await?)?;
+
+            Ok(Some(transaction))
+        }
+        .boxed()
+        .compat();
+
+        Box::new(transaction)
+    }
+}
+
+impl MatchingTransactions for BitcoindConnector {
+    fn matching_transactions(
+        &self,
+        _pattern: TransactionPattern,
+    ) -> Box<dyn Future<Item = Option<Vec<Transaction>>, Error = anyhow::Error> + Send + 'static>
+    {
+        // bitcoind only indexes the transactions of its wallet.
+        Box::new(futures::future::ok(None))
+    }
+}
+
+impl BlockFilterByHash for BitcoindConnector {
+    fn block_filter_by_hash(
+        &self,
+        _block_hash: BlockHash,
+    ) -> Box<
+        dyn Future<Item = Option<(BlockHeader, BlockFilter)>, Error = anyhow::Error>
+            + Send
+            + 'static,
+    > {
+        // We only ask bitcoind for filters over JSON-RPC.
+        Box::new(futures::future::ok(None))
+    }
+}
+
+impl NodeStatus for BitcoindConnector {
+    type ChainId = Network;
+
+    fn chain_id(
+        &self,
+    ) -> Box<dyn Future<Item = Self::ChainId, Error = anyhow::Error> + Send + 'static> {
+        let this = self.clone();
+
+        let network = async move { Ok(this.chain_info().await?.network()?) }
+            .boxed()
+            .compat();
+
+        Box::new(network)
+    }
+
+    fn block_height(&self) -> Box<dyn Future<Item = u64, Error = anyhow::Error> + Send + 'static> {
+        let this = self.clone();
+
+        let block_height = async move { Ok(this.chain_info().await?.blocks) }
+            .boxed()
+            .compat();
+
+        Box::new(block_height)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -152,6 +299,17 @@ mod tests {
                     .unwrap();
             let raw_block_by_hash_url = connector.raw_block_by_hash_url(&block_id.into());
             assert_eq!(raw_block_by_hash_url, Url::parse("http://localhost:8080/rest/block/2a593b84b1943521be01f97a59fc7feba30e7e8527fb2ba20b0158ca09016d02.hex").unwrap());
+
+            assert_eq!(
+                connector.mempool_url,
+                Url::parse("http://localhost:8080/rest/mempool/contents.json").unwrap()
+            );
+
+            let txid: Txid = "2a593b84b1943521be01f97a59fc7feba30e7e8527fb2ba20b0158ca09016d02"
+                .parse()
+                .unwrap();
+            let raw_transaction_by_id_url = connector.raw_transaction_by_id_url(&txid);
+            assert_eq!(raw_transaction_by_id_url, Url::parse("http://localhost:8080/rest/tx/2a593b84b1943521be01f97a59fc7feba30e7e8527fb2ba20b0158ca09016d02.hex").unwrap());
         }
     }
 }
@@ -0,0 +1,239 @@
+use crate::btsieve::{
+    bitcoin::{
+        decode_response, BitcoindRpcClient, BlockFilterByHash, BlockNotification, ChainInfo,
+        MatchingTransactions, NewBlocks, RpcError, TransactionPattern,
+    },
+    failover::NodeStatus,
+    wait_for_new_block_notification, BlockByHash, LatestBlock, Notification, PendingTransactions,
+};
+use bitcoin::{util::bip158::BlockFilter, BlockHash, BlockHeader, Network, Transaction, Txid};
+use futures::Future;
+use futures_core::{compat::Future01CompatExt, FutureExt, TryFutureExt};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize)]
+struct BlockFilterResponse {
+    filter: String,
+}
+
+/// Talks to the JSON-RPC interface of bitcoind, for nodes that have the REST
+/// interface disabled.
+#[derive(Clone, Debug)]
+pub struct BitcoindRpcConnector {
+    client: BitcoindRpcClient,
+    new_blocks: Option<NewBlocks>,
+}
+
+impl BitcoindRpcConnector {
+    pub fn new(client: BitcoindRpcClient) -> Self {
+        Self {
+            client,
+            new_blocks: None,
+        }
+    }
+
+    /// Takes the latest block from `new_blocks` instead of polling bitcoind
+    /// while it sends notifications, see `Notification::fresh`.
+    pub fn with_new_blocks(self, new_blocks: NewBlocks) -> Self {
+        Self {
+            new_blocks: Some(new_blocks),
+            ..self
+        }
+    }
+}
+
+impl LatestBlock for BitcoindRpcConnector {
+    type Block = bitcoin::Block;
+    type BlockHash = bitcoin::BlockHash;
+
+    fn latest_block(
+        &mut self,
+    ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
+        match Notification::fresh(self.new_blocks.as_ref()) {
+            Some(BlockNotification::Raw(block)) => return Box::new(futures::future::ok(block)),
+            Some(BlockNotification::Hash(block_hash)) => return self.block_by_hash(block_hash),
+            None => {}
+        }
+
+        let this = self.clone();
+
+        let latest_block = async move {
+            let block_hash: BlockHash = this.client.call("getbestblockhash", vec![]).await?;
+
+            let block = this.block_by_hash(block_hash).compat().await?;
+
+            Ok(block)
+        };
+
+        Box::new(latest_block.boxed().compat())
+    }
+
+    fn wait_for_new_block(
+        &self,
+    ) -> Box<dyn Future<Item = (), Error = anyhow::Error> + Send + 'static> {
+        wait_for_new_block_notification(self.new_blocks.as_ref(), |notification| {
+            notification.block.block_hash()
+        })
+    }
+}
+
+impl BlockByHash for BitcoindRpcConnector {
+    type Block = bitcoin::Block;
+    type BlockHash = bitcoin::BlockHash;
+
+    fn block_by_hash(
+        &self,
+        block_hash: Self::BlockHash,
+    ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
+        let client = self.client.clone();
+
+        let block = async move {
+            // Verbosity 0 returns the serialized block.
+            let hex: String = client
+                .call("getblock", vec![json!(block_hash), json!(0)])
+                .await?;
+            let block: Self::Block = decode_response(hex)?;
+            tracing::debug!(
+                "Fetched block {} with {} transactions from bitcoind",
+                block_hash,
+                block.txdata.len()
+            );
+
+            Ok(block)
+        }
+        .boxed()
+        .compat();
+
+        Box::new(block)
+    }
+}
+
+impl PendingTransactions for BitcoindRpcConnector {
+    type Transaction = Transaction;
+    type TransactionId = Txid;
+
+    fn pending_transaction_ids(
+        &self,
+    ) -> Box<dyn Future<Item = Vec<Self::TransactionId>, Error = anyhow::Error> + Send + 'static>
+    {
+        let client = self.client.clone();
+
+        let txids = async move { client.call("getrawmempool", vec![]).await }
+            .boxed()
+            .compat();
+
+        Box::new(txids)
+    }
+
+    fn pending_transaction(
+        &self,
+        txid: Self::TransactionId,
+    ) -> Box<dyn Future<Item = Option<Self::Transaction>, Error = anyhow::Error> + Send + 'static>
+    {
+        let client = self.client.clone();
+
+        let transaction = async move {
+            let hex: String = match client.call("getrawtransaction", vec![json!(txid)]).await {
+                Ok(hex) => hex,
+                Err(e) => match e.downcast_ref::<RpcError>() {
+                    Some(error) if error.is_not_found() => return Ok(None),
+                    _ => return Err(e),
+                },
+            };
+
+            let transaction: Transaction = decode_response(hex)?;
+
+            Ok(Some(transaction))
+        }
+        .boxed()
+        .compat();
+
+        Box::new(transaction)
+    }
+}
+
+impl MatchingTransactions for BitcoindRpcConnector {
+    fn matching_transactions(
+        &self,
+        _pattern: TransactionPattern,
+    ) -> Box<dyn Future<Item = Option<Vec<Transaction>>, Error = anyhow::Error> + Send + 'static>
+    {
+        // bitcoind only indexes the transactions of its wallet.
+        Box::new(futures::future::ok(None))
+    }
+}
+
+impl BlockFilterByHash for BitcoindRpcConnector {
+    fn block_filter_by_hash(
+        &self,
+        block_hash: BlockHash,
+    ) -> Box<
+        dyn Future<Item = Option<(BlockHeader, BlockFilter)>, Error = anyhow::Error>
+            + Send
+            + 'static,
+    > {
+        let client = self.client.clone();
+
+        let header_and_filter = async move {
+            let response: BlockFilterResponse = match client
+                .call("getblockfilter", vec![json!(block_hash), json!("basic")])
+                .await
+            {
+                Ok(response) => response,
+                // bitcoind refuses to serve filters unless it was started
+                // with `-blockfilterindex`.
+                Err(e) => match e.downcast_ref::<RpcError>() {
+                    Some(error) => {
+                        tracing::debug!("bitcoind does not serve block filters: {}", error);
+                        return Ok(None);
+                    }
+                    None => return Err(e),
+                },
+            };
+            let filter = BlockFilter::new(&hex::decode(response.filter)?);
+
+            // Verbose set to false returns the serialized header.
+            let hex: String = client
+                .call("getblockheader", vec![json!(block_hash), json!(false)])
+                .await?;
+            let header: BlockHeader = decode_response(hex)?;
+
+            Ok(Some((header, filter)))
+        }
+        .boxed()
+        .compat();
+
+        Box::new(header_and_filter)
+    }
+}
+
+impl NodeStatus for BitcoindRpcConnector {
+    type ChainId = Network;
+
+    fn chain_id(
+        &self,
+    ) -> Box<dyn Future<Item = Self::ChainId, Error = anyhow::Error> + Send + 'static> {
+        let client = self.client.clone();
+
+        let network = async move {
+            let chain_info: ChainInfo = client.call("getblockchaininfo", vec![]).await?;
+
+            Ok(chain_info.network()?)
+        }
+        .boxed()
+        .compat();
+
+        Box::new(network)
+    }
+
+    fn block_height(&self) -> Box<dyn Future<Item = u64, Error = anyhow::Error> + Send + 'static> {
+        let client = self.client.clone();
+
+        let block_height = async move { client.call("getblockcount", vec![]).await }
+            .boxed()
+            .compat();
+
+        Box::new(block_height)
+    }
+}
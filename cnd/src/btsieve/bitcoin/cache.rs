@@ -1,5 +1,13 @@
-use crate::btsieve::{BlockByHash, LatestBlock};
-use bitcoin::{util::hash::BitcoinHash, Block, BlockHash as Hash, BlockHash};
+use crate::btsieve::{
+    bitcoin::{BlockFilterByHash, MatchingTransactions, TransactionPattern},
+    disk_cache::{CacheKey, DiskCache, Persist},
+    BlockByHash, LatestBlock,
+};
+use bitcoin::{
+    consensus::encode::{deserialize, serialize},
+    util::{bip158::BlockFilter, hash::BitcoinHash},
+    Block, BlockHash as Hash, BlockHash, BlockHeader, Transaction,
+};
 use derivative::Derivative;
 use futures::Future;
 use futures_core::{
@@ -16,6 +24,8 @@ pub struct Cache<C> {
     pub connector: C,
     #[derivative(Debug = "ignore")]
     pub block_cache: Arc<Mutex<LruCache<BlockHash, Block>>>,
+    #[derivative(Debug = "ignore")]
+    pub disk_block_cache: Option<Arc<DiskCache<BlockHash, Block>>>,
 }
 
 impl<C> Cache<C> {
@@ -24,6 +34,15 @@ impl<C> Cache<C> {
         Cache {
             connector,
             block_cache,
+            disk_block_cache: None,
+        }
+    }
+
+    /// Keeps the fetched blocks on disk in addition to memory.
+    pub fn with_disk_cache(self, disk_block_cache: DiskCache<BlockHash, Block>) -> Self {
+        Cache {
+            disk_block_cache: Some(Arc::new(disk_block_cache)),
+            ..self
         }
     }
 }
@@ -39,15 +58,27 @@ where
         &mut self,
     ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
         let cache = Arc::clone(&self.block_cache);
+        let disk_cache = self.disk_block_cache.clone();
         let mut connector = self.connector.clone();
 
         let future = async move {
             let block = connector.latest_block().compat().await?;
 
             let block_hash = block.bitcoin_hash();
-            let mut guard = cache.lock().await;
-            if !guard.contains(&block_hash) {
-                guard.put(block_hash, block.clone());
+            let is_new = {
+                let mut guard = cache.lock().await;
+                let is_new = !guard.contains(&block_hash);
+                if is_new {
+                    guard.put(block_hash, block.clone());
+                }
+
+                is_new
+            };
+
+            // The lock is released while we write to the disk.
+            match disk_cache {
+                Some(disk_cache) if is_new => disk_cache.store(block_hash, block.clone()).await,
+                _ => {}
             }
 
             Ok(block)
@@ -57,6 +88,61 @@ where
 
         Box::new(future)
     }
+
+    fn wait_for_new_block(
+        &self,
+    ) -> Box<dyn Future<Item = (), Error = anyhow::Error> + Send + 'static> {
+        self.connector.wait_for_new_block()
+    }
 }
 
 impl_block_by_hash!();
+
+impl<C> MatchingTransactions for Cache<C>
+where
+    C: MatchingTransactions,
+{
+    fn matching_transactions(
+        &self,
+        pattern: TransactionPattern,
+    ) -> Box<dyn Future<Item = Option<Vec<Transaction>>, Error = anyhow::Error> + Send + 'static>
+    {
+        self.connector.matching_transactions(pattern)
+    }
+}
+
+impl<C> BlockFilterByHash for Cache<C>
+where
+    C: BlockFilterByHash,
+{
+    fn block_filter_by_hash(
+        &self,
+        block_hash: BlockHash,
+    ) -> Box<
+        dyn Future<Item = Option<(BlockHeader, BlockFilter)>, Error = anyhow::Error>
+            + Send
+            + 'static,
+    > {
+        self.connector.block_filter_by_hash(block_hash)
+    }
+}
+
+impl CacheKey for BlockHash {
+    fn to_file_name(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_file_name(file_name: &str) -> Option<Self> {
+        file_name.parse().ok()
+    }
+}
+
+impl Persist for Block {
+    fn to_bytes(&self) -> Option<Vec<u8>> {
+        Some(serialize(self))
+    }
+
+    fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(deserialize(bytes)?)
+    }
+}
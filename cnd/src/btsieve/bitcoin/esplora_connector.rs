@@ -0,0 +1,420 @@
+use crate::btsieve::{
+    bitcoin::{decode_response, BlockFilterByHash, MatchingTransactions, TransactionPattern},
+    failover::NodeStatus,
+    BlockByHash, LatestBlock, PendingTransactions,
+};
+use anyhow::anyhow;
+use bitcoin::{
+    blockdata::constants::genesis_block, consensus::encode::deserialize, util::bip158::BlockFilter,
+    Address, BitcoinHash, BlockHash, BlockHeader, Network, OutPoint, Transaction, Txid,
+};
+use futures::Future;
+use futures_core::{compat::Future01CompatExt, FutureExt, TryFutureExt};
+use reqwest::{Client, StatusCode, Url};
+use serde::Deserialize;
+
+/// Talks to the HTTP API of Esplora, the block explorer backed by Electrs.
+///
+/// Esplora indexes transactions by address and outpoint, which lets us look
+/// up the transactions of a `TransactionPattern` instead of scanning blocks.
+#[derive(Clone, Debug)]
+pub struct EsploraConnector {
+    base_url: Url,
+    client: Client,
+}
+
+#[derive(Deserialize)]
+struct AddressTransaction {
+    txid: Txid,
+    status: Status,
+}
+
+#[derive(Deserialize)]
+struct Outspend {
+    spent: bool,
+    txid: Option<Txid>,
+    status: Option<Status>,
+}
+
+#[derive(Deserialize)]
+struct Status {
+    confirmed: bool,
+}
+
+impl EsploraConnector {
+    pub fn new(mut base_url: Url) -> Self {
+        // Esplora usually lives under a path, e.g. `/api`, which `Url::join`
+        // would replace unless it ends with a slash.
+        if !base_url.path().ends_with('/') {
+            let path = format!("{}/", base_url.path());
+            base_url.set_path(&path);
+        }
+
+        Self {
+            base_url,
+            client: Client::new(),
+        }
+    }
+
+    fn url(&self, path: &str) -> Url {
+        self.base_url.join(path).expect("building url should work")
+    }
+
+    fn tip_hash_url(&self) -> Url {
+        self.url("blocks/tip/hash")
+    }
+
+    fn tip_height_url(&self) -> Url {
+        self.url("blocks/tip/height")
+    }
+
+    fn genesis_hash_url(&self) -> Url {
+        self.url("block-height/0")
+    }
+
+    fn raw_block_by_hash_url(&self, block_hash: &BlockHash) -> Url {
+        self.url(&format!("block/{}/raw", block_hash))
+    }
+
+    fn mempool_url(&self) -> Url {
+        self.url("mempool/txids")
+    }
+
+    fn raw_transaction_by_id_url(&self, txid: &Txid) -> Url {
+        self.url(&format!("tx/{}/hex", txid))
+    }
+
+    fn address_transactions_url(&self, address: &Address) -> Url {
+        self.url(&format!("address/{}/txs", address))
+    }
+
+    fn outspend_url(&self, outpoint: &OutPoint) -> Url {
+        self.url(&format!("tx/{}/outspend/{}", outpoint.txid, outpoint.vout))
+    }
+
+    async fn get_text(&self, url: Url) -> anyhow::Result<String> {
+        let text = self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        Ok(text)
+    }
+
+    /// Returns `None` if Esplora does not know about the transaction.
+    async fn transaction(&self, txid: Txid) -> anyhow::Result<Option<Transaction>> {
+        let response = self
+            .client
+            .get(self.raw_transaction_by_id_url(&txid))
+            .send()
+            .await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let transaction = decode_response(response.error_for_status()?.text().await?)?;
+
+        Ok(Some(transaction))
+    }
+
+    /// The confirmed transactions that involve `address`, as sender or
+    /// recipient.
+    async fn confirmed_transaction_ids(&self, address: &Address) -> anyhow::Result<Vec<Txid>> {
+        let transactions = self
+            .client
+            .get(self.address_transactions_url(address))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Vec<AddressTransaction>>()
+            .await?;
+
+        Ok(transactions
+            .into_iter()
+            .filter(|transaction| transaction.status.confirmed)
+            .map(|transaction| transaction.txid)
+            .collect())
+    }
+
+    /// The confirmed transaction that spends `outpoint`, if any.
+    async fn confirmed_spending_transaction_id(
+        &self,
+        outpoint: &OutPoint,
+    ) -> anyhow::Result<Option<Txid>> {
+        let outspend = self
+            .client
+            .get(self.outspend_url(outpoint))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Outspend>()
+            .await?;
+
+        match outspend {
+            Outspend {
+                spent: true,
+                txid: Some(txid),
+                status: Some(Status { confirmed: true }),
+            } => Ok(Some(txid)),
+            _ => Ok(None),
+        }
+    }
+}
+
+impl LatestBlock for EsploraConnector {
+    type Block = bitcoin::Block;
+    type BlockHash = bitcoin::BlockHash;
+
+    fn latest_block(
+        &mut self,
+    ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
+        let this = self.clone();
+
+        let latest_block = async move {
+            let block_hash = this
+                .get_text(this.tip_hash_url())
+                .await?
+                .trim()
+                .parse::<BlockHash>()?;
+
+            let block = this.block_by_hash(block_hash).compat().await?;
+
+            Ok(block)
+        };
+
+        Box::new(latest_block.boxed().compat())
+    }
+}
+
+impl BlockByHash for EsploraConnector {
+    type Block = bitcoin::Block;
+    type BlockHash = bitcoin::BlockHash;
+
+    fn block_by_hash(
+        &self,
+        block_hash: Self::BlockHash,
+    ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
+        let url = self.raw_block_by_hash_url(&block_hash);
+        let client = self.client.clone();
+
+        let block = async move {
+            let bytes = client
+                .get(url)
+                .send()
+                .await?
+                .error_for_status()?
+                .bytes()
+                .await?;
+            let block: Self::Block = deserialize(&bytes)?;
+            tracing::debug!(
+                "Fetched block {} with {} transactions from esplora",
+                block_hash,
+                block.txdata.len()
+            );
+
+            Ok(block)
+        }
+        .boxed()
+        .compat();
+
+        Box::new(block)
+    }
+}
+
+impl PendingTransactions for EsploraConnector {
+    type Transaction = Transaction;
+    type TransactionId = Txid;
+
+    fn pending_transaction_ids(
+        &self,
+    ) -> Box<dyn Future<Item = Vec<Self::TransactionId>, Error = anyhow::Error> + Send + 'static>
+    {
+        let url = self.mempool_url();
+        let client = self.client.clone();
+
+        let txids = async move {
+            let txids = client
+                .get(url)
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<Vec<Txid>>()
+                .await?;
+
+            Ok(txids)
+        }
+        .boxed()
+        .compat();
+
+        Box::new(txids)
+    }
+
+    fn pending_transaction(
+        &self,
+        txid: Self::TransactionId,
+    ) -> Box<dyn Future<Item = Option<Self::Transaction>, Error = anyhow::Error> + Send + 'static>
+    {
+        let this = self.clone();
+
+        let transaction = async move { this.transaction(txid).await }.boxed().compat();
+
+        Box::new(transaction)
+    }
+}
+
+impl MatchingTransactions for EsploraConnector {
+    fn matching_transactions(
+        &self,
+        pattern: TransactionPattern,
+    ) -> Box<dyn Future<Item = Option<Vec<Transaction>>, Error = anyhow::Error> + Send + 'static>
+    {
+        let this = self.clone();
+
+        let transactions = async move {
+            let txids = match (&pattern.from_outpoint, &pattern.to_address) {
+                (Some(outpoint), _) => this
+                    .confirmed_spending_transaction_id(outpoint)
+                    .await?
+                    .into_iter()
+                    .collect(),
+                (None, Some(address)) => this.confirmed_transaction_ids(address).await?,
+                // Esplora does not index unlock scripts.
+                (None, None) => return Ok(None),
+            };
+
+            let mut transactions = Vec::new();
+            for txid in txids {
+                match this.transaction(txid).await? {
+                    Some(transaction) if pattern.matches(&transaction) => {
+                        transactions.push(transaction)
+                    }
+                    _ => {}
+                }
+            }
+
+            tracing::debug!(
+                "Found {} transactions matching {:?} through esplora",
+                transactions.len(),
+                pattern
+            );
+
+            Ok(Some(transactions))
+        }
+        .boxed()
+        .compat();
+
+        Box::new(transactions)
+    }
+}
+
+impl BlockFilterByHash for EsploraConnector {
+    fn block_filter_by_hash(
+        &self,
+        _block_hash: BlockHash,
+    ) -> Box<
+        dyn Future<Item = Option<(BlockHeader, BlockFilter)>, Error = anyhow::Error>
+            + Send
+            + 'static,
+    > {
+        // Esplora does not serve block filters.
+        Box::new(futures::future::ok(None))
+    }
+}
+
+impl NodeStatus for EsploraConnector {
+    type ChainId = Network;
+
+    fn chain_id(
+        &self,
+    ) -> Box<dyn Future<Item = Self::ChainId, Error = anyhow::Error> + Send + 'static> {
+        let this = self.clone();
+
+        let network = async move {
+            // Esplora does not tell which network it follows, its genesis block
+            // does.
+            let genesis_hash = this
+                .get_text(this.genesis_hash_url())
+                .await?
+                .trim()
+                .parse::<BlockHash>()?;
+
+            vec![Network::Bitcoin, Network::Testnet, Network::Regtest]
+                .into_iter()
+                .find(|network| genesis_block(*network).bitcoin_hash() == genesis_hash)
+                .ok_or_else(|| anyhow!("unknown genesis block {}", genesis_hash))
+        }
+        .boxed()
+        .compat();
+
+        Box::new(network)
+    }
+
+    fn block_height(&self) -> Box<dyn Future<Item = u64, Error = anyhow::Error> + Send + 'static> {
+        let this = self.clone();
+
+        let block_height = async move {
+            let block_height = this
+                .get_text(this.tip_height_url())
+                .await?
+                .trim()
+                .parse::<u64>()?;
+
+            Ok(block_height)
+        }
+        .boxed()
+        .compat();
+
+        Box::new(block_height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base_urls() -> Vec<Url> {
+        vec![
+            "http://localhost:3002/api".parse().unwrap(),
+            "http://localhost:3002/api/".parse().unwrap(),
+        ]
+    }
+
+    #[test]
+    fn given_different_base_urls_correct_sub_urls_are_built() {
+        for base_url in base_urls() {
+            let connector = EsploraConnector::new(base_url);
+
+            assert_eq!(
+                connector.tip_hash_url(),
+                Url::parse("http://localhost:3002/api/blocks/tip/hash").unwrap()
+            );
+
+            let block_hash: BlockHash =
+                "2a593b84b1943521be01f97a59fc7feba30e7e8527fb2ba20b0158ca09016d02"
+                    .parse()
+                    .unwrap();
+            assert_eq!(connector.raw_block_by_hash_url(&block_hash), Url::parse("http://localhost:3002/api/block/2a593b84b1943521be01f97a59fc7feba30e7e8527fb2ba20b0158ca09016d02/raw").unwrap());
+
+            let address: Address = "2MubReUTptB6isbuFmsRiN3BPHaeHpiAjQM".parse().unwrap();
+            assert_eq!(
+                connector.address_transactions_url(&address),
+                Url::parse(
+                    "http://localhost:3002/api/address/2MubReUTptB6isbuFmsRiN3BPHaeHpiAjQM/txs"
+                )
+                .unwrap()
+            );
+
+            let outpoint = OutPoint {
+                txid: "2a593b84b1943521be01f97a59fc7feba30e7e8527fb2ba20b0158ca09016d02"
+                    .parse()
+                    .unwrap(),
+                vout: 1,
+            };
+            assert_eq!(connector.outspend_url(&outpoint), Url::parse("http://localhost:3002/api/tx/2a593b84b1943521be01f97a59fc7feba30e7e8527fb2ba20b0158ca09016d02/outspend/1").unwrap());
+        }
+    }
+}
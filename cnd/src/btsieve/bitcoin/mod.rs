@@ -1,131 +1,195 @@
+mod backend;
 mod bitcoind_connector;
+mod bitcoind_rpc_connector;
 mod cache;
+mod esplora_connector;
+mod rpc_client;
 mod transaction_ext;
 mod transaction_pattern;
+mod zmq_subscriber;
 
 pub use self::{
-    bitcoind_connector::BitcoindConnector, cache::Cache, transaction_ext::TransactionExt,
+    backend::BitcoinBackend,
+    bitcoind_connector::BitcoindConnector,
+    bitcoind_rpc_connector::BitcoindRpcConnector,
+    cache::Cache,
+    esplora_connector::EsploraConnector,
+    rpc_client::{BitcoindRpcClient, RpcAuth, RpcError},
+    transaction_ext::TransactionExt,
     transaction_pattern::TransactionPattern,
+    zmq_subscriber::{parse_notification, subscribe_zmq_blocks, BlockNotification, NewBlocks},
 };
-use crate::btsieve::{BlockByHash, LatestBlock, Predates};
+use crate::btsieve::{BlockByHash, LatestBlock, LinkedBlock, Predates, Scanner};
 use bitcoin::{
     consensus::{encode::deserialize, Decodable},
-    BitcoinHash,
+    util::bip158::BlockFilter,
+    BitcoinHash, BlockHash, BlockHeader, Network,
 };
 use chrono::NaiveDateTime;
+use futures::Future;
 use futures_core::compat::Future01CompatExt;
 use reqwest::{Client, Url};
-use std::collections::HashSet;
+use serde::Deserialize;
+
+/// Looks up the transactions matching a pattern in an index of the chain
+/// instead of scanning blocks, e.g. the address and outpoint index of Esplora.
+///
+/// Only transactions that are included in a block are returned. Resolves to
+/// `None` if the connector cannot answer the pattern from an index.
+pub trait MatchingTransactions: Send + Sync + 'static {
+    fn matching_transactions(
+        &self,
+        pattern: TransactionPattern,
+    ) -> Box<
+        dyn Future<Item = Option<Vec<bitcoin::Transaction>>, Error = anyhow::Error>
+            + Send
+            + 'static,
+    >;
+}
 
-pub async fn matching_transaction<C>(
-    mut blockchain_connector: C,
-    pattern: TransactionPattern,
-    start_of_swap: NaiveDateTime,
-) -> anyhow::Result<bitcoin::Transaction>
+/// Serves the BIP158 basic filter of a block along with its header, so that
+/// blocks can be skipped without downloading them.
+///
+/// Resolves to `None` if the node does not serve filters, e.g. because bitcoind
+/// was started without `-blockfilterindex`.
+pub trait BlockFilterByHash: Send + Sync + 'static {
+    fn block_filter_by_hash(
+        &self,
+        block_hash: bitcoin::BlockHash,
+    ) -> Box<
+        dyn Future<Item = Option<(BlockHeader, BlockFilter)>, Error = anyhow::Error>
+            + Send
+            + 'static,
+    >;
+}
+
+impl<C> Scanner<C, bitcoin::Block>
 where
     C: LatestBlock<Block = bitcoin::Block>
         + BlockByHash<Block = bitcoin::Block, BlockHash = bitcoin::BlockHash>
+        + MatchingTransactions
+        + BlockFilterByHash
         + Clone,
 {
-    // Verify that we can successfully addPeer to the blockchain connector and check
-    // if the transaction is in the latest block.
-    let latest_block = blockchain_connector.latest_block().compat().await?;
-    if let Some(transaction) = check_block_against_pattern(&latest_block.clone(), &pattern) {
-        return Ok(transaction.clone());
-    };
-
-    // We didn't find the transaction, now we need to do two things; keep polling
-    // for latest block so that we see transactions in new blocks and also go
-    // back up the blockchain until 'start_of_swap' i.e., look back in the
-    // past.
-
-    let mut prev_blockhashes: HashSet<bitcoin::BlockHash> = HashSet::new();
-    let mut missing_block_futures: Vec<_> = Vec::new();
-
-    let mut oldest_block: Option<bitcoin::Block> = Some(latest_block.clone());
-    prev_blockhashes.insert(latest_block.bitcoin_hash());
-
-    let prev_blockhash = latest_block.header.prev_blockhash;
-    let future = blockchain_connector.block_by_hash(prev_blockhash).compat();
-    missing_block_futures.push((future, prev_blockhash));
-
-    loop {
-        // Delay so that we don't overload the CPU in the event that
-        // latest_block() and block_by_hash() resolve quickly.
-
-        tokio::time::delay_for(std::time::Duration::from_secs(1)).await;
-
-        let mut new_missing_block_futures = Vec::new();
-        for (block_future, blockhash) in missing_block_futures.into_iter() {
-            match block_future.await {
-                Ok(block) => {
-                    match check_block_against_pattern(&block, &pattern) {
-                        Some(transaction) => return Ok(transaction.clone()),
-                        None => {
-                            let prev_blockhash = block.header.prev_blockhash;
-                            let unknown_parent = prev_blockhashes.insert(prev_blockhash);
-
-                            if unknown_parent {
-                                let future =
-                                    blockchain_connector.block_by_hash(prev_blockhash).compat();
-                                new_missing_block_futures.push((future, prev_blockhash));
-                            }
-                        }
-                    };
+    /// Watches for a transaction matching `pattern` since the start of the
+    /// swap, served from the blocks of the scanner. The history is looked up
+    /// in the index of the connector, unless it does not have one. Otherwise
+    /// we only download the blocks whose filter matches the pattern, if the
+    /// node serves filters.
+    pub async fn matching_transaction(
+        &self,
+        pattern: TransactionPattern,
+        start_of_swap: NaiveDateTime,
+    ) -> anyhow::Result<bitcoin::Transaction> {
+        let matcher = {
+            let pattern = pattern.clone();
+            move |block: &bitcoin::Block| check_block_against_pattern(block, &pattern).cloned()
+        };
+
+        // Register before asking the index so that we do not miss the blocks
+        // mined in the meantime.
+        let mut watch = self.watch(None, matcher.clone());
+
+        match self
+            .connector()
+            .matching_transactions(pattern.clone())
+            .compat()
+            .await?
+        {
+            Some(transactions) => {
+                if let Some(transaction) = transactions.into_iter().next() {
+                    return Ok(transaction);
                 }
-                Err(e) => {
-                    tracing::warn!("Could not get block with hash {}: {}", blockhash, e);
-
-                    let future = blockchain_connector.block_by_hash(blockhash).compat();
-                    new_missing_block_futures.push((future, blockhash));
+            }
+            None => {
+                let mut connector = self.connector().clone();
+                match scan_filtered_history(&mut connector, &pattern, start_of_swap).await? {
+                    FilterScan::Found(transaction) => return Ok(transaction),
+                    FilterScan::NotFound => {}
+                    FilterScan::Unsupported => {
+                        tracing::debug!(
+                            "connector has no index or filters for {:?}, scanning blocks",
+                            pattern
+                        );
+                        watch = self.watch(Some(start_of_swap), matcher);
+                    }
                 }
-            };
-        }
-        missing_block_futures = new_missing_block_futures;
-
-        // Look back into the past (upto timestamp) for one block.
-
-        if let Some(block) = oldest_block.as_ref() {
-            if !block.predates(start_of_swap) {
-                match blockchain_connector
-                    .block_by_hash(block.header.prev_blockhash)
-                    .compat()
-                    .await
-                {
-                    Ok(block) => match check_block_against_pattern(&block, &pattern) {
-                        Some(transaction) => return Ok(transaction.clone()),
-                        None => {
-                            oldest_block.replace(block);
-                        }
-                    },
-                    Err(e) => tracing::warn!(
-                        "Could not get block with hash {}: {}",
-                        block.bitcoin_hash(),
-                        e
-                    ),
-                };
             }
         }
 
-        // Check if a new block has been mined.
+        watch.next().await
+    }
+}
+
+/// The outcome of looking for a transaction in the history with the help of
+/// block filters.
+enum FilterScan {
+    Found(bitcoin::Transaction),
+    NotFound,
+    /// The pattern or the node do not allow us to filter blocks, they have to
+    /// be scanned in full.
+    Unsupported,
+}
 
-        if let Ok(latest_block) = blockchain_connector.latest_block().compat().await {
-            // If we can insert then we have not seen this block.
-            if prev_blockhashes.insert(latest_block.bitcoin_hash()) {
-                if let Some(transaction) = check_block_against_pattern(&latest_block, &pattern) {
-                    return Ok(transaction.clone());
-                };
+/// Walks back from the latest block to the last one that predates the start
+/// of the swap. Only the blocks whose BIP158 filter matches the pattern are
+/// downloaded, the filters of all other blocks tell us they do not contain a
+/// matching transaction.
+async fn scan_filtered_history<C>(
+    connector: &mut C,
+    pattern: &TransactionPattern,
+    start_of_swap: NaiveDateTime,
+) -> anyhow::Result<FilterScan>
+where
+    C: LatestBlock<Block = bitcoin::Block>
+        + BlockByHash<Block = bitcoin::Block, BlockHash = bitcoin::BlockHash>
+        + BlockFilterByHash,
+{
+    let scripts = match pattern.filter_scripts() {
+        Some(scripts) => scripts,
+        None => return Ok(FilterScan::Unsupported),
+    };
 
-                // In case we missed a block somehow, check this blocks parent.
-                if !prev_blockhashes.contains(&latest_block.header.prev_blockhash) {
-                    let prev_blockhash = latest_block.header.prev_blockhash;
-                    let future = blockchain_connector.block_by_hash(prev_blockhash).compat();
+    let latest_block = connector.latest_block().compat().await?;
+    if let Some(transaction) = check_block_against_pattern(&latest_block, pattern) {
+        return Ok(FilterScan::Found(transaction.clone()));
+    }
 
-                    missing_block_futures.push((future, prev_blockhash));
-                }
+    let mut block_hash = latest_block.header.prev_blockhash;
+    let mut predates_swap = latest_block.predates(start_of_swap);
+    let mut downloaded_blocks = 0;
+
+    // Block timestamps are set by miners and are therefore not exact, to be
+    // on the safe side we also look at the last block that predates the swap.
+    while !predates_swap {
+        let (header, filter) = match connector.block_filter_by_hash(block_hash).compat().await? {
+            Some(header_and_filter) => header_and_filter,
+            None => return Ok(FilterScan::Unsupported),
+        };
+
+        let mut query = scripts.iter().map(|script| script.as_bytes());
+        if filter.match_any(&block_hash, &mut query)? {
+            let block = connector.block_by_hash(block_hash).compat().await?;
+            downloaded_blocks += 1;
+
+            // Filters have false positives, the block may not contain a
+            // matching transaction after all.
+            if let Some(transaction) = check_block_against_pattern(&block, pattern) {
+                return Ok(FilterScan::Found(transaction.clone()));
             }
         }
+
+        predates_swap = header.predates(start_of_swap);
+        block_hash = header.prev_blockhash;
     }
+
+    tracing::debug!(
+        "no transaction in the history matches {:?}, downloaded {} blocks whose filter matched",
+        pattern,
+        downloaded_blocks
+    );
+
+    Ok(FilterScan::NotFound)
 }
 
 fn check_block_against_pattern<'b>(
@@ -168,20 +232,62 @@ pub enum Error {
     Deserialization(#[from] bitcoin::consensus::encode::Error),
 }
 
+/// What bitcoind tells about the chain it follows, through `chaininfo.json`
+/// of the REST interface or `getblockchaininfo`.
+#[derive(Deserialize)]
+struct ChainInfo {
+    bestblockhash: BlockHash,
+    chain: String,
+    blocks: u64,
+}
+
+impl ChainInfo {
+    fn network(&self) -> Result<Network, Error> {
+        match self.chain.as_str() {
+            "main" => Ok(Network::Bitcoin),
+            "test" => Ok(Network::Testnet),
+            "regtest" => Ok(Network::Regtest),
+            chain => Err(Error::UnsupportedNetwork(chain.to_owned())),
+        }
+    }
+}
+
 pub fn decode_response<T: Decodable>(response_text: String) -> Result<T, Error> {
     let bytes = hex::decode(response_text.trim()).map_err(Error::Hex)?;
     deserialize(bytes.as_slice()).map_err(Error::Deserialization)
 }
 
 impl Predates for bitcoin::Block {
+    fn predates(&self, timestamp: NaiveDateTime) -> bool {
+        self.header.predates(timestamp)
+    }
+}
+
+impl Predates for BlockHeader {
     fn predates(&self, timestamp: NaiveDateTime) -> bool {
         let unix_timestamp = timestamp.timestamp();
-        let block_time = self.header.time as i64;
+        let block_time = self.time as i64;
 
         block_time < unix_timestamp
     }
 }
 
+impl LinkedBlock for bitcoin::Block {
+    type Hash = bitcoin::BlockHash;
+
+    fn block_hash(&self) -> Option<Self::Hash> {
+        Some(self.bitcoin_hash())
+    }
+
+    fn parent_hash(&self) -> Self::Hash {
+        self.header.prev_blockhash
+    }
+
+    fn block_time(&self) -> NaiveDateTime {
+        NaiveDateTime::from_timestamp(i64::from(self.header.time), 0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
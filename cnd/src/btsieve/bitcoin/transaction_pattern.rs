@@ -1,5 +1,5 @@
 use crate::btsieve::bitcoin::transaction_ext::TransactionExt;
-use ::bitcoin::{Address, OutPoint, Transaction};
+use ::bitcoin::{Address, OutPoint, Script, Transaction};
 
 #[derive(Clone, Default, Eq, PartialEq, serde::Serialize, serdebug::SerDebug)]
 /// If the field is set to Some(foo) then only transactions matching foo are
@@ -10,6 +10,11 @@ pub struct TransactionPattern {
     pub to_address: Option<Address>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub from_outpoint: Option<OutPoint>,
+    /// The script of the output at `from_outpoint`. A transaction cannot be
+    /// matched against it, but it tells us which blocks may contain a
+    /// matching transaction from their BIP158 filter.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_outpoint_script: Option<Script>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub unlock_script: Option<Vec<Vec<u8>>>,
 }
@@ -23,6 +28,7 @@ impl TransactionPattern {
                 to_address,
                 from_outpoint,
                 unlock_script,
+                ..
             } => {
                 if let Some(to_address) = to_address {
                     if !transaction.spends_to(to_address) {
@@ -53,6 +59,20 @@ impl TransactionPattern {
             }
         }
     }
+
+    /// The scripts to look for in the BIP158 basic filter of a block. The
+    /// filter of a block that contains a matching transaction matches any of
+    /// them.
+    ///
+    /// `None` if the pattern does not tell which scripts a matching
+    /// transaction pays to or spends from.
+    pub fn filter_scripts(&self) -> Option<Vec<Script>> {
+        match (&self.to_address, &self.from_outpoint_script) {
+            (Some(to_address), _) => Some(vec![to_address.script_pubkey()]),
+            (None, Some(from_outpoint_script)) => Some(vec![from_outpoint_script.clone()]),
+            (None, None) => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -90,6 +110,7 @@ mod tests {
         let pattern = TransactionPattern {
             to_address: Some("329XTScM6cJgu8VZvaqYWpfuxT1eQDSJkP".parse().unwrap()),
             from_outpoint: None,
+            from_outpoint_script: None,
             unlock_script: None,
         };
 
@@ -108,6 +129,7 @@ mod tests {
         let pattern = TransactionPattern {
             to_address: None,
             from_outpoint: None,
+            from_outpoint_script: None,
             unlock_script: Some(unlock_script),
         };
 
@@ -124,6 +146,7 @@ mod tests {
         let pattern = TransactionPattern {
             to_address: None,
             from_outpoint: None,
+            from_outpoint_script: None,
             unlock_script: Some(unlock_script),
         };
 
@@ -146,10 +169,35 @@ mod tests {
         let pattern = TransactionPattern {
             to_address: None,
             from_outpoint: Some(outpoint),
+            from_outpoint_script: None,
             unlock_script: Some(unlock_script),
         };
 
         let result = pattern.matches(&tx);
         assert_that(&result).is_true();
     }
+
+    #[test]
+    fn given_a_spend_pattern_without_its_script_then_blocks_cannot_be_filtered() {
+        let address: Address = "329XTScM6cJgu8VZvaqYWpfuxT1eQDSJkP".parse().unwrap();
+        let outpoint = create_outpoint(
+            "ad067ee417ee5518122374307d1fa494c67e30c75d38c7061d944b59e56fe024",
+            1u32,
+        );
+
+        let spend_pattern = TransactionPattern {
+            to_address: None,
+            from_outpoint: Some(outpoint),
+            from_outpoint_script: Some(address.script_pubkey()),
+            unlock_script: Some(vec![vec![1u8]]),
+        };
+        let unlock_script_pattern = TransactionPattern {
+            from_outpoint_script: None,
+            ..spend_pattern.clone()
+        };
+
+        assert_that(&spend_pattern.filter_scripts())
+            .is_equal_to(Some(vec![address.script_pubkey()]));
+        assert_that(&unlock_script_pattern.filter_scripts()).is_none();
+    }
 }
@@ -0,0 +1,149 @@
+//! Learning about new blocks through the ZMQ notifications of bitcoind.
+//!
+//! bitcoind publishes a multipart message `[topic, body, sequence]` for every
+//! new block: `rawblock` carries the serialized block, `hashblock` its hash.
+//! The messages are received on a dedicated thread and published to all
+//! watchers through a `watch` channel.
+
+use crate::btsieve::Notification;
+use bitcoin::{consensus::encode::deserialize, hashes::Hash, util::hash::BitcoinHash, BlockHash};
+use tokio::sync::watch;
+
+const RAW_BLOCK: &[u8] = b"rawblock";
+const HASH_BLOCK: &[u8] = b"hashblock";
+
+/// The latest block announced by bitcoind, `None` while there are no
+/// notifications.
+pub type NewBlocks = watch::Receiver<Option<Notification<BlockNotification>>>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BlockNotification {
+    Raw(bitcoin::Block),
+    Hash(BlockHash),
+}
+
+impl BlockNotification {
+    pub fn block_hash(&self) -> BlockHash {
+        match self {
+            BlockNotification::Raw(block) => block.bitcoin_hash(),
+            BlockNotification::Hash(block_hash) => *block_hash,
+        }
+    }
+}
+
+/// Subscribes to the `rawblock` and `hashblock` notifications bitcoind
+/// publishes on `endpoint`.
+///
+/// ZMQ reconnects on its own if bitcoind goes away. Should the socket fail
+/// nonetheless, the receiver goes back to `None` and watchers poll the REST
+/// interface instead.
+pub fn subscribe_zmq_blocks(endpoint: &str) -> anyhow::Result<NewBlocks> {
+    let context = zmq::Context::new();
+    let socket = context.socket(zmq::SUB)?;
+    socket.connect(endpoint)?;
+    socket.set_subscribe(RAW_BLOCK)?;
+    socket.set_subscribe(HASH_BLOCK)?;
+
+    let (sender, receiver) = watch::channel(None);
+
+    let endpoint = endpoint.to_owned();
+    std::thread::Builder::new()
+        .name("bitcoind-zmq".to_owned())
+        .spawn(move || receive_notifications(&endpoint, socket, sender))?;
+
+    Ok(receiver)
+}
+
+fn receive_notifications(
+    endpoint: &str,
+    socket: zmq::Socket,
+    sender: watch::Sender<Option<Notification<BlockNotification>>>,
+) {
+    tracing::info!("subscribed to bitcoind block notifications on {}", endpoint);
+
+    loop {
+        let message = match socket.recv_multipart(0) {
+            Ok(message) => message,
+            Err(e) => {
+                tracing::warn!("receiving notification from {} failed: {}", endpoint, e);
+                let _ = sender.broadcast(None);
+                return;
+            }
+        };
+
+        let notification = match parse_notification(&message) {
+            Ok(Some(notification)) => notification,
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::warn!("ignoring malformed notification from {}: {:?}", endpoint, e);
+                continue;
+            }
+        };
+
+        tracing::trace!("bitcoind announced block {}", notification.block_hash());
+
+        if sender
+            .broadcast(Some(Notification::new(notification)))
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
+/// Parses a ZMQ message of bitcoind, messages of topics other than `rawblock`
+/// and `hashblock` yield `None`.
+pub fn parse_notification(message: &[Vec<u8>]) -> anyhow::Result<Option<BlockNotification>> {
+    let (topic, body) = match (message.get(0), message.get(1)) {
+        (Some(topic), Some(body)) => (topic.as_slice(), body.as_slice()),
+        _ => anyhow::bail!("expected a multipart message of topic and body"),
+    };
+
+    if topic == RAW_BLOCK {
+        let block = deserialize(body)?;
+
+        Ok(Some(BlockNotification::Raw(block)))
+    } else if topic == HASH_BLOCK {
+        // The hash is published in the byte order it is displayed in, which is
+        // the reverse of the internal one.
+        let mut bytes = body.to_vec();
+        bytes.reverse();
+        let block_hash = BlockHash::from_slice(&bytes)?;
+
+        Ok(Some(BlockNotification::Hash(block_hash)))
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spectral::prelude::*;
+
+    #[test]
+    fn hashblock_is_parsed_in_display_byte_order() {
+        let block_hash = "2a593b84b1943521be01f97a59fc7feba30e7e8527fb2ba20b0158ca09016d02";
+        let message = vec![HASH_BLOCK.to_vec(), hex::decode(block_hash).unwrap(), vec![
+            0, 0, 0, 0,
+        ]];
+
+        let notified_block_hash = parse_notification(&message)
+            .unwrap()
+            .map(|notification| notification.block_hash());
+
+        let block_hash: BlockHash = block_hash.parse().unwrap();
+        assert_that(&notified_block_hash)
+            .is_some()
+            .is_equal_to(&block_hash);
+    }
+
+    #[test]
+    fn other_topics_are_ignored() {
+        let message = vec![b"rawtx".to_vec(), vec![1, 2, 3], vec![0, 0, 0, 0]];
+
+        let notification = parse_notification(&message).unwrap();
+
+        assert_that(&notification).is_none();
+    }
+}
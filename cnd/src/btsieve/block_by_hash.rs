@@ -13,13 +13,15 @@ macro_rules! impl_block_by_hash {
             ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
                 let connector = self.connector.clone();
                 let cache = Arc::clone(&self.block_cache);
-                Box::new(Box::pin(block_by_hash(connector, cache, block_hash)).compat())
+                let disk_cache = self.disk_block_cache.clone();
+                Box::new(Box::pin(block_by_hash(connector, cache, disk_cache, block_hash)).compat())
             }
         }
 
         async fn block_by_hash<C>(
             connector: C,
             cache: Arc<Mutex<LruCache<Hash, Block>>>,
+            disk_cache: Option<Arc<DiskCache<Hash, Block>>>,
             block_hash: Hash,
         ) -> anyhow::Result<Block>
         where
@@ -30,13 +32,24 @@ macro_rules! impl_block_by_hash {
                 return Ok(block.clone());
             }
 
+            if let Some(disk_cache) = &disk_cache {
+                if let Some(block) = Arc::clone(disk_cache).load(block_hash).await {
+                    tracing::trace!("Found block in disk cache: {:x}", block_hash);
+                    cache.lock().await.put(block_hash, block.clone());
+                    return Ok(block);
+                }
+            }
+
             let block = connector.block_by_hash(block_hash.clone()).compat().await?;
             tracing::trace!("Fetched block from connector: {:x}", block_hash);
 
             // We dropped the lock so at this stage the block may have been inserted by
             // another thread, no worries, inserting the same block twice does not hurt.
-            let mut guard = cache.lock().await;
-            guard.put(block_hash, block.clone());
+            cache.lock().await.put(block_hash, block.clone());
+
+            if let Some(disk_cache) = disk_cache {
+                disk_cache.store(block_hash, block.clone()).await;
+            }
 
             Ok(block)
         }
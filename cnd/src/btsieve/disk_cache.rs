@@ -0,0 +1,308 @@
+//! Keeps blocks and receipts fetched from a node in the data directory, so
+//! they don't have to be fetched again after a restart.
+
+use anyhow::Context;
+use lru::LruCache;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fmt, fs,
+    hash::Hash,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// A value that can be stored in a `DiskCache`.
+pub trait Persist: Sized {
+    /// Returns `None` for values that must not outlive the process, e.g. the
+    /// answer of a node that did not know about a block yet.
+    fn to_bytes(&self) -> Option<Vec<u8>>;
+
+    fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self>;
+}
+
+/// A key of a `DiskCache`, each entry is stored in a file named after its key.
+pub trait CacheKey: Hash + Eq + Clone {
+    fn to_file_name(&self) -> String;
+
+    fn from_file_name(file_name: &str) -> Option<Self>;
+}
+
+impl<T> Persist for Option<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    fn to_bytes(&self) -> Option<Vec<u8>> {
+        self.as_ref()
+            .and_then(|value| serde_json::to_vec(value).ok())
+    }
+
+    fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(Some(serde_json::from_slice(bytes)?))
+    }
+}
+
+/// A cache that stores one file per entry in a directory.
+///
+/// Once the files take up more than `max_size` bytes, the least recently used
+/// entries are removed. The entries found in the directory on startup are
+/// ordered by the time they were written.
+///
+/// The cache is best effort: failing to read or write an entry is logged and
+/// treated as a cache miss.
+///
+/// `get` and `put` block on the file system, async code uses `load` and
+/// `store` instead.
+pub struct DiskCache<K: CacheKey, V> {
+    dir: PathBuf,
+    max_size: u64,
+    entries: Mutex<Entries<K>>,
+    value: PhantomData<fn() -> V>,
+}
+
+impl<K: CacheKey, V> fmt::Debug for DiskCache<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DiskCache")
+            .field("dir", &self.dir)
+            .field("max_size", &self.max_size)
+            .finish()
+    }
+}
+
+/// The size of each entry in bytes, in the order they were used.
+struct Entries<K: CacheKey> {
+    sizes: LruCache<K, u64>,
+    total_size: u64,
+}
+
+impl<K: CacheKey, V: Persist> DiskCache<K, V> {
+    pub fn open(dir: impl Into<PathBuf>, max_size: u64) -> anyhow::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("failed to create cache directory {}", dir.display()))?;
+
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if !metadata.is_file() {
+                continue;
+            }
+
+            let key = match entry.file_name().to_str().and_then(K::from_file_name) {
+                Some(key) => key,
+                None => continue,
+            };
+
+            files.push((metadata.modified()?, key, metadata.len()));
+        }
+        files.sort_by_key(|(modified, ..)| *modified);
+
+        let mut entries = Entries {
+            sizes: LruCache::unbounded(),
+            total_size: 0,
+        };
+        for (_, key, size) in files {
+            entries.sizes.put(key, size);
+            entries.total_size += size;
+        }
+
+        let cache = Self {
+            dir,
+            max_size,
+            entries: Mutex::new(entries),
+            value: PhantomData,
+        };
+        cache.evict(&mut cache.entries.lock().expect("not poisoned"));
+
+        Ok(cache)
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        if self
+            .entries
+            .lock()
+            .expect("not poisoned")
+            .sizes
+            .get(key)
+            .is_none()
+        {
+            return None;
+        }
+
+        let path = self.path(key);
+        let value = fs::read(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| V::from_bytes(&bytes));
+
+        match value {
+            Ok(value) => Some(value),
+            Err(e) => {
+                tracing::warn!("failed to read {} from disk cache: {:?}", path.display(), e);
+                self.remove(key);
+                None
+            }
+        }
+    }
+
+    pub fn put(&self, key: K, value: &V) {
+        let bytes = match value.to_bytes() {
+            Some(bytes) => bytes,
+            None => return,
+        };
+
+        let path = self.path(&key);
+        if let Err(e) = write_atomically(&path, &bytes) {
+            tracing::warn!("failed to write {} to disk cache: {:?}", path.display(), e);
+            return;
+        }
+
+        let mut entries = self.entries.lock().expect("not poisoned");
+        if let Some(previous_size) = entries.sizes.put(key, bytes.len() as u64) {
+            entries.total_size -= previous_size;
+        }
+        entries.total_size += bytes.len() as u64;
+        self.evict(&mut entries);
+    }
+
+    fn remove(&self, key: &K) {
+        let mut entries = self.entries.lock().expect("not poisoned");
+        if let Some(size) = entries.sizes.pop(key) {
+            entries.total_size -= size;
+        }
+        let _ = fs::remove_file(self.path(key));
+    }
+
+    fn evict(&self, entries: &mut Entries<K>) {
+        while entries.total_size > self.max_size {
+            let (key, size) = match entries.sizes.pop_lru() {
+                Some(entry) => entry,
+                None => return,
+            };
+            entries.total_size -= size;
+
+            let path = self.path(&key);
+            if let Err(e) = fs::remove_file(&path) {
+                tracing::warn!(
+                    "failed to evict {} from disk cache: {:?}",
+                    path.display(),
+                    e
+                );
+            }
+        }
+    }
+
+    fn path(&self, key: &K) -> PathBuf {
+        self.dir.join(key.to_file_name())
+    }
+}
+
+impl<K, V> DiskCache<K, V>
+where
+    K: CacheKey + Send + Sync + 'static,
+    V: Persist + Send + 'static,
+{
+    /// Reads the entry on the thread pool for blocking operations.
+    pub async fn load(self: Arc<Self>, key: K) -> Option<V> {
+        match tokio::task::spawn_blocking(move || self.get(&key)).await {
+            Ok(value) => value,
+            Err(e) => {
+                tracing::warn!("failed to read from disk cache: {:?}", e);
+                None
+            }
+        }
+    }
+
+    /// Writes the entry on the thread pool for blocking operations.
+    pub async fn store(self: Arc<Self>, key: K, value: V) {
+        if let Err(e) = tokio::task::spawn_blocking(move || self.put(key, &value)).await {
+            tracing::warn!("failed to write to disk cache: {:?}", e);
+        }
+    }
+}
+
+/// Readers never see a partially written entry, even if we crash while
+/// writing it.
+fn write_atomically(path: &Path, bytes: &[u8]) -> anyhow::Result<()> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, bytes)?;
+    fs::rename(&tmp, path)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ethereum::H256;
+    use spectral::prelude::*;
+
+    type Cache = DiskCache<H256, Option<String>>;
+
+    fn value(size: usize) -> Option<String> {
+        // The JSON encoding adds two quotes.
+        Some("a".repeat(size - 2))
+    }
+
+    #[test]
+    fn entries_survive_reopening_the_cache() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let cache = Cache::open(dir.path(), 100).unwrap();
+        cache.put(H256::repeat_byte(1), &value(10));
+        cache.put(H256::repeat_byte(2), &None);
+        drop(cache);
+
+        let cache = Cache::open(dir.path(), 100).unwrap();
+        assert_that(&cache.get(&H256::repeat_byte(1)))
+            .is_some()
+            .is_equal_to(value(10));
+        assert_that(&cache.get(&H256::repeat_byte(2))).is_none();
+    }
+
+    #[test]
+    fn least_recently_used_entries_are_evicted() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::open(dir.path(), 100).unwrap();
+
+        cache.put(H256::repeat_byte(1), &value(40));
+        cache.put(H256::repeat_byte(2), &value(40));
+        let _ = cache.get(&H256::repeat_byte(1));
+        cache.put(H256::repeat_byte(3), &value(40));
+
+        assert_that(&cache.get(&H256::repeat_byte(1))).is_some();
+        assert_that(&cache.get(&H256::repeat_byte(2))).is_none();
+        assert_that(&cache.get(&H256::repeat_byte(3))).is_some();
+        assert_that(&fs::read_dir(dir.path()).unwrap().count()).is_equal_to(2);
+    }
+
+    #[test]
+    fn oversized_directory_is_trimmed_on_open() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let cache = Cache::open(dir.path(), 100).unwrap();
+        cache.put(H256::repeat_byte(1), &value(40));
+        cache.put(H256::repeat_byte(2), &value(40));
+        drop(cache);
+
+        let _cache = Cache::open(dir.path(), 50).unwrap();
+
+        assert_that(&fs::read_dir(dir.path()).unwrap().count()).is_equal_to(1);
+    }
+
+    #[test]
+    fn entries_are_stored_and_loaded_off_the_runtime() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Arc::new(Cache::open(dir.path(), 100).unwrap());
+
+        let loaded = tokio::runtime::Runtime::new().unwrap().block_on(async {
+            Arc::clone(&cache)
+                .store(H256::repeat_byte(1), value(10))
+                .await;
+
+            cache.load(H256::repeat_byte(1)).await
+        });
+
+        assert_that(&loaded).is_some().is_equal_to(value(10));
+    }
+}
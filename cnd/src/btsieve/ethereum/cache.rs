@@ -1,9 +1,10 @@
 use crate::{
     btsieve::{
-        ethereum::{self, Hash},
-        BlockByHash, LatestBlock, ReceiptByHash,
+        disk_cache::{CacheKey, DiskCache},
+        ethereum::{self, Event, Hash},
+        BlockByHash, BlockByNumber, LatestBlock, LogsInRange, ReceiptByHash,
     },
-    ethereum::TransactionReceipt,
+    ethereum::{Log, TransactionReceipt},
 };
 use derivative::Derivative;
 use futures::Future;
@@ -27,6 +28,10 @@ pub struct Cache<C> {
     pub block_cache: Arc<Mutex<LruCache<Hash, Block>>>,
     #[derivative(Debug = "ignore")]
     pub receipt_cache: Arc<Mutex<LruCache<Hash, Option<TransactionReceipt>>>>,
+    #[derivative(Debug = "ignore")]
+    pub disk_block_cache: Option<Arc<DiskCache<Hash, Block>>>,
+    #[derivative(Debug = "ignore")]
+    pub disk_receipt_cache: Option<Arc<DiskCache<Hash, Option<TransactionReceipt>>>>,
 }
 
 impl<C> Cache<C> {
@@ -41,6 +46,21 @@ impl<C> Cache<C> {
             connector,
             block_cache,
             receipt_cache,
+            disk_block_cache: None,
+            disk_receipt_cache: None,
+        }
+    }
+
+    /// Keeps the fetched blocks and receipts on disk in addition to memory.
+    pub fn with_disk_cache(
+        self,
+        disk_block_cache: DiskCache<Hash, Block>,
+        disk_receipt_cache: DiskCache<Hash, Option<TransactionReceipt>>,
+    ) -> Self {
+        Cache {
+            disk_block_cache: Some(Arc::new(disk_block_cache)),
+            disk_receipt_cache: Some(Arc::new(disk_receipt_cache)),
+            ..self
         }
     }
 }
@@ -56,16 +76,30 @@ where
         &mut self,
     ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
         let cache = self.block_cache.clone();
+        let disk_cache = self.disk_block_cache.clone();
         let mut connector = self.connector.clone();
 
         let future = async move {
             let block = connector.latest_block().compat().await?;
 
-            if let Some(block) = block.clone() {
-                let block_hash = block.hash.expect("no blocks without hash");
-                let mut guard = cache.lock().await;
-                if !guard.contains(&block_hash) {
-                    guard.put(block_hash, Some(block.clone()));
+            if let Some(block_hash) = block
+                .as_ref()
+                .map(|block| block.hash.expect("no blocks without hash"))
+            {
+                let is_new = {
+                    let mut guard = cache.lock().await;
+                    let is_new = !guard.contains(&block_hash);
+                    if is_new {
+                        guard.put(block_hash, block.clone());
+                    }
+
+                    is_new
+                };
+
+                // The lock is released while we write to the disk.
+                match disk_cache {
+                    Some(disk_cache) if is_new => disk_cache.store(block_hash, block.clone()).await,
+                    _ => {}
                 }
             };
 
@@ -76,10 +110,52 @@ where
 
         Box::new(future)
     }
+
+    fn wait_for_new_block(
+        &self,
+    ) -> Box<dyn Future<Item = (), Error = anyhow::Error> + Send + 'static> {
+        self.connector.wait_for_new_block()
+    }
 }
 
 impl_block_by_hash!();
 
+impl<C> BlockByNumber for Cache<C>
+where
+    C: BlockByNumber<Block = Block, BlockNumber = u64> + Clone,
+{
+    type Block = Block;
+    type BlockNumber = u64;
+
+    // Blocks are cached by hash, the block at a given number changes with every
+    // re-organisation, hence we always ask the connector.
+    fn block_by_number(
+        &self,
+        block_number: Self::BlockNumber,
+    ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
+        self.connector.block_by_number(block_number)
+    }
+}
+
+impl<C> LogsInRange for Cache<C>
+where
+    C: LogsInRange<Filter = Event, Log = Log, BlockNumber = u64> + Clone,
+{
+    type Filter = Event;
+    type Log = Log;
+    type BlockNumber = u64;
+
+    fn logs_in_range(
+        &self,
+        filter: Self::Filter,
+        from_block: Self::BlockNumber,
+        to_block: Self::BlockNumber,
+    ) -> Box<dyn Future<Item = Option<Vec<Self::Log>>, Error = anyhow::Error> + Send + 'static>
+    {
+        self.connector.logs_in_range(filter, from_block, to_block)
+    }
+}
+
 impl<C> ReceiptByHash for Cache<C>
 where
     C: ReceiptByHash<Receipt = Option<TransactionReceipt>, TransactionHash = Hash> + Clone,
@@ -93,13 +169,23 @@ where
     ) -> Box<dyn Future<Item = Self::Receipt, Error = anyhow::Error> + Send + 'static> {
         let connector = self.connector.clone();
         let cache = Arc::clone(&self.receipt_cache);
-        Box::new(Box::pin(receipt_by_hash(connector, cache, transaction_hash)).compat())
+        let disk_cache = self.disk_receipt_cache.clone();
+        Box::new(
+            Box::pin(receipt_by_hash(
+                connector,
+                cache,
+                disk_cache,
+                transaction_hash,
+            ))
+            .compat(),
+        )
     }
 }
 
 async fn receipt_by_hash<C>(
     connector: C,
     cache: Arc<Mutex<LruCache<Hash, Option<TransactionReceipt>>>>,
+    disk_cache: Option<Arc<DiskCache<Hash, Option<TransactionReceipt>>>>,
     transaction_hash: Hash,
 ) -> anyhow::Result<Option<TransactionReceipt>>
 where
@@ -110,6 +196,14 @@ where
         return Ok(receipt.clone());
     }
 
+    if let Some(disk_cache) = &disk_cache {
+        if let Some(receipt) = Arc::clone(disk_cache).load(transaction_hash).await {
+            tracing::trace!("Found receipt in disk cache: {:x}", transaction_hash);
+            cache.lock().await.put(transaction_hash, receipt.clone());
+            return Ok(receipt);
+        }
+    }
+
     let receipt = connector
         .receipt_by_hash(transaction_hash.clone())
         .compat()
@@ -119,8 +213,21 @@ where
 
     // We dropped the lock so at this stage the receipt may have been inserted by
     // another thread, no worries, inserting the same receipt twice does not hurt.
-    let mut guard = cache.lock().await;
-    guard.put(transaction_hash, receipt.clone());
+    cache.lock().await.put(transaction_hash, receipt.clone());
+
+    if let Some(disk_cache) = disk_cache {
+        disk_cache.store(transaction_hash, receipt.clone()).await;
+    }
 
     Ok(receipt)
 }
+
+impl CacheKey for Hash {
+    fn to_file_name(&self) -> String {
+        format!("{:x}", self)
+    }
+
+    fn from_file_name(file_name: &str) -> Option<Self> {
+        file_name.parse().ok()
+    }
+}
@@ -0,0 +1,47 @@
+//! JSON-RPC over the IPC socket of an Ethereum node, as served by geth and
+//! Parity on the same host.
+
+use anyhow::{anyhow, Context};
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::Path;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::UnixStream,
+};
+
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Sends `request` over a new connection to the socket at `path` and reads
+/// the response.
+///
+/// Nodes neither delimit nor prefix their responses with a length, we read
+/// until the bytes received so far form a complete JSON value.
+pub async fn send<Req, Res>(path: &Path, request: &Req) -> anyhow::Result<Res>
+where
+    Req: Serialize,
+    Res: DeserializeOwned,
+{
+    let mut stream = UnixStream::connect(path)
+        .await
+        .with_context(|| format!("failed to connect to {}", path.display()))?;
+    stream.write_all(&serde_json::to_vec(request)?).await?;
+
+    let mut response = Vec::new();
+    let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+    loop {
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            return Err(anyhow!(
+                "{} closed the connection before responding",
+                path.display()
+            ));
+        }
+        response.extend_from_slice(&buffer[..read]);
+
+        match serde_json::from_slice::<serde_json::Value>(&response) {
+            Ok(response) => return Ok(serde_json::from_value(response)?),
+            Err(e) if e.is_eof() => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
@@ -1,70 +1,299 @@
 mod cache;
+mod ipc;
+mod new_heads;
 mod web3_connector;
 
-pub use self::{cache::Cache, web3_connector::Web3Connector};
+pub use self::{
+    cache::Cache,
+    new_heads::{subscribe_new_heads, NewHeads},
+    web3_connector::Web3Connector,
+};
 use crate::{
-    btsieve::{BlockByHash, LatestBlock, Predates, ReceiptByHash},
+    btsieve::{
+        BlockByHash, BlockByNumber, LatestBlock, LinkedBlock, LogsInRange, LogsQueryTooLarge,
+        Predates, ReceiptByHash, Scanner,
+    },
     ethereum::{Address, Bytes, IsStatusOk, Log, Transaction, TransactionReceipt, H256, U256},
-    Never,
 };
 use anyhow;
 use chrono::NaiveDateTime;
 use ethbloom::Input;
 use futures_core::compat::Future01CompatExt;
-use genawaiter::{
-    sync::{Co, Gen},
-    GeneratorState,
-};
-use std::collections::HashSet;
 
 type Hash = H256;
 type Block = crate::ethereum::Block<Transaction>;
 
-pub async fn watch_for_contract_creation<C>(
-    blockchain_connector: C,
-    start_of_swap: NaiveDateTime,
-    bytecode: Bytes,
-) -> anyhow::Result<(Transaction, Address)>
+/// The number of blocks covered by a single `eth_getLogs` query. Nodes limit
+/// the size of a response, hence we do not ask for the whole history at once.
+/// If a node refuses a query nevertheless, the range is halved until it
+/// answers.
+const LOGS_QUERY_RANGE: u64 = 5_000;
+
+impl<C> Scanner<C, Block>
 where
     C: LatestBlock<Block = Option<Block>>
         + BlockByHash<Block = Option<Block>, BlockHash = Hash>
+        + BlockByNumber<Block = Option<Block>, BlockNumber = u64>
+        + LogsInRange<Filter = Event, Log = Log, BlockNumber = u64>
         + ReceiptByHash<Receipt = Option<TransactionReceipt>, TransactionHash = Hash>
         + Clone,
 {
-    let (transaction, receipt) =
-        matching_transaction_and_receipt(blockchain_connector, start_of_swap, |transaction| {
-            // transaction.to address is None if, and only if, the transaction
-            // creates a contract.
-            transaction.to.is_none() && transaction.input == bytecode
-        })
-        .await?;
+    /// Watches for the successful transaction that deploys `bytecode` since
+    /// the start of the swap, served from the blocks of the scanner.
+    pub async fn watch_for_contract_creation(
+        &self,
+        start_of_swap: NaiveDateTime,
+        bytecode: Bytes,
+    ) -> anyhow::Result<(Transaction, Address)> {
+        let mut watch = self.watch(Some(start_of_swap), move |block: &Block| {
+            let candidates = block
+                .transactions
+                .iter()
+                .filter(|transaction| transaction.to.is_none() && transaction.input == bytecode)
+                .cloned()
+                .collect::<Vec<_>>();
+
+            if candidates.is_empty() {
+                None
+            } else {
+                Some(candidates)
+            }
+        });
+
+        loop {
+            for transaction in watch.next().await? {
+                let receipt = fetch_receipt(self.connector().clone(), transaction.hash).await?;
+                if !receipt.is_status_ok() {
+                    tracing::warn!(
+                        "transaction matched {:x} but status was NOT OK",
+                        transaction.hash,
+                    );
+                    continue;
+                }
+                tracing::trace!("transaction matched {:x}", transaction.hash);
+
+                return match receipt.contract_address {
+                    Some(location) => Ok((transaction, location)),
+                    None => Err(anyhow::anyhow!("contract address missing from receipt")),
+                };
+            }
+        }
+    }
+
+    /// Watches for a log matching `event` that was emitted since the start of
+    /// the swap. New blocks are served from the scanner, the history is
+    /// searched with `eth_getLogs` queries unless the node cannot answer
+    /// them, in which case the blocks of the history are scanned as well.
+    pub async fn watch_for_event(
+        &self,
+        start_of_swap: NaiveDateTime,
+        event: Event,
+    ) -> anyhow::Result<(Transaction, Log)> {
+        let matcher = {
+            let topics = event.topics.clone();
+            move |block: &Block| {
+                if bloom_may_contain(block, &topics) {
+                    Some(block.clone())
+                } else {
+                    None
+                }
+            }
+        };
+
+        // Register before searching the history so that we do not miss the
+        // blocks mined in the meantime.
+        let mut watch = self.watch(None, matcher.clone());
+
+        let mut connector = self.connector().clone();
+        let latest_block = latest_block_number(&mut connector).await?;
+        let from_block = first_block_not_predating(&connector, start_of_swap, latest_block)
+            .await?
+            .saturating_sub(1);
+
+        match search_logs(&connector, &event, from_block, latest_block).await? {
+            LogSearch::Found(transaction, log) => return Ok((transaction, log)),
+            LogSearch::NotFound => {}
+            LogSearch::Unsupported => {
+                tracing::info!(
+                    "node does not support log queries, scanning blocks for {:?}",
+                    event
+                );
+                watch = self.watch(Some(start_of_swap), matcher);
+            }
+        }
+
+        loop {
+            let block = watch.next().await?;
 
-    match receipt.contract_address {
-        Some(location) => Ok((transaction, location)),
-        None => Err(anyhow::anyhow!("contract address missing from receipt")),
+            if let Some(found) = event_in_block(&connector, &event, block).await? {
+                return Ok(found);
+            }
+        }
     }
 }
 
-pub async fn watch_for_event<C>(
-    blockchain_connector: C,
-    start_of_swap: NaiveDateTime,
-    event: Event,
-) -> anyhow::Result<(Transaction, Log)>
+/// The outcome of searching a range of blocks with `eth_getLogs` queries.
+enum LogSearch {
+    Found(Transaction, Log),
+    NotFound,
+    /// The node refused a query, the log has to be found by other means.
+    Unsupported,
+}
+
+async fn search_logs<C>(
+    connector: &C,
+    event: &Event,
+    from_block: u64,
+    to_block: u64,
+) -> anyhow::Result<LogSearch>
 where
-    C: LatestBlock<Block = Option<Block>>
-        + BlockByHash<Block = Option<Block>, BlockHash = Hash>
+    C: BlockByHash<Block = Option<Block>, BlockHash = Hash>
+        + LogsInRange<Filter = Event, Log = Log, BlockNumber = u64>,
+{
+    let mut from = from_block;
+    let mut range = LOGS_QUERY_RANGE;
+
+    while from <= to_block {
+        let to = std::cmp::min(from + range - 1, to_block);
+
+        let logs = match connector
+            .logs_in_range(event.clone(), from, to)
+            .compat()
+            .await
+        {
+            Ok(Some(logs)) => logs,
+            Ok(None) => return Ok(LogSearch::Unsupported),
+            Err(e) if to > from && e.downcast_ref::<LogsQueryTooLarge>().is_some() => {
+                range = (to - from + 1) / 2;
+                tracing::debug!("{}, querying {} blocks at once instead", e, range);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        // Logs are only emitted by successful transactions, hence there is
+        // no need to check the status of the receipt.
+        if let Some(log) = logs
+            .into_iter()
+            .find(|log| log.removed != Some(true) && log_matches_event(event, log))
+        {
+            let transaction = transaction_of_log(connector, &log).await?;
+            tracing::trace!("transaction matched {:x}", transaction.hash);
+
+            return Ok(LogSearch::Found(transaction, log));
+        }
+
+        from = to + 1;
+    }
+
+    Ok(LogSearch::NotFound)
+}
+
+/// Finds the log matching `event` in `block`, with a log query if the node
+/// supports them and by looking at the receipts of all transactions
+/// otherwise.
+async fn event_in_block<C>(
+    connector: &C,
+    event: &Event,
+    block: Block,
+) -> anyhow::Result<Option<(Transaction, Log)>>
+where
+    C: BlockByHash<Block = Option<Block>, BlockHash = Hash>
+        + LogsInRange<Filter = Event, Log = Log, BlockNumber = u64>
         + ReceiptByHash<Receipt = Option<TransactionReceipt>, TransactionHash = Hash>
         + Clone,
 {
-    matching_transaction_and_log(
-        blockchain_connector.clone(),
-        start_of_swap,
-        event.topics.clone(),
-        |receipt| find_log_for_event_in_receipt(&event, receipt),
-    )
+    if let Some(number) = block.number.map(|number| number.as_u64()) {
+        match search_logs(connector, event, number, number).await? {
+            LogSearch::Found(transaction, log) => return Ok(Some((transaction, log))),
+            LogSearch::NotFound => return Ok(None),
+            LogSearch::Unsupported => {}
+        }
+    }
+
+    find_log_in_receipts(connector.clone(), block, |receipt| {
+        find_log_for_event_in_receipt(event, receipt)
+    })
     .await
 }
 
+async fn latest_block_number<C>(connector: &mut C) -> anyhow::Result<u64>
+where
+    C: LatestBlock<Block = Option<Block>>,
+{
+    let block = connector
+        .latest_block()
+        .compat()
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Connector returned null latest block"))?;
+
+    block
+        .number
+        .map(|number| number.as_u64())
+        .ok_or_else(|| anyhow::anyhow!("Connector returned latest block without number"))
+}
+
+/// Binary searches for the number of the first block that does not predate
+/// `timestamp`. If all blocks up to `latest_block` predate it, `latest_block`
+/// is returned.
+async fn first_block_not_predating<C>(
+    connector: &C,
+    timestamp: NaiveDateTime,
+    latest_block: u64,
+) -> anyhow::Result<u64>
+where
+    C: BlockByNumber<Block = Option<Block>, BlockNumber = u64>,
+{
+    let (mut low, mut high) = (0, latest_block);
+
+    while low < high {
+        let middle = low + (high - low) / 2;
+        let block = connector
+            .block_by_number(middle)
+            .compat()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Could not fetch block with number {}", middle))?;
+
+        if block.predates(timestamp) {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+
+    Ok(low)
+}
+
+async fn transaction_of_log<C>(connector: &C, log: &Log) -> anyhow::Result<Transaction>
+where
+    C: BlockByHash<Block = Option<Block>, BlockHash = Hash>,
+{
+    let block_hash = log
+        .block_hash
+        .ok_or_else(|| anyhow::anyhow!("log without block hash"))?;
+    let transaction_hash = log
+        .transaction_hash
+        .ok_or_else(|| anyhow::anyhow!("log without transaction hash"))?;
+
+    let block = connector
+        .block_by_hash(block_hash)
+        .compat()
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Could not fetch block with hash {}", block_hash))?;
+
+    block
+        .transactions
+        .into_iter()
+        .find(|transaction| transaction.hash == transaction_hash)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "block {:x} does not contain transaction {:x}",
+                block_hash,
+                transaction_hash
+            )
+        })
+}
+
 /// Fetch receipt from connector using transaction hash.
 async fn fetch_receipt<C>(blockchain_connector: C, hash: Hash) -> anyhow::Result<TransactionReceipt>
 where
@@ -84,9 +313,16 @@ where
 }
 
 fn find_log_for_event_in_receipt(event: &Event, receipt: TransactionReceipt) -> Option<Log> {
+    receipt
+        .logs
+        .into_iter()
+        .find(|log| log_matches_event(event, log))
+}
+
+fn log_matches_event(event: &Event, log: &Log) -> bool {
     match event {
-        Event { topics, .. } if topics.is_empty() => None,
-        Event { address, topics } => receipt.logs.into_iter().find(|log| {
+        Event { topics, .. } if topics.is_empty() => false,
+        Event { address, topics } => {
             if address != &log.address {
                 return false;
             }
@@ -99,264 +335,73 @@ fn find_log_for_event_in_receipt(event: &Event, receipt: TransactionReceipt) ->
                 let topic = &topics[index];
                 topic.as_ref().map_or(true, |topic| tx_topic == &topic.0)
             })
-        }),
+        }
     }
 }
 
-pub async fn matching_transaction_and_receipt<C, F>(
-    connector: C,
-    start_of_swap: NaiveDateTime,
-    matcher: F,
-) -> anyhow::Result<(Transaction, TransactionReceipt)>
-where
-    C: LatestBlock<Block = Option<Block>>
-        + BlockByHash<Block = Option<Block>, BlockHash = Hash>
-        + ReceiptByHash<Receipt = Option<TransactionReceipt>, TransactionHash = Hash>
-        + Clone,
-    F: Fn(Transaction) -> bool,
-{
-    let mut block_generator = Gen::new({
-        let connector = connector.clone();
-        |co| async move { find_relevant_blocks(connector, &co, start_of_swap).await }
-    });
-
-    loop {
-        match block_generator.async_resume().await {
-            GeneratorState::Yielded(block) => {
-                for transaction in block.transactions.into_iter() {
-                    if matcher(transaction.clone()) {
-                        let receipt = fetch_receipt(connector.clone(), transaction.hash).await?;
-                        if !receipt.is_status_ok() {
-                            // This can be caused by a failed attempt to complete an action,
-                            // for example, sending a transaction with low gas.
-                            tracing::warn!(
-                                "transaction matched {:x} but status was NOT OK",
-                                transaction.hash,
-                            );
-                            continue;
-                        }
-                        tracing::trace!("transaction matched {:x}", transaction.hash,);
-                        return Ok((transaction, receipt));
-                    }
-                }
-            }
-            GeneratorState::Complete(Err(e)) => return Err(e),
-            // By matching against the never type explicitly, we assert that the `Ok` value of the
-            // result is actually the never type and has not been changed since this line was
-            // written. The never type can never be constructed, so we can never reach this line.
-            GeneratorState::Complete(Ok(never)) => match never {},
-        }
-    }
+/// Whether the bloom filter of `block` indicates that it may contain a log
+/// with the given topics.
+fn bloom_may_contain(block: &Block, topics: &[Option<Topic>]) -> bool {
+    topics.iter().all(|topic| {
+        topic.as_ref().map_or(true, |topic| {
+            block
+                .logs_bloom
+                .contains_input(Input::Raw(topic.0.as_ref()))
+        })
+    })
 }
 
-async fn matching_transaction_and_log<C, F>(
+async fn find_log_in_receipts<C, F>(
     connector: C,
-    start_of_swap: NaiveDateTime,
-    topics: Vec<Option<Topic>>,
+    block: Block,
     matcher: F,
-) -> anyhow::Result<(Transaction, Log)>
+) -> anyhow::Result<Option<(Transaction, Log)>>
 where
-    C: LatestBlock<Block = Option<Block>>
-        + BlockByHash<Block = Option<Block>, BlockHash = Hash>
-        + ReceiptByHash<Receipt = Option<TransactionReceipt>, TransactionHash = Hash>
-        + Clone,
+    C: ReceiptByHash<Receipt = Option<TransactionReceipt>, TransactionHash = Hash> + Clone,
     F: Fn(TransactionReceipt) -> Option<Log>,
 {
-    let mut block_generator = Gen::new({
-        let connector = connector.clone();
-        |co| async move { find_relevant_blocks(connector, &co, start_of_swap).await }
-    });
-
-    loop {
-        match block_generator.async_resume().await {
-            GeneratorState::Yielded(block) => {
-                let block_hash = block
-                    .hash
-                    .ok_or_else(|| anyhow::anyhow!("block without hash"))?;
-
-                let maybe_contains_transaction = topics.iter().all(|topic| {
-                    topic.as_ref().map_or(true, |topic| {
-                        block
-                            .logs_bloom
-                            .contains_input(Input::Raw(topic.0.as_ref()))
-                    })
-                });
-                if !maybe_contains_transaction {
-                    tracing::trace!(
-                        "bloom filter indicates that block does not contain transaction:
-                {:x}",
-                        block_hash,
-                    );
-                    continue;
-                }
-
-                tracing::trace!(
-                    "bloom filter indicates that we should check the block for transactions: {:x}",
-                    block_hash,
+    for transaction in block.transactions.into_iter() {
+        let receipt = fetch_receipt(connector.clone(), transaction.hash).await?;
+        let status_is_ok = receipt.is_status_ok();
+        if let Some(log) = matcher(receipt) {
+            if !status_is_ok {
+                // This can be caused by a failed attempt to complete an action,
+                // for example, sending a transaction with low gas.
+                tracing::warn!(
+                    "transaction matched {:x} but status was NOT OK",
+                    transaction.hash,
                 );
-                for transaction in block.transactions.into_iter() {
-                    let receipt = fetch_receipt(connector.clone(), transaction.hash).await?;
-                    let status_is_ok = receipt.is_status_ok();
-                    if let Some(log) = matcher(receipt) {
-                        if !status_is_ok {
-                            // This can be caused by a failed attempt to complete an action,
-                            // for example, sending a transaction with low gas.
-                            tracing::warn!(
-                                "transaction matched {:x} but status was NOT OK",
-                                transaction.hash,
-                            );
-                            continue;
-                        }
-                        tracing::trace!("transaction matched {:x}", transaction.hash,);
-                        return Ok((transaction, log));
-                    }
-                }
+                continue;
             }
-            GeneratorState::Complete(Err(e)) => return Err(e),
-            // By matching against the never type explicitly, we assert that the `Ok` value of the
-            // result is actually the never type and has not been changed since this line was
-            // written. The never type can never be constructed, so we can never reach this line.
-            GeneratorState::Complete(Ok(never)) => match never {},
+            tracing::trace!("transaction matched {:x}", transaction.hash,);
+            return Ok(Some((transaction, log)));
         }
     }
-}
-
-/// This function uses the `connector` to find blocks relevant to a swap.  To do
-/// this we must get the latest block, for each latest block we receive we must
-/// ensure that we saw its parent i.e., that we did not miss any blocks between
-/// this latest block and the previous latest block we received.  Finally, we
-/// must also get each block back until the time that the swap started i.e.,
-/// look into the past (in case any action occurred on chain while we were not
-/// watching).
-///
-/// It yields those blocks as part of the process.
-async fn find_relevant_blocks<C>(
-    mut connector: C,
-    co: &Co<Block>,
-    start_of_swap: NaiveDateTime,
-) -> anyhow::Result<Never>
-where
-    C: LatestBlock<Block = Option<Block>>
-        + BlockByHash<Block = Option<Block>, BlockHash = Hash>
-        + ReceiptByHash<Receipt = Option<TransactionReceipt>, TransactionHash = Hash>
-        + Clone,
-{
-    let mut seen_blocks: HashSet<Hash> = HashSet::new();
 
-    let block = connector
-        .latest_block()
-        .compat()
-        .await?
-        .ok_or_else(|| anyhow::anyhow!("Connector returned null latest block"))?;
-    co.yield_(block.clone()).await;
-
-    let blockhash = block
-        .hash
-        .ok_or_else(|| anyhow::anyhow!("Connector returned latest block with null hash"))?;
-    seen_blocks.insert(blockhash);
-
-    // Look back in time until we get a block that predates start_of_swap.
-    let parent_hash = block.parent_hash;
-    walk_back_until(
-        predates_start_of_swap(start_of_swap),
-        connector.clone(),
-        co,
-        parent_hash,
-    )
-    .await?;
-
-    loop {
-        let block = connector
-            .latest_block()
-            .compat()
-            .await?
-            .ok_or_else(|| anyhow::anyhow!("Connector returned null latest block"))?;
-        co.yield_(block.clone()).await;
-
-        let blockhash = block
-            .hash
-            .ok_or_else(|| anyhow::anyhow!("Connector returned latest block with null hash"))?;
-        seen_blocks.insert(blockhash);
-
-        // Look back along the blockchain for missing blocks.
-        let parent_hash = block.parent_hash;
-        if !seen_blocks.contains(&parent_hash) {
-            walk_back_until(
-                seen_block_or_predates_start_of_swap(seen_blocks.clone(), start_of_swap),
-                connector.clone(),
-                co,
-                parent_hash,
-            )
-            .await?;
-        }
-
-        // The duration of this timeout could/should depend on the network
-        tokio::time::delay_for(std::time::Duration::from_secs(1)).await;
-    }
+    Ok(None)
 }
 
-/// Walks the blockchain backwards from the given hash until the predicate given
-/// in `stop_condition` returns `true`.
-///
-/// This functions yields all blocks as part of its process.
-async fn walk_back_until<C, P>(
-    stop_condition: P,
-    connector: C,
-    co: &Co<Block>,
-    starting_blockhash: Hash,
-) -> anyhow::Result<()>
-where
-    C: BlockByHash<Block = Option<Block>, BlockHash = Hash>,
-    P: Fn(&Block) -> anyhow::Result<bool>,
-{
-    let mut blockhash = starting_blockhash;
-
-    loop {
-        let block = connector
-            .block_by_hash(blockhash)
-            .compat()
-            .await?
-            .ok_or_else(|| anyhow::anyhow!("Could not fetch block with hash {}", blockhash))?;
-
-        co.yield_(block.clone()).await;
+impl Predates for Block {
+    fn predates(&self, timestamp: NaiveDateTime) -> bool {
+        let unix_timestamp = timestamp.timestamp();
 
-        if stop_condition(&block)? {
-            return Ok(());
-        } else {
-            blockhash = block.parent_hash
-        }
+        self.timestamp < U256::from(unix_timestamp)
     }
 }
 
-/// Constructs a predicate that returns `true` if the given block predates the
-/// start_of_swap timestamp.
-fn predates_start_of_swap(start_of_swap: NaiveDateTime) -> impl Fn(&Block) -> anyhow::Result<bool> {
-    move |block| Ok(block.predates(start_of_swap))
-}
+impl LinkedBlock for Block {
+    type Hash = Hash;
 
-/// Constructs a predicate that returns `true` if we have seen the given block
-/// or the block predates the start_of_swap timestamp.
-fn seen_block_or_predates_start_of_swap(
-    seen_blocks: HashSet<Hash>,
-    start_of_swap: NaiveDateTime,
-) -> impl Fn(&Block) -> anyhow::Result<bool> {
-    move |block: &Block| {
-        let have_seen_block = seen_blocks.contains(
-            &block
-                .hash
-                .ok_or_else(|| anyhow::anyhow!("block without hash"))?,
-        );
-        let predates_start_of_swap = predates_start_of_swap(start_of_swap)(block)?;
-
-        Ok(have_seen_block || predates_start_of_swap)
+    fn block_hash(&self) -> Option<Self::Hash> {
+        self.hash
     }
-}
 
-impl Predates for Block {
-    fn predates(&self, timestamp: NaiveDateTime) -> bool {
-        let unix_timestamp = timestamp.timestamp();
+    fn parent_hash(&self) -> Self::Hash {
+        self.parent_hash
+    }
 
-        self.timestamp < U256::from(unix_timestamp)
+    fn block_time(&self) -> NaiveDateTime {
+        NaiveDateTime::from_timestamp(self.timestamp.low_u64() as i64, 0)
     }
 }
 
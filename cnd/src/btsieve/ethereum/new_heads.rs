@@ -0,0 +1,100 @@
+//! Learning about new blocks through a `newHeads` subscription.
+//!
+//! A single subscription per node is shared by all watchers: each new block
+//! is fetched once and published through a `watch` channel, watchers wake up
+//! on every new block instead of polling the node.
+
+use crate::{
+    btsieve::{ethereum::Web3Connector, BlockByHash, Notification},
+    ethereum::{Block, Transaction},
+};
+use futures_core::{
+    compat::{Future01CompatExt, Stream01CompatExt},
+    StreamExt,
+};
+use reqwest::Url;
+use std::{future::Future, time::Duration};
+use tokio::sync::watch;
+
+/// How long we wait before re-establishing a broken subscription.
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The latest block announced by the subscription, `None` while there is no
+/// subscription.
+pub type NewHeads = watch::Receiver<Option<Notification<Block<Transaction>>>>;
+
+/// Subscribes to `newHeads` on `ws_url`, blocks are fetched in full through
+/// `connector`.
+///
+/// The returned future keeps the subscription alive and has to be spawned. It
+/// resubscribes whenever the websocket connection breaks and only completes
+/// once all receivers are dropped.
+pub fn subscribe_new_heads(
+    ws_url: Url,
+    connector: Web3Connector,
+) -> (NewHeads, impl Future<Output = ()> + Send + 'static) {
+    let (sender, receiver) = watch::channel(None);
+
+    (receiver, keep_subscribed(ws_url, connector, sender))
+}
+
+async fn keep_subscribed(
+    ws_url: Url,
+    connector: Web3Connector,
+    sender: watch::Sender<Option<Notification<Block<Transaction>>>>,
+) {
+    loop {
+        if let Err(e) = forward_new_heads(&ws_url, &connector, &sender).await {
+            tracing::warn!("newHeads subscription on {} broke: {:?}", ws_url, e);
+        }
+
+        // Watchers fall back to polling until we are subscribed again.
+        if sender.broadcast(None).is_err() {
+            return;
+        }
+
+        tokio::time::delay_for(RECONNECT_INTERVAL).await;
+    }
+}
+
+async fn forward_new_heads(
+    ws_url: &Url,
+    connector: &Web3Connector,
+    sender: &watch::Sender<Option<Notification<Block<Transaction>>>>,
+) -> anyhow::Result<()> {
+    // The event loop of the transport stops when the handle is dropped.
+    let (_event_loop, transport) = web3::transports::WebSocket::new(ws_url.as_str())
+        .map_err(|e| anyhow::anyhow!("failed to connect to {}: {:?}", ws_url, e))?;
+
+    let mut new_heads = web3::Web3::new(transport)
+        .eth_subscribe()
+        .subscribe_new_heads()
+        .compat()
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to subscribe to newHeads: {:?}", e))?
+        .compat();
+
+    tracing::info!("subscribed to newHeads on {}", ws_url);
+
+    while let Some(header) = new_heads.next().await {
+        let header =
+            header.map_err(|e| anyhow::anyhow!("newHeads subscription failed: {:?}", e))?;
+        let block_hash = header
+            .hash
+            .ok_or_else(|| anyhow::anyhow!("newHeads announced a block without hash"))?;
+
+        let block = connector
+            .block_by_hash(block_hash)
+            .compat()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Could not fetch block with hash {}", block_hash))?;
+
+        tracing::trace!("new head {:x}", block_hash);
+
+        if sender.broadcast(Some(Notification::new(block))).is_err() {
+            return Ok(());
+        }
+    }
+
+    anyhow::bail!("subscription was closed by the node")
+}
@@ -1,25 +1,123 @@
 use crate::{
-    btsieve::{BlockByHash, LatestBlock, ReceiptByHash},
-    ethereum::{BlockId, BlockNumber},
+    btsieve::{
+        ethereum::{ipc, Event, NewHeads},
+        failover::NodeStatus,
+        wait_for_new_block_notification, BlockByHash, BlockByNumber, LatestBlock, LogsInRange,
+        LogsQueryTooLarge, Notification, PendingTransactions, ReceiptByHash,
+    },
+    ethereum::{Address, BlockId, BlockNumber, Bytes, Log, H256, U256},
+    swap_protocols::ledger::ethereum::ChainId,
 };
 use anyhow::Context;
 use futures::Future;
 use futures_core::{FutureExt, TryFutureExt};
 use reqwest::{Client, Url};
-use serde::Serialize;
-use std::sync::Arc;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{path::PathBuf, sync::Arc};
 
 #[derive(Clone, Debug)]
 pub struct Web3Connector {
-    web3: Arc<Client>,
-    url: Url,
+    transport: Transport,
+    new_heads: Option<NewHeads>,
+}
+
+/// How JSON-RPC requests reach the node.
+#[derive(Clone, Debug)]
+enum Transport {
+    Http {
+        client: Arc<Client>,
+        url: Url,
+    },
+    /// The IPC socket of a node on the same host, e.g. `geth.ipc`.
+    Ipc(PathBuf),
 }
 
 impl Web3Connector {
     pub fn new(node_url: Url) -> Self {
         Self {
-            web3: Arc::new(Client::new()),
-            url: node_url,
+            transport: Transport::Http {
+                client: Arc::new(Client::new()),
+                url: node_url,
+            },
+            new_heads: None,
+        }
+    }
+
+    /// Talks to the node through its IPC socket at `ipc_path` instead of
+    /// HTTP.
+    pub fn with_ipc_path(ipc_path: PathBuf) -> Self {
+        Self {
+            transport: Transport::Ipc(ipc_path),
+            new_heads: None,
+        }
+    }
+
+    /// Takes the latest block from `new_heads` instead of polling the node
+    /// while the subscription is up, see `Notification::fresh`.
+    pub fn with_new_heads(self, new_heads: NewHeads) -> Self {
+        Self {
+            new_heads: Some(new_heads),
+            ..self
+        }
+    }
+
+    /// Returns the number of transactions sent from `address`, including
+    /// pending ones, i.e. the nonce of the next transaction.
+    pub async fn transaction_count(&self, address: Address) -> anyhow::Result<U256> {
+        self.call("eth_getTransactionCount", vec![
+            serialize(address)?,
+            serialize(BlockNumber::Pending)?,
+        ])
+        .await
+    }
+
+    pub async fn gas_price(&self) -> anyhow::Result<U256> {
+        self.call("eth_gasPrice", Vec::new()).await
+    }
+
+    pub async fn send_raw_transaction(&self, transaction: Bytes) -> anyhow::Result<H256> {
+        self.call("eth_sendRawTransaction", vec![serialize(transaction)?])
+            .await
+    }
+
+    async fn call<R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Vec<serde_json::Value>,
+    ) -> anyhow::Result<R> {
+        let request = JsonRpcRequest::new(method, params);
+
+        let response: JsonRpcResponse<R> = self.send(&request).await?;
+
+        match response {
+            JsonRpcResponse::Success { result } => Ok(result),
+            JsonRpcResponse::Error { code, message } => Err(anyhow::anyhow!(
+                "{} request failed with {}: {}",
+                method,
+                code,
+                message
+            )),
+        }
+    }
+
+    async fn send<P, R>(&self, request: &JsonRpcRequest<P>) -> anyhow::Result<JsonRpcResponse<R>>
+    where
+        P: Serialize,
+        R: DeserializeOwned,
+    {
+        match &self.transport {
+            Transport::Http { client, url } => {
+                let response = client
+                    .post(url.clone())
+                    .json(request)
+                    .send()
+                    .await?
+                    .json::<JsonRpcResponse<R>>()
+                    .await?;
+
+                Ok(response)
+            }
+            Transport::Ipc(path) => ipc::send(path, request).await,
         }
     }
 }
@@ -31,8 +129,11 @@ impl LatestBlock for Web3Connector {
     fn latest_block(
         &mut self,
     ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
-        let web3 = self.web3.clone();
-        let url = self.url.clone();
+        if let Some(block) = Notification::fresh(self.new_heads.as_ref()) {
+            return Box::new(futures::future::ok(Some(block)));
+        }
+
+        let this = self.clone();
 
         let future = async move {
             let request = JsonRpcRequest::new("eth_getBlockByNumber", vec![
@@ -40,13 +141,8 @@ impl LatestBlock for Web3Connector {
                 serialize(true)?,
             ]);
 
-            let response = web3
-                .post(url)
-                .json(&request)
-                .send()
-                .await?
-                .json::<JsonRpcResponse<crate::ethereum::Block<crate::ethereum::Transaction>>>()
-                .await?;
+            let response: JsonRpcResponse<crate::ethereum::Block<crate::ethereum::Transaction>> =
+                this.send(&request).await?;
 
             let block = match response {
                 JsonRpcResponse::Success { result } => result,
@@ -72,6 +168,14 @@ impl LatestBlock for Web3Connector {
 
         Box::new(future)
     }
+
+    fn wait_for_new_block(
+        &self,
+    ) -> Box<dyn Future<Item = (), Error = anyhow::Error> + Send + 'static> {
+        wait_for_new_block_notification(self.new_heads.as_ref(), |notification| {
+            notification.block.hash
+        })
+    }
 }
 
 #[derive(serde::Serialize)]
@@ -108,8 +212,7 @@ impl BlockByHash for Web3Connector {
         &self,
         block_hash: Self::BlockHash,
     ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
-        let web3 = self.web3.clone();
-        let url = self.url.clone();
+        let this = self.clone();
 
         let future = async move {
             let request = JsonRpcRequest::new("eth_getBlockByHash", vec![
@@ -117,13 +220,8 @@ impl BlockByHash for Web3Connector {
                 serialize(true)?,
             ]);
 
-            let response = web3
-                .post(url)
-                .json(&request)
-                .send()
-                .await?
-                .json::<JsonRpcResponse<crate::ethereum::Block<crate::ethereum::Transaction>>>()
-                .await?;
+            let response: JsonRpcResponse<crate::ethereum::Block<crate::ethereum::Transaction>> =
+                this.send(&request).await?;
 
             let block = match response {
                 JsonRpcResponse::Success { result } => result,
@@ -148,6 +246,138 @@ impl BlockByHash for Web3Connector {
     }
 }
 
+impl BlockByNumber for Web3Connector {
+    type Block = Option<crate::ethereum::Block<crate::ethereum::Transaction>>;
+    type BlockNumber = u64;
+
+    fn block_by_number(
+        &self,
+        block_number: Self::BlockNumber,
+    ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
+        let this = self.clone();
+
+        let future = async move {
+            let request = JsonRpcRequest::new("eth_getBlockByNumber", vec![
+                serialize(quantity(block_number))?,
+                serialize(true)?,
+            ]);
+
+            let response: JsonRpcResponse<
+                Option<crate::ethereum::Block<crate::ethereum::Transaction>>,
+            > = this.send(&request).await?;
+
+            let block = match response {
+                JsonRpcResponse::Success { result } => result,
+                JsonRpcResponse::Error { code, message } => {
+                    tracing::warn!(
+                        "eth_getBlockByNumber request failed with {}: {}",
+                        code,
+                        message
+                    );
+                    return Ok(None);
+                }
+            };
+
+            tracing::trace!("Fetched block from web3: {}", block_number);
+
+            Ok(block)
+        }
+        .boxed()
+        .compat();
+
+        Box::new(future)
+    }
+}
+
+/// The JSON-RPC error codes of a node that does not know or support a method,
+/// see EIP-1474.
+const METHOD_NOT_FOUND: i64 = -32601;
+const METHOD_NOT_SUPPORTED: i64 = -32004;
+
+/// The JSON-RPC error code of a node that hit one of its limits, see EIP-1474.
+const LIMIT_EXCEEDED: i64 = -32005;
+
+/// Whether the node refused an `eth_getLogs` query because of its range or
+/// the size of its response.
+///
+/// Not every node uses `LIMIT_EXCEEDED` for this, hence we look at the
+/// message as well.
+fn is_logs_query_too_large(code: i64, message: &str) -> bool {
+    let message = message.to_lowercase();
+
+    code == LIMIT_EXCEEDED
+        || ["range", "more than", "too large", "too many", "exceed"]
+            .iter()
+            .any(|hint| message.contains(hint))
+}
+
+/// The filter object of `eth_getLogs`.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LogFilter {
+    from_block: String,
+    to_block: String,
+    #[serde(flatten)]
+    event: Event,
+}
+
+impl LogsInRange for Web3Connector {
+    type Filter = Event;
+    type Log = Log;
+    type BlockNumber = u64;
+
+    fn logs_in_range(
+        &self,
+        event: Self::Filter,
+        from_block: Self::BlockNumber,
+        to_block: Self::BlockNumber,
+    ) -> Box<dyn Future<Item = Option<Vec<Self::Log>>, Error = anyhow::Error> + Send + 'static>
+    {
+        let this = self.clone();
+
+        let future = async move {
+            let request = JsonRpcRequest::new("eth_getLogs", vec![serialize(LogFilter {
+                from_block: quantity(from_block),
+                to_block: quantity(to_block),
+                event,
+            })?]);
+
+            let response: JsonRpcResponse<Vec<Log>> = this.send(&request).await?;
+
+            let logs = match response {
+                JsonRpcResponse::Success { result } => result,
+                JsonRpcResponse::Error { code, message }
+                    if code == METHOD_NOT_FOUND || code == METHOD_NOT_SUPPORTED =>
+                {
+                    tracing::warn!("node does not support eth_getLogs: {}", message);
+                    return Ok(None);
+                }
+                JsonRpcResponse::Error { code, message }
+                    if is_logs_query_too_large(code, &message) =>
+                {
+                    return Err(LogsQueryTooLarge { message }.into());
+                }
+                JsonRpcResponse::Error { code, message } => {
+                    anyhow::bail!("eth_getLogs request failed with {}: {}", code, message)
+                }
+            };
+
+            tracing::trace!(
+                "Fetched {} logs from web3 for blocks {} to {}",
+                logs.len(),
+                from_block,
+                to_block
+            );
+
+            Ok(Some(logs))
+        }
+        .boxed()
+        .compat();
+
+        Box::new(future)
+    }
+}
+
 impl ReceiptByHash for Web3Connector {
     type Receipt = Option<crate::ethereum::TransactionReceipt>;
     type TransactionHash = crate::ethereum::H256;
@@ -156,21 +386,15 @@ impl ReceiptByHash for Web3Connector {
         &self,
         transaction_hash: Self::TransactionHash,
     ) -> Box<dyn Future<Item = Self::Receipt, Error = anyhow::Error> + Send + 'static> {
-        let web3 = self.web3.clone();
-        let url = self.url.clone();
+        let this = self.clone();
 
         let future = async move {
             let request = JsonRpcRequest::new("eth_getTransactionReceipt", vec![serialize(
                 transaction_hash,
             )?]);
 
-            let response = web3
-                .post(url)
-                .json(&request)
-                .send()
-                .await?
-                .json::<JsonRpcResponse<crate::ethereum::TransactionReceipt>>()
-                .await?;
+            let response: JsonRpcResponse<crate::ethereum::TransactionReceipt> =
+                this.send(&request).await?;
 
             let receipt = match response {
                 JsonRpcResponse::Success { result } => result,
@@ -195,8 +419,151 @@ impl ReceiptByHash for Web3Connector {
     }
 }
 
+impl PendingTransactions for Web3Connector {
+    type Transaction = crate::ethereum::Transaction;
+    type TransactionId = crate::ethereum::H256;
+
+    fn pending_transaction_ids(
+        &self,
+    ) -> Box<dyn Future<Item = Vec<Self::TransactionId>, Error = anyhow::Error> + Send + 'static>
+    {
+        let this = self.clone();
+
+        let future = async move {
+            let block = this
+                .call::<Option<crate::ethereum::Block<H256>>>("eth_getBlockByNumber", vec![
+                    serialize(BlockId::Number(BlockNumber::Pending))?,
+                    serialize(false)?,
+                ])
+                .await?;
+
+            Ok(block
+                .map(|block| block.transactions)
+                .unwrap_or_else(Vec::new))
+        }
+        .boxed()
+        .compat();
+
+        Box::new(future)
+    }
+
+    fn pending_transaction(
+        &self,
+        transaction_hash: Self::TransactionId,
+    ) -> Box<dyn Future<Item = Option<Self::Transaction>, Error = anyhow::Error> + Send + 'static>
+    {
+        let this = self.clone();
+
+        let future = async move {
+            this.call::<Option<crate::ethereum::Transaction>>("eth_getTransactionByHash", vec![
+                serialize(transaction_hash)?,
+            ])
+            .await
+        }
+        .boxed()
+        .compat();
+
+        Box::new(future)
+    }
+}
+
+impl NodeStatus for Web3Connector {
+    type ChainId = ChainId;
+
+    fn chain_id(
+        &self,
+    ) -> Box<dyn Future<Item = Self::ChainId, Error = anyhow::Error> + Send + 'static> {
+        let this = self.clone();
+
+        let future = async move {
+            let chain_id = this.call::<U256>("eth_chainId", Vec::new()).await?;
+
+            Ok(ChainId::from(chain_id.low_u32()))
+        }
+        .boxed()
+        .compat();
+
+        Box::new(future)
+    }
+
+    fn block_height(&self) -> Box<dyn Future<Item = u64, Error = anyhow::Error> + Send + 'static> {
+        let this = self.clone();
+
+        let future = async move {
+            let block_number = this.call::<U256>("eth_blockNumber", Vec::new()).await?;
+
+            Ok(block_number.low_u64())
+        }
+        .boxed()
+        .compat();
+
+        Box::new(future)
+    }
+}
+
+/// Encodes a number as a JSON-RPC quantity, i.e. as hex without leading
+/// zeros.
+fn quantity(number: u64) -> String {
+    format!("0x{:x}", number)
+}
+
 fn serialize<T: Serialize>(t: T) -> anyhow::Result<serde_json::Value> {
     let value = serde_json::to_value(t).context("failed to serialize parameter")?;
 
     Ok(value)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::btsieve::ethereum::Topic;
+    use spectral::prelude::*;
+
+    #[test]
+    fn log_filter_serializes_block_range_as_quantities() {
+        let filter = LogFilter {
+            from_block: quantity(0),
+            to_block: quantity(6_000_000),
+            event: Event {
+                address: "a3c1e324ca1ce40db73ed6026c4a177f099b5770".parse().unwrap(),
+                topics: vec![
+                    None,
+                    Some(Topic(
+                        "a64da754fccf55aa65a1f0128a648633fade3884b236e879ee9f64c78df5d5d7"
+                            .parse()
+                            .unwrap(),
+                    )),
+                ],
+            },
+        };
+
+        let json = serde_json::to_value(filter).unwrap();
+
+        assert_that(&json).is_equal_to(serde_json::json!({
+            "fromBlock": "0x0",
+            "toBlock": "0x5b8d80",
+            "address": "0xa3c1e324ca1ce40db73ed6026c4a177f099b5770",
+            "topics": [
+                null,
+                "0xa64da754fccf55aa65a1f0128a648633fade3884b236e879ee9f64c78df5d5d7"
+            ]
+        }));
+    }
+
+    #[test]
+    fn limits_of_common_nodes_are_recognized() {
+        assert!(is_logs_query_too_large(
+            -32005,
+            "query returned more than 10000 results"
+        ));
+        assert!(is_logs_query_too_large(
+            -32602,
+            "Log response size exceeded. You can make eth_getLogs requests with up to a 2K block range"
+        ));
+        assert!(is_logs_query_too_large(
+            -32000,
+            "exceed maximum block range: 5000"
+        ));
+        assert!(!is_logs_query_too_large(-32000, "header not found"));
+    }
+}
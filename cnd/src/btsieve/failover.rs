@@ -0,0 +1,562 @@
+//! Spreading the requests of a ledger over several nodes.
+//!
+//! A `Failover` wraps the connectors to an ordered list of node endpoints.
+//! Requests go to the first endpoint that is healthy and fall over to the
+//! next one if they fail. A background task regularly checks that every
+//! endpoint follows the expected chain and that its tip does not lag behind
+//! the others. Endpoints that follow another chain are never asked.
+
+use crate::btsieve::{
+    bitcoin::{BlockFilterByHash, MatchingTransactions, TransactionPattern},
+    BlockByHash, BlockByNumber, LatestBlock, LogsInRange, PendingTransactions, ReceiptByHash,
+};
+use ::bitcoin::{util::bip158::BlockFilter, BlockHash, BlockHeader, Transaction};
+use anyhow::anyhow;
+use derivative::Derivative;
+use futures::Future;
+use futures_core::{compat::Future01CompatExt, FutureExt, TryFutureExt};
+use reqwest::Url;
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Weak,
+    },
+    time::Duration,
+};
+
+/// How often the health of all endpoints is checked.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// What a `Failover` needs to know about a node to tell whether it can rely
+/// on it.
+pub trait NodeStatus: Send + Sync + 'static {
+    type ChainId: PartialEq + Debug + Send + Sync + 'static;
+
+    /// The network or chain id of the chain the node follows.
+    fn chain_id(
+        &self,
+    ) -> Box<dyn Future<Item = Self::ChainId, Error = anyhow::Error> + Send + 'static>;
+
+    /// The height of the latest block the node knows about.
+    fn block_height(&self) -> Box<dyn Future<Item = u64, Error = anyhow::Error> + Send + 'static>;
+}
+
+/// None of the endpoints of a ledger is known to follow the configured chain.
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+#[error("none of the node endpoints follows the configured chain")]
+pub struct NoEndpointOnChain;
+
+#[derive(Derivative)]
+#[derivative(Debug, Clone(bound = "C: Clone"))]
+pub struct Failover<C> {
+    endpoints: Arc<Vec<(Url, C)>>,
+    /// The result of the last health check of each endpoint.
+    #[derivative(Debug = "ignore")]
+    health: Arc<Vec<Health>>,
+}
+
+#[derive(Debug)]
+struct Health {
+    /// Whether the endpoint passed the last health check.
+    healthy: AtomicBool,
+    /// Whether the endpoint was seen following another chain. This is kept
+    /// while the endpoint is not reachable.
+    on_other_chain: AtomicBool,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self {
+            healthy: AtomicBool::new(true),
+            on_other_chain: AtomicBool::new(false),
+        }
+    }
+}
+
+impl<C> Failover<C>
+where
+    C: NodeStatus + Clone,
+{
+    /// The endpoints are preferred in the given order. Every endpoint counts
+    /// as healthy until the first health check says otherwise.
+    ///
+    /// The returned future checks the health of the endpoints until the
+    /// `Failover` is dropped, it has to be spawned. An endpoint is unhealthy
+    /// if it does not answer, follows a chain other than `chain_id` or its
+    /// tip is more than `max_lag` blocks behind the best one.
+    pub fn new(
+        endpoints: Vec<(Url, C)>,
+        chain_id: C::ChainId,
+        max_lag: u64,
+    ) -> anyhow::Result<(Self, impl std::future::Future<Output = ()> + Send + 'static)> {
+        if endpoints.is_empty() {
+            return Err(anyhow!("at least one node endpoint is required"));
+        }
+
+        let health = endpoints.iter().map(|_| Health::default()).collect();
+        let failover = Self {
+            endpoints: Arc::new(endpoints),
+            health: Arc::new(health),
+        };
+
+        let health_check = check_health(
+            Arc::clone(&failover.endpoints),
+            Arc::downgrade(&failover.health),
+            chain_id,
+            max_lag,
+        );
+
+        Ok((failover, health_check))
+    }
+}
+
+impl<C> Failover<C>
+where
+    C: Clone + Send + 'static,
+{
+    /// The endpoints in the order they are tried: healthy ones first, the
+    /// unhealthy ones are still tried as a last resort. Endpoints that follow
+    /// another chain are left out, their answers cannot be trusted.
+    fn candidates(&self) -> Result<Vec<(Url, C)>, NoEndpointOnChain> {
+        let (healthy, unhealthy): (Vec<_>, Vec<_>) = self
+            .endpoints
+            .iter()
+            .zip(self.health.iter())
+            .filter(|(_, health)| !health.on_other_chain.load(Ordering::SeqCst))
+            .partition(|(_, health)| health.healthy.load(Ordering::SeqCst));
+
+        let candidates: Vec<_> = healthy
+            .into_iter()
+            .chain(unhealthy)
+            .map(|(endpoint, _)| endpoint.clone())
+            .collect();
+
+        if candidates.is_empty() {
+            tracing::error!("none of the node endpoints follows the configured chain");
+            return Err(NoEndpointOnChain);
+        }
+
+        Ok(candidates)
+    }
+
+    fn preferred(&self) -> Result<C, NoEndpointOnChain> {
+        let mut candidates = self.candidates()?;
+        let (_, connector) = candidates.remove(0);
+
+        Ok(connector)
+    }
+
+    /// Sends `request` to one endpoint after the other until one of them
+    /// answers.
+    fn request<T, F>(
+        &self,
+        request: F,
+    ) -> Box<dyn Future<Item = T, Error = anyhow::Error> + Send + 'static>
+    where
+        T: Send + 'static,
+        F: Fn(&mut C) -> Box<dyn Future<Item = T, Error = anyhow::Error> + Send + 'static>
+            + Send
+            + 'static,
+    {
+        let candidates = self.candidates();
+
+        let future = async move {
+            let candidates = candidates?;
+            let mut last_error = None;
+
+            for (url, mut connector) in candidates {
+                match request(&mut connector).compat().await {
+                    Ok(item) => return Ok(item),
+                    Err(e) => {
+                        tracing::warn!("request to {} failed: {:?}", url, e);
+                        last_error = Some(e);
+                    }
+                }
+            }
+
+            Err(last_error.expect("at least one endpoint"))
+        }
+        .boxed()
+        .compat();
+
+        Box::new(future)
+    }
+}
+
+async fn check_health<C>(
+    endpoints: Arc<Vec<(Url, C)>>,
+    health: Weak<Vec<Health>>,
+    chain_id: C::ChainId,
+    max_lag: u64,
+) where
+    C: NodeStatus,
+{
+    loop {
+        match health.upgrade() {
+            Some(health) => check_health_once(&endpoints, &health, &chain_id, max_lag).await,
+            None => return,
+        }
+
+        tokio::time::delay_for(HEALTH_CHECK_INTERVAL).await;
+    }
+}
+
+async fn check_health_once<C>(
+    endpoints: &[(Url, C)],
+    health: &[Health],
+    chain_id: &C::ChainId,
+    max_lag: u64,
+) where
+    C: NodeStatus,
+{
+    let mut statuses = Vec::with_capacity(endpoints.len());
+    for (_, connector) in endpoints {
+        let status = async {
+            let actual_chain_id = connector.chain_id().compat().await?;
+            let block_height = connector.block_height().compat().await?;
+
+            Ok::<_, anyhow::Error>((actual_chain_id, block_height))
+        };
+        statuses.push(status.await);
+    }
+
+    let best_block_height = statuses
+        .iter()
+        .filter_map(|status| match status {
+            Ok((actual_chain_id, block_height)) if actual_chain_id == chain_id => {
+                Some(*block_height)
+            }
+            _ => None,
+        })
+        .max();
+
+    for (((url, _), status), health) in endpoints.iter().zip(statuses).zip(health) {
+        let is_healthy = match status {
+            Ok((actual_chain_id, _)) if actual_chain_id != *chain_id => {
+                tracing::error!(
+                    "node at {} follows chain {:?} instead of {:?}, no requests are sent to it",
+                    url,
+                    actual_chain_id,
+                    chain_id
+                );
+                health.on_other_chain.store(true, Ordering::SeqCst);
+                false
+            }
+            Ok((_, block_height)) => {
+                health.on_other_chain.store(false, Ordering::SeqCst);
+
+                match best_block_height {
+                    Some(best_block_height) if block_height + max_lag < best_block_height => {
+                        tracing::warn!(
+                            "node at {} is at block {} while others are at block {}",
+                            url,
+                            block_height,
+                            best_block_height
+                        );
+                        false
+                    }
+                    _ => true,
+                }
+            }
+            Err(e) => {
+                tracing::warn!("node at {} is not reachable: {:?}", url, e);
+                false
+            }
+        };
+
+        let was_healthy = health.healthy.swap(is_healthy, Ordering::SeqCst);
+        if !was_healthy && is_healthy {
+            tracing::info!("node at {} is healthy again", url);
+        }
+    }
+}
+
+impl<C> LatestBlock for Failover<C>
+where
+    C: LatestBlock + Clone,
+    C::Block: Send + 'static,
+{
+    type Block = C::Block;
+    type BlockHash = C::BlockHash;
+
+    fn latest_block(
+        &mut self,
+    ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
+        self.request(|connector| connector.latest_block())
+    }
+
+    fn wait_for_new_block(
+        &self,
+    ) -> Box<dyn Future<Item = (), Error = anyhow::Error> + Send + 'static> {
+        match self.preferred() {
+            Ok(connector) => connector.wait_for_new_block(),
+            Err(e) => Box::new(futures::future::err(e.into())),
+        }
+    }
+}
+
+impl<C> BlockByHash for Failover<C>
+where
+    C: BlockByHash + Clone,
+    C::Block: Send + 'static,
+    C::BlockHash: Clone + Send + Sync + 'static,
+{
+    type Block = C::Block;
+    type BlockHash = C::BlockHash;
+
+    fn block_by_hash(
+        &self,
+        block_hash: Self::BlockHash,
+    ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
+        self.request(move |connector| connector.block_by_hash(block_hash.clone()))
+    }
+}
+
+impl<C> BlockByNumber for Failover<C>
+where
+    C: BlockByNumber + Clone,
+    C::Block: Send + 'static,
+    C::BlockNumber: Clone + Send + Sync + 'static,
+{
+    type Block = C::Block;
+    type BlockNumber = C::BlockNumber;
+
+    fn block_by_number(
+        &self,
+        block_number: Self::BlockNumber,
+    ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
+        self.request(move |connector| connector.block_by_number(block_number.clone()))
+    }
+}
+
+impl<C> LogsInRange for Failover<C>
+where
+    C: LogsInRange + Clone,
+    C::Filter: Clone + Send + Sync + 'static,
+    C::Log: Send + 'static,
+    C::BlockNumber: Clone + Send + Sync + 'static,
+{
+    type Filter = C::Filter;
+    type Log = C::Log;
+    type BlockNumber = C::BlockNumber;
+
+    fn logs_in_range(
+        &self,
+        filter: Self::Filter,
+        from_block: Self::BlockNumber,
+        to_block: Self::BlockNumber,
+    ) -> Box<dyn Future<Item = Option<Vec<Self::Log>>, Error = anyhow::Error> + Send + 'static>
+    {
+        self.request(move |connector| {
+            connector.logs_in_range(filter.clone(), from_block.clone(), to_block.clone())
+        })
+    }
+}
+
+impl<C> ReceiptByHash for Failover<C>
+where
+    C: ReceiptByHash + Clone,
+    C::Receipt: Send + 'static,
+    C::TransactionHash: Clone + Send + Sync + 'static,
+{
+    type Receipt = C::Receipt;
+    type TransactionHash = C::TransactionHash;
+
+    fn receipt_by_hash(
+        &self,
+        transaction_hash: Self::TransactionHash,
+    ) -> Box<dyn Future<Item = Self::Receipt, Error = anyhow::Error> + Send + 'static> {
+        self.request(move |connector| connector.receipt_by_hash(transaction_hash.clone()))
+    }
+}
+
+impl<C> PendingTransactions for Failover<C>
+where
+    C: PendingTransactions + Clone,
+    C::Transaction: Send + 'static,
+    C::TransactionId: Clone + Send + Sync + 'static,
+{
+    type Transaction = C::Transaction;
+    type TransactionId = C::TransactionId;
+
+    // Nodes do not share their mempool, we stick to the preferred one.
+    fn pending_transaction_ids(
+        &self,
+    ) -> Box<dyn Future<Item = Vec<Self::TransactionId>, Error = anyhow::Error> + Send + 'static>
+    {
+        match self.preferred() {
+            Ok(connector) => connector.pending_transaction_ids(),
+            Err(e) => Box::new(futures::future::err(e.into())),
+        }
+    }
+
+    fn pending_transaction(
+        &self,
+        transaction_id: Self::TransactionId,
+    ) -> Box<dyn Future<Item = Option<Self::Transaction>, Error = anyhow::Error> + Send + 'static>
+    {
+        match self.preferred() {
+            Ok(connector) => connector.pending_transaction(transaction_id),
+            Err(e) => Box::new(futures::future::err(e.into())),
+        }
+    }
+}
+
+impl<C> MatchingTransactions for Failover<C>
+where
+    C: MatchingTransactions + Clone,
+{
+    fn matching_transactions(
+        &self,
+        pattern: TransactionPattern,
+    ) -> Box<dyn Future<Item = Option<Vec<Transaction>>, Error = anyhow::Error> + Send + 'static>
+    {
+        self.request(move |connector| connector.matching_transactions(pattern.clone()))
+    }
+}
+
+impl<C> BlockFilterByHash for Failover<C>
+where
+    C: BlockFilterByHash + Clone,
+{
+    fn block_filter_by_hash(
+        &self,
+        block_hash: BlockHash,
+    ) -> Box<
+        dyn Future<Item = Option<(BlockHeader, BlockFilter)>, Error = anyhow::Error>
+            + Send
+            + 'static,
+    > {
+        self.request(move |connector| connector.block_filter_by_hash(block_hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spectral::prelude::*;
+
+    /// A node that answers with its block height, or not at all.
+    #[derive(Clone, Debug)]
+    struct StaticNode {
+        chain_id: u32,
+        block_height: u64,
+        reachable: bool,
+    }
+
+    impl StaticNode {
+        fn answer<T: Send + 'static>(
+            &self,
+            item: T,
+        ) -> Box<dyn Future<Item = T, Error = anyhow::Error> + Send + 'static> {
+            if self.reachable {
+                Box::new(futures::future::ok(item))
+            } else {
+                Box::new(futures::future::err(anyhow!("connection refused")))
+            }
+        }
+    }
+
+    impl NodeStatus for StaticNode {
+        type ChainId = u32;
+
+        fn chain_id(
+            &self,
+        ) -> Box<dyn Future<Item = Self::ChainId, Error = anyhow::Error> + Send + 'static> {
+            self.answer(self.chain_id)
+        }
+
+        fn block_height(
+            &self,
+        ) -> Box<dyn Future<Item = u64, Error = anyhow::Error> + Send + 'static> {
+            self.answer(self.block_height)
+        }
+    }
+
+    impl LatestBlock for StaticNode {
+        type Block = u64;
+        type BlockHash = u64;
+
+        fn latest_block(
+            &mut self,
+        ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
+            self.answer(self.block_height)
+        }
+    }
+
+    fn node(chain_id: u32, block_height: u64, reachable: bool) -> (Url, StaticNode) {
+        let url = format!("http://node-{}-{}.local", chain_id, block_height);
+
+        (url.parse().unwrap(), StaticNode {
+            chain_id,
+            block_height,
+            reachable,
+        })
+    }
+
+    async fn checked(endpoints: Vec<(Url, StaticNode)>) -> Failover<StaticNode> {
+        let (failover, _) = Failover::new(endpoints, 17, 2).unwrap();
+        check_health_once(&failover.endpoints, &failover.health, &17, 2).await;
+
+        failover
+    }
+
+    #[tokio::test]
+    async fn falls_over_to_the_next_endpoint_if_a_request_fails() {
+        let (mut failover, _) =
+            Failover::new(vec![node(17, 100, false), node(17, 101, true)], 17, 2).unwrap();
+
+        let block = failover.latest_block().compat().await;
+
+        assert_that(&block).is_ok().is_equal_to(101);
+    }
+
+    #[tokio::test]
+    async fn prefers_the_first_endpoint_while_it_is_healthy() {
+        let mut failover = checked(vec![node(17, 100, true), node(17, 101, true)]).await;
+
+        let block = failover.latest_block().compat().await;
+
+        assert_that(&block).is_ok().is_equal_to(100);
+    }
+
+    #[tokio::test]
+    async fn avoids_endpoints_on_another_chain_or_lagging_behind() {
+        let mut failover = checked(vec![
+            node(1, 200, true),
+            node(17, 90, true),
+            node(17, 100, true),
+        ])
+        .await;
+
+        let block = failover.latest_block().compat().await;
+
+        assert_that(&block).is_ok().is_equal_to(100);
+    }
+
+    #[tokio::test]
+    async fn fails_if_the_only_reachable_endpoint_is_on_another_chain() {
+        let mut failover = checked(vec![node(17, 100, false), node(1, 200, true)]).await;
+
+        let block = failover.latest_block().compat().await;
+
+        assert_that(&block).is_err();
+    }
+
+    #[tokio::test]
+    async fn fails_if_all_endpoints_are_on_another_chain() {
+        let mut failover = checked(vec![node(1, 200, true)]).await;
+
+        let block = failover.latest_block().compat().await;
+
+        assert_that(&block).is_err();
+    }
+
+    #[test]
+    fn requires_at_least_one_endpoint() {
+        let result = Failover::<StaticNode>::new(vec![], 17, 2);
+
+        assert_that(&result.is_err()).is_true();
+    }
+}
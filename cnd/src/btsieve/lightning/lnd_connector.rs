@@ -0,0 +1,490 @@
+use crate::{
+    asset,
+    btsieve::lightning::{Invoice, InvoiceState, LndApi, Payment, PaymentStatus},
+    lightning::PublicKey,
+    swap_protocols::rfc003::{Secret, SecretHash},
+};
+use anyhow::Context;
+use async_trait::async_trait;
+use reqwest::{Certificate, Client, Url};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::path::Path;
+
+/// Talks to lnd through its REST API.
+///
+/// Requests are authenticated with the admin macaroon of the node and the
+/// connection is secured with the self-signed certificate lnd creates.
+#[derive(Clone, derivative::Derivative)]
+#[derivative(Debug)]
+pub struct LndConnector {
+    client: Client,
+    url: Url,
+    #[derivative(Debug = "ignore")]
+    macaroon: String,
+    identity: PublicKey,
+    network: ::bitcoin::Network,
+}
+
+impl LndConnector {
+    /// Connects to the node at `url` and asks it for its identity and
+    /// network.
+    pub async fn connect(url: Url, tls_cert: &Path, macaroon: &Path) -> anyhow::Result<Self> {
+        let tls_cert = std::fs::read(tls_cert)
+            .with_context(|| format!("failed to read lnd tls cert {}", tls_cert.display()))?;
+        let macaroon = std::fs::read(macaroon)
+            .with_context(|| format!("failed to read lnd macaroon {}", macaroon.display()))?;
+
+        let client = Client::builder()
+            .add_root_certificate(Certificate::from_pem(&tls_cert)?)
+            .build()?;
+        let macaroon = hex::encode(macaroon);
+
+        let info = get::<GetInfoResponse>(&client, &url, &macaroon, "v1/getinfo").await?;
+        let identity = info.identity_pubkey.parse()?;
+        let network = match info.chains.first().map(|chain| chain.network.as_str()) {
+            Some("mainnet") => ::bitcoin::Network::Bitcoin,
+            Some("testnet") => ::bitcoin::Network::Testnet,
+            Some("regtest") => ::bitcoin::Network::Regtest,
+            other => anyhow::bail!("lnd runs on unsupported network {:?}", other),
+        };
+
+        Ok(Self {
+            client,
+            url,
+            macaroon,
+            identity,
+            network,
+        })
+    }
+
+    pub fn network(&self) -> ::bitcoin::Network {
+        self.network
+    }
+
+    /// Adds a hold invoice for `payment_hash` that is only settled once the
+    /// preimage is handed to `settle_invoice`.
+    pub async fn add_hold_invoice(
+        &self,
+        payment_hash: SecretHash,
+        amount: asset::Bitcoin,
+        cltv_expiry: u32,
+    ) -> anyhow::Result<String> {
+        let response: AddHoldInvoiceResponse = self
+            .post("v2/invoices/hodl", &AddHoldInvoiceRequest {
+                hash: base64::encode(payment_hash.as_raw()),
+                value: amount.as_sat().to_string(),
+                cltv_expiry: cltv_expiry.to_string(),
+            })
+            .await?;
+
+        Ok(response.payment_request)
+    }
+
+    pub async fn settle_invoice(&self, preimage: Secret) -> anyhow::Result<()> {
+        let _: serde_json::Value = self
+            .post("v2/invoices/settle", &SettleInvoiceRequest {
+                preimage: base64::encode(preimage.as_raw_secret()),
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Pays `amount` to the node `to` for `payment_hash`.
+    ///
+    /// lnd only answers once the payment is either settled or failed, which
+    /// for a hold invoice may take until the end of the swap.
+    pub async fn send_payment(
+        &self,
+        to: PublicKey,
+        amount: asset::Bitcoin,
+        payment_hash: SecretHash,
+        final_cltv_delta: u32,
+    ) -> anyhow::Result<Secret> {
+        let response: SendResponse = self
+            .post("v1/channels/transactions", &SendRequest {
+                dest: base64::encode(&::bitcoin::secp256k1::PublicKey::from(to).serialize()[..]),
+                amt: amount.as_sat().to_string(),
+                payment_hash: base64::encode(payment_hash.as_raw()),
+                final_cltv_delta,
+            })
+            .await?;
+
+        if !response.payment_error.is_empty() {
+            anyhow::bail!("payment failed: {}", response.payment_error)
+        }
+
+        Secret::from_vec(&base64::decode(&response.payment_preimage)?)
+            .context("lnd returned an invalid preimage")
+    }
+
+    async fn post<B: Serialize, R: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> anyhow::Result<R> {
+        let response = self
+            .client
+            .post(self.url.join(path)?)
+            .header(MACAROON_HEADER, self.macaroon.as_str())
+            .json(body)
+            .send()
+            .await?;
+
+        parse_response(path, response).await
+    }
+}
+
+#[async_trait]
+impl LndApi for LndConnector {
+    fn identity(&self) -> PublicKey {
+        self.identity
+    }
+
+    async fn lookup_invoice(&self, payment_hash: SecretHash) -> anyhow::Result<Option<Invoice>> {
+        let path = format!("v1/invoice/{}", payment_hash);
+
+        let response = self
+            .client
+            .get(self.url.join(&path)?)
+            .header(MACAROON_HEADER, self.macaroon.as_str())
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error = response.json::<ErrorResponse>().await?;
+
+            if error.error.contains(UNKNOWN_INVOICE) {
+                return Ok(None);
+            }
+
+            anyhow::bail!("{} request failed: {}", path, error.error)
+        }
+
+        let invoice = response.json::<InvoiceResponse>().await?;
+
+        Ok(Some(invoice.into_invoice()?))
+    }
+
+    /// Asks lnd to track the payment for `payment_hash` and only reads the
+    /// first update, which is the current state of the latest attempt.
+    async fn lookup_payment(&self, payment_hash: SecretHash) -> anyhow::Result<Option<Payment>> {
+        let path = format!(
+            "v2/router/track/{}",
+            base64::encode_config(payment_hash.as_raw(), base64::URL_SAFE)
+        );
+
+        let mut response = self
+            .client
+            .get(self.url.join(&path)?)
+            .header(MACAROON_HEADER, self.macaroon.as_str())
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error = response.json::<ErrorResponse>().await?;
+
+            if error.error.contains(PAYMENT_NOT_INITIATED) {
+                return Ok(None);
+            }
+
+            anyhow::bail!("{} request failed: {}", path, error.error)
+        }
+
+        // The updates are streamed as one JSON object per line, dropping the
+        // response stops the stream.
+        let mut update = Vec::new();
+        while !update.contains(&b'\n') {
+            match response.chunk().await? {
+                Some(chunk) => update.extend_from_slice(&chunk),
+                None => break,
+            }
+        }
+
+        parse_payment_update(&path, &update)
+    }
+}
+
+/// Parses the first line of a stream of payment updates.
+fn parse_payment_update(path: &str, stream: &[u8]) -> anyhow::Result<Option<Payment>> {
+    let line = stream.split(|byte| *byte == b'\n').next().unwrap_or(&[]);
+
+    match serde_json::from_slice::<PaymentUpdate>(line)? {
+        PaymentUpdate::Result(payment) => payment.into_payment(),
+        PaymentUpdate::Error(error) if error.message.contains(PAYMENT_NOT_INITIATED) => Ok(None),
+        PaymentUpdate::Error(error) => anyhow::bail!("{} request failed: {}", path, error.message),
+    }
+}
+
+const MACAROON_HEADER: &str = "Grpc-Metadata-macaroon";
+const UNKNOWN_INVOICE: &str = "unable to locate invoice";
+const PAYMENT_NOT_INITIATED: &str = "payment isn't initiated";
+
+async fn get<R: DeserializeOwned>(
+    client: &Client,
+    url: &Url,
+    macaroon: &str,
+    path: &str,
+) -> anyhow::Result<R> {
+    let response = client
+        .get(url.join(path)?)
+        .header(MACAROON_HEADER, macaroon)
+        .send()
+        .await?;
+
+    parse_response(path, response).await
+}
+
+async fn parse_response<R: DeserializeOwned>(
+    path: &str,
+    response: reqwest::Response,
+) -> anyhow::Result<R> {
+    if !response.status().is_success() {
+        let error = response.json::<ErrorResponse>().await?;
+        anyhow::bail!("{} request failed: {}", path, error.error)
+    }
+
+    Ok(response.json::<R>().await?)
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+#[derive(Deserialize)]
+struct GetInfoResponse {
+    identity_pubkey: String,
+    chains: Vec<Chain>,
+}
+
+#[derive(Deserialize)]
+struct Chain {
+    network: String,
+}
+
+/// lnd encodes 64 bit integers as strings and bytes in base64.
+#[derive(Deserialize)]
+struct InvoiceResponse {
+    state: String,
+    #[serde(default)]
+    amt_paid_sat: Option<String>,
+    #[serde(default)]
+    r_preimage: Option<String>,
+}
+
+impl InvoiceResponse {
+    fn into_invoice(self) -> anyhow::Result<Invoice> {
+        let state = match self.state.as_str() {
+            "OPEN" => InvoiceState::Open,
+            "ACCEPTED" => InvoiceState::Accepted,
+            "SETTLED" => InvoiceState::Settled,
+            "CANCELED" => InvoiceState::Canceled,
+            other => anyhow::bail!("unknown invoice state {}", other),
+        };
+        let amount_paid = parse_sat(self.amt_paid_sat)?;
+        let preimage = match self.r_preimage {
+            Some(preimage) if !preimage.is_empty() => {
+                Some(Secret::from_vec(&base64::decode(&preimage)?)?)
+            }
+            _ => None,
+        };
+
+        Ok(Invoice {
+            state,
+            amount_paid,
+            preimage,
+        })
+    }
+}
+
+/// A message of a streaming endpoint, which wraps either the update or the
+/// error that ended the stream.
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum PaymentUpdate {
+    Result(PaymentResponse),
+    Error(StreamError),
+}
+
+#[derive(Deserialize)]
+struct StreamError {
+    message: String,
+}
+
+/// Unlike invoices, payments encode hashes and preimages in hex.
+#[derive(Deserialize)]
+struct PaymentResponse {
+    #[serde(default)]
+    value_sat: Option<String>,
+    status: String,
+    #[serde(default)]
+    payment_preimage: Option<String>,
+}
+
+impl PaymentResponse {
+    fn into_payment(self) -> anyhow::Result<Option<Payment>> {
+        let status = match self.status.as_str() {
+            "IN_FLIGHT" => PaymentStatus::InFlight,
+            "SUCCEEDED" => PaymentStatus::Succeeded,
+            "FAILED" => PaymentStatus::Failed,
+            // lnd does not know yet what became of the payment.
+            "UNKNOWN" => return Ok(None),
+            other => anyhow::bail!("unknown payment status {}", other),
+        };
+        let amount = parse_sat(self.value_sat)?;
+        let preimage = match self.payment_preimage {
+            Some(preimage) if status == PaymentStatus::Succeeded => Some(preimage.parse()?),
+            _ => None,
+        };
+
+        Ok(Some(Payment {
+            status,
+            amount,
+            preimage,
+        }))
+    }
+}
+
+fn parse_sat(sat: Option<String>) -> anyhow::Result<asset::Bitcoin> {
+    let sat = match sat {
+        Some(sat) => sat.parse::<u64>()?,
+        None => 0,
+    };
+
+    Ok(asset::Bitcoin::from_sat(sat))
+}
+
+#[derive(Serialize)]
+struct AddHoldInvoiceRequest {
+    hash: String,
+    value: String,
+    cltv_expiry: String,
+}
+
+#[derive(Deserialize)]
+struct AddHoldInvoiceResponse {
+    payment_request: String,
+}
+
+#[derive(Serialize)]
+struct SettleInvoiceRequest {
+    preimage: String,
+}
+
+#[derive(Serialize)]
+struct SendRequest {
+    dest: String,
+    amt: String,
+    payment_hash: String,
+    final_cltv_delta: u32,
+}
+
+#[derive(Deserialize)]
+struct SendResponse {
+    #[serde(default)]
+    payment_error: String,
+    #[serde(default)]
+    payment_preimage: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spectral::prelude::*;
+
+    #[test]
+    fn deserialize_accepted_invoice() {
+        let json = r#"{
+            "memo": "",
+            "r_hash": "aNYnlxZDpvl/J8WJV4Jvy6hT7CB3/RDsa5PY5h3rTOw=",
+            "value": "10000",
+            "state": "ACCEPTED",
+            "amt_paid_sat": "10000"
+        }"#;
+
+        let invoice = serde_json::from_str::<InvoiceResponse>(json)
+            .unwrap()
+            .into_invoice();
+
+        assert_that(&invoice).is_ok_containing(Invoice {
+            state: InvoiceState::Accepted,
+            amount_paid: asset::Bitcoin::from_sat(10_000),
+            preimage: None,
+        });
+    }
+
+    #[test]
+    fn deserialize_settled_invoice_with_preimage() {
+        let secret = Secret::from(*b"hello world, you are beautiful!!");
+        let json = format!(
+            r#"{{ "state": "SETTLED", "amt_paid_sat": "10000", "r_preimage": "{}" }}"#,
+            base64::encode(secret.as_raw_secret())
+        );
+
+        let invoice = serde_json::from_str::<InvoiceResponse>(&json)
+            .unwrap()
+            .into_invoice();
+
+        assert_that(&invoice)
+            .is_ok()
+            .map(|invoice| &invoice.preimage)
+            .is_equal_to(&Some(secret));
+    }
+
+    #[test]
+    fn deserialize_succeeded_payment_with_preimage() {
+        let secret = Secret::from(*b"hello world, you are beautiful!!");
+        let json = format!(
+            r#"{{ "payment_hash": "{}", "value_sat": "10000", "status": "SUCCEEDED", "payment_preimage": "{:x}" }}"#,
+            secret.hash(),
+            secret
+        );
+
+        let payment = serde_json::from_str::<PaymentResponse>(&json)
+            .unwrap()
+            .into_payment();
+
+        assert_that(&payment).is_ok_containing(Some(Payment {
+            status: PaymentStatus::Succeeded,
+            amount: asset::Bitcoin::from_sat(10_000),
+            preimage: Some(secret),
+        }));
+    }
+
+    #[test]
+    fn first_payment_update_of_stream_is_parsed() {
+        let stream =
+            br#"{"result": { "payment_hash": "00", "value_sat": "10000", "status": "IN_FLIGHT" }}
+{"result": { "payment_hash": "00", "value_sat": "10000", "status": "FAILED" }}
+"#;
+
+        let payment = parse_payment_update("track", stream);
+
+        assert_that(&payment).is_ok_containing(Some(Payment {
+            status: PaymentStatus::InFlight,
+            amount: asset::Bitcoin::from_sat(10_000),
+            preimage: None,
+        }));
+    }
+
+    #[test]
+    fn payment_that_was_not_initiated_is_not_found() {
+        let stream = br#"{"error": { "grpc_code": 5, "message": "payment isn't initiated" }}"#;
+
+        let payment = parse_payment_update("track", stream);
+
+        assert_that(&payment).is_ok_containing(None);
+    }
+
+    #[test]
+    fn payment_with_unknown_status_is_ignored() {
+        let json = r#"{ "payment_hash": "00", "value_sat": "10000", "status": "UNKNOWN" }"#;
+
+        let payment = serde_json::from_str::<PaymentResponse>(json)
+            .unwrap()
+            .into_payment();
+
+        assert_that(&payment).is_ok_containing(None);
+    }
+}
@@ -0,0 +1,153 @@
+mod lnd_connector;
+
+pub use self::lnd_connector::LndConnector;
+
+use crate::{
+    asset,
+    lightning::PublicKey,
+    swap_protocols::rfc003::{Secret, SecretHash},
+    timestamp::Timestamp,
+};
+use async_trait::async_trait;
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The average time between two Bitcoin blocks in seconds.
+const AVERAGE_BLOCK_TIME_SECS: u32 = 600;
+
+/// The number of blocks the CLTV of an HTLC is shortened by, to cover blocks
+/// that are mined faster than on average.
+const CLTV_SAFETY_MARGIN_BLOCKS: u32 = 6;
+
+/// Converts the absolute expiry of a swap into the number of blocks lnd
+/// expects for the CLTV of an HTLC.
+///
+/// Block times vary, hence the HTLC is made to expire
+/// `CLTV_SAFETY_MARGIN_BLOCKS` earlier than the average block time suggests
+/// rather than after the expiry the parties agreed on.
+pub fn cltv_delta(expiry: Timestamp) -> u32 {
+    let seconds = u32::from(expiry).saturating_sub(u32::from(Timestamp::now()));
+
+    (seconds / AVERAGE_BLOCK_TIME_SECS).saturating_sub(CLTV_SAFETY_MARGIN_BLOCKS)
+}
+
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+#[error("cnd is not connected to an lnd node")]
+pub struct LndNotConnected;
+
+/// The parts of an lnd node that are needed to watch HTLCs on the Lightning
+/// Network.
+///
+/// A node only knows about its own invoices and payments, hence the HTLC of a
+/// swap can only be watched through the node of one of the two parties.
+#[async_trait]
+pub trait LndApi: Send + Sync + 'static {
+    /// The public key of the node.
+    fn identity(&self) -> PublicKey;
+
+    async fn lookup_invoice(&self, payment_hash: SecretHash) -> anyhow::Result<Option<Invoice>>;
+
+    async fn lookup_payment(&self, payment_hash: SecretHash) -> anyhow::Result<Option<Payment>>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Invoice {
+    pub state: InvoiceState,
+    pub amount_paid: asset::Bitcoin,
+    pub preimage: Option<Secret>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InvoiceState {
+    Open,
+    /// The payment has arrived but the invoice is neither settled nor
+    /// canceled yet. Only hold invoices can be in this state.
+    Accepted,
+    Settled,
+    Canceled,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Payment {
+    pub status: PaymentStatus,
+    pub amount: asset::Bitcoin,
+    pub preimage: Option<Secret>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PaymentStatus {
+    InFlight,
+    Succeeded,
+    Failed,
+}
+
+/// Polls the node until the invoice with the given payment hash satisfies
+/// `predicate`.
+pub async fn watch_for_invoice<C, P>(
+    connector: &C,
+    payment_hash: SecretHash,
+    predicate: P,
+) -> anyhow::Result<Invoice>
+where
+    C: LndApi,
+    P: Fn(&Invoice) -> bool,
+{
+    loop {
+        if let Some(invoice) = connector.lookup_invoice(payment_hash).await? {
+            if predicate(&invoice) {
+                return Ok(invoice);
+            }
+        }
+
+        tokio::time::delay_for(POLL_INTERVAL).await;
+    }
+}
+
+/// Polls the node until the payment with the given payment hash satisfies
+/// `predicate`.
+pub async fn watch_for_payment<C, P>(
+    connector: &C,
+    payment_hash: SecretHash,
+    predicate: P,
+) -> anyhow::Result<Payment>
+where
+    C: LndApi,
+    P: Fn(&Payment) -> bool,
+{
+    loop {
+        if let Some(payment) = connector.lookup_payment(payment_hash).await? {
+            if predicate(&payment) {
+                return Ok(payment);
+            }
+        }
+
+        tokio::time::delay_for(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spectral::prelude::*;
+
+    #[test]
+    fn cltv_delta_counts_blocks_until_expiry_minus_margin() {
+        let expiry =
+            Timestamp::now().plus(20 * AVERAGE_BLOCK_TIME_SECS + AVERAGE_BLOCK_TIME_SECS / 2);
+
+        assert_that(&cltv_delta(expiry)).is_equal_to(20 - CLTV_SAFETY_MARGIN_BLOCKS);
+    }
+
+    #[test]
+    fn cltv_delta_within_margin_is_zero() {
+        let expiry = Timestamp::now().plus(CLTV_SAFETY_MARGIN_BLOCKS * AVERAGE_BLOCK_TIME_SECS);
+
+        assert_that(&cltv_delta(expiry)).is_equal_to(0);
+    }
+
+    #[test]
+    fn cltv_delta_of_past_expiry_is_zero() {
+        assert_that(&cltv_delta(Timestamp::from(0))).is_equal_to(0);
+    }
+}
@@ -4,10 +4,66 @@
 #[macro_use]
 pub mod block_by_hash;
 pub mod bitcoin;
+pub mod disk_cache;
 pub mod ethereum;
+pub mod failover;
+pub mod lightning;
+mod pending;
+mod scanner;
+
+pub use self::{
+    pending::{Mempool, PendingChange},
+    scanner::{Confirmation, LinkedBlock, Scanner, Watch},
+};
 
 use chrono::NaiveDateTime;
 use futures::Future;
+use futures_core::{FutureExt, TryFutureExt};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// How often watchers ask for the latest block if the connector is not
+/// notified about new blocks.
+// The duration of this interval could/should depend on the network
+pub const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a block notification of a node is trusted to be its tip.
+const NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A block a node notified us about.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Notification<T> {
+    pub block: T,
+    received_at: Instant,
+}
+
+impl<T> Notification<T> {
+    pub fn new(block: T) -> Self {
+        Self {
+            block,
+            received_at: Instant::now(),
+        }
+    }
+
+    /// The block of the latest notification, unless it arrived more than
+    /// `NOTIFICATION_TIMEOUT` ago.
+    ///
+    /// A node that goes away without closing the connection leaves us with
+    /// its last notification. Once that is too old, the caller has to ask the
+    /// node for its tip instead.
+    pub fn fresh(notifications: Option<&watch::Receiver<Option<Self>>>) -> Option<T>
+    where
+        T: Clone,
+    {
+        let notifications = notifications?;
+        let notification = notifications.borrow();
+
+        notification
+            .as_ref()
+            .filter(|notification| notification.received_at.elapsed() < NOTIFICATION_TIMEOUT)
+            .map(|notification| notification.block.clone())
+    }
+}
 
 pub trait LatestBlock: Send + Sync + 'static {
     type Block;
@@ -16,6 +72,70 @@ pub trait LatestBlock: Send + Sync + 'static {
     fn latest_block(
         &mut self,
     ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static>;
+
+    /// Resolves once a new block may be available, watchers call this between
+    /// two calls to `latest_block`.
+    fn wait_for_new_block(
+        &self,
+    ) -> Box<dyn Future<Item = (), Error = anyhow::Error> + Send + 'static> {
+        wait_for_poll_interval()
+    }
+}
+
+pub fn wait_for_poll_interval() -> Box<dyn Future<Item = (), Error = anyhow::Error> + Send + 'static>
+{
+    let future = async {
+        tokio::time::delay_for(POLL_INTERVAL).await;
+        Ok(())
+    }
+    .boxed()
+    .compat();
+
+    Box::new(future)
+}
+
+/// Waits until `notifications` announce a block with an id other than the
+/// current one. Without notifications we are back to polling.
+///
+/// Watchers ask for the latest block after `NOTIFICATION_TIMEOUT` anyway and
+/// by then connectors no longer take it from the last notification, see
+/// `Notification::fresh`. Hence notifications that silently stopped do not
+/// stall watchers.
+pub fn wait_for_new_block_notification<T, I, F>(
+    notifications: Option<&watch::Receiver<Option<T>>>,
+    id: F,
+) -> Box<dyn Future<Item = (), Error = anyhow::Error> + Send + 'static>
+where
+    T: Clone + Send + Sync + 'static,
+    I: PartialEq + Send + 'static,
+    F: Fn(&T) -> I + Send + 'static,
+{
+    let mut notifications = match notifications {
+        Some(notifications) if notifications.borrow().is_some() => notifications.clone(),
+        _ => return wait_for_poll_interval(),
+    };
+
+    let seen = notifications.borrow().as_ref().map(&id);
+
+    let future = async move {
+        let new_block = async {
+            while let Some(notification) = notifications.recv().await {
+                if notification.as_ref().map(&id) != seen {
+                    return;
+                }
+            }
+
+            // The notifications are gone for good, back to polling.
+            tokio::time::delay_for(POLL_INTERVAL).await;
+        };
+        let _ = tokio::time::timeout(NOTIFICATION_TIMEOUT, new_block).await;
+
+        Ok(())
+    }
+    .boxed()
+    .compat();
+
+    Box::new(future)
 }
 
 pub trait BlockByHash: Send + Sync + 'static {
@@ -28,6 +148,42 @@ pub trait BlockByHash: Send + Sync + 'static {
     ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static>;
 }
 
+pub trait BlockByNumber: Send + Sync + 'static {
+    type Block;
+    type BlockNumber;
+
+    fn block_by_number(
+        &self,
+        block_number: Self::BlockNumber,
+    ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static>;
+}
+
+/// The node refused a log query because the range of blocks or the response
+/// is too large, a smaller range might still be answered.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("log query is too large: {message}")]
+pub struct LogsQueryTooLarge {
+    pub message: String,
+}
+
+/// Queries the logs matching `filter` within a range of blocks, both ends
+/// inclusive.
+///
+/// Resolves to `None` if the node does not support log queries at all. Fails
+/// with `LogsQueryTooLarge` if the node hit one of its limits.
+pub trait LogsInRange: Send + Sync + 'static {
+    type Filter;
+    type Log;
+    type BlockNumber;
+
+    fn logs_in_range(
+        &self,
+        filter: Self::Filter,
+        from_block: Self::BlockNumber,
+        to_block: Self::BlockNumber,
+    ) -> Box<dyn Future<Item = Option<Vec<Self::Log>>, Error = anyhow::Error> + Send + 'static>;
+}
+
 pub trait ReceiptByHash: Send + Sync + 'static {
     type Receipt;
     type TransactionHash;
@@ -38,7 +194,53 @@ pub trait ReceiptByHash: Send + Sync + 'static {
     ) -> Box<dyn Future<Item = Self::Receipt, Error = anyhow::Error> + Send + 'static>;
 }
 
+/// The transactions a node knows about that are not included in a block yet,
+/// i.e. the mempool of bitcoind or the pending transactions of an Ethereum
+/// node.
+pub trait PendingTransactions: Send + Sync + 'static {
+    type Transaction;
+    type TransactionId;
+
+    fn pending_transaction_ids(
+        &self,
+    ) -> Box<dyn Future<Item = Vec<Self::TransactionId>, Error = anyhow::Error> + Send + 'static>;
+
+    /// Resolves to `None` if the transaction is no longer known to the node,
+    /// e.g. because it left the mempool in the meantime.
+    fn pending_transaction(
+        &self,
+        transaction_id: Self::TransactionId,
+    ) -> Box<dyn Future<Item = Option<Self::Transaction>, Error = anyhow::Error> + Send + 'static>;
+}
+
 /// Checks if a given block predates a certain timestamp.
 pub trait Predates {
     fn predates(&self, timestamp: NaiveDateTime) -> bool;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spectral::prelude::*;
+
+    #[test]
+    fn recent_notification_is_fresh() {
+        let (_sender, notifications) = watch::channel(Some(Notification::new(42)));
+
+        let block = Notification::fresh(Some(&notifications));
+
+        assert_that(&block).contains_value(42);
+    }
+
+    #[test]
+    fn notification_older_than_timeout_is_not_fresh() {
+        let (_sender, notifications) = watch::channel(Some(Notification {
+            block: 42,
+            received_at: Instant::now() - NOTIFICATION_TIMEOUT,
+        }));
+
+        let block = Notification::fresh(Some(&notifications));
+
+        assert_that(&block).is_none();
+    }
+}
@@ -0,0 +1,374 @@
+//! Watching transactions before they are mined.
+//!
+//! Like the `Scanner` does for blocks, a `Mempool` polls the pending
+//! transactions of a node once for all watches of the ledger and dispatches
+//! every transaction that enters or leaves the mempool to them.
+
+use crate::btsieve::{PendingTransactions, Watch, POLL_INTERVAL};
+use derivative::Derivative;
+use futures_core::compat::Future01CompatExt;
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::mpsc;
+
+/// A change to the pending transactions a watch is interested in.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PendingChange<T> {
+    /// A matching transaction entered the mempool.
+    Entered(T),
+    /// A matching transaction left the mempool, because it was mined,
+    /// replaced or evicted.
+    Left,
+}
+
+enum MempoolEvent<I, T> {
+    Entered(I, T),
+    Left(I),
+}
+
+/// Dispatches an event to a watch, returns `false` once the watch is gone.
+type Dispatch<I, T> = Box<dyn FnMut(&MempoolEvent<I, T>) -> bool + Send>;
+
+struct Registry<I, T> {
+    next_id: u64,
+    watches: HashMap<u64, Dispatch<I, T>>,
+    /// Watches that registered since the last poll. They did not see the
+    /// transactions that were already pending back then.
+    newcomers: Vec<u64>,
+    /// Whether a task is polling the mempool. The task stops once there are
+    /// no watches left and is started again by the next registration.
+    running: bool,
+}
+
+impl<I, T> Registry<I, T> {
+    fn dispatch(&mut self, event: &MempoolEvent<I, T>) {
+        self.watches.retain(|_, dispatch| dispatch(event));
+    }
+
+    fn dispatch_to(&mut self, ids: &[u64], event: &MempoolEvent<I, T>) {
+        for id in ids {
+            let gone = match self.watches.get_mut(id) {
+                Some(dispatch) => !dispatch(event),
+                None => false,
+            };
+
+            if gone {
+                self.watches.remove(id);
+            }
+        }
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "C: std::fmt::Debug"), Clone(bound = "C: Clone"))]
+pub struct Mempool<C, I, T> {
+    connector: C,
+    #[derivative(Debug = "ignore")]
+    registry: Arc<Mutex<Registry<I, T>>>,
+}
+
+impl<C, I, T> Mempool<C, I, T>
+where
+    C: PendingTransactions<TransactionId = I, Transaction = T> + Clone,
+    I: Clone + Eq + Hash + Send + Sync + 'static,
+    T: Send + 'static,
+{
+    pub fn new(connector: C) -> Self {
+        Self {
+            connector,
+            registry: Arc::new(Mutex::new(Registry {
+                next_id: 0,
+                watches: HashMap::new(),
+                newcomers: Vec::new(),
+                running: false,
+            })),
+        }
+    }
+
+    /// Dispatches every pending transaction to `matcher`, starting with the
+    /// ones that are pending already.
+    ///
+    /// The watch yields what `matcher` returns as the transaction enters the
+    /// mempool and tells once it leaves again; it deregisters from the
+    /// mempool when it is dropped.
+    pub fn watch<R, M>(&self, matcher: M) -> Watch<PendingChange<R>>
+    where
+        R: Send + 'static,
+        M: Fn(&T) -> Option<R> + Send + 'static,
+    {
+        let (sender, items) = mpsc::unbounded_channel();
+        let mut matched = HashSet::new();
+
+        let dispatch: Dispatch<I, T> = Box::new(move |event: &MempoolEvent<I, T>| {
+            let change = match event {
+                MempoolEvent::Entered(id, transaction) => matcher(transaction).map(|item| {
+                    matched.insert(id.clone());
+                    PendingChange::Entered(item)
+                }),
+                MempoolEvent::Left(id) => {
+                    if matched.remove(id) {
+                        Some(PendingChange::Left)
+                    } else {
+                        None
+                    }
+                }
+            };
+
+            match change {
+                Some(change) => sender.send(Ok(change)).is_ok(),
+                None => true,
+            }
+        });
+
+        let id = {
+            let mut registry = self
+                .registry
+                .lock()
+                .expect("mempool registry lock poisoned");
+
+            let id = registry.next_id;
+            registry.next_id += 1;
+            registry.watches.insert(id, dispatch);
+            registry.newcomers.push(id);
+
+            if !registry.running {
+                registry.running = true;
+                tokio::spawn(follow_mempool(
+                    self.connector.clone(),
+                    Arc::clone(&self.registry),
+                ));
+            }
+
+            id
+        };
+
+        let registry = Arc::downgrade(&self.registry);
+        Watch::new(
+            items,
+            Box::new(move || {
+                if let Some(registry) = registry.upgrade() {
+                    registry
+                        .lock()
+                        .expect("mempool registry lock poisoned")
+                        .watches
+                        .remove(&id);
+                }
+            }),
+        )
+    }
+}
+
+async fn follow_mempool<C, I, T>(connector: C, registry: Arc<Mutex<Registry<I, T>>>)
+where
+    C: PendingTransactions<TransactionId = I, Transaction = T>,
+    I: Clone + Eq + Hash,
+{
+    let mut known = HashSet::new();
+
+    loop {
+        let newcomers = {
+            let mut registry = registry.lock().expect("mempool registry lock poisoned");
+            if registry.watches.is_empty() {
+                registry.running = false;
+                return;
+            }
+
+            std::mem::replace(&mut registry.newcomers, Vec::new())
+        };
+
+        if let Err(e) = poll_mempool(&connector, &registry, &mut known, &newcomers).await {
+            tracing::warn!("failed to fetch pending transactions: {:?}", e);
+
+            // The newcomers are told about the pending transactions next time.
+            registry
+                .lock()
+                .expect("mempool registry lock poisoned")
+                .newcomers
+                .extend(newcomers);
+        }
+
+        tokio::time::delay_for(POLL_INTERVAL).await;
+    }
+}
+
+/// Dispatches the transactions that entered or left the mempool since the
+/// last poll.
+///
+/// Each transaction is only fetched once while it stays pending, unless there
+/// are `newcomers` that have not seen it yet.
+async fn poll_mempool<C, I, T>(
+    connector: &C,
+    registry: &Mutex<Registry<I, T>>,
+    known: &mut HashSet<I>,
+    newcomers: &[u64],
+) -> anyhow::Result<()>
+where
+    C: PendingTransactions<TransactionId = I, Transaction = T>,
+    I: Clone + Eq + Hash,
+{
+    let pending = connector
+        .pending_transaction_ids()
+        .compat()
+        .await?
+        .into_iter()
+        .collect::<HashSet<_>>();
+
+    for id in pending.iter() {
+        let is_new = !known.contains(id);
+        if !is_new && newcomers.is_empty() {
+            continue;
+        }
+
+        let transaction = match connector.pending_transaction(id.clone()).compat().await? {
+            Some(transaction) => transaction,
+            None => continue,
+        };
+        let event = MempoolEvent::Entered(id.clone(), transaction);
+
+        let mut registry = registry.lock().expect("mempool registry lock poisoned");
+        if is_new {
+            registry.dispatch(&event);
+            known.insert(id.clone());
+        } else {
+            registry.dispatch_to(newcomers, &event);
+        }
+    }
+
+    // A transaction that comes back after a reorg enters the mempool again.
+    let left = known
+        .iter()
+        .filter(|id| !pending.contains(id))
+        .cloned()
+        .collect::<Vec<_>>();
+    for id in left {
+        known.remove(&id);
+        registry
+            .lock()
+            .expect("mempool registry lock poisoned")
+            .dispatch(&MempoolEvent::Left(id));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{future::IntoFuture, Future};
+    use spectral::prelude::*;
+
+    /// A node that counts how often each transaction was fetched.
+    #[derive(Clone, Default)]
+    struct Node {
+        transactions: Arc<Mutex<Vec<u32>>>,
+        fetched: Arc<Mutex<Vec<u32>>>,
+    }
+
+    impl PendingTransactions for Node {
+        type Transaction = u32;
+        type TransactionId = u32;
+
+        fn pending_transaction_ids(
+            &self,
+        ) -> Box<dyn Future<Item = Vec<u32>, Error = anyhow::Error> + Send + 'static> {
+            let transactions = self.transactions.lock().unwrap().clone();
+
+            Box::new(Ok(transactions).into_future())
+        }
+
+        fn pending_transaction(
+            &self,
+            transaction_id: u32,
+        ) -> Box<dyn Future<Item = Option<u32>, Error = anyhow::Error> + Send + 'static> {
+            self.fetched.lock().unwrap().push(transaction_id);
+            let transaction = self
+                .transactions
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|transaction| **transaction == transaction_id)
+                .copied();
+
+            Box::new(Ok(transaction).into_future())
+        }
+    }
+
+    fn only(wanted: u32) -> impl Fn(&u32) -> Option<u32> + Send + 'static {
+        move |transaction| Some(*transaction).filter(|transaction| *transaction == wanted)
+    }
+
+    #[test]
+    fn pending_transactions_are_fetched_once_for_all_watches() {
+        let node = Node::default();
+        *node.transactions.lock().unwrap() = vec![1, 2];
+        let mempool = Mempool::new(node.clone());
+
+        let (first, second) = tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let mut first = mempool.watch(only(3));
+            let mut second = mempool.watch(only(3));
+            let broadcast = async {
+                tokio::time::delay_for(POLL_INTERVAL * 2).await;
+                node.transactions.lock().unwrap().push(3);
+            };
+
+            let (first, second, ()) =
+                futures_core::future::join3(first.next(), second.next(), broadcast).await;
+            (first, second)
+        });
+
+        assert_that(&first)
+            .is_ok()
+            .is_equal_to(PendingChange::Entered(3));
+        assert_that(&second)
+            .is_ok()
+            .is_equal_to(PendingChange::Entered(3));
+        let mut fetched = node.fetched.lock().unwrap().clone();
+        fetched.sort();
+        assert_that(&fetched).is_equal_to(vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn late_watch_sees_transactions_that_are_already_pending() {
+        let node = Node::default();
+        *node.transactions.lock().unwrap() = vec![1, 2];
+        let mempool = Mempool::new(node);
+
+        let late = tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let _early = mempool.watch(only(3));
+            tokio::time::delay_for(POLL_INTERVAL * 2).await;
+
+            mempool.watch(only(2)).next().await
+        });
+
+        assert_that(&late)
+            .is_ok()
+            .is_equal_to(PendingChange::Entered(2));
+    }
+
+    #[test]
+    fn matched_transaction_that_leaves_the_mempool_is_reported() {
+        let node = Node::default();
+        *node.transactions.lock().unwrap() = vec![1, 2];
+        let mempool = Mempool::new(node.clone());
+
+        let changes = tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let mut watch = mempool.watch(only(2));
+            let entered = watch.next().await;
+
+            // transaction 2 is replaced by 3
+            *node.transactions.lock().unwrap() = vec![1, 3];
+
+            (entered, watch.next().await)
+        });
+
+        assert_that(&changes.0)
+            .is_ok()
+            .is_equal_to(PendingChange::Entered(2));
+        assert_that(&changes.1)
+            .is_ok()
+            .is_equal_to(PendingChange::Left);
+    }
+}
@@ -0,0 +1,581 @@
+//! Following a chain once for all watchers.
+//!
+//! Every swap watches for up to four events on each of its ledgers. Instead of
+//! each of them polling the node for the latest block on its own, they
+//! register with the `Scanner` of the ledger which fetches every new block
+//! once and dispatches it to all registered watches.
+
+use crate::btsieve::{BlockByHash, LatestBlock, Predates, POLL_INTERVAL};
+use chrono::NaiveDateTime;
+use derivative::Derivative;
+use futures_core::compat::Future01CompatExt;
+use lru::LruCache;
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    hash::Hash,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::mpsc;
+
+/// The number of block hashes the scanner remembers to tell new blocks from
+/// known ones.
+const SEEN_BLOCKS_CAPACITY: usize = 10_000;
+
+/// The maximum number of blocks the scanner walks back to find blocks it
+/// missed, e.g. while the node was not reachable.
+const MAX_MISSED_BLOCKS: usize = 1_000;
+
+/// The parts of a block a `Scanner` needs to follow the chain.
+pub trait LinkedBlock: Predates + Clone + Send + Sync + 'static {
+    type Hash: Copy + Eq + Hash + Debug + Send + Sync + 'static;
+
+    /// `None` for blocks that are not mined yet.
+    fn block_hash(&self) -> Option<Self::Hash>;
+
+    fn parent_hash(&self) -> Self::Hash;
+
+    /// The time the block was mined at according to its header.
+    fn block_time(&self) -> NaiveDateTime;
+}
+
+/// What became of a transaction that was seen on the chain.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Confirmation {
+    /// The transaction is buried under the required number of blocks, the
+    /// block that includes it was mined at `block_time`.
+    Confirmed { block_time: NaiveDateTime },
+    /// A reorg removed the block that included the transaction.
+    Orphaned,
+}
+
+/// Dispatches a block to a watch, returns `false` once the watch is gone.
+type Dispatch<B> = Box<dyn FnMut(&B) -> bool + Send>;
+
+struct Registry<B> {
+    next_id: u64,
+    watches: HashMap<u64, Dispatch<B>>,
+    /// Whether a task is following the chain. The task stops once there are
+    /// no watches left and is started again by the next registration.
+    running: bool,
+}
+
+impl<B> Registry<B> {
+    fn dispatch(&mut self, block: &B) {
+        self.watches.retain(|_, dispatch| dispatch(block));
+    }
+}
+
+#[derive(Derivative, Clone)]
+#[derivative(Debug)]
+pub struct Scanner<C, B> {
+    connector: C,
+    #[derivative(Debug = "ignore")]
+    registry: Arc<Mutex<Registry<B>>>,
+}
+
+impl<C, B, L> Scanner<C, B>
+where
+    C: LatestBlock<Block = L> + BlockByHash<Block = L, BlockHash = B::Hash> + Clone,
+    B: LinkedBlock,
+    L: Into<Option<B>> + Send + 'static,
+{
+    pub fn new(connector: C) -> Self {
+        Self {
+            connector,
+            registry: Arc::new(Mutex::new(Registry {
+                next_id: 0,
+                watches: HashMap::new(),
+                running: false,
+            })),
+        }
+    }
+
+    pub fn connector(&self) -> &C {
+        &self.connector
+    }
+
+    /// Dispatches every block mined from now on to `matcher`. If
+    /// `start_of_swap` is given, the blocks back to the last one that
+    /// predates it are dispatched as well.
+    ///
+    /// The watch yields everything `matcher` returns; it deregisters from the
+    /// scanner when it is dropped.
+    pub fn watch<T, M>(&self, start_of_swap: Option<NaiveDateTime>, matcher: M) -> Watch<T>
+    where
+        T: Send + 'static,
+        M: Fn(&B) -> Option<T> + Send + Sync + 'static,
+    {
+        let matcher = Arc::new(matcher);
+        let (sender, items) = mpsc::unbounded_channel();
+
+        let id = self.register({
+            let matcher = Arc::clone(&matcher);
+            let sender = sender.clone();
+
+            Box::new(move |block: &B| match matcher(block) {
+                Some(item) => sender.send(Ok(item)).is_ok(),
+                None => true,
+            })
+        });
+
+        // The scanner only sees blocks that are mined from now on, we look
+        // into the past on our own.
+        if let Some(start_of_swap) = start_of_swap {
+            tokio::spawn(look_into_the_past(
+                self.connector.clone(),
+                start_of_swap,
+                matcher,
+                sender,
+            ));
+        }
+
+        let registry = Arc::downgrade(&self.registry);
+        Watch::new(
+            items,
+            Box::new(move || {
+                if let Some(registry) = registry.upgrade() {
+                    registry
+                        .lock()
+                        .expect("scanner registry lock poisoned")
+                        .watches
+                        .remove(&id);
+                }
+            }),
+        )
+    }
+
+    /// Resolves once the block matched by `contains` has `required`
+    /// confirmations, or as soon as no block back to the start of the swap
+    /// matches anymore.
+    pub async fn confirmation<F>(
+        &self,
+        start_of_swap: NaiveDateTime,
+        required: u32,
+        contains: F,
+    ) -> anyhow::Result<Confirmation>
+    where
+        F: Fn(&B) -> bool,
+    {
+        // We look at the chain again whenever a new block is mined.
+        let mut new_blocks = self.watch(None, |_: &B| Some(()));
+        let mut connector = self.connector.clone();
+
+        loop {
+            match confirmations(&mut connector, start_of_swap, &contains).await? {
+                Some((confirmations, block_time)) if confirmations >= required => {
+                    return Ok(Confirmation::Confirmed { block_time })
+                }
+                Some(_) => {}
+                None => return Ok(Confirmation::Orphaned),
+            }
+
+            new_blocks.next().await?;
+        }
+    }
+
+    fn register(&self, dispatch: Dispatch<B>) -> u64 {
+        let mut registry = self
+            .registry
+            .lock()
+            .expect("scanner registry lock poisoned");
+
+        let id = registry.next_id;
+        registry.next_id += 1;
+        registry.watches.insert(id, dispatch);
+
+        if !registry.running {
+            registry.running = true;
+            tokio::spawn(follow_chain(
+                self.connector.clone(),
+                Arc::clone(&self.registry),
+            ));
+        }
+
+        id
+    }
+}
+
+/// A registration with a `Scanner`.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct Watch<T> {
+    #[derivative(Debug = "ignore")]
+    items: mpsc::UnboundedReceiver<anyhow::Result<T>>,
+    #[derivative(Debug = "ignore")]
+    deregister: Option<Box<dyn FnOnce() + Send + Sync>>,
+}
+
+impl<T> Watch<T> {
+    /// A watch that yields what is sent through `items` and calls
+    /// `deregister` when it is dropped.
+    pub(super) fn new(
+        items: mpsc::UnboundedReceiver<anyhow::Result<T>>,
+        deregister: Box<dyn FnOnce() + Send + Sync>,
+    ) -> Self {
+        Self {
+            items,
+            deregister: Some(deregister),
+        }
+    }
+
+    /// A watch that never yields anything.
+    pub fn never() -> Self
+    where
+        T: Send + 'static,
+    {
+        let (sender, items) = mpsc::unbounded_channel();
+
+        // The channel stays open as long as the sender lives.
+        Self::new(items, Box::new(move || drop(sender)))
+    }
+
+    /// Resolves with the next match, in no particular order.
+    pub async fn next(&mut self) -> anyhow::Result<T> {
+        match self.items.recv().await {
+            Some(item) => item,
+            None => Err(anyhow::anyhow!("watch stopped dispatching")),
+        }
+    }
+}
+
+impl<T> Drop for Watch<T> {
+    fn drop(&mut self) {
+        if let Some(deregister) = self.deregister.take() {
+            deregister()
+        }
+    }
+}
+
+async fn follow_chain<C, B, L>(mut connector: C, registry: Arc<Mutex<Registry<B>>>)
+where
+    C: LatestBlock<Block = L> + BlockByHash<Block = L, BlockHash = B::Hash>,
+    B: LinkedBlock,
+    L: Into<Option<B>>,
+{
+    let mut seen_blocks = LruCache::new(SEEN_BLOCKS_CAPACITY);
+
+    loop {
+        {
+            let mut registry = registry.lock().expect("scanner registry lock poisoned");
+            if registry.watches.is_empty() {
+                registry.running = false;
+                return;
+            }
+        }
+
+        match new_blocks(&mut connector, &mut seen_blocks).await {
+            Ok(blocks) => {
+                let mut registry = registry.lock().expect("scanner registry lock poisoned");
+                for block in blocks.iter() {
+                    registry.dispatch(block);
+                }
+            }
+            Err(e) => tracing::warn!("failed to fetch new blocks: {:?}", e),
+        }
+
+        if let Err(e) = connector.wait_for_new_block().compat().await {
+            tracing::warn!("failed to wait for a new block: {:?}", e);
+            tokio::time::delay_for(POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Fetches the blocks that were mined since the last call, oldest first.
+///
+/// On the first call, this is only the latest block. Afterwards we walk back
+/// from the latest block until we reach one we have seen.
+async fn new_blocks<C, B, L>(
+    connector: &mut C,
+    seen_blocks: &mut LruCache<B::Hash, ()>,
+) -> anyhow::Result<Vec<B>>
+where
+    C: LatestBlock<Block = L> + BlockByHash<Block = L, BlockHash = B::Hash>,
+    B: LinkedBlock,
+    L: Into<Option<B>>,
+{
+    let latest_block: Option<B> = connector.latest_block().compat().await?.into();
+    let latest_block =
+        latest_block.ok_or_else(|| anyhow::anyhow!("Connector returned null latest block"))?;
+    let latest_hash = latest_block
+        .block_hash()
+        .ok_or_else(|| anyhow::anyhow!("Connector returned latest block without hash"))?;
+
+    if seen_blocks.contains(&latest_hash) {
+        return Ok(Vec::new());
+    }
+
+    let first_call = seen_blocks.is_empty();
+    let mut blocks = vec![latest_block];
+
+    while !first_call && blocks.len() < MAX_MISSED_BLOCKS {
+        let parent_hash = blocks[blocks.len() - 1].parent_hash();
+        if seen_blocks.contains(&parent_hash) {
+            break;
+        }
+
+        let parent: Option<B> = connector.block_by_hash(parent_hash).compat().await?.into();
+        let parent = parent
+            .ok_or_else(|| anyhow::anyhow!("Could not fetch block with hash {:?}", parent_hash))?;
+        blocks.push(parent);
+    }
+
+    blocks.reverse();
+    for block in blocks.iter() {
+        if let Some(hash) = block.block_hash() {
+            seen_blocks.put(hash, ());
+        }
+    }
+
+    Ok(blocks)
+}
+
+/// Returns the number of confirmations of the block matched by `contains`, the
+/// latest block having one, together with the time of that block. `None` if no
+/// block back to the one that predates `start_of_swap` matches.
+async fn confirmations<C, B, L, F>(
+    connector: &mut C,
+    start_of_swap: NaiveDateTime,
+    contains: &F,
+) -> anyhow::Result<Option<(u32, NaiveDateTime)>>
+where
+    C: LatestBlock<Block = L> + BlockByHash<Block = L, BlockHash = B::Hash>,
+    B: LinkedBlock,
+    L: Into<Option<B>>,
+    F: Fn(&B) -> bool,
+{
+    let latest_block: Option<B> = connector.latest_block().compat().await?.into();
+    let mut block =
+        latest_block.ok_or_else(|| anyhow::anyhow!("Connector returned null latest block"))?;
+    let mut confirmations = 1;
+
+    loop {
+        if contains(&block) {
+            return Ok(Some((confirmations, block.block_time())));
+        }
+
+        if block.predates(start_of_swap) {
+            return Ok(None);
+        }
+
+        let parent_hash = block.parent_hash();
+        let parent: Option<B> = connector.block_by_hash(parent_hash).compat().await?.into();
+        block = parent
+            .ok_or_else(|| anyhow::anyhow!("Could not fetch block with hash {:?}", parent_hash))?;
+        confirmations += 1;
+    }
+}
+
+async fn look_into_the_past<C, B, L, T, M>(
+    connector: C,
+    start_of_swap: NaiveDateTime,
+    matcher: Arc<M>,
+    sender: mpsc::UnboundedSender<anyhow::Result<T>>,
+) where
+    C: LatestBlock<Block = L> + BlockByHash<Block = L, BlockHash = B::Hash>,
+    B: LinkedBlock,
+    L: Into<Option<B>>,
+    M: Fn(&B) -> Option<T>,
+{
+    if let Err(e) =
+        walk_back_until_start_of_swap(connector, start_of_swap, &*matcher, &sender).await
+    {
+        let _ = sender.send(Err(e));
+    }
+}
+
+async fn walk_back_until_start_of_swap<C, B, L, T, M>(
+    mut connector: C,
+    start_of_swap: NaiveDateTime,
+    matcher: &M,
+    sender: &mpsc::UnboundedSender<anyhow::Result<T>>,
+) -> anyhow::Result<()>
+where
+    C: LatestBlock<Block = L> + BlockByHash<Block = L, BlockHash = B::Hash>,
+    B: LinkedBlock,
+    L: Into<Option<B>>,
+    M: Fn(&B) -> Option<T>,
+{
+    let latest_block: Option<B> = connector.latest_block().compat().await?.into();
+    let mut block =
+        latest_block.ok_or_else(|| anyhow::anyhow!("Connector returned null latest block"))?;
+
+    loop {
+        if let Some(item) = matcher(&block) {
+            if sender.send(Ok(item)).is_err() {
+                // The watch is gone, nobody is interested in the past anymore.
+                return Ok(());
+            }
+        }
+
+        if block.predates(start_of_swap) {
+            return Ok(());
+        }
+
+        let parent_hash = block.parent_hash();
+        let parent: Option<B> = connector.block_by_hash(parent_hash).compat().await?.into();
+        block = parent
+            .ok_or_else(|| anyhow::anyhow!("Could not fetch block with hash {:?}", parent_hash))?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{future::IntoFuture, Future};
+    use spectral::prelude::*;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct TestBlock {
+        hash: u32,
+        parent: u32,
+        time: i64,
+    }
+
+    impl Predates for TestBlock {
+        fn predates(&self, timestamp: NaiveDateTime) -> bool {
+            self.time < timestamp.timestamp()
+        }
+    }
+
+    impl LinkedBlock for TestBlock {
+        type Hash = u32;
+
+        fn block_hash(&self) -> Option<Self::Hash> {
+            Some(self.hash)
+        }
+
+        fn parent_hash(&self) -> Self::Hash {
+            self.parent
+        }
+
+        fn block_time(&self) -> NaiveDateTime {
+            NaiveDateTime::from_timestamp(self.time, 0)
+        }
+    }
+
+    /// A chain that does not grow, the last block is the latest one.
+    #[derive(Clone, Debug)]
+    struct StaticChain(Vec<TestBlock>);
+
+    impl LatestBlock for StaticChain {
+        type Block = TestBlock;
+        type BlockHash = u32;
+
+        fn latest_block(
+            &mut self,
+        ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
+            Box::new(Ok(self.0[self.0.len() - 1].clone()).into_future())
+        }
+    }
+
+    impl BlockByHash for StaticChain {
+        type Block = TestBlock;
+        type BlockHash = u32;
+
+        fn block_by_hash(
+            &self,
+            block_hash: Self::BlockHash,
+        ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
+            let block = self
+                .0
+                .iter()
+                .find(|block| block.hash == block_hash)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("unknown block {}", block_hash));
+
+            Box::new(block.into_future())
+        }
+    }
+
+    fn scanner() -> Scanner<StaticChain, TestBlock> {
+        Scanner::new(StaticChain(vec![
+            TestBlock {
+                hash: 1,
+                parent: 0,
+                time: 10,
+            },
+            TestBlock {
+                hash: 2,
+                parent: 1,
+                time: 20,
+            },
+            TestBlock {
+                hash: 3,
+                parent: 2,
+                time: 30,
+            },
+        ]))
+    }
+
+    fn number_of_watches<C, B>(scanner: &Scanner<C, B>) -> usize {
+        scanner.registry.lock().unwrap().watches.len()
+    }
+
+    #[test]
+    fn every_watch_sees_blocks_since_start_of_swap() {
+        let scanner = scanner();
+        let start_of_swap = NaiveDateTime::from_timestamp(20, 0);
+
+        let (first, second) = tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let mut first = scanner.watch(Some(start_of_swap), |block: &TestBlock| {
+                Some(block.hash).filter(|hash| *hash == 2)
+            });
+            let mut second = scanner.watch(Some(start_of_swap), |block: &TestBlock| {
+                Some(block.hash).filter(|hash| *hash == 3)
+            });
+
+            (first.next().await, second.next().await)
+        });
+
+        assert_that(&first).is_ok().is_equal_to(2);
+        assert_that(&second).is_ok().is_equal_to(3);
+    }
+
+    #[test]
+    fn block_below_required_depth_is_confirmed() {
+        let scanner = scanner();
+        let start_of_swap = NaiveDateTime::from_timestamp(20, 0);
+
+        let confirmation = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(scanner.confirmation(start_of_swap, 2, |block: &TestBlock| block.hash == 2));
+
+        assert_that(&confirmation)
+            .is_ok()
+            .is_equal_to(Confirmation::Confirmed {
+                block_time: NaiveDateTime::from_timestamp(20, 0),
+            });
+    }
+
+    #[test]
+    fn transaction_missing_from_chain_is_orphaned() {
+        let scanner = scanner();
+        let start_of_swap = NaiveDateTime::from_timestamp(20, 0);
+
+        let confirmation = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(scanner.confirmation(start_of_swap, 1, |block: &TestBlock| block.hash == 4));
+
+        assert_that(&confirmation)
+            .is_ok()
+            .is_equal_to(Confirmation::Orphaned);
+    }
+
+    #[test]
+    fn dropping_a_watch_deregisters_it() {
+        let scanner = scanner();
+
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let first = scanner.watch(None, |_: &TestBlock| Some(()));
+            let second = scanner.watch(None, |_: &TestBlock| Some(()));
+            assert_that(&number_of_watches(&scanner)).is_equal_to(2);
+
+            drop(first);
+            assert_that(&number_of_watches(&scanner)).is_equal_to(1);
+
+            drop(second);
+            assert_that(&number_of_watches(&scanner)).is_equal_to(0);
+        });
+    }
+}
@@ -1,7 +1,11 @@
 use crate::{
     asset::{self, AssetKind},
     libp2p_comit_ext::{FromHeader, ToHeader},
-    swap_protocols::{ledger::Ethereum, rfc003::messages::Decision, SwapId, SwapProtocol},
+    swap_protocols::{
+        ledger::{litecoin, Ethereum, Lightning, Litecoin},
+        rfc003::messages::Decision,
+        SwapId, SwapProtocol,
+    },
 };
 use libp2p_comit::frame::Header;
 use serde::de::Error;
@@ -14,6 +18,8 @@ pub enum LedgerKind {
     BitcoinTestnet,
     BitcoinRegtest,
     Ethereum(Ethereum),
+    Lightning(Lightning),
+    Litecoin(Litecoin),
 }
 
 impl FromHeader for LedgerKind {
@@ -30,6 +36,26 @@ impl FromHeader for LedgerKind {
                 }
             },
             "ethereum" => LedgerKind::Ethereum(Ethereum::new(header.take_parameter("chain_id")?)),
+            "lightning" => match header.take_parameter::<String>("network")?.as_ref() {
+                "mainnet" => LedgerKind::Lightning(Lightning::new(::bitcoin::Network::Bitcoin)),
+                "testnet" => LedgerKind::Lightning(Lightning::new(::bitcoin::Network::Testnet)),
+                "regtest" => LedgerKind::Lightning(Lightning::new(::bitcoin::Network::Regtest)),
+                _ => {
+                    return Err(serde_json::Error::custom(
+                        "unexpected lightning network variant",
+                    ))
+                }
+            },
+            "litecoin" => match header.take_parameter::<String>("network")?.as_ref() {
+                "mainnet" => LedgerKind::Litecoin(Litecoin::new(litecoin::Network::Mainnet)),
+                "testnet" => LedgerKind::Litecoin(Litecoin::new(litecoin::Network::Testnet)),
+                "regtest" => LedgerKind::Litecoin(Litecoin::new(litecoin::Network::Regtest)),
+                _ => {
+                    return Err(serde_json::Error::custom(
+                        "unexpected litecoin network variant",
+                    ))
+                }
+            },
             unknown => {
                 return Err(serde_json::Error::custom(format!(
                     "unknown ledger: {}",
@@ -57,6 +83,19 @@ impl ToHeader for LedgerKind {
             LedgerKind::Ethereum(ethereum) => {
                 Header::with_str_value("ethereum").with_parameter("chain_id", ethereum.chain_id)?
             }
+
+            LedgerKind::Lightning(lightning) => {
+                let network = match lightning.network {
+                    ::bitcoin::Network::Bitcoin => "mainnet",
+                    ::bitcoin::Network::Testnet => "testnet",
+                    ::bitcoin::Network::Regtest => "regtest",
+                };
+
+                Header::with_str_value("lightning").with_parameter("network", network)?
+            }
+
+            LedgerKind::Litecoin(litecoin) => Header::with_str_value("litecoin")
+                .with_parameter("network", litecoin.network.to_string())?,
         })
     }
 }
@@ -112,6 +151,13 @@ impl FromHeader for AssetKind {
                 header.take_parameter("address")?,
                 header.take_parameter("quantity")?,
             )),
+            "litecoin" => {
+                let quantity = header.take_parameter::<String>("quantity")?;
+                let quantity =
+                    u64::from_str(quantity.as_str()).map_err(serde_json::Error::custom)?;
+
+                AssetKind::Litecoin(asset::Litecoin::from_litoshi(quantity))
+            }
             unknown => {
                 return Err(serde_json::Error::custom(format!(
                     "unknown asset: {}",
@@ -133,6 +179,8 @@ impl ToHeader for AssetKind {
             AssetKind::Erc20(erc20) => Header::with_str_value("erc20")
                 .with_parameter("address", erc20.token_contract.clone())?
                 .with_parameter("quantity", erc20.quantity.clone())?,
+            AssetKind::Litecoin(litecoin) => Header::with_str_value("litecoin")
+                .with_parameter("quantity", litecoin.as_litoshi().to_string())?,
         })
     }
 }
@@ -227,6 +275,20 @@ mod tests {
         assert_eq!(quantity, AssetKind::Bitcoin(amount));
     }
 
+    #[test]
+    fn litecoin_quantity_to_header_roundtrip() {
+        let quantity = AssetKind::Litecoin(asset::Litecoin::from_litoshi(100_000_000));
+        let header = quantity.to_header().unwrap();
+
+        assert_eq!(
+            header,
+            Header::with_str_value("litecoin")
+                .with_parameter("quantity", "100000000")
+                .unwrap()
+        );
+        assert_eq!(AssetKind::from_header(header).unwrap(), quantity);
+    }
+
     #[test]
     fn ethereum_ledger_to_header() {
         let ledger = LedgerKind::Ethereum(Ethereum::new(ethereum::ChainId::ropsten()));
@@ -275,4 +337,54 @@ mod tests {
         assert_eq!(serialized_headers, headers);
         assert_eq!(constructed_ledgerkinds, ledgerkinds);
     }
+
+    #[test]
+    fn lightning_ledger_to_header_roundtrip() {
+        let ledger = LedgerKind::Lightning(Lightning::new(::bitcoin::Network::Testnet));
+
+        let header = ledger.to_header().unwrap();
+        assert_eq!(
+            header,
+            Header::with_str_value("lightning")
+                .with_parameter("network", "testnet")
+                .unwrap()
+        );
+
+        let constructed_ledger = LedgerKind::from_header(header).unwrap();
+        assert_eq!(constructed_ledger, ledger);
+    }
+
+    #[test]
+    fn unknown_lightning_network_from_header_fails() {
+        let header = Header::with_str_value("lightning")
+            .with_parameter("network", "signet")
+            .unwrap();
+
+        assert!(LedgerKind::from_header(header).is_err());
+    }
+
+    #[test]
+    fn litecoin_ledger_to_header_roundtrip() {
+        let ledger = LedgerKind::Litecoin(Litecoin::new(litecoin::Network::Regtest));
+
+        let header = ledger.to_header().unwrap();
+        assert_eq!(
+            header,
+            Header::with_str_value("litecoin")
+                .with_parameter("network", "regtest")
+                .unwrap()
+        );
+
+        let constructed_ledger = LedgerKind::from_header(header).unwrap();
+        assert_eq!(constructed_ledger, ledger);
+    }
+
+    #[test]
+    fn unknown_litecoin_network_from_header_fails() {
+        let header = Header::with_str_value("litecoin")
+            .with_parameter("network", "signet")
+            .unwrap();
+
+        assert!(LedgerKind::from_header(header).is_err());
+    }
 }
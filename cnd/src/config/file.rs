@@ -1,6 +1,9 @@
 use crate::{
-    config::{Bitcoind, Data, Network, Parity, Socket},
-    swap_protocols::ledger::ethereum,
+    config::{
+        Autopilot, BitcoinWallet, Bitcoind, Data, Esplora, EthereumChain, EthereumWallet,
+        Lightning, Litecoin, Network, Parity, Policy, Socket,
+    },
+    swap_protocols::{ledger::ethereum, rfc003::expiries::ExpiryMargins},
 };
 use config as config_rs;
 use log::LevelFilter;
@@ -20,19 +23,33 @@ pub struct File {
     pub logging: Option<Logging>,
     pub bitcoin: Option<Bitcoin>,
     pub ethereum: Option<Ethereum>,
+    pub lightning: Option<Lightning>,
+    pub litecoin: Option<Litecoin>,
+    pub autopilot: Option<Autopilot>,
+    pub policy: Option<Policy>,
+    pub expiry_margins: Option<ExpiryMargins>,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Bitcoin {
     #[serde(with = "crate::config::serde_bitcoin_network")]
     pub network: bitcoin::Network,
+    pub confirmations: Option<u32>,
+    pub mempool: Option<bool>,
     pub bitcoind: Option<Bitcoind>,
+    pub esplora: Option<Esplora>,
+    pub wallet: Option<BitcoinWallet>,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Ethereum {
     pub chain_id: ethereum::ChainId,
+    pub confirmations: Option<u32>,
+    pub mempool: Option<bool>,
     pub parity: Option<Parity>,
+    pub wallet: Option<EthereumWallet>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub other_chains: Vec<EthereumChain>,
 }
 
 impl File {
@@ -44,6 +61,11 @@ impl File {
             logging: Option::None,
             bitcoin: Option::None,
             ethereum: Option::None,
+            lightning: Option::None,
+            litecoin: Option::None,
+            autopilot: Option::None,
+            policy: Option::None,
+            expiry_margins: Option::None,
         }
     }
 
@@ -130,8 +152,11 @@ pub enum None {
 mod tests {
     use super::*;
     use crate::{
-        config::{Bitcoind, Parity, Settings},
-        swap_protocols::ledger::ethereum,
+        config::{
+            Bitcoind, BitcoindRpc, BlockCache, Litecoind, Lnd, Parity, PolicyAsset, PolicyLedger,
+            PolicyRule, Settings,
+        },
+        swap_protocols::ledger::{ethereum, litecoin},
     };
     use reqwest::Url;
     use spectral::prelude::*;
@@ -199,20 +224,77 @@ allowed_origins = "all"
 [data]
 dir = "/tmp/comit/"
 
+[data.block_cache]
+max_size_mb = 512
+
 [logging]
 level = "Debug"
 
 [bitcoin]
 network = "regtest"
+confirmations = 3
+mempool = true
 
 [bitcoin.bitcoind]
 node_url = "http://localhost:18443/"
+fallback_node_urls = ["http://localhost:18444/"]
+zmq_url = "tcp://127.0.0.1:28332"
+
+[bitcoin.bitcoind.rpc]
+cookie_file = "/home/comit/.bitcoin/regtest/.cookie"
+
+[bitcoin.esplora]
+url = "http://localhost:3002/"
 
 [ethereum]
 chain_id = 17
+confirmations = 12
+mempool = true
 
 [ethereum.parity]
 node_url = "http://localhost:8545/"
+fallback_node_urls = ["http://localhost:8555/"]
+ws_url = "ws://localhost:8546/"
+ipc_path = "/home/comit/.ethereum/geth.ipc"
+
+[[ethereum.other_chains]]
+chain_id = 3
+
+[ethereum.other_chains.parity]
+node_url = "http://localhost:8645/"
+
+[lightning]
+network = "regtest"
+
+[lightning.lnd]
+rest_url = "https://localhost:8080/"
+tls_cert_path = "/home/comit/.lnd/tls.cert"
+macaroon_path = "/home/comit/.lnd/data/chain/bitcoin/regtest/admin.macaroon"
+
+[litecoin]
+network = "regtest"
+confirmations = 6
+
+[litecoin.litecoind]
+node_url = "http://localhost:19443/"
+
+[autopilot]
+redeem = true
+
+[policy]
+ethereum_identity = "0x00a329c0648769a73afac7f9381e08fb43dbea72"
+
+[[policy.rules]]
+alpha_ledger = "bitcoin"
+beta_ledger = "ethereum"
+alpha_asset = "bitcoin"
+beta_asset = "ether"
+min_alpha_quantity = "100000"
+min_rate = "0.00000001"
+min_expiry_distance_secs = 3600
+
+[expiry_margins]
+min_gap_secs = 7200
 "#;
 
         let file = File {
@@ -230,21 +312,96 @@ node_url = "http://localhost:8545/"
             }),
             data: Some(Data {
                 dir: PathBuf::from("/tmp/comit/"),
+                block_cache: Some(BlockCache { max_size_mb: 512 }),
             }),
             logging: Some(Logging {
                 level: Some(Level::Debug),
             }),
             bitcoin: Some(Bitcoin {
                 network: bitcoin::Network::Regtest,
+                confirmations: Some(3),
+                mempool: Some(true),
                 bitcoind: Some(Bitcoind {
                     node_url: "http://localhost:18443".parse().unwrap(),
+                    fallback_node_urls: vec!["http://localhost:18444".parse().unwrap()],
+                    zmq_url: Some("tcp://127.0.0.1:28332".parse().unwrap()),
+                    rpc: Some(BitcoindRpc {
+                        cookie_file: Some(PathBuf::from("/home/comit/.bitcoin/regtest/.cookie")),
+                        user: None,
+                        password: None,
+                    }),
                 }),
+                esplora: Some(Esplora {
+                    url: "http://localhost:3002".parse().unwrap(),
+                }),
+                wallet: None,
             }),
             ethereum: Some(Ethereum {
                 chain_id: ethereum::ChainId::regtest(),
+                confirmations: Some(12),
+                mempool: Some(true),
                 parity: Some(Parity {
                     node_url: "http://localhost:8545".parse().unwrap(),
+                    fallback_node_urls: vec!["http://localhost:8555".parse().unwrap()],
+                    ws_url: Some("ws://localhost:8546".parse().unwrap()),
+                    ipc_path: Some(PathBuf::from("/home/comit/.ethereum/geth.ipc")),
                 }),
+                wallet: None,
+                other_chains: vec![EthereumChain {
+                    chain_id: ethereum::ChainId::ropsten(),
+                    parity: Parity {
+                        node_url: "http://localhost:8645".parse().unwrap(),
+                        fallback_node_urls: vec![],
+                        ws_url: None,
+                        ipc_path: None,
+                    },
+                }],
+            }),
+            lightning: Some(Lightning {
+                network: bitcoin::Network::Regtest,
+                lnd: Lnd {
+                    rest_url: "https://localhost:8080".parse().unwrap(),
+                    tls_cert_path: PathBuf::from("/home/comit/.lnd/tls.cert"),
+                    macaroon_path: PathBuf::from(
+                        "/home/comit/.lnd/data/chain/bitcoin/regtest/admin.macaroon",
+                    ),
+                },
+            }),
+            litecoin: Some(Litecoin {
+                network: litecoin::Network::Regtest,
+                confirmations: Some(6),
+                litecoind: Litecoind {
+                    node_url: "http://localhost:19443".parse().unwrap(),
+                },
+            }),
+            autopilot: Some(Autopilot {
+                redeem: true,
+                refund: false,
+            }),
+            policy: Some(Policy {
+                ethereum_identity: Some(
+                    "00a329c0648769a73afac7f9381e08fb43dbea72".parse().unwrap(),
+                ),
+                rules: vec![PolicyRule {
+                    alpha_ledger: PolicyLedger::Bitcoin,
+                    beta_ledger: PolicyLedger::Ethereum,
+                    alpha_asset: PolicyAsset::Bitcoin,
+                    beta_asset: PolicyAsset::Ether,
+                    alpha_token_contract: None,
+                    beta_token_contract: None,
+                    peers: None,
+                    min_alpha_quantity: Some(100_000u32.into()),
+                    max_alpha_quantity: None,
+                    min_beta_quantity: None,
+                    max_beta_quantity: None,
+                    min_rate: Some("0.00000001".parse().unwrap()),
+                    max_rate: None,
+                    min_expiry_distance_secs: Some(3600),
+                }],
+            }),
+            expiry_margins: Some(ExpiryMargins {
+                min_gap_secs: 7200,
+                ..ExpiryMargins::default()
             }),
         };
 
@@ -269,6 +426,30 @@ node_url = "http://localhost:8545/"
         assert_eq!(file, file_with_effective_settings)
     }
 
+    #[test]
+    fn bitcoind_rpc_password_is_not_serialized() {
+        let rpc = BitcoindRpc {
+            cookie_file: None,
+            user: Some("bitcoin".to_owned()),
+            password: Some("secret".to_owned()),
+        };
+
+        let serialized = toml::to_string(&rpc).unwrap();
+
+        assert_that(&serialized.contains("password")).is_false();
+    }
+
+    #[test]
+    fn ethereum_private_key_is_not_serialized() {
+        let wallet = EthereumWallet {
+            private_key: Some(secp256k1::SecretKey::from_slice(&[0x46; 32]).unwrap()),
+        };
+
+        let serialized = toml::to_string(&wallet).unwrap();
+
+        assert_that(&serialized.contains("private_key")).is_false();
+    }
+
     #[test]
     fn bitcoin_deserializes_correctly() {
         let file_contents = vec![
@@ -287,25 +468,68 @@ node_url = "http://localhost:8545/"
             [bitcoind]
             node_url = "http://example.com:18443"
             "#,
+            r#"
+            network = "regtest"
+            [bitcoind]
+            node_url = "http://example.com:18443"
+            [wallet]
+            fallback_fee_per_wu = 10
+            "#,
         ];
 
         let expected = vec![
             Bitcoin {
                 network: bitcoin::Network::Bitcoin,
+                confirmations: None,
+                mempool: None,
                 bitcoind: Some(Bitcoind {
                     node_url: Url::parse("http://example.com:8332").unwrap(),
+                    fallback_node_urls: vec![],
+                    zmq_url: None,
+                    rpc: None,
                 }),
+                esplora: None,
+                wallet: None,
             },
             Bitcoin {
                 network: bitcoin::Network::Testnet,
+                confirmations: None,
+                mempool: None,
                 bitcoind: Some(Bitcoind {
                     node_url: Url::parse("http://example.com:18332").unwrap(),
+                    fallback_node_urls: vec![],
+                    zmq_url: None,
+                    rpc: None,
+                }),
+                esplora: None,
+                wallet: None,
+            },
+            Bitcoin {
+                network: bitcoin::Network::Regtest,
+                confirmations: None,
+                mempool: None,
+                bitcoind: Some(Bitcoind {
+                    node_url: Url::parse("http://example.com:18443").unwrap(),
+                    fallback_node_urls: vec![],
+                    zmq_url: None,
+                    rpc: None,
                 }),
+                esplora: None,
+                wallet: None,
             },
             Bitcoin {
                 network: bitcoin::Network::Regtest,
+                confirmations: None,
+                mempool: None,
                 bitcoind: Some(Bitcoind {
                     node_url: Url::parse("http://example.com:18443").unwrap(),
+                    fallback_node_urls: vec![],
+                    zmq_url: None,
+                    rpc: None,
+                }),
+                esplora: None,
+                wallet: Some(BitcoinWallet {
+                    fallback_fee_per_wu: 10,
                 }),
             },
         ];
@@ -337,26 +561,106 @@ node_url = "http://localhost:8545/"
             [parity]
             node_url = "http://example.com:8545"
             "#,
+            r#"
+            chain_id = 17
+            [parity]
+            node_url = "http://example.com:8545"
+            [wallet]
+            "#,
+            r#"
+            chain_id = 17
+            [parity]
+            node_url = "http://example.com:8545"
+            [wallet]
+            private_key = "4646464646464646464646464646464646464646464646464646464646464646"
+            "#,
+            r#"
+            chain_id = 17
+            [parity]
+            ipc_path = "/home/comit/.ethereum/geth.ipc"
+            "#,
         ];
 
         let expected = vec![
             Ethereum {
                 chain_id: ethereum::ChainId::regtest(),
+                confirmations: None,
+                mempool: None,
                 parity: Some(Parity {
                     node_url: Url::parse("http://example.com:8545").unwrap(),
+                    fallback_node_urls: vec![],
+                    ws_url: None,
+                    ipc_path: None,
                 }),
+                wallet: None,
+                other_chains: vec![],
             },
             Ethereum {
                 chain_id: ethereum::ChainId::ropsten(),
+                confirmations: None,
+                mempool: None,
                 parity: Some(Parity {
                     node_url: Url::parse("http://example.com:8545").unwrap(),
+                    fallback_node_urls: vec![],
+                    ws_url: None,
+                    ipc_path: None,
                 }),
+                wallet: None,
+                other_chains: vec![],
             },
             Ethereum {
                 chain_id: ethereum::ChainId::mainnet(),
+                confirmations: None,
+                mempool: None,
+                parity: Some(Parity {
+                    node_url: Url::parse("http://example.com:8545").unwrap(),
+                    fallback_node_urls: vec![],
+                    ws_url: None,
+                    ipc_path: None,
+                }),
+                wallet: None,
+                other_chains: vec![],
+            },
+            Ethereum {
+                chain_id: ethereum::ChainId::regtest(),
+                confirmations: None,
+                mempool: None,
+                parity: Some(Parity {
+                    node_url: Url::parse("http://example.com:8545").unwrap(),
+                    fallback_node_urls: vec![],
+                    ws_url: None,
+                    ipc_path: None,
+                }),
+                wallet: Some(EthereumWallet { private_key: None }),
+                other_chains: vec![],
+            },
+            Ethereum {
+                chain_id: ethereum::ChainId::regtest(),
+                confirmations: None,
+                mempool: None,
                 parity: Some(Parity {
                     node_url: Url::parse("http://example.com:8545").unwrap(),
+                    fallback_node_urls: vec![],
+                    ws_url: None,
+                    ipc_path: None,
+                }),
+                wallet: Some(EthereumWallet {
+                    private_key: Some(secp256k1::SecretKey::from_slice(&[0x46; 32]).unwrap()),
+                }),
+                other_chains: vec![],
+            },
+            Ethereum {
+                chain_id: ethereum::ChainId::regtest(),
+                confirmations: None,
+                mempool: None,
+                parity: Some(Parity {
+                    node_url: Url::parse("http://localhost:8545").unwrap(),
+                    fallback_node_urls: vec![],
+                    ws_url: None,
+                    ipc_path: Some(PathBuf::from("/home/comit/.ethereum/geth.ipc")),
                 }),
+                wallet: None,
+                other_chains: vec![],
             },
         ];
 
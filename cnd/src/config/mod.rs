@@ -1,18 +1,34 @@
 pub mod file;
 mod serde_bitcoin_network;
+mod serde_option_from_str;
+mod serde_secret_key;
 pub mod settings;
 
-use crate::swap_protocols::ledger::ethereum;
+use crate::swap_protocols::ledger::{ethereum, litecoin};
+use bigdecimal::BigDecimal;
 use libp2p::Multiaddr;
+use num::BigUint;
 use serde::{Deserialize, Serialize};
 use std::{net::IpAddr, path::PathBuf};
 
 pub use self::{file::File, settings::Settings};
 use reqwest::Url;
 
+/// The number of blocks a transaction needs to be included in before cnd
+/// considers it final, unless configured otherwise for a ledger.
+pub const DEFAULT_CONFIRMATIONS: u32 = 1;
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Data {
     pub dir: PathBuf,
+    pub block_cache: Option<BlockCache>,
+}
+
+/// Keeps the blocks and receipts fetched from the ledgers in the data
+/// directory, up to `max_size_mb` megabytes for each chain.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BlockCache {
+    pub max_size_mb: u64,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -26,26 +42,171 @@ pub struct Socket {
     pub port: u16,
 }
 
+/// Lets cnd redeem and refund swaps by itself using the built-in wallets.
+///
+/// Redeeming happens as soon as the action becomes available, refunding as
+/// soon as the expiry of the HTLC has passed.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Autopilot {
+    #[serde(default)]
+    pub redeem: bool,
+    #[serde(default)]
+    pub refund: bool,
+}
+
+impl Autopilot {
+    pub fn is_enabled(&self) -> bool {
+        self.redeem || self.refund
+    }
+}
+
+/// Lets cnd answer inbound swap requests by itself.
+///
+/// The rules are tried in order and the first one that applies to a request
+/// decides it. Requests that no rule applies to are left for manual handling
+/// through the HTTP API.
+///
+/// Accepting a request requires an Ethereum identity. Unless one is
+/// configured here, the address of the built-in Ethereum wallet is used.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Policy {
+    pub ethereum_identity: Option<crate::ethereum::Address>,
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+}
+
+impl Policy {
+    pub fn is_enabled(&self) -> bool {
+        !self.rules.is_empty()
+    }
+}
+
+/// A rule applies to all requests for its ledgers and assets that come from
+/// one of its peers, or from any peer if none are given.
+///
+/// A request the rule applies to is accepted if it satisfies all bounds and
+/// declined otherwise. Quantities are given in the smallest unit of the asset
+/// and exchange rates as the quantity of alpha asset per quantity of beta
+/// asset, both in their smallest unit rather than in whole coins: 1 BTC for
+/// 50 ETH is a rate of 100_000_000 satoshi per 50 * 10^18 wei, i.e. 2e-12.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PolicyRule {
+    pub alpha_ledger: PolicyLedger,
+    pub beta_ledger: PolicyLedger,
+    pub alpha_asset: PolicyAsset,
+    pub beta_asset: PolicyAsset,
+    pub alpha_token_contract: Option<crate::ethereum::Address>,
+    pub beta_token_contract: Option<crate::ethereum::Address>,
+    pub peers: Option<Vec<String>>,
+    #[serde(default, with = "crate::config::serde_option_from_str")]
+    pub min_alpha_quantity: Option<BigUint>,
+    #[serde(default, with = "crate::config::serde_option_from_str")]
+    pub max_alpha_quantity: Option<BigUint>,
+    #[serde(default, with = "crate::config::serde_option_from_str")]
+    pub min_beta_quantity: Option<BigUint>,
+    #[serde(default, with = "crate::config::serde_option_from_str")]
+    pub max_beta_quantity: Option<BigUint>,
+    #[serde(default, with = "crate::config::serde_option_from_str")]
+    pub min_rate: Option<BigDecimal>,
+    #[serde(default, with = "crate::config::serde_option_from_str")]
+    pub max_rate: Option<BigDecimal>,
+    pub min_expiry_distance_secs: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyLedger {
+    Bitcoin,
+    Ethereum,
+    Lightning,
+    Litecoin,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyAsset {
+    Bitcoin,
+    Ether,
+    Erc20,
+    Litecoin,
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Bitcoin {
     #[serde(with = "crate::config::serde_bitcoin_network")]
     pub network: bitcoin::Network,
+    pub confirmations: u32,
+    pub mempool: bool,
     pub bitcoind: Bitcoind,
+    pub esplora: Option<Esplora>,
+    pub wallet: Option<BitcoinWallet>,
 }
 
+/// The REST interface of bitcoind, or its JSON-RPC interface if `rpc` is set.
+///
+/// Requests fall over to the nodes at `fallback_node_urls`, in that order,
+/// while the node at `node_url` is down, lagging behind or on another
+/// network. They are talked to the same way as `node_url`, with the same
+/// credentials.
+///
+/// If `zmq_url` is set, new blocks are learned through the `rawblock` and
+/// `hashblock` notifications bitcoind publishes there (`-zmqpubrawblock`,
+/// `-zmqpubhashblock`) instead of polling bitcoind.
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Bitcoind {
     pub node_url: reqwest::Url,
+    #[serde(default)]
+    pub fallback_node_urls: Vec<reqwest::Url>,
+    pub zmq_url: Option<reqwest::Url>,
+    pub rpc: Option<BitcoindRpc>,
+}
+
+/// Talk to bitcoind over JSON-RPC at `node_url`.
+///
+/// Authenticates with the `.cookie` file bitcoind writes to its data directory
+/// or with `rpcuser` and `rpcpassword`. Without either, the credentials of
+/// `node_url` are used, if any. The password is never serialized, hence
+/// `--dump-config` does not print it.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct BitcoindRpc {
+    pub cookie_file: Option<PathBuf>,
+    pub user: Option<String>,
+    #[serde(default, skip_serializing)]
+    pub password: Option<String>,
+}
+
+/// Follow the chain through an Esplora (Electrs) HTTP API instead of bitcoind.
+///
+/// bitcoind is then only needed for the built-in wallet.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Esplora {
+    pub url: reqwest::Url,
+}
+
+/// Enables the built-in Bitcoin wallet.
+///
+/// The fallback fee rate is used whenever bitcoind cannot estimate a fee,
+/// which is usually the case on regtest.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BitcoinWallet {
+    pub fallback_fee_per_wu: usize,
 }
 
 impl Default for Bitcoin {
     fn default() -> Self {
         Self {
             network: bitcoin::Network::Regtest,
+            confirmations: DEFAULT_CONFIRMATIONS,
+            mempool: false,
             bitcoind: Bitcoind {
                 node_url: Url::parse("http://localhost:18443")
                     .expect("static string to be a valid url"),
+                fallback_node_urls: vec![],
+                zmq_url: None,
+                rpc: None,
             },
+            esplora: None,
+            wallet: None,
         }
     }
 }
@@ -54,7 +215,11 @@ impl From<Bitcoin> for file::Bitcoin {
     fn from(bitcoin: Bitcoin) -> Self {
         file::Bitcoin {
             network: bitcoin.network,
+            confirmations: Some(bitcoin.confirmations),
+            mempool: Some(bitcoin.mempool),
             bitcoind: Some(bitcoin.bitcoind),
+            esplora: bitcoin.esplora,
+            wallet: bitcoin.wallet,
         }
     }
 }
@@ -62,14 +227,22 @@ impl From<Bitcoin> for file::Bitcoin {
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Ethereum {
     pub chain_id: ethereum::ChainId,
+    pub confirmations: u32,
+    pub mempool: bool,
     pub parity: Parity,
+    pub wallet: Option<EthereumWallet>,
+    pub other_chains: Vec<EthereumChain>,
 }
 
 impl From<Ethereum> for file::Ethereum {
     fn from(ethereum: Ethereum) -> Self {
         file::Ethereum {
             chain_id: ethereum.chain_id,
+            confirmations: Some(ethereum.confirmations),
+            mempool: Some(ethereum.mempool),
             parity: Some(ethereum.parity),
+            wallet: ethereum.wallet,
+            other_chains: ethereum.other_chains,
         }
     }
 }
@@ -78,16 +251,102 @@ impl Default for Ethereum {
     fn default() -> Self {
         Self {
             chain_id: ethereum::ChainId::regtest(),
+            confirmations: DEFAULT_CONFIRMATIONS,
+            mempool: false,
             parity: Parity {
-                node_url: Url::parse("http://localhost:8545")
-                    .expect("static string to be a valid url"),
+                node_url: default_parity_node_url(),
+                fallback_node_urls: vec![],
+                ws_url: None,
+                ipc_path: None,
             },
+            wallet: None,
+            other_chains: vec![],
         }
     }
 }
 
+/// The JSON-RPC endpoints of the Ethereum node.
+///
+/// Requests fall over to the nodes at `fallback_node_urls`, in that order,
+/// while the node at `node_url` is down, lagging behind or on another chain.
+///
+/// If `ws_url` is set, new blocks are learned through a `newHeads`
+/// subscription on that websocket endpoint instead of polling `node_url`.
+///
+/// If `ipc_path` is set, the node is talked to through its IPC socket instead
+/// of `node_url`, which then does not need to be exposed at all.
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Parity {
+    #[serde(default = "default_parity_node_url")]
+    pub node_url: reqwest::Url,
+    #[serde(default)]
+    pub fallback_node_urls: Vec<reqwest::Url>,
+    pub ws_url: Option<reqwest::Url>,
+    pub ipc_path: Option<PathBuf>,
+}
+
+fn default_parity_node_url() -> Url {
+    Url::parse("http://localhost:8545").expect("static string to be a valid url")
+}
+
+/// Another Ethereum chain cnd follows, which allows swaps between two
+/// Ethereum chains.
+///
+/// The confirmations and mempool settings of the primary chain apply here as
+/// well. The built-in wallet only acts on the primary chain.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct EthereumChain {
+    pub chain_id: ethereum::ChainId,
+    pub parity: Parity,
+}
+
+/// Enables the built-in Ethereum wallet.
+///
+/// Without a private key, the account is derived from the seed of cnd. The
+/// private key is never serialized, hence `--dump-config` does not print it.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct EthereumWallet {
+    #[serde(
+        default,
+        skip_serializing,
+        deserialize_with = "crate::config::serde_secret_key::deserialize"
+    )]
+    pub private_key: Option<secp256k1::SecretKey>,
+}
+
+/// Connects cnd to the Lightning Network through an lnd node.
+///
+/// The node has to run on the configured network; cnd refuses to start
+/// otherwise.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Lightning {
+    #[serde(with = "crate::config::serde_bitcoin_network")]
+    pub network: bitcoin::Network,
+    pub lnd: Lnd,
+}
+
+/// lnd is talked to through its REST API which is secured with the TLS
+/// certificate of the node and authenticated with its admin macaroon.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Lnd {
+    pub rest_url: reqwest::Url,
+    pub tls_cert_path: PathBuf,
+    pub macaroon_path: PathBuf,
+}
+
+/// Connects cnd to the Litecoin blockchain through a litecoind node.
+///
+/// Litecoin swaps are watched like Bitcoin swaps, through the REST interface
+/// of the node.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Litecoin {
+    pub network: litecoin::Network,
+    pub confirmations: Option<u32>,
+    pub litecoind: Litecoind,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Litecoind {
     pub node_url: reqwest::Url,
 }
 
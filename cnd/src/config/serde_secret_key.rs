@@ -0,0 +1,15 @@
+use secp256k1::SecretKey;
+use serde::{de, Deserialize, Deserializer};
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<SecretKey>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|hex| {
+            let bytes = hex::decode(hex.trim_start_matches("0x")).map_err(de::Error::custom)?;
+
+            SecretKey::from_slice(&bytes).map_err(de::Error::custom)
+        })
+        .transpose()
+}
@@ -1,4 +1,10 @@
-use crate::config::{file, Bitcoin, Bitcoind, Data, Ethereum, File, Network, Parity, Socket};
+use crate::{
+    config::{
+        file, Autopilot, Bitcoin, Bitcoind, Data, Ethereum, File, Lightning, Litecoin, Network,
+        Parity, Policy, Socket, DEFAULT_CONFIRMATIONS,
+    },
+    swap_protocols::{ledger::ConnectedLedgers, rfc003::expiries::ExpiryMargins},
+};
 use anyhow::Context;
 use log::LevelFilter;
 use std::net::{IpAddr, Ipv4Addr};
@@ -17,50 +23,75 @@ pub struct Settings {
     pub logging: Logging,
     pub bitcoin: Bitcoin,
     pub ethereum: Ethereum,
+    pub lightning: Option<Lightning>,
+    pub litecoin: Option<Litecoin>,
+    pub autopilot: Autopilot,
+    pub policy: Policy,
+    pub expiry_margins: ExpiryMargins,
 }
 
 fn derive_url_bitcoin(bitcoin: Option<file::Bitcoin>) -> Bitcoin {
     match bitcoin {
         None => Bitcoin::default(),
         Some(bitcoin) => {
-            let node_url = match bitcoin.bitcoind {
-                Some(bitcoind) => bitcoind.node_url,
-                None => match bitcoin.network {
-                    bitcoin::Network::Bitcoin => "http://localhost:8332"
-                        .parse()
-                        .expect("to be valid static string"),
-                    bitcoin::Network::Testnet => "http://localhost:18332"
-                        .parse()
-                        .expect("to be valid static string"),
-                    bitcoin::Network::Regtest => "http://localhost:18443"
-                        .parse()
-                        .expect("to be valid static string"),
+            let bitcoind = match bitcoin.bitcoind {
+                Some(bitcoind) => bitcoind,
+                None => Bitcoind {
+                    node_url: default_bitcoind_url(bitcoin.network),
+                    fallback_node_urls: vec![],
+                    zmq_url: None,
+                    rpc: None,
                 },
             };
             Bitcoin {
                 network: bitcoin.network,
-                bitcoind: Bitcoind { node_url },
+                confirmations: bitcoin.confirmations.unwrap_or(DEFAULT_CONFIRMATIONS),
+                mempool: bitcoin.mempool.unwrap_or_default(),
+                bitcoind,
+                esplora: bitcoin.esplora,
+                wallet: bitcoin.wallet,
             }
         }
     }
 }
 
+fn default_bitcoind_url(network: bitcoin::Network) -> reqwest::Url {
+    match network {
+        bitcoin::Network::Bitcoin => "http://localhost:8332"
+            .parse()
+            .expect("to be valid static string"),
+        bitcoin::Network::Testnet => "http://localhost:18332"
+            .parse()
+            .expect("to be valid static string"),
+        bitcoin::Network::Regtest => "http://localhost:18443"
+            .parse()
+            .expect("to be valid static string"),
+    }
+}
+
 fn derive_url_ethereum(ethereum: Option<file::Ethereum>) -> Ethereum {
     match ethereum {
         None => Ethereum::default(),
         Some(ethereum) => {
-            let node_url = match ethereum.parity {
-                None => {
+            let parity = match ethereum.parity {
+                None => Parity {
                     // default is always localhost:8545
-                    "http://localhost:8545"
+                    node_url: "http://localhost:8545"
                         .parse()
-                        .expect("to be valid static string")
-                }
-                Some(parity) => parity.node_url,
+                        .expect("to be valid static string"),
+                    fallback_node_urls: vec![],
+                    ws_url: None,
+                    ipc_path: None,
+                },
+                Some(parity) => parity,
             };
             Ethereum {
                 chain_id: ethereum.chain_id,
-                parity: Parity { node_url },
+                confirmations: ethereum.confirmations.unwrap_or(DEFAULT_CONFIRMATIONS),
+                mempool: ethereum.mempool.unwrap_or_default(),
+                parity,
+                wallet: ethereum.wallet,
+                other_chains: ethereum.other_chains,
             }
         }
     }
@@ -75,6 +106,11 @@ impl From<Settings> for File {
             logging: Logging { level },
             bitcoin,
             ethereum,
+            lightning,
+            litecoin,
+            autopilot,
+            policy,
+            expiry_margins,
         } = settings;
 
         File {
@@ -95,6 +131,11 @@ impl From<Settings> for File {
             }),
             bitcoin: Some(bitcoin.into()),
             ethereum: Some(ethereum.into()),
+            lightning,
+            litecoin,
+            autopilot: Some(autopilot),
+            policy: Some(policy),
+            expiry_margins: Some(expiry_margins),
         }
     }
 }
@@ -145,6 +186,22 @@ pub struct Logging {
 }
 
 impl Settings {
+    pub fn connected_ledgers(&self) -> ConnectedLedgers {
+        ConnectedLedgers {
+            bitcoin: self.bitcoin.network,
+            ethereum: std::iter::once(self.ethereum.chain_id)
+                .chain(
+                    self.ethereum
+                        .other_chains
+                        .iter()
+                        .map(|chain| chain.chain_id),
+                )
+                .collect(),
+            lightning: self.lightning.as_ref().map(|lightning| lightning.network),
+            litecoin: self.litecoin.as_ref().map(|litecoin| litecoin.network),
+        }
+    }
+
     pub fn from_config_file_and_defaults(config_file: File) -> anyhow::Result<Self> {
         let File {
             network,
@@ -153,6 +210,11 @@ impl Settings {
             logging,
             bitcoin,
             ethereum,
+            lightning,
+            litecoin,
+            autopilot,
+            policy,
+            expiry_margins,
         } = config_file;
 
         Ok(Self {
@@ -189,6 +251,7 @@ impl Settings {
                     crate::data_dir().context("unable to determine default data path")?;
                 data.unwrap_or_else(|| Data {
                     dir: default_data_dir,
+                    block_cache: None,
                 })
             },
 
@@ -205,6 +268,11 @@ impl Settings {
             },
             bitcoin: derive_url_bitcoin(bitcoin),
             ethereum: derive_url_ethereum(ethereum),
+            lightning,
+            litecoin,
+            autopilot: autopilot.unwrap_or_default(),
+            policy: policy.unwrap_or_default(),
+            expiry_margins: expiry_margins.unwrap_or_default(),
         })
     }
 }
@@ -308,9 +376,16 @@ mod tests {
             .map(|settings| &settings.bitcoin)
             .is_equal_to(Bitcoin {
                 network: bitcoin::Network::Regtest,
+                confirmations: 1,
+                mempool: false,
                 bitcoind: Bitcoind {
                     node_url: "http://localhost:18443".parse().unwrap(),
+                    fallback_node_urls: vec![],
+                    zmq_url: None,
+                    rpc: None,
                 },
+                esplora: None,
+                wallet: None,
             })
     }
 
@@ -326,7 +401,11 @@ mod tests {
             let config_file = File {
                 bitcoin: Some(file::Bitcoin {
                     network,
+                    confirmations: None,
+                    mempool: None,
                     bitcoind: None,
+                    esplora: None,
+                    wallet: None,
                 }),
                 ..File::default()
             };
@@ -338,9 +417,16 @@ mod tests {
                 .map(|settings| &settings.bitcoin)
                 .is_equal_to(Bitcoin {
                     network,
+                    confirmations: 1,
+                    mempool: false,
                     bitcoind: Bitcoind {
                         node_url: url.parse().unwrap(),
+                        fallback_node_urls: vec![],
+                        zmq_url: None,
+                        rpc: None,
                     },
+                    esplora: None,
+                    wallet: None,
                 })
         }
     }
@@ -356,9 +442,16 @@ mod tests {
             .map(|settings| &settings.ethereum)
             .is_equal_to(Ethereum {
                 chain_id: ethereum::ChainId::regtest(),
+                confirmations: 1,
+                mempool: false,
                 parity: Parity {
                     node_url: "http://localhost:8545".parse().unwrap(),
+                    fallback_node_urls: vec![],
+                    ws_url: None,
+                    ipc_path: None,
                 },
+                wallet: None,
+                other_chains: vec![],
             })
     }
 
@@ -373,7 +466,11 @@ mod tests {
         for (chain_id, url) in defaults {
             let ethereum = Some(file::Ethereum {
                 chain_id,
+                confirmations: None,
+                mempool: None,
                 parity: None,
+                wallet: None,
+                other_chains: vec![],
             });
             let config_file = File {
                 ethereum,
@@ -387,9 +484,16 @@ mod tests {
                 .map(|settings| &settings.ethereum)
                 .is_equal_to(Ethereum {
                     chain_id,
+                    confirmations: 1,
+                    mempool: false,
                     parity: Parity {
                         node_url: url.parse().unwrap(),
+                        fallback_node_urls: vec![],
+                        ws_url: None,
+                        ipc_path: None,
                     },
+                    wallet: None,
+                    other_chains: vec![],
                 })
         }
     }
@@ -0,0 +1,114 @@
+use crate::{
+    db::{
+        schema::{self, rfc003_executed_actions},
+        wrapper_types::custom_sql_types::Text,
+        Sqlite,
+    },
+    swap_protocols::{rfc003::actions::ActionKind, SwapId},
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+
+/// An action that cnd executed with its built-in wallets on behalf of the
+/// user.
+///
+/// The outcome of a failed execution is an object with the `error` only.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutedAction {
+    pub action: ActionKind,
+    pub outcome: serde_json::Value,
+    pub at: NaiveDateTime,
+}
+
+impl ExecutedAction {
+    /// The outcome to record for an execution that failed with `error`.
+    pub fn failure(error: &anyhow::Error) -> serde_json::Value {
+        serde_json::json!({ "error": format!("{:#}", error) })
+    }
+
+    pub fn failed(&self) -> bool {
+        self.outcome.get("error").is_some()
+    }
+}
+
+/// Record and load the actions that were executed for a swap.
+#[async_trait]
+#[ambassador::delegatable_trait]
+pub trait ExecutedActions: Send + Sync + 'static {
+    async fn save_executed_action(
+        &self,
+        swap_id: &SwapId,
+        action: ActionKind,
+        outcome: serde_json::Value,
+    ) -> anyhow::Result<()>;
+    async fn load_executed_actions(&self, swap_id: &SwapId) -> anyhow::Result<Vec<ExecutedAction>>;
+}
+
+#[async_trait]
+impl ExecutedActions for Sqlite {
+    async fn save_executed_action(
+        &self,
+        swap_id: &SwapId,
+        action: ActionKind,
+        outcome: serde_json::Value,
+    ) -> anyhow::Result<()> {
+        let insertable = InsertableExecutedAction {
+            swap_id: Text(*swap_id),
+            action: Text(action),
+            outcome: outcome.to_string(),
+        };
+
+        self.do_in_transaction(|connection| {
+            diesel::insert_into(schema::rfc003_executed_actions::dsl::rfc003_executed_actions)
+                .values(&insertable)
+                .execute(&*connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn load_executed_actions(&self, swap_id: &SwapId) -> anyhow::Result<Vec<ExecutedAction>> {
+        use self::schema::rfc003_executed_actions as executed_actions;
+
+        let records: Vec<QueryableExecutedAction> = self
+            .do_in_transaction(|connection| {
+                let key = Text(swap_id);
+
+                executed_actions::table
+                    .filter(executed_actions::swap_id.eq(key))
+                    .order(executed_actions::id.asc())
+                    .load(connection)
+            })
+            .await?;
+
+        records
+            .into_iter()
+            .map(|record| -> anyhow::Result<ExecutedAction> {
+                Ok(ExecutedAction {
+                    action: *record.action,
+                    outcome: serde_json::from_str(&record.outcome)?,
+                    at: record.at,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "rfc003_executed_actions"]
+struct InsertableExecutedAction {
+    swap_id: Text<SwapId>,
+    action: Text<ActionKind>,
+    outcome: String,
+}
+
+#[derive(Queryable, Debug, Clone, PartialEq)]
+struct QueryableExecutedAction {
+    id: i32,
+    swap_id: Text<SwapId>,
+    action: Text<ActionKind>,
+    outcome: String,
+    at: NaiveDateTime,
+}
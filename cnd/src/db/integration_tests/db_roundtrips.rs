@@ -1,14 +1,22 @@
 use crate::{
-    asset::{Bitcoin as BitcoinAsset, Erc20, Ether},
+    asset::{Bitcoin as BitcoinAsset, Erc20, Ether, Litecoin as LitecoinAsset},
     db::{
         load_swaps::LoadAcceptedSwap,
         swap_types::{DetermineTypes, SwapTypes},
-        AssetKind, BitcoinLedgerKind, LedgerKind, Retrieve, Save, Sqlite, Swap,
+        AssetKind, BitcoinLedgerKind, ExecutedAction, ExecutedActions, LedgerKind, Retrieve, Save,
+        Sqlite, Swap, SwapEvents,
     },
+    ethereum::{Address, Transaction},
     quickcheck::Quickcheck,
     swap_protocols::{
-        ledger::Ethereum,
-        rfc003::{Accept, Ledger, Request},
+        ledger::{Ethereum, Litecoin},
+        rfc003::{
+            actions::ActionKind,
+            create_swap::{SwapEvent, SwapEventOnLedger},
+            events::{Deployed, Funded},
+            Accept, Ledger, Request,
+        },
+        SwapId,
     },
 };
 use std::path::Path;
@@ -133,3 +141,166 @@ db_roundtrip_test!(Ethereum, Mainnet, Erc20, BitcoinAsset, |role| {
         role,
     }
 });
+db_roundtrip_test!(Ethereum, Ethereum, Ether, Erc20, |role| {
+    SwapTypes {
+        alpha_ledger: LedgerKind::Ethereum,
+        beta_ledger: LedgerKind::Ethereum,
+        alpha_asset: AssetKind::Ether,
+        beta_asset: AssetKind::Erc20,
+        role,
+    }
+});
+db_roundtrip_test!(Ethereum, Ethereum, Erc20, Ether, |role| {
+    SwapTypes {
+        alpha_ledger: LedgerKind::Ethereum,
+        beta_ledger: LedgerKind::Ethereum,
+        alpha_asset: AssetKind::Erc20,
+        beta_asset: AssetKind::Ether,
+        role,
+    }
+});
+db_roundtrip_test!(Ethereum, Ethereum, Erc20, Erc20, |role| {
+    SwapTypes {
+        alpha_ledger: LedgerKind::Ethereum,
+        beta_ledger: LedgerKind::Ethereum,
+        alpha_asset: AssetKind::Erc20,
+        beta_asset: AssetKind::Erc20,
+        role,
+    }
+});
+db_roundtrip_test!(Litecoin, Ethereum, LitecoinAsset, Ether, |role| {
+    SwapTypes {
+        alpha_ledger: LedgerKind::Litecoin,
+        beta_ledger: LedgerKind::Ethereum,
+        alpha_asset: AssetKind::Litecoin,
+        beta_asset: AssetKind::Ether,
+        role,
+    }
+});
+db_roundtrip_test!(Ethereum, Litecoin, Ether, LitecoinAsset, |role| {
+    SwapTypes {
+        alpha_ledger: LedgerKind::Ethereum,
+        beta_ledger: LedgerKind::Litecoin,
+        alpha_asset: AssetKind::Ether,
+        beta_asset: AssetKind::Litecoin,
+        role,
+    }
+});
+db_roundtrip_test!(Litecoin, Ethereum, LitecoinAsset, Erc20, |role| {
+    SwapTypes {
+        alpha_ledger: LedgerKind::Litecoin,
+        beta_ledger: LedgerKind::Ethereum,
+        alpha_asset: AssetKind::Litecoin,
+        beta_asset: AssetKind::Erc20,
+        role,
+    }
+});
+db_roundtrip_test!(Ethereum, Litecoin, Erc20, LitecoinAsset, |role| {
+    SwapTypes {
+        alpha_ledger: LedgerKind::Ethereum,
+        beta_ledger: LedgerKind::Litecoin,
+        alpha_asset: AssetKind::Erc20,
+        beta_asset: AssetKind::Litecoin,
+        role,
+    }
+});
+
+type EthereumBitcoinEtherBitcoinEvent = SwapEventOnLedger<Ethereum, Regtest, Ether, BitcoinAsset>;
+
+#[test]
+fn roundtrip_test_swap_events() {
+    fn prop(
+        swap_id: Quickcheck<SwapId>,
+        other_swap_id: Quickcheck<SwapId>,
+        transaction: Quickcheck<Transaction>,
+        location: Quickcheck<Address>,
+        asset: Quickcheck<Ether>,
+    ) -> anyhow::Result<bool> {
+        let db = Sqlite::new(&Path::new(":memory:"))?;
+
+        let saved_events: Vec<EthereumBitcoinEtherBitcoinEvent> = vec![
+            SwapEvent::AlphaDeployed(Deployed {
+                transaction: (*transaction).clone(),
+                location: *location,
+            }),
+            SwapEvent::AlphaFunded(Funded {
+                transaction: (*transaction).clone(),
+                asset: (*asset).clone(),
+            }),
+        ];
+
+        let loaded_events = tokio::runtime::Runtime::new()?.block_on(async {
+            for event in saved_events.clone() {
+                db.save_swap_event(&swap_id, event).await?;
+            }
+            // events of other swaps must not be loaded
+            db.save_swap_event(&other_swap_id, saved_events[0].clone())
+                .await?;
+
+            SwapEvents::<EthereumBitcoinEtherBitcoinEvent>::load_swap_events(&db, &swap_id).await
+        })?;
+
+        Ok(saved_events == loaded_events)
+    }
+
+    quickcheck::quickcheck(
+        prop as fn(
+            Quickcheck<SwapId>,
+            Quickcheck<SwapId>,
+            Quickcheck<Transaction>,
+            Quickcheck<Address>,
+            Quickcheck<Ether>,
+        ) -> anyhow::Result<bool>,
+    );
+}
+
+#[test]
+fn roundtrip_test_executed_actions() {
+    fn prop(
+        swap_id: Quickcheck<SwapId>,
+        other_swap_id: Quickcheck<SwapId>,
+    ) -> anyhow::Result<bool> {
+        let db = Sqlite::new(&Path::new(":memory:"))?;
+
+        let redeem_outcome = serde_json::json!({ "type": "redeem" });
+        let refund_outcome = serde_json::json!({ "type": "refund" });
+
+        let loaded_actions = tokio::runtime::Runtime::new()?.block_on(async {
+            db.save_executed_action(&swap_id, ActionKind::Redeem, redeem_outcome.clone())
+                .await?;
+            db.save_executed_action(&other_swap_id, ActionKind::Refund, refund_outcome)
+                .await?;
+
+            db.load_executed_actions(&swap_id).await
+        })?;
+
+        Ok(loaded_actions.len() == 1
+            && loaded_actions[0].action == ActionKind::Redeem
+            && loaded_actions[0].outcome == redeem_outcome
+            && !loaded_actions[0].failed())
+    }
+
+    quickcheck::quickcheck(
+        prop as fn(Quickcheck<SwapId>, Quickcheck<SwapId>) -> anyhow::Result<bool>,
+    );
+}
+
+#[test]
+fn roundtrip_test_failed_executed_action() {
+    fn prop(swap_id: Quickcheck<SwapId>) -> anyhow::Result<bool> {
+        let db = Sqlite::new(&Path::new(":memory:"))?;
+
+        let failure = ExecutedAction::failure(&anyhow::anyhow!("non-final"));
+
+        let loaded_actions = tokio::runtime::Runtime::new()?.block_on(async {
+            db.save_executed_action(&swap_id, ActionKind::Refund, failure)
+                .await?;
+
+            db.load_executed_actions(&swap_id).await
+        })?;
+
+        Ok(loaded_actions.len() == 1 && loaded_actions[0].failed())
+    }
+
+    quickcheck::quickcheck(prop as fn(Quickcheck<SwapId>) -> anyhow::Result<bool>);
+}
@@ -3,10 +3,23 @@
 /// activity that involves migration scripts to migrate old data. These tests
 /// make sure we don't change the format accidentally!
 use crate::{
-    db::wrapper_types::{Erc20Amount, Ether, EthereumAddress, Satoshis},
-    swap_protocols::{rfc003::SecretHash, HashFunction, SwapId},
+    asset::{self, ethereum::Erc20Quantity},
+    db::{
+        wrapper_types::{Erc20Amount, Ether, EthereumAddress, Satoshis},
+        AcceptedSwap, AssetColumns, AssetKind, BitcoinLedgerKind, DetermineTypes, IdentityColumn,
+        LedgerColumns, LedgerKind, LoadAcceptedSwap, Sqlite, SwapTypes,
+    },
+    lightning,
+    swap_protocols::{
+        ledger::{self, ethereum::ChainId, Ethereum, Lightning, Litecoin},
+        rfc003::{Accept, Request, SecretHash},
+        HashFunction, Role, SwapId,
+    },
+    timestamp::Timestamp,
 };
-use std::{fmt, str::FromStr};
+use diesel::{connection::SimpleConnection, sqlite::SqliteConnection, Connection};
+use std::{fmt, str::FromStr, sync::Arc};
+use tokio::{runtime::Runtime, sync::Mutex};
 
 #[test]
 fn swap_id() {
@@ -60,6 +73,259 @@ fn secrethash() {
     );
 }
 
+#[test]
+fn ledger_columns() {
+    ledger_columns_test::<ledger::bitcoin::Mainnet>("bitcoin", "mainnet");
+    ledger_columns_test::<ledger::bitcoin::Testnet>("bitcoin", "testnet");
+    ledger_columns_test::<ledger::bitcoin::Regtest>("bitcoin", "regtest");
+    ledger_columns_test::<Ethereum>("ethereum", "17");
+    ledger_columns_test::<Lightning>("lightning", "regtest");
+    ledger_columns_test::<Litecoin>("litecoin", "testnet");
+}
+
+#[test]
+fn bitcoin_ledger_columns_only_accept_their_network() {
+    assert!(ledger::bitcoin::Mainnet::from_network("regtest").is_err());
+}
+
+#[test]
+fn asset_columns() {
+    asset_columns_test::<asset::Bitcoin>("bitcoin", "100000000000", None);
+    asset_columns_test::<asset::Ether>("ether", "1000000000000000", None);
+    asset_columns_test::<asset::Erc20>(
+        "erc20",
+        "1000000000000000",
+        Some("68917b35bacf71dbadf37628b3b7f290f6d88877"),
+    );
+    asset_columns_test::<asset::Litecoin>("litecoin", "100000000000", None);
+}
+
+#[test]
+fn identity_columns() {
+    identity_column_test::<crate::bitcoin::PublicKey>(
+        "0216867374f539badfd90d7b2269008d893ae7bd4f9ee7c695c967d01d6953c401",
+    );
+    identity_column_test::<crate::ethereum::Address>("68917b35bacf71dbadf37628b3b7f290f6d88877");
+    identity_column_test::<lightning::PublicKey>(
+        "0216867374f539badfd90d7b2269008d893ae7bd4f9ee7c695c967d01d6953c401",
+    );
+}
+
+#[test]
+fn bitcoin_ethereum_messages_are_migrated_to_generic_tables() {
+    let db = migrated_db(
+        r#"
+        INSERT INTO rfc003_swaps (swap_id, role, counterparty)
+        VALUES ('7f3a105d-ecf2-4cc6-b35c-b4351ac28a34', 'Alice', 'QmfUfpC2frwFvcDzpspnfZitHt5wct6n4kpG5jzgRdsxkY');
+
+        INSERT INTO rfc003_bitcoin_ethereum_bitcoin_ether_request_messages
+        (swap_id, bitcoin_network, ethereum_chain_id, bitcoin_amount, ether_amount, hash_function, bitcoin_refund_identity, ethereum_redeem_identity, bitcoin_expiry, ethereum_expiry, secret_hash)
+        VALUES ('7f3a105d-ecf2-4cc6-b35c-b4351ac28a34', 'regtest', 17, '100000000000', '1000000000000000', 'SHA-256', '0216867374f539badfd90d7b2269008d893ae7bd4f9ee7c695c967d01d6953c401', '68917b35bacf71dbadf37628b3b7f290f6d88877', 1585000000, 1584000000, '68917b35bacf71dbadf37628b3b7f290f6d88877d7b2269008d893ae7bd4f9ee');
+
+        INSERT INTO rfc003_bitcoin_ethereum_accept_messages (swap_id, bitcoin_redeem_identity, ethereum_refund_identity)
+        VALUES ('7f3a105d-ecf2-4cc6-b35c-b4351ac28a34', '0216867374f539badfd90d7b2269008d893ae7bd4f9ee7c695c967d01d6953c401', '68917b35bacf71dbadf37628b3b7f290f6d88877');
+        "#,
+    );
+    let swap_id = SwapId::from_str("7f3a105d-ecf2-4cc6-b35c-b4351ac28a34").unwrap();
+    let mut runtime = Runtime::new().unwrap();
+
+    let swap_types = runtime.block_on(db.determine_types(&swap_id)).unwrap();
+    let (request, accept, _at): AcceptedSwap<
+        ledger::bitcoin::Regtest,
+        Ethereum,
+        asset::Bitcoin,
+        asset::Ether,
+    > = runtime.block_on(db.load_accepted_swap(&swap_id)).unwrap();
+
+    assert_eq!(swap_types, SwapTypes {
+        alpha_ledger: LedgerKind::Bitcoin(BitcoinLedgerKind::Regtest),
+        beta_ledger: LedgerKind::Ethereum,
+        alpha_asset: AssetKind::Bitcoin,
+        beta_asset: AssetKind::Ether,
+        role: Role::Alice,
+    });
+    assert_eq!(request, Request {
+        swap_id,
+        alpha_ledger: ledger::bitcoin::Regtest,
+        beta_ledger: Ethereum::new(ChainId::from(17)),
+        alpha_asset: asset::Bitcoin::from_sat(100_000_000_000),
+        beta_asset: asset::Ether::from_wei_dec_str("1000000000000000").unwrap(),
+        hash_function: HashFunction::Sha256,
+        alpha_ledger_refund_identity: bitcoin_public_key(),
+        beta_ledger_redeem_identity: ethereum_address(),
+        alpha_expiry: Timestamp::from(1_585_000_000),
+        beta_expiry: Timestamp::from(1_584_000_000),
+        secret_hash: secret_hash(),
+    });
+    assert_eq!(accept, Accept {
+        swap_id,
+        alpha_ledger_redeem_identity: bitcoin_public_key(),
+        beta_ledger_refund_identity: ethereum_address(),
+    });
+}
+
+#[test]
+fn ethereum_ethereum_messages_are_migrated_to_generic_tables() {
+    let db = migrated_db(
+        r#"
+        INSERT INTO rfc003_swaps (swap_id, role, counterparty)
+        VALUES ('7f3a105d-ecf2-4cc6-b35c-b4351ac28a34', 'Bob', 'QmfUfpC2frwFvcDzpspnfZitHt5wct6n4kpG5jzgRdsxkY');
+
+        INSERT INTO rfc003_ethereum_ethereum_erc20_erc20_request_messages
+        (swap_id, alpha_ethereum_chain_id, beta_ethereum_chain_id, alpha_erc20_amount, alpha_erc20_token_contract, beta_erc20_amount, beta_erc20_token_contract, hash_function, alpha_ethereum_refund_identity, beta_ethereum_redeem_identity, alpha_expiry, beta_expiry, secret_hash)
+        VALUES ('7f3a105d-ecf2-4cc6-b35c-b4351ac28a34', 1, 17, '1000000000000000', '68917b35bacf71dbadf37628b3b7f290f6d88877', '2000000000000000', '68917b35bacf71dbadf37628b3b7f290f6d88877', 'SHA-256', '68917b35bacf71dbadf37628b3b7f290f6d88877', '68917b35bacf71dbadf37628b3b7f290f6d88877', 1585000000, 1584000000, '68917b35bacf71dbadf37628b3b7f290f6d88877d7b2269008d893ae7bd4f9ee');
+
+        INSERT INTO rfc003_ethereum_ethereum_accept_messages (swap_id, alpha_ethereum_redeem_identity, beta_ethereum_refund_identity)
+        VALUES ('7f3a105d-ecf2-4cc6-b35c-b4351ac28a34', '68917b35bacf71dbadf37628b3b7f290f6d88877', '68917b35bacf71dbadf37628b3b7f290f6d88877');
+        "#,
+    );
+    let swap_id = SwapId::from_str("7f3a105d-ecf2-4cc6-b35c-b4351ac28a34").unwrap();
+    let mut runtime = Runtime::new().unwrap();
+
+    let swap_types = runtime.block_on(db.determine_types(&swap_id)).unwrap();
+    let (request, accept, _at): AcceptedSwap<Ethereum, Ethereum, asset::Erc20, asset::Erc20> =
+        runtime.block_on(db.load_accepted_swap(&swap_id)).unwrap();
+
+    assert_eq!(swap_types, SwapTypes {
+        alpha_ledger: LedgerKind::Ethereum,
+        beta_ledger: LedgerKind::Ethereum,
+        alpha_asset: AssetKind::Erc20,
+        beta_asset: AssetKind::Erc20,
+        role: Role::Bob,
+    });
+    assert_eq!(request, Request {
+        swap_id,
+        alpha_ledger: Ethereum::new(ChainId::from(1)),
+        beta_ledger: Ethereum::new(ChainId::from(17)),
+        alpha_asset: asset::Erc20::new(
+            ethereum_address(),
+            Erc20Quantity::from_wei_dec_str("1000000000000000").unwrap(),
+        ),
+        beta_asset: asset::Erc20::new(
+            ethereum_address(),
+            Erc20Quantity::from_wei_dec_str("2000000000000000").unwrap(),
+        ),
+        hash_function: HashFunction::Sha256,
+        alpha_ledger_refund_identity: ethereum_address(),
+        beta_ledger_redeem_identity: ethereum_address(),
+        alpha_expiry: Timestamp::from(1_585_000_000),
+        beta_expiry: Timestamp::from(1_584_000_000),
+        secret_hash: secret_hash(),
+    });
+    assert_eq!(accept, Accept {
+        swap_id,
+        alpha_ledger_redeem_identity: ethereum_address(),
+        beta_ledger_refund_identity: ethereum_address(),
+    });
+}
+
+#[test]
+fn litecoin_amounts_are_migrated_to_the_litecoin_asset() {
+    let db = migrated_db(
+        r#"
+        INSERT INTO rfc003_swaps (swap_id, role, counterparty)
+        VALUES ('7f3a105d-ecf2-4cc6-b35c-b4351ac28a34', 'Alice', 'QmfUfpC2frwFvcDzpspnfZitHt5wct6n4kpG5jzgRdsxkY');
+
+        INSERT INTO rfc003_ethereum_litecoin_ether_bitcoin_request_messages
+        (swap_id, ethereum_chain_id, litecoin_network, ether_amount, bitcoin_amount, hash_function, ethereum_refund_identity, litecoin_redeem_identity, ethereum_expiry, litecoin_expiry, secret_hash)
+        VALUES ('7f3a105d-ecf2-4cc6-b35c-b4351ac28a34', 17, 'regtest', '1000000000000000', '100000000000', 'SHA-256', '68917b35bacf71dbadf37628b3b7f290f6d88877', '0216867374f539badfd90d7b2269008d893ae7bd4f9ee7c695c967d01d6953c401', 1585000000, 1584000000, '68917b35bacf71dbadf37628b3b7f290f6d88877d7b2269008d893ae7bd4f9ee');
+        "#,
+    );
+    let swap_id = SwapId::from_str("7f3a105d-ecf2-4cc6-b35c-b4351ac28a34").unwrap();
+    let mut runtime = Runtime::new().unwrap();
+
+    let swap_types = runtime.block_on(db.determine_types(&swap_id)).unwrap();
+
+    assert_eq!(swap_types, SwapTypes {
+        alpha_ledger: LedgerKind::Ethereum,
+        beta_ledger: LedgerKind::Litecoin,
+        alpha_asset: AssetKind::Ether,
+        beta_asset: AssetKind::Litecoin,
+        role: Role::Alice,
+    });
+}
+
+/// Asserts that a ledger is stored with the given name and that the given
+/// network is read and written the same way.
+fn ledger_columns_test<L: LedgerColumns>(name: &str, network: &str) {
+    assert_eq!(L::NAME, name);
+
+    let read = L::from_network(network).unwrap();
+
+    assert_eq!(read.network(), network)
+}
+
+/// Asserts that an asset is stored with the given name and that the given
+/// quantity and token contract are read and written the same way.
+fn asset_columns_test<A: AssetColumns>(name: &str, quantity: &str, token_contract: Option<&str>) {
+    assert_eq!(A::NAME, name);
+
+    let read = A::from_columns(quantity, token_contract).unwrap();
+
+    assert_eq!(read.quantity(), quantity);
+    assert_eq!(read.token_contract().as_deref(), token_contract)
+}
+
+fn identity_column_test<I: IdentityColumn>(stored_value: &str) {
+    let read = I::from_column(stored_value).unwrap();
+
+    assert_eq!(read.to_column(), stored_value)
+}
+
+/// Creates a database in the format prior to the generic message tables,
+/// inserts `old_messages` and then migrates it to the generic message tables
+/// and on to the current format.
+fn migrated_db(old_messages: &str) -> Sqlite {
+    let connection = SqliteConnection::establish(":memory:").unwrap();
+
+    for migration in &[
+        include_str!("../../../migrations/2019-11-11-034058_create-message-tables/up.sql"),
+        include_str!("../../../migrations/2020-02-20-000000_create-swap-event-table/up.sql"),
+        include_str!("../../../migrations/2020-02-22-000000_create-executed-actions-table/up.sql"),
+        include_str!(
+            "../../../migrations/2020-03-02-000000_create-lightning-message-tables/up.sql"
+        ),
+        include_str!(
+            "../../../migrations/2020-03-16-000000_create-ethereum-ethereum-message-tables/up.sql"
+        ),
+        include_str!("../../../migrations/2020-03-23-000000_create-litecoin-message-tables/up.sql"),
+    ] {
+        connection.batch_execute(migration).unwrap();
+    }
+    connection.batch_execute(old_messages).unwrap();
+    for migration in &[
+        include_str!("../../../migrations/2020-03-30-000000_create-generic-message-tables/up.sql"),
+        include_str!(
+            "../../../migrations/2020-04-06-000000_store-litecoin-amounts-as-litecoin/up.sql"
+        ),
+    ] {
+        connection.batch_execute(migration).unwrap();
+    }
+
+    Sqlite {
+        connection: Arc::new(Mutex::new(connection)),
+    }
+}
+
+fn bitcoin_public_key() -> crate::bitcoin::PublicKey {
+    ::bitcoin::PublicKey::from_str(
+        "0216867374f539badfd90d7b2269008d893ae7bd4f9ee7c695c967d01d6953c401",
+    )
+    .unwrap()
+    .into()
+}
+
+fn ethereum_address() -> crate::ethereum::Address {
+    EthereumAddress::from_str("68917b35bacf71dbadf37628b3b7f290f6d88877")
+        .unwrap()
+        .into()
+}
+
+fn secret_hash() -> SecretHash {
+    SecretHash::from_str("68917b35bacf71dbadf37628b3b7f290f6d88877d7b2269008d893ae7bd4f9ee")
+        .unwrap()
+}
+
 /// Given a string representation of a value T, this function will assert
 /// that T can be constructed through the `FromStr` trait and its implementation
 /// is symmetric to the `Display` implementation.
@@ -1,15 +1,12 @@
 use crate::{
-    asset::{self, Asset},
+    asset::Asset,
     db::{
-        schema,
-        wrapper_types::{
-            custom_sql_types::{Text, U32},
-            BitcoinNetwork, Erc20Amount, Ether, EthereumAddress, Satoshis,
-        },
-        Sqlite,
+        message_columns::ensure_column_value,
+        schema::{self, rfc003_accept_messages, rfc003_request_messages},
+        wrapper_types::custom_sql_types::{Text, U32},
+        AssetColumns, IdentityColumn, LedgerColumns, Sqlite,
     },
     swap_protocols::{
-        ledger::{bitcoin, Ethereum},
         rfc003::{
             messages::{Accept, Request},
             Ledger, SecretHash,
@@ -20,15 +17,6 @@ use crate::{
 use async_trait::async_trait;
 use chrono::NaiveDateTime;
 use diesel::{self, prelude::*, RunQueryDsl};
-use impl_template::impl_template;
-use schema::{
-    rfc003_bitcoin_ethereum_accept_messages,
-    rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages,
-    rfc003_bitcoin_ethereum_bitcoin_ether_request_messages,
-    rfc003_ethereum_bitcoin_accept_messages,
-    rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages,
-    rfc003_ethereum_bitcoin_ether_bitcoin_request_messages,
-};
 
 pub type AcceptedSwap<AL, BL, AA, BA> = (
     Request<AL, BL, AA, BA>,
@@ -44,441 +32,106 @@ pub trait LoadAcceptedSwap<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> {
     ) -> anyhow::Result<AcceptedSwap<AL, BL, AA, BA>>;
 }
 
-diesel::allow_tables_to_appear_in_same_query!(
-    rfc003_bitcoin_ethereum_bitcoin_ether_request_messages,
-    rfc003_bitcoin_ethereum_accept_messages
-);
-diesel::allow_tables_to_appear_in_same_query!(
-    rfc003_ethereum_bitcoin_ether_bitcoin_request_messages,
-    rfc003_ethereum_bitcoin_accept_messages
-);
-diesel::allow_tables_to_appear_in_same_query!(
-    rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages,
-    rfc003_bitcoin_ethereum_accept_messages
-);
-diesel::allow_tables_to_appear_in_same_query!(
-    rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages,
-    rfc003_ethereum_bitcoin_accept_messages
-);
-
-// Once #1862 is fully done (ie, no more networks here) we should be able to
-// include this declaration in the macro and merge it with the $select fields.
-#[derive(Queryable, Debug, Clone, PartialEq)]
-struct BitcoinEthereumBitcoinEtherAcceptedSwap {
-    // Request fields.
-    swap_id: Text<SwapId>,
-    bitcoin_network: Text<BitcoinNetwork>,
-    ethereum_chain_id: U32,
-    bitcoin_amount: Text<Satoshis>,
-    ether_amount: Text<Ether>,
-    hash_function: Text<HashFunction>,
-    bitcoin_refund_identity: Text<::bitcoin::PublicKey>,
-    ethereum_redeem_identity: Text<EthereumAddress>,
-    bitcoin_expiry: U32,
-    ethereum_expiry: U32,
-    secret_hash: Text<SecretHash>,
-    // Accept fields.
-    bitcoin_redeem_identity: Text<::bitcoin::PublicKey>,
-    ethereum_refund_identity: Text<EthereumAddress>,
-
-    at: NaiveDateTime,
-}
-
-#[impl_template]
-impl From<BitcoinEthereumBitcoinEtherAcceptedSwap>
-    for AcceptedSwap<
-        ((bitcoin::Mainnet, bitcoin::Testnet, bitcoin::Regtest)),
-        Ethereum,
-        asset::Bitcoin,
-        asset::Ether,
-    >
-{
-    fn from(record: BitcoinEthereumBitcoinEtherAcceptedSwap) -> Self {
-        (
-            Request {
-                swap_id: *record.swap_id,
-                alpha_ledger: __TYPE0__,
-                beta_ledger: Ethereum {
-                    chain_id: record.ethereum_chain_id.0.into(),
-                },
-                alpha_asset: record.bitcoin_amount.0.into(),
-                beta_asset: record.ether_amount.0.into(),
-                hash_function: *record.hash_function,
-                alpha_ledger_refund_identity: record.bitcoin_refund_identity.0.into(),
-                beta_ledger_redeem_identity: record.ethereum_redeem_identity.0.into(),
-                alpha_expiry: record.bitcoin_expiry.into(),
-                beta_expiry: record.ethereum_expiry.0.into(),
-                secret_hash: *record.secret_hash,
-            },
-            Accept {
-                swap_id: *record.swap_id,
-                alpha_ledger_redeem_identity: record.bitcoin_redeem_identity.0.into(),
-                beta_ledger_refund_identity: record.ethereum_refund_identity.0.into(),
-            },
-            record.at,
-        )
-    }
-}
-
-#[impl_template]
-#[async_trait]
-impl
-    LoadAcceptedSwap<
-        ((bitcoin::Mainnet, bitcoin::Testnet, bitcoin::Regtest)),
-        Ethereum,
-        asset::Bitcoin,
-        asset::Ether,
-    > for Sqlite
-{
-    async fn load_accepted_swap(
-        &self,
-        key: &SwapId,
-    ) -> anyhow::Result<AcceptedSwap<__TYPE0__, Ethereum, asset::Bitcoin, asset::Ether>> {
-        use schema::{
-            rfc003_bitcoin_ethereum_accept_messages as accept_messages,
-            rfc003_bitcoin_ethereum_bitcoin_ether_request_messages as request_messages,
-        };
-
-        let record: BitcoinEthereumBitcoinEtherAcceptedSwap = self
-            .do_in_transaction(|connection| {
-                let key = Text(key);
-
-                request_messages::table
-                    .inner_join(
-                        accept_messages::table
-                            .on(request_messages::swap_id.eq(accept_messages::swap_id)),
-                    )
-                    .select((
-                        request_messages::swap_id,
-                        request_messages::bitcoin_network,
-                        request_messages::ethereum_chain_id,
-                        request_messages::bitcoin_amount,
-                        request_messages::ether_amount,
-                        request_messages::hash_function,
-                        request_messages::bitcoin_refund_identity,
-                        request_messages::ethereum_redeem_identity,
-                        request_messages::bitcoin_expiry,
-                        request_messages::ethereum_expiry,
-                        request_messages::secret_hash,
-                        accept_messages::bitcoin_redeem_identity,
-                        accept_messages::ethereum_refund_identity,
-                        accept_messages::at,
-                    ))
-                    .filter(accept_messages::swap_id.eq(key))
-                    .first(connection)
-            })
-            .await?;
-
-        Ok(record.into())
-    }
-}
+diesel::allow_tables_to_appear_in_same_query!(rfc003_request_messages, rfc003_accept_messages);
 
 #[derive(Queryable, Debug, Clone, PartialEq)]
-struct EthereumBitcoinEtherBitcoinAcceptedSwap {
+struct QueryableAcceptedSwap {
     // Request fields.
     swap_id: Text<SwapId>,
-    ethereum_chain_id: U32,
-    bitcoin_network: Text<BitcoinNetwork>,
-    ether_amount: Text<Ether>,
-    bitcoin_amount: Text<Satoshis>,
+    alpha_ledger: String,
+    alpha_ledger_network: String,
+    beta_ledger: String,
+    beta_ledger_network: String,
+    alpha_asset: String,
+    alpha_asset_quantity: String,
+    alpha_asset_token_contract: Option<String>,
+    beta_asset: String,
+    beta_asset_quantity: String,
+    beta_asset_token_contract: Option<String>,
     hash_function: Text<HashFunction>,
-    ethereum_refund_identity: Text<EthereumAddress>,
-    bitcoin_redeem_identity: Text<::bitcoin::PublicKey>,
-    ethereum_expiry: U32,
-    bitcoin_expiry: U32,
+    alpha_ledger_refund_identity: String,
+    beta_ledger_redeem_identity: String,
+    alpha_expiry: U32,
+    beta_expiry: U32,
     secret_hash: Text<SecretHash>,
     // Accept fields.
-    ethereum_redeem_identity: Text<EthereumAddress>,
-    bitcoin_refund_identity: Text<::bitcoin::PublicKey>,
+    alpha_ledger_redeem_identity: String,
+    beta_ledger_refund_identity: String,
 
     at: NaiveDateTime,
 }
 
-#[impl_template]
-impl From<EthereumBitcoinEtherBitcoinAcceptedSwap>
-    for AcceptedSwap<
-        Ethereum,
-        ((bitcoin::Mainnet, bitcoin::Testnet, bitcoin::Regtest)),
-        asset::Ether,
-        asset::Bitcoin,
-    >
-{
-    fn from(record: EthereumBitcoinEtherBitcoinAcceptedSwap) -> Self {
-        (
-            Request {
-                swap_id: *record.swap_id,
-                alpha_ledger: Ethereum {
-                    chain_id: record.ethereum_chain_id.0.into(),
-                },
-                beta_ledger: __TYPE0__,
-                alpha_asset: record.ether_amount.0.into(),
-                beta_asset: record.bitcoin_amount.0.into(),
-                hash_function: *record.hash_function,
-                alpha_ledger_refund_identity: record.ethereum_refund_identity.0.into(),
-                beta_ledger_redeem_identity: record.bitcoin_redeem_identity.0.into(),
-                alpha_expiry: record.ethereum_expiry.0.into(),
-                beta_expiry: record.bitcoin_expiry.0.into(),
-                secret_hash: *record.secret_hash,
-            },
-            Accept {
-                swap_id: *record.swap_id,
-                alpha_ledger_redeem_identity: record.ethereum_redeem_identity.0.into(),
-                beta_ledger_refund_identity: record.bitcoin_refund_identity.0.into(),
-            },
-            record.at,
-        )
-    }
-}
-
-#[impl_template]
-#[async_trait]
-impl
-    LoadAcceptedSwap<
-        Ethereum,
-        ((bitcoin::Mainnet, bitcoin::Testnet, bitcoin::Regtest)),
-        asset::Ether,
-        asset::Bitcoin,
-    > for Sqlite
-{
-    async fn load_accepted_swap(
-        &self,
-        key: &SwapId,
-    ) -> anyhow::Result<AcceptedSwap<Ethereum, __TYPE0__, asset::Ether, asset::Bitcoin>> {
-        use schema::{
-            rfc003_ethereum_bitcoin_accept_messages as accept_messages,
-            rfc003_ethereum_bitcoin_ether_bitcoin_request_messages as request_messages,
+impl QueryableAcceptedSwap {
+    fn into_accepted_swap<AL, BL, AA, BA>(self) -> anyhow::Result<AcceptedSwap<AL, BL, AA, BA>>
+    where
+        AL: LedgerColumns,
+        BL: LedgerColumns,
+        AA: AssetColumns,
+        BA: AssetColumns,
+        AL::Identity: IdentityColumn,
+        BL::Identity: IdentityColumn,
+    {
+        ensure_column_value("alpha_ledger", AL::NAME, &self.alpha_ledger)?;
+        ensure_column_value("beta_ledger", BL::NAME, &self.beta_ledger)?;
+        ensure_column_value("alpha_asset", AA::NAME, &self.alpha_asset)?;
+        ensure_column_value("beta_asset", BA::NAME, &self.beta_asset)?;
+
+        let request = Request {
+            swap_id: *self.swap_id,
+            alpha_ledger: AL::from_network(&self.alpha_ledger_network)?,
+            beta_ledger: BL::from_network(&self.beta_ledger_network)?,
+            alpha_asset: AA::from_columns(
+                &self.alpha_asset_quantity,
+                self.alpha_asset_token_contract.as_deref(),
+            )?,
+            beta_asset: BA::from_columns(
+                &self.beta_asset_quantity,
+                self.beta_asset_token_contract.as_deref(),
+            )?,
+            hash_function: *self.hash_function,
+            alpha_ledger_refund_identity: IdentityColumn::from_column(
+                &self.alpha_ledger_refund_identity,
+            )?,
+            beta_ledger_redeem_identity: IdentityColumn::from_column(
+                &self.beta_ledger_redeem_identity,
+            )?,
+            alpha_expiry: self.alpha_expiry.into(),
+            beta_expiry: self.beta_expiry.into(),
+            secret_hash: *self.secret_hash,
         };
-
-        let record: EthereumBitcoinEtherBitcoinAcceptedSwap = self
-            .do_in_transaction(|connection| {
-                let key = Text(key);
-
-                request_messages::table
-                    .inner_join(
-                        accept_messages::table
-                            .on(request_messages::swap_id.eq(accept_messages::swap_id)),
-                    )
-                    .select((
-                        request_messages::swap_id,
-                        request_messages::ethereum_chain_id,
-                        request_messages::bitcoin_network,
-                        request_messages::ether_amount,
-                        request_messages::bitcoin_amount,
-                        request_messages::hash_function,
-                        request_messages::ethereum_refund_identity,
-                        request_messages::bitcoin_redeem_identity,
-                        request_messages::ethereum_expiry,
-                        request_messages::bitcoin_expiry,
-                        request_messages::secret_hash,
-                        accept_messages::ethereum_redeem_identity,
-                        accept_messages::bitcoin_refund_identity,
-                        accept_messages::at,
-                    ))
-                    .filter(accept_messages::swap_id.eq(key))
-                    .first(connection)
-            })
-            .await?;
-
-        Ok(record.into())
-    }
-}
-
-#[derive(Queryable, Debug, Clone, PartialEq)]
-struct BitcoinEthereumBitcoinErc20AcceptedSwap {
-    // Request fields.
-    swap_id: Text<SwapId>,
-    bitcoin_network: Text<BitcoinNetwork>,
-    ethereum_chain_id: U32,
-    bitcoin_amount: Text<Satoshis>,
-    erc20_token_contract: Text<EthereumAddress>,
-    erc20_amount: Text<Erc20Amount>,
-    hash_function: Text<HashFunction>,
-    bitcoin_refund_identity: Text<::bitcoin::PublicKey>,
-    ethereum_redeem_identity: Text<EthereumAddress>,
-    bitcoin_expiry: U32,
-    ethereum_expiry: U32,
-    secret_hash: Text<SecretHash>,
-    // Accept fields.
-    bitcoin_redeem_identity: Text<::bitcoin::PublicKey>,
-    ethereum_refund_identity: Text<EthereumAddress>,
-
-    at: NaiveDateTime,
-}
-
-#[impl_template]
-impl From<BitcoinEthereumBitcoinErc20AcceptedSwap>
-    for AcceptedSwap<
-        ((bitcoin::Mainnet, bitcoin::Testnet, bitcoin::Regtest)),
-        Ethereum,
-        asset::Bitcoin,
-        asset::Erc20,
-    >
-{
-    fn from(record: BitcoinEthereumBitcoinErc20AcceptedSwap) -> Self {
-        (
-            Request {
-                swap_id: *record.swap_id,
-                alpha_ledger: __TYPE0__,
-                beta_ledger: Ethereum {
-                    chain_id: record.ethereum_chain_id.0.into(),
-                },
-                alpha_asset: record.bitcoin_amount.0.into(),
-                beta_asset: asset::Erc20::new(
-                    record.erc20_token_contract.0.into(),
-                    record.erc20_amount.0.into(),
-                ),
-                hash_function: *record.hash_function,
-                alpha_ledger_refund_identity: record.bitcoin_refund_identity.0.into(),
-                beta_ledger_redeem_identity: record.ethereum_redeem_identity.0.into(),
-                alpha_expiry: record.bitcoin_expiry.0.into(),
-                beta_expiry: record.ethereum_expiry.0.into(),
-                secret_hash: *record.secret_hash,
-            },
-            Accept {
-                swap_id: *record.swap_id,
-                alpha_ledger_redeem_identity: record.bitcoin_redeem_identity.0.into(),
-                beta_ledger_refund_identity: record.ethereum_refund_identity.0.into(),
-            },
-            record.at,
-        )
-    }
-}
-
-#[impl_template]
-#[async_trait]
-impl
-    LoadAcceptedSwap<
-        ((bitcoin::Mainnet, bitcoin::Testnet, bitcoin::Regtest)),
-        Ethereum,
-        asset::Bitcoin,
-        asset::Erc20,
-    > for Sqlite
-{
-    async fn load_accepted_swap(
-        &self,
-        key: &SwapId,
-    ) -> anyhow::Result<AcceptedSwap<__TYPE0__, Ethereum, asset::Bitcoin, asset::Erc20>> {
-        use schema::{
-            rfc003_bitcoin_ethereum_accept_messages as accept_messages,
-            rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages as request_messages,
+        let accept = Accept {
+            swap_id: *self.swap_id,
+            alpha_ledger_redeem_identity: IdentityColumn::from_column(
+                &self.alpha_ledger_redeem_identity,
+            )?,
+            beta_ledger_refund_identity: IdentityColumn::from_column(
+                &self.beta_ledger_refund_identity,
+            )?,
         };
 
-        let record: BitcoinEthereumBitcoinErc20AcceptedSwap = self
-            .do_in_transaction(|connection| {
-                let key = Text(key);
-
-                request_messages::table
-                    .inner_join(
-                        accept_messages::table
-                            .on(request_messages::swap_id.eq(accept_messages::swap_id)),
-                    )
-                    .select((
-                        request_messages::swap_id,
-                        request_messages::bitcoin_network,
-                        request_messages::ethereum_chain_id,
-                        request_messages::bitcoin_amount,
-                        request_messages::erc20_token_contract,
-                        request_messages::erc20_amount,
-                        request_messages::hash_function,
-                        request_messages::bitcoin_refund_identity,
-                        request_messages::ethereum_redeem_identity,
-                        request_messages::bitcoin_expiry,
-                        request_messages::ethereum_expiry,
-                        request_messages::secret_hash,
-                        accept_messages::bitcoin_redeem_identity,
-                        accept_messages::ethereum_refund_identity,
-                        accept_messages::at,
-                    ))
-                    .filter(accept_messages::swap_id.eq(key))
-                    .first(connection)
-            })
-            .await?;
-
-        Ok(record.into())
-    }
-}
-
-#[derive(Queryable, Debug, Clone, PartialEq)]
-struct EthereumBitcoinErc20BitcoinAcceptedSwap {
-    // Request fields.
-    swap_id: Text<SwapId>,
-    ethereum_chain_id: U32,
-    bitcoin_network: Text<BitcoinNetwork>,
-    erc20_token_contract: Text<EthereumAddress>,
-    erc20_amount: Text<Erc20Amount>,
-    bitcoin_amount: Text<Satoshis>,
-    hash_function: Text<HashFunction>,
-    ethereum_refund_identity: Text<EthereumAddress>,
-    bitcoin_redeem_identity: Text<::bitcoin::PublicKey>,
-    ethereum_expiry: U32,
-    bitcoin_expiry: U32,
-    secret_hash: Text<SecretHash>,
-    // Accept fields.
-    ethereum_redeem_identity: Text<EthereumAddress>,
-    bitcoin_refund_identity: Text<::bitcoin::PublicKey>,
-
-    at: NaiveDateTime,
-}
-
-#[impl_template]
-impl From<EthereumBitcoinErc20BitcoinAcceptedSwap>
-    for AcceptedSwap<
-        Ethereum,
-        ((bitcoin::Mainnet, bitcoin::Testnet, bitcoin::Regtest)),
-        asset::Erc20,
-        asset::Bitcoin,
-    >
-{
-    fn from(record: EthereumBitcoinErc20BitcoinAcceptedSwap) -> Self {
-        (
-            Request {
-                swap_id: *record.swap_id,
-                alpha_ledger: Ethereum {
-                    chain_id: record.ethereum_chain_id.0.into(),
-                },
-                beta_ledger: __TYPE0__,
-                alpha_asset: asset::Erc20::new(
-                    record.erc20_token_contract.0.into(),
-                    record.erc20_amount.0.into(),
-                ),
-                beta_asset: record.bitcoin_amount.0.into(),
-                hash_function: *record.hash_function,
-                alpha_ledger_refund_identity: record.ethereum_refund_identity.0.into(),
-                beta_ledger_redeem_identity: record.bitcoin_redeem_identity.0.into(),
-                alpha_expiry: record.ethereum_expiry.0.into(),
-                beta_expiry: record.bitcoin_expiry.0.into(),
-                secret_hash: *record.secret_hash,
-            },
-            Accept {
-                swap_id: *record.swap_id,
-                alpha_ledger_redeem_identity: record.ethereum_redeem_identity.0.into(),
-                beta_ledger_refund_identity: record.bitcoin_refund_identity.0.into(),
-            },
-            record.at,
-        )
+        Ok((request, accept, self.at))
     }
 }
 
-#[impl_template]
 #[async_trait]
-impl
-    LoadAcceptedSwap<
-        Ethereum,
-        ((bitcoin::Mainnet, bitcoin::Testnet, bitcoin::Regtest)),
-        asset::Erc20,
-        asset::Bitcoin,
-    > for Sqlite
+impl<AL, BL, AA, BA> LoadAcceptedSwap<AL, BL, AA, BA> for Sqlite
+where
+    AL: LedgerColumns,
+    BL: LedgerColumns,
+    AA: AssetColumns,
+    BA: AssetColumns,
+    AL::Identity: IdentityColumn,
+    BL::Identity: IdentityColumn,
 {
     async fn load_accepted_swap(
         &self,
         key: &SwapId,
-    ) -> anyhow::Result<AcceptedSwap<Ethereum, __TYPE0__, asset::Erc20, asset::Bitcoin>> {
+    ) -> anyhow::Result<AcceptedSwap<AL, BL, AA, BA>> {
         use schema::{
-            rfc003_ethereum_bitcoin_accept_messages as accept_messages,
-            rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages as request_messages,
+            rfc003_accept_messages as accept_messages, rfc003_request_messages as request_messages,
         };
 
-        let record: EthereumBitcoinErc20BitcoinAcceptedSwap = self
+        let record: QueryableAcceptedSwap = self
             .do_in_transaction(|connection| {
                 let key = Text(key);
 
@@ -489,19 +142,24 @@ impl
                     )
                     .select((
                         request_messages::swap_id,
-                        request_messages::ethereum_chain_id,
-                        request_messages::bitcoin_network,
-                        request_messages::erc20_token_contract,
-                        request_messages::erc20_amount,
-                        request_messages::bitcoin_amount,
+                        request_messages::alpha_ledger,
+                        request_messages::alpha_ledger_network,
+                        request_messages::beta_ledger,
+                        request_messages::beta_ledger_network,
+                        request_messages::alpha_asset,
+                        request_messages::alpha_asset_quantity,
+                        request_messages::alpha_asset_token_contract,
+                        request_messages::beta_asset,
+                        request_messages::beta_asset_quantity,
+                        request_messages::beta_asset_token_contract,
                         request_messages::hash_function,
-                        request_messages::ethereum_refund_identity,
-                        request_messages::bitcoin_redeem_identity,
-                        request_messages::ethereum_expiry,
-                        request_messages::bitcoin_expiry,
+                        request_messages::alpha_ledger_refund_identity,
+                        request_messages::beta_ledger_redeem_identity,
+                        request_messages::alpha_expiry,
+                        request_messages::beta_expiry,
                         request_messages::secret_hash,
-                        accept_messages::ethereum_redeem_identity,
-                        accept_messages::bitcoin_refund_identity,
+                        accept_messages::alpha_ledger_redeem_identity,
+                        accept_messages::beta_ledger_refund_identity,
                         accept_messages::at,
                     ))
                     .filter(accept_messages::swap_id.eq(key))
@@ -509,6 +167,6 @@ impl
             })
             .await?;
 
-        Ok(record.into())
+        record.into_accepted_swap()
     }
 }
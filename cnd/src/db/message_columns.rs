@@ -0,0 +1,255 @@
+//! How the ledgers, assets and identities of a swap are stored in the
+//! `rfc003_request_messages` and `rfc003_accept_messages` tables.
+//!
+//! A ledger is stored as its name and network, an asset as its name, quantity
+//! and optionally token contract. This way, every combination of ledgers and
+//! assets shares the same tables. Changing any of these representations needs
+//! a migration, see the `serialization_format_stability` tests.
+
+use crate::{
+    asset::{self, Asset},
+    db::wrapper_types::{BitcoinNetwork, Erc20Amount, Ether, EthereumAddress, Satoshis},
+    ethereum,
+    swap_protocols::{
+        ledger::{bitcoin, ethereum::ChainId, litecoin, Ethereum, Lightning, Litecoin},
+        rfc003::Ledger,
+    },
+};
+use std::str::FromStr;
+
+/// A ledger as stored in the `*_ledger` and `*_ledger_network` columns.
+pub trait LedgerColumns: Ledger {
+    /// The name of the ledger.
+    const NAME: &'static str;
+
+    /// The network of Bitcoin-like ledgers, the chain id of Ethereum.
+    fn network(&self) -> String;
+
+    fn from_network(network: &str) -> anyhow::Result<Self>;
+}
+
+/// An asset as stored in the `*_asset`, `*_asset_quantity` and
+/// `*_asset_token_contract` columns.
+pub trait AssetColumns: Asset {
+    /// The name of the asset.
+    const NAME: &'static str;
+
+    /// The quantity in the smallest unit of the asset, as a decimal number.
+    fn quantity(&self) -> String;
+
+    fn token_contract(&self) -> Option<String>;
+
+    fn from_columns(quantity: &str, token_contract: Option<&str>) -> anyhow::Result<Self>;
+}
+
+/// An identity as stored in the identity columns of the message tables.
+pub trait IdentityColumn: Sized + Send + Sync + 'static {
+    fn to_column(&self) -> String;
+
+    fn from_column(column: &str) -> anyhow::Result<Self>;
+}
+
+/// A column contains a value that belongs to a different ledger or asset than
+/// the one it is loaded as.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("expected {column} to be {expected} but it is {found}")]
+pub struct UnexpectedColumnValue {
+    column: &'static str,
+    expected: String,
+    found: String,
+}
+
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+#[error("the token contract of an ERC20 asset is missing")]
+pub struct MissingTokenContract;
+
+pub(in crate::db) fn ensure_column_value(
+    column: &'static str,
+    expected: &str,
+    found: &str,
+) -> Result<(), UnexpectedColumnValue> {
+    if expected != found {
+        return Err(UnexpectedColumnValue {
+            column,
+            expected: expected.to_owned(),
+            found: found.to_owned(),
+        });
+    }
+
+    Ok(())
+}
+
+macro_rules! impl_ledger_columns_for_bitcoin {
+    ($network:ident) => {
+        impl LedgerColumns for bitcoin::$network {
+            const NAME: &'static str = "bitcoin";
+
+            fn network(&self) -> String {
+                BitcoinNetwork::$network.to_string()
+            }
+
+            fn from_network(network: &str) -> anyhow::Result<Self> {
+                let expected = BitcoinNetwork::$network.to_string();
+                ensure_column_value("network", &expected, network)?;
+
+                Ok(bitcoin::$network)
+            }
+        }
+    };
+}
+
+impl_ledger_columns_for_bitcoin!(Mainnet);
+impl_ledger_columns_for_bitcoin!(Testnet);
+impl_ledger_columns_for_bitcoin!(Regtest);
+
+impl LedgerColumns for Ethereum {
+    const NAME: &'static str = "ethereum";
+
+    fn network(&self) -> String {
+        u32::from(self.chain_id).to_string()
+    }
+
+    fn from_network(network: &str) -> anyhow::Result<Self> {
+        let chain_id = u32::from_str(network)?;
+
+        Ok(Ethereum::new(ChainId::from(chain_id)))
+    }
+}
+
+impl LedgerColumns for Lightning {
+    const NAME: &'static str = "lightning";
+
+    fn network(&self) -> String {
+        BitcoinNetwork::from(self.network).to_string()
+    }
+
+    fn from_network(network: &str) -> anyhow::Result<Self> {
+        let network = BitcoinNetwork::from_str(network)?;
+
+        Ok(Lightning::new(network.into()))
+    }
+}
+
+impl LedgerColumns for Litecoin {
+    const NAME: &'static str = "litecoin";
+
+    fn network(&self) -> String {
+        self.network.to_string()
+    }
+
+    fn from_network(network: &str) -> anyhow::Result<Self> {
+        let network = litecoin::Network::from_str(network)?;
+
+        Ok(Litecoin::new(network))
+    }
+}
+
+impl AssetColumns for asset::Bitcoin {
+    const NAME: &'static str = "bitcoin";
+
+    fn quantity(&self) -> String {
+        Satoshis::from(*self).to_string()
+    }
+
+    fn token_contract(&self) -> Option<String> {
+        None
+    }
+
+    fn from_columns(quantity: &str, _: Option<&str>) -> anyhow::Result<Self> {
+        let quantity = Satoshis::from_str(quantity)?;
+
+        Ok(quantity.into())
+    }
+}
+
+impl AssetColumns for asset::Ether {
+    const NAME: &'static str = "ether";
+
+    fn quantity(&self) -> String {
+        Ether::from(self.clone()).to_string()
+    }
+
+    fn token_contract(&self) -> Option<String> {
+        None
+    }
+
+    fn from_columns(quantity: &str, _: Option<&str>) -> anyhow::Result<Self> {
+        let quantity = Ether::from_str(quantity)?;
+
+        Ok(quantity.into())
+    }
+}
+
+impl AssetColumns for asset::Erc20 {
+    const NAME: &'static str = "erc20";
+
+    fn quantity(&self) -> String {
+        Erc20Amount::from(self.quantity.clone()).to_string()
+    }
+
+    fn token_contract(&self) -> Option<String> {
+        Some(EthereumAddress::from(self.token_contract).to_string())
+    }
+
+    fn from_columns(quantity: &str, token_contract: Option<&str>) -> anyhow::Result<Self> {
+        let quantity = Erc20Amount::from_str(quantity)?;
+        let token_contract = token_contract.ok_or(MissingTokenContract)?;
+        let token_contract = EthereumAddress::from_str(token_contract)?;
+
+        Ok(asset::Erc20::new(token_contract.into(), quantity.into()))
+    }
+}
+
+impl AssetColumns for asset::Litecoin {
+    const NAME: &'static str = "litecoin";
+
+    fn quantity(&self) -> String {
+        self.as_litoshi().to_string()
+    }
+
+    fn token_contract(&self) -> Option<String> {
+        None
+    }
+
+    fn from_columns(quantity: &str, _: Option<&str>) -> anyhow::Result<Self> {
+        let quantity = u64::from_str(quantity)?;
+
+        Ok(asset::Litecoin::from_litoshi(quantity))
+    }
+}
+
+impl IdentityColumn for crate::bitcoin::PublicKey {
+    fn to_column(&self) -> String {
+        ::bitcoin::PublicKey::from(*self).to_string()
+    }
+
+    fn from_column(column: &str) -> anyhow::Result<Self> {
+        let public_key = ::bitcoin::PublicKey::from_str(column)?;
+
+        Ok(public_key.into())
+    }
+}
+
+impl IdentityColumn for ethereum::Address {
+    fn to_column(&self) -> String {
+        EthereumAddress::from(*self).to_string()
+    }
+
+    fn from_column(column: &str) -> anyhow::Result<Self> {
+        let address = EthereumAddress::from_str(column)?;
+
+        Ok(address.into())
+    }
+}
+
+impl IdentityColumn for crate::lightning::PublicKey {
+    fn to_column(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_column(column: &str) -> anyhow::Result<Self> {
+        let public_key = crate::lightning::PublicKey::from_str(column)?;
+
+        Ok(public_key)
+    }
+}
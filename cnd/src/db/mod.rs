@@ -1,8 +1,11 @@
 #[cfg(test)]
 mod integration_tests;
+mod executed_actions;
 mod load_swaps;
+mod message_columns;
 mod save;
 mod schema;
+mod swap_events;
 mod wrapper_types;
 #[macro_use]
 mod swap;
@@ -13,9 +16,14 @@ pub mod with_swap_types;
 embed_migrations!("./migrations");
 
 pub use self::{
+    executed_actions::{ExecutedAction, ExecutedActions},
     load_swaps::{AcceptedSwap, LoadAcceptedSwap},
+    message_columns::{
+        AssetColumns, IdentityColumn, LedgerColumns, MissingTokenContract, UnexpectedColumnValue,
+    },
     save::*,
     swap::*,
+    swap_events::SwapEvents,
     swap_types::*,
 };
 
@@ -1,22 +1,16 @@
 use crate::{
-    asset,
     db::{
         schema::{self, *},
-        wrapper_types::{
-            custom_sql_types::{Text, U32},
-            BitcoinNetwork, Erc20Amount, Ether, EthereumAddress, Satoshis,
-        },
-        Sqlite, Swap,
+        wrapper_types::custom_sql_types::{Text, U32},
+        AssetColumns, IdentityColumn, LedgerColumns, Sqlite, Swap,
     },
     swap_protocols::{
-        ledger::{self, Ethereum},
         rfc003::{Accept, Decline, Request, SecretHash},
         HashFunction, Role, SwapId,
     },
 };
 use async_trait::async_trait;
 use diesel::RunQueryDsl;
-use impl_template::impl_template;
 use libp2p::{self, PeerId};
 
 /// Save swap to database.
@@ -60,196 +54,42 @@ impl From<Swap> for InsertableSwap {
 }
 
 #[derive(Insertable, Debug, Clone)]
-#[table_name = "rfc003_bitcoin_ethereum_bitcoin_ether_request_messages"]
-struct InsertableBitcoinEthereumBitcoinEtherRequestMessage {
+#[table_name = "rfc003_request_messages"]
+struct InsertableRequestMessage {
     swap_id: Text<SwapId>,
-    bitcoin_network: Text<BitcoinNetwork>,
-    ethereum_chain_id: U32,
-    bitcoin_amount: Text<Satoshis>,
-    ether_amount: Text<Ether>,
+    alpha_ledger: String,
+    alpha_ledger_network: String,
+    beta_ledger: String,
+    beta_ledger_network: String,
+    alpha_asset: String,
+    alpha_asset_quantity: String,
+    alpha_asset_token_contract: Option<String>,
+    beta_asset: String,
+    beta_asset_quantity: String,
+    beta_asset_token_contract: Option<String>,
     hash_function: Text<HashFunction>,
-    bitcoin_refund_identity: Text<::bitcoin::PublicKey>,
-    ethereum_redeem_identity: Text<EthereumAddress>,
-    bitcoin_expiry: U32,
-    ethereum_expiry: U32,
+    alpha_ledger_refund_identity: String,
+    beta_ledger_redeem_identity: String,
+    alpha_expiry: U32,
+    beta_expiry: U32,
     secret_hash: Text<SecretHash>,
 }
 
-#[impl_template]
 #[async_trait]
-impl
-    Save<
-        Request<
-            ((
-                ledger::bitcoin::Mainnet,
-                ledger::bitcoin::Testnet,
-                ledger::bitcoin::Regtest,
-            )),
-            Ethereum,
-            asset::Bitcoin,
-            asset::Ether,
-        >,
-    > for Sqlite
+impl<AL, BL, AA, BA> Save<Request<AL, BL, AA, BA>> for Sqlite
+where
+    AL: LedgerColumns,
+    BL: LedgerColumns,
+    AA: AssetColumns,
+    BA: AssetColumns,
+    AL::Identity: IdentityColumn,
+    BL::Identity: IdentityColumn,
 {
-    async fn save(
-        &self,
-        message: Request<__TYPE0__, Ethereum, asset::Bitcoin, asset::Ether>,
-    ) -> anyhow::Result<()> {
-        let Request {
-            swap_id,
-            alpha_asset,
-            beta_ledger,
-            beta_asset,
-            hash_function,
-            alpha_ledger_refund_identity,
-            beta_ledger_redeem_identity,
-            alpha_expiry,
-            beta_expiry,
-            secret_hash,
-            ..
-        } = message;
-
-        let insertable = InsertableBitcoinEthereumBitcoinEtherRequestMessage {
-            swap_id: Text(swap_id),
-            bitcoin_network: Text(BitcoinNetwork::from(__TYPE0__)),
-            ethereum_chain_id: U32(beta_ledger.chain_id.into()),
-            bitcoin_amount: Text(alpha_asset.into()),
-            ether_amount: Text(beta_asset.into()),
-            hash_function: Text(hash_function),
-            bitcoin_refund_identity: Text(alpha_ledger_refund_identity.into()),
-            ethereum_redeem_identity: Text(beta_ledger_redeem_identity.into()),
-            bitcoin_expiry: U32(alpha_expiry.into()),
-            ethereum_expiry: U32(beta_expiry.into()),
-            secret_hash: Text(secret_hash),
-        };
-
-        self.do_in_transaction(|connection| {
-            diesel::insert_into(rfc003_bitcoin_ethereum_bitcoin_ether_request_messages::table)
-                .values(&insertable)
-                .execute(connection)
-        })
-        .await?;
-
-        Ok(())
-    }
-}
-
-#[derive(Insertable, Debug, Clone)]
-#[table_name = "rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages"]
-struct InsertableBitcoinEthereumBitcoinErc20RequestMessage {
-    swap_id: Text<SwapId>,
-    bitcoin_network: Text<BitcoinNetwork>,
-    ethereum_chain_id: U32,
-    bitcoin_amount: Text<Satoshis>,
-    erc20_amount: Text<Erc20Amount>,
-    erc20_token_contract: Text<EthereumAddress>,
-    hash_function: Text<HashFunction>,
-    bitcoin_refund_identity: Text<::bitcoin::PublicKey>,
-    ethereum_redeem_identity: Text<EthereumAddress>,
-    bitcoin_expiry: U32,
-    ethereum_expiry: U32,
-    secret_hash: Text<SecretHash>,
-}
-
-#[impl_template]
-#[async_trait]
-impl
-    Save<
-        Request<
-            ((
-                ledger::bitcoin::Mainnet,
-                ledger::bitcoin::Testnet,
-                ledger::bitcoin::Regtest,
-            )),
-            Ethereum,
-            asset::Bitcoin,
-            asset::Erc20,
-        >,
-    > for Sqlite
-{
-    async fn save(
-        &self,
-        message: Request<__TYPE0__, Ethereum, asset::Bitcoin, asset::Erc20>,
-    ) -> anyhow::Result<()> {
-        let Request {
-            swap_id,
-            alpha_asset,
-            beta_ledger,
-            beta_asset,
-            hash_function,
-            alpha_ledger_refund_identity,
-            beta_ledger_redeem_identity,
-            alpha_expiry,
-            beta_expiry,
-            secret_hash,
-            ..
-        } = message;
-
-        let insertable = InsertableBitcoinEthereumBitcoinErc20RequestMessage {
-            swap_id: Text(swap_id),
-            bitcoin_network: Text(BitcoinNetwork::from(__TYPE0__)),
-            ethereum_chain_id: U32(beta_ledger.chain_id.into()),
-            bitcoin_amount: Text(alpha_asset.into()),
-            erc20_amount: Text(beta_asset.quantity.into()),
-            erc20_token_contract: Text(beta_asset.token_contract.into()),
-            hash_function: Text(hash_function),
-            bitcoin_refund_identity: Text(alpha_ledger_refund_identity.into()),
-            ethereum_redeem_identity: Text(beta_ledger_redeem_identity.into()),
-            bitcoin_expiry: U32(alpha_expiry.into()),
-            ethereum_expiry: U32(beta_expiry.into()),
-            secret_hash: Text(secret_hash),
-        };
-
-        self.do_in_transaction(|connection| {
-            diesel::insert_into(rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages::table)
-                .values(&insertable)
-                .execute(connection)
-        })
-        .await?;
-
-        Ok(())
-    }
-}
-
-#[derive(Insertable, Debug, Clone)]
-#[table_name = "rfc003_ethereum_bitcoin_ether_bitcoin_request_messages"]
-struct InsertableEthereumBitcoinEtherBitcoinRequestMessage {
-    swap_id: Text<SwapId>,
-    ethereum_chain_id: U32,
-    bitcoin_network: Text<BitcoinNetwork>,
-    ether_amount: Text<Ether>,
-    bitcoin_amount: Text<Satoshis>,
-    hash_function: Text<HashFunction>,
-    ethereum_refund_identity: Text<EthereumAddress>,
-    bitcoin_redeem_identity: Text<::bitcoin::PublicKey>,
-    ethereum_expiry: U32,
-    bitcoin_expiry: U32,
-    secret_hash: Text<SecretHash>,
-}
-
-#[impl_template]
-#[async_trait]
-impl
-    Save<
-        Request<
-            Ethereum,
-            ((
-                ledger::bitcoin::Mainnet,
-                ledger::bitcoin::Testnet,
-                ledger::bitcoin::Regtest,
-            )),
-            asset::Ether,
-            asset::Bitcoin,
-        >,
-    > for Sqlite
-{
-    async fn save(
-        &self,
-        message: Request<Ethereum, __TYPE0__, asset::Ether, asset::Bitcoin>,
-    ) -> anyhow::Result<()> {
+    async fn save(&self, message: Request<AL, BL, AA, BA>) -> anyhow::Result<()> {
         let Request {
             swap_id,
             alpha_ledger,
+            beta_ledger,
             alpha_asset,
             beta_asset,
             hash_function,
@@ -258,25 +98,30 @@ impl
             alpha_expiry,
             beta_expiry,
             secret_hash,
-            ..
         } = message;
 
-        let insertable = InsertableEthereumBitcoinEtherBitcoinRequestMessage {
+        let insertable = InsertableRequestMessage {
             swap_id: Text(swap_id),
-            bitcoin_network: Text(BitcoinNetwork::from(__TYPE0__)),
-            ethereum_chain_id: U32(alpha_ledger.chain_id.into()),
-            ether_amount: Text(alpha_asset.into()),
-            bitcoin_amount: Text(beta_asset.into()),
+            alpha_ledger: AL::NAME.to_owned(),
+            alpha_ledger_network: alpha_ledger.network(),
+            beta_ledger: BL::NAME.to_owned(),
+            beta_ledger_network: beta_ledger.network(),
+            alpha_asset: AA::NAME.to_owned(),
+            alpha_asset_quantity: alpha_asset.quantity(),
+            alpha_asset_token_contract: alpha_asset.token_contract(),
+            beta_asset: BA::NAME.to_owned(),
+            beta_asset_quantity: beta_asset.quantity(),
+            beta_asset_token_contract: beta_asset.token_contract(),
             hash_function: Text(hash_function),
-            ethereum_refund_identity: Text(alpha_ledger_refund_identity.into()),
-            bitcoin_redeem_identity: Text(beta_ledger_redeem_identity.into()),
-            ethereum_expiry: U32(alpha_expiry.into()),
-            bitcoin_expiry: U32(beta_expiry.into()),
+            alpha_ledger_refund_identity: alpha_ledger_refund_identity.to_column(),
+            beta_ledger_redeem_identity: beta_ledger_redeem_identity.to_column(),
+            alpha_expiry: U32(alpha_expiry.into()),
+            beta_expiry: U32(beta_expiry.into()),
             secret_hash: Text(secret_hash),
         };
 
         self.do_in_transaction(|connection| {
-            diesel::insert_into(rfc003_ethereum_bitcoin_ether_bitcoin_request_messages::table)
+            diesel::insert_into(rfc003_request_messages::table)
                 .values(&insertable)
                 .execute(connection)
         })
@@ -287,147 +132,34 @@ impl
 }
 
 #[derive(Insertable, Debug, Clone)]
-#[table_name = "rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages"]
-struct InsertableEthereumBitcoinErc20BitcoinRequestMessage {
+#[table_name = "rfc003_accept_messages"]
+struct InsertableAcceptMessage {
     swap_id: Text<SwapId>,
-    ethereum_chain_id: U32,
-    bitcoin_network: Text<BitcoinNetwork>,
-    erc20_amount: Text<Erc20Amount>,
-    erc20_token_contract: Text<EthereumAddress>,
-    bitcoin_amount: Text<Satoshis>,
-    hash_function: Text<HashFunction>,
-    ethereum_refund_identity: Text<EthereumAddress>,
-    bitcoin_redeem_identity: Text<::bitcoin::PublicKey>,
-    ethereum_expiry: U32,
-    bitcoin_expiry: U32,
-    secret_hash: Text<SecretHash>,
+    alpha_ledger_redeem_identity: String,
+    beta_ledger_refund_identity: String,
 }
 
-#[impl_template]
 #[async_trait]
-impl
-    Save<
-        Request<
-            Ethereum,
-            ((
-                ledger::bitcoin::Mainnet,
-                ledger::bitcoin::Testnet,
-                ledger::bitcoin::Regtest,
-            )),
-            asset::Erc20,
-            asset::Bitcoin,
-        >,
-    > for Sqlite
+impl<AI, BI> Save<Accept<AI, BI>> for Sqlite
+where
+    AI: IdentityColumn,
+    BI: IdentityColumn,
 {
-    async fn save(
-        &self,
-        message: Request<Ethereum, __TYPE0__, asset::Erc20, asset::Bitcoin>,
-    ) -> anyhow::Result<()> {
-        let Request {
-            swap_id,
-            alpha_ledger,
-            alpha_asset,
-            beta_asset,
-            hash_function,
-            alpha_ledger_refund_identity,
-            beta_ledger_redeem_identity,
-            alpha_expiry,
-            beta_expiry,
-            secret_hash,
-            ..
-        } = message;
-
-        let insertable = InsertableEthereumBitcoinErc20BitcoinRequestMessage {
-            swap_id: Text(swap_id),
-            ethereum_chain_id: U32(alpha_ledger.chain_id.into()),
-            bitcoin_network: Text(BitcoinNetwork::from(__TYPE0__)),
-            erc20_amount: Text(alpha_asset.quantity.into()),
-            erc20_token_contract: Text(alpha_asset.token_contract.into()),
-            bitcoin_amount: Text(beta_asset.into()),
-            hash_function: Text(hash_function),
-            ethereum_refund_identity: Text(alpha_ledger_refund_identity.into()),
-            bitcoin_redeem_identity: Text(beta_ledger_redeem_identity.into()),
-            ethereum_expiry: U32(alpha_expiry.into()),
-            bitcoin_expiry: U32(beta_expiry.into()),
-            secret_hash: Text(secret_hash),
-        };
-
-        self.do_in_transaction(|connection| {
-            diesel::insert_into(rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages::table)
-                .values(&insertable)
-                .execute(connection)
-        })
-        .await?;
-
-        Ok(())
-    }
-}
-
-#[derive(Insertable, Debug, Copy, Clone)]
-#[table_name = "rfc003_ethereum_bitcoin_accept_messages"]
-struct InsertableEthereumBitcoinAcceptMessage {
-    swap_id: Text<SwapId>,
-    ethereum_redeem_identity: Text<EthereumAddress>,
-    bitcoin_refund_identity: Text<bitcoin::PublicKey>,
-}
-
-#[async_trait]
-impl Save<Accept<crate::ethereum::Address, crate::bitcoin::PublicKey>> for Sqlite {
-    async fn save(
-        &self,
-        message: Accept<crate::ethereum::Address, crate::bitcoin::PublicKey>,
-    ) -> anyhow::Result<()> {
-        let Accept {
-            swap_id,
-            alpha_ledger_redeem_identity,
-            beta_ledger_refund_identity,
-        } = message;
-
-        let insertable = InsertableEthereumBitcoinAcceptMessage {
-            swap_id: Text(swap_id),
-            ethereum_redeem_identity: Text(alpha_ledger_redeem_identity.into()),
-            bitcoin_refund_identity: Text(beta_ledger_refund_identity.into()),
-        };
-
-        self.do_in_transaction(|connection| {
-            diesel::insert_into(rfc003_ethereum_bitcoin_accept_messages::table)
-                .values(&insertable)
-                .execute(&*connection)
-        })
-        .await?;
-
-        Ok(())
-    }
-}
-
-#[derive(Insertable, Debug, Copy, Clone)]
-#[table_name = "rfc003_bitcoin_ethereum_accept_messages"]
-struct InsertableBitcoinEthereumAcceptMessage {
-    swap_id: Text<SwapId>,
-    bitcoin_redeem_identity: Text<bitcoin::PublicKey>,
-    ethereum_refund_identity: Text<EthereumAddress>,
-}
-
-#[async_trait]
-impl Save<Accept<crate::bitcoin::PublicKey, crate::ethereum::Address>> for Sqlite {
-    async fn save(
-        &self,
-        message: Accept<crate::bitcoin::PublicKey, crate::ethereum::Address>,
-    ) -> anyhow::Result<()> {
+    async fn save(&self, message: Accept<AI, BI>) -> anyhow::Result<()> {
         let Accept {
             swap_id,
             alpha_ledger_redeem_identity,
             beta_ledger_refund_identity,
         } = message;
 
-        let insertable = InsertableBitcoinEthereumAcceptMessage {
+        let insertable = InsertableAcceptMessage {
             swap_id: Text(swap_id),
-            bitcoin_redeem_identity: Text(alpha_ledger_redeem_identity.into()),
-            ethereum_refund_identity: Text(beta_ledger_refund_identity.into()),
+            alpha_ledger_redeem_identity: alpha_ledger_redeem_identity.to_column(),
+            beta_ledger_refund_identity: beta_ledger_refund_identity.to_column(),
         };
 
         self.do_in_transaction(|connection| {
-            diesel::insert_into(rfc003_bitcoin_ethereum_accept_messages::table)
+            diesel::insert_into(rfc003_accept_messages::table)
                 .values(&insertable)
                 .execute(&*connection)
         })
@@ -0,0 +1,90 @@
+use crate::{
+    db::{
+        schema::{self, rfc003_swap_events},
+        wrapper_types::custom_sql_types::Text,
+        Sqlite,
+    },
+    swap_protocols::SwapId,
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Record and reload the ledger events that were observed while executing a
+/// swap.
+///
+/// Events are stored in the order they were saved. Replaying them on top of
+/// the state of an accepted swap yields the ledger states the swap had before
+/// cnd was stopped.
+#[async_trait]
+pub trait SwapEvents<E>: Send + Sync + 'static {
+    async fn save_swap_event(&self, swap_id: &SwapId, event: E) -> anyhow::Result<()>;
+    async fn load_swap_events(&self, swap_id: &SwapId) -> anyhow::Result<Vec<E>>;
+}
+
+#[async_trait]
+impl<E> SwapEvents<E> for Sqlite
+where
+    E: Serialize + DeserializeOwned + Send + 'static,
+{
+    async fn save_swap_event(&self, swap_id: &SwapId, event: E) -> anyhow::Result<()> {
+        let insertable = InsertableSwapEvent {
+            swap_id: Text(*swap_id),
+            event: serde_json::to_string(&event)?,
+        };
+
+        self.do_in_transaction(|connection| {
+            diesel::insert_into(schema::rfc003_swap_events::dsl::rfc003_swap_events)
+                .values(&insertable)
+                .execute(&*connection)
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn load_swap_events(&self, swap_id: &SwapId) -> anyhow::Result<Vec<E>> {
+        use self::schema::rfc003_swap_events as swap_events;
+
+        let records: Vec<QueryableSwapEvent> = self
+            .do_in_transaction(|connection| {
+                let key = Text(swap_id);
+
+                swap_events::table
+                    .filter(swap_events::swap_id.eq(key))
+                    .order(swap_events::id.asc())
+                    .load(connection)
+            })
+            .await?;
+
+        records
+            .into_iter()
+            .map(|record| {
+                serde_json::from_str(&record.event).map_err(|e| {
+                    anyhow::anyhow!(
+                        "failed to deserialize event {} of swap {}: {}",
+                        record.id,
+                        *record.swap_id,
+                        e
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[table_name = "rfc003_swap_events"]
+struct InsertableSwapEvent {
+    swap_id: Text<SwapId>,
+    event: String,
+}
+
+#[derive(Queryable, Debug, Clone, PartialEq)]
+struct QueryableSwapEvent {
+    id: i32,
+    swap_id: Text<SwapId>,
+    event: String,
+    at: NaiveDateTime,
+}
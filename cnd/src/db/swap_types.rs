@@ -1,11 +1,7 @@
 use crate::{
     asset, comit_api,
-    db::{
-        schema,
-        wrapper_types::{custom_sql_types::Text, BitcoinNetwork},
-        Sqlite,
-    },
-    diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl},
+    db::{schema, wrapper_types::custom_sql_types::Text, AssetColumns, LedgerColumns, Sqlite},
+    diesel::{ExpressionMethods, QueryDsl, RunQueryDsl},
     swap_protocols::{Role, SwapId},
 };
 use async_trait::async_trait;
@@ -24,103 +20,89 @@ pub trait DetermineTypes: Send + Sync + 'static {
 #[async_trait]
 impl DetermineTypes for Sqlite {
     async fn determine_types(&self, key: &SwapId) -> anyhow::Result<SwapTypes> {
-        let role = self.role(key).await?;
-
-        if let Some(bitcoin_network) = self
-            .rfc003_bitcoin_ethereum_bitcoin_ether_request_messages_has_swap(key)
-            .await?
-        {
-            return Ok(SwapTypes {
-                alpha_ledger: bitcoin_network.into(),
-                beta_ledger: LedgerKind::Ethereum,
-                alpha_asset: AssetKind::Bitcoin,
-                beta_asset: AssetKind::Ether,
-                role,
-            });
-        }
-
-        if let Some(bitcoin_network) = self
-            .rfc003_ethereum_bitcoin_ether_bitcoin_request_messages_has_swap(key)
-            .await?
-        {
-            return Ok(SwapTypes {
-                alpha_ledger: LedgerKind::Ethereum,
-                beta_ledger: bitcoin_network.into(),
-                alpha_asset: AssetKind::Ether,
-                beta_asset: AssetKind::Bitcoin,
-                role,
-            });
-        }
-
-        if let Some(bitcoin_network) = self
-            .rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages_has_swap(key)
-            .await?
-        {
-            return Ok(SwapTypes {
-                alpha_ledger: bitcoin_network.into(),
-                beta_ledger: LedgerKind::Ethereum,
-                alpha_asset: AssetKind::Bitcoin,
-                beta_asset: AssetKind::Erc20,
-                role,
-            });
-        }
+        use schema::rfc003_request_messages as request_messages;
 
-        if let Some(bitcoin_network) = self
-            .rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages_has_swap(key)
-            .await?
-        {
-            return Ok(SwapTypes {
-                alpha_ledger: LedgerKind::Ethereum,
-                beta_ledger: bitcoin_network.into(),
-                alpha_asset: AssetKind::Erc20,
-                beta_asset: AssetKind::Bitcoin,
-                role,
-            });
-        }
-
-        unreachable!("we got role for swap so the swap_id must exist")
+        let role = self.role(key).await?;
+        let descriptors: SwapDescriptors = self
+            .do_in_transaction(|connection| {
+                let key = Text(key);
+
+                request_messages::table
+                    .filter(request_messages::swap_id.eq(key))
+                    .select((
+                        request_messages::alpha_ledger,
+                        request_messages::alpha_ledger_network,
+                        request_messages::beta_ledger,
+                        request_messages::beta_ledger_network,
+                        request_messages::alpha_asset,
+                        request_messages::beta_asset,
+                    ))
+                    .first(connection)
+            })
+            .await?;
+
+        crate::swap_registry!(_determine_types!(descriptors, key, role))
     }
 }
 
-macro_rules! impl_has_swap {
-    ($table:ident) => {
-        paste::item! {
-            async fn [<$table _has_swap>](&self, key: &SwapId) -> anyhow::Result<Option<BitcoinNetwork>> {
-                use schema::$table as swaps;
-
-                let record: Option<QueryableSwap> = self.do_in_transaction(|connection| {
-                    let key = Text(key);
-                    swaps::table
-                        .filter(swaps::swap_id.eq(key))
-                        .select((swaps::swap_id,swaps::bitcoin_network))
-                        .first(&*connection)
-                        .optional()
-                })
-                .await?;
-
-                match record {
-                    None => Ok(None),
-                    Some(swap) => {
-                        let network: BitcoinNetwork = *swap.bitcoin_network;
-                        Ok(Some(network))
-                    }
-                }
+#[doc(hidden)]
+#[macro_export]
+macro_rules! _determine_types {
+    (($descriptors:ident, $key:ident, $role:ident) [$({ $al:ty, $bl:ty, $aa:ty, $ba:ty, $accept:ty })*]) => {{
+        use $crate::{
+            db::SwapTypes,
+            swap_protocols::registry::{RegisteredAsset, RegisteredLedger},
+        };
+
+        $(
+            if $descriptors.describe::<$al, $bl, $aa, $ba>() {
+                return Ok(SwapTypes {
+                    alpha_ledger: <$al as RegisteredLedger>::KIND,
+                    beta_ledger: <$bl as RegisteredLedger>::KIND,
+                    alpha_asset: <$aa as RegisteredAsset>::KIND,
+                    beta_asset: <$ba as RegisteredAsset>::KIND,
+                    role: $role,
+                });
             }
-        }
-    };
-}
+        )*
 
-impl Sqlite {
-    impl_has_swap!(rfc003_bitcoin_ethereum_bitcoin_ether_request_messages);
-    impl_has_swap!(rfc003_ethereum_bitcoin_ether_bitcoin_request_messages);
-    impl_has_swap!(rfc003_bitcoin_ethereum_bitcoin_erc20_request_messages);
-    impl_has_swap!(rfc003_ethereum_bitcoin_erc20_bitcoin_request_messages);
+        Err(anyhow::anyhow!(
+            "swap {} is stored with an unsupported combination of ledgers and assets",
+            $key
+        ))
+    }};
 }
 
+/// The ledgers and assets a swap is stored with.
 #[derive(Queryable, Debug, Clone, PartialEq)]
-struct QueryableSwap {
-    swap_id: Text<SwapId>,
-    bitcoin_network: Text<BitcoinNetwork>,
+struct SwapDescriptors {
+    alpha_ledger: String,
+    alpha_ledger_network: String,
+    beta_ledger: String,
+    beta_ledger_network: String,
+    alpha_asset: String,
+    beta_asset: String,
+}
+
+impl SwapDescriptors {
+    /// Whether the swap is of the given combination of ledgers and assets.
+    ///
+    /// Bitcoin is a different ledger on every network, so the network has to
+    /// be taken into account too.
+    fn describe<AL, BL, AA, BA>(&self) -> bool
+    where
+        AL: LedgerColumns,
+        BL: LedgerColumns,
+        AA: AssetColumns,
+        BA: AssetColumns,
+    {
+        self.alpha_ledger == AL::NAME
+            && self.beta_ledger == BL::NAME
+            && self.alpha_asset == AA::NAME
+            && self.beta_asset == BA::NAME
+            && AL::from_network(&self.alpha_ledger_network).is_ok()
+            && BL::from_network(&self.beta_ledger_network).is_ok()
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -143,6 +125,8 @@ pub enum BitcoinLedgerKind {
 pub enum LedgerKind {
     Bitcoin(BitcoinLedgerKind),
     Ethereum,
+    Lightning,
+    Litecoin,
 }
 
 impl From<comit_api::LedgerKind> for LedgerKind {
@@ -158,6 +142,8 @@ impl From<comit_api::LedgerKind> for LedgerKind {
                 LedgerKind::Bitcoin(BitcoinLedgerKind::Regtest)
             }
             comit_api::LedgerKind::Ethereum(_) => LedgerKind::Ethereum,
+            comit_api::LedgerKind::Lightning(_) => LedgerKind::Lightning,
+            comit_api::LedgerKind::Litecoin(_) => LedgerKind::Litecoin,
         }
     }
 }
@@ -167,6 +153,7 @@ pub enum AssetKind {
     Bitcoin,
     Ether,
     Erc20,
+    Litecoin,
 }
 
 impl From<asset::AssetKind> for AssetKind {
@@ -175,6 +162,7 @@ impl From<asset::AssetKind> for AssetKind {
             asset::AssetKind::Bitcoin(_) => AssetKind::Bitcoin,
             asset::AssetKind::Ether(_) => AssetKind::Ether,
             asset::AssetKind::Erc20(_) => AssetKind::Erc20,
+            asset::AssetKind::Litecoin(_) => AssetKind::Litecoin,
         }
     }
 }
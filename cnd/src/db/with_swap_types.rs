@@ -1,6 +1,8 @@
+#[doc(hidden)]
+#[macro_export]
 macro_rules! _match_role {
     ($role:ident, $fn:expr) => {{
-        use crate::swap_protocols::{
+        use $crate::swap_protocols::{
             rfc003::{alice, bob},
             Role,
         };
@@ -21,233 +23,49 @@ macro_rules! _match_role {
     }};
 }
 
+/// Evaluates `$fn` with the types of the swap described by `$swap_types`.
+///
+/// In `$fn`, the ledgers and assets of the swap are available as `AL`, `BL`,
+/// `AA` and `BA`, the state of our role as `ROLE` and the body to accept the
+/// swap as `AcceptBody`. The combinations are taken from `swap_registry!`.
 #[macro_export]
 macro_rules! with_swap_types {
     ($swap_types:expr, $fn:expr) => {{
-        use crate::{
-            asset,
-            db::{AssetKind, BitcoinLedgerKind, LedgerKind, SwapTypes},
-            ethereum,
-            swap_protocols::ledger::{bitcoin, Ethereum},
-        };
-        let swap_types: SwapTypes = $swap_types;
-        let role = swap_types.role;
-
-        match swap_types {
-            SwapTypes {
-                alpha_ledger: LedgerKind::Bitcoin(bitcoin_ledger_kind),
-                beta_ledger: LedgerKind::Ethereum,
-                alpha_asset: AssetKind::Bitcoin,
-                beta_asset: AssetKind::Ether,
-                ..
-            } => match bitcoin_ledger_kind {
-                BitcoinLedgerKind::Mainnet => {
-                    #[allow(dead_code)]
-                    type AL = bitcoin::Mainnet;
-                    #[allow(dead_code)]
-                    type BL = Ethereum;
-                    #[allow(dead_code)]
-                    type AA = asset::Bitcoin;
-                    #[allow(dead_code)]
-                    type BA = asset::Ether;
-                    #[allow(dead_code)]
-                    type AcceptBody =
-                        crate::http_api::routes::rfc003::accept::OnlyRefund<ethereum::Address>;
-
-                    _match_role!(role, $fn)
-                }
-                BitcoinLedgerKind::Testnet => {
-                    #[allow(dead_code)]
-                    type AL = bitcoin::Testnet;
-                    #[allow(dead_code)]
-                    type BL = Ethereum;
-                    #[allow(dead_code)]
-                    type AA = asset::Bitcoin;
-                    #[allow(dead_code)]
-                    type BA = asset::Ether;
-                    #[allow(dead_code)]
-                    type AcceptBody =
-                        crate::http_api::routes::rfc003::accept::OnlyRefund<ethereum::Address>;
-
-                    _match_role!(role, $fn)
-                }
-                BitcoinLedgerKind::Regtest => {
-                    #[allow(dead_code)]
-                    type AL = bitcoin::Regtest;
-                    #[allow(dead_code)]
-                    type BL = Ethereum;
-                    #[allow(dead_code)]
-                    type AA = asset::Bitcoin;
-                    #[allow(dead_code)]
-                    type BA = asset::Ether;
-                    #[allow(dead_code)]
-                    type AcceptBody =
-                        crate::http_api::routes::rfc003::accept::OnlyRefund<ethereum::Address>;
-
-                    _match_role!(role, $fn)
-                }
-            },
-            SwapTypes {
-                alpha_ledger: LedgerKind::Bitcoin(bitcoin_ledger_kind),
-                beta_ledger: LedgerKind::Ethereum,
-                alpha_asset: AssetKind::Bitcoin,
-                beta_asset: AssetKind::Erc20,
-                ..
-            } => match bitcoin_ledger_kind {
-                BitcoinLedgerKind::Testnet => {
-                    #[allow(dead_code)]
-                    type AL = bitcoin::Testnet;
-                    #[allow(dead_code)]
-                    type BL = Ethereum;
-                    #[allow(dead_code)]
-                    type AA = asset::Bitcoin;
-                    #[allow(dead_code)]
-                    type BA = asset::Erc20;
-                    #[allow(dead_code)]
-                    type AcceptBody =
-                        crate::http_api::routes::rfc003::accept::OnlyRefund<ethereum::Address>;
-
-                    _match_role!(role, $fn)
-                }
-                BitcoinLedgerKind::Regtest => {
-                    #[allow(dead_code)]
-                    type AL = bitcoin::Regtest;
-                    #[allow(dead_code)]
-                    type BL = Ethereum;
-                    #[allow(dead_code)]
-                    type AA = asset::Bitcoin;
-                    #[allow(dead_code)]
-                    type BA = asset::Erc20;
-                    #[allow(dead_code)]
-                    type AcceptBody =
-                        crate::http_api::routes::rfc003::accept::OnlyRefund<ethereum::Address>;
-
-                    _match_role!(role, $fn)
-                }
-                BitcoinLedgerKind::Mainnet => {
-                    #[allow(dead_code)]
-                    type AL = bitcoin::Mainnet;
-                    #[allow(dead_code)]
-                    type BL = Ethereum;
-                    #[allow(dead_code)]
-                    type AA = asset::Bitcoin;
-                    #[allow(dead_code)]
-                    type BA = asset::Erc20;
-                    #[allow(dead_code)]
-                    type AcceptBody =
-                        crate::http_api::routes::rfc003::accept::OnlyRefund<ethereum::Address>;
-
-                    _match_role!(role, $fn)
-                }
-            },
-
-            SwapTypes {
-                alpha_ledger: LedgerKind::Ethereum,
-                beta_ledger: LedgerKind::Bitcoin(bitcoin_ledger_kind),
-                alpha_asset: AssetKind::Ether,
-                beta_asset: AssetKind::Bitcoin,
-                ..
-            } => match bitcoin_ledger_kind {
-                BitcoinLedgerKind::Mainnet => {
-                    #[allow(dead_code)]
-                    type AL = Ethereum;
-                    #[allow(dead_code)]
-                    type BL = bitcoin::Mainnet;
-                    #[allow(dead_code)]
-                    type AA = asset::Ether;
-                    #[allow(dead_code)]
-                    type BA = asset::Bitcoin;
-                    #[allow(dead_code)]
-                    type AcceptBody =
-                        crate::http_api::routes::rfc003::accept::OnlyRedeem<ethereum::Address>;
-
-                    _match_role!(role, $fn)
-                }
-                BitcoinLedgerKind::Testnet => {
-                    #[allow(dead_code)]
-                    type AL = Ethereum;
-                    #[allow(dead_code)]
-                    type BL = bitcoin::Testnet;
-                    #[allow(dead_code)]
-                    type AA = asset::Ether;
-                    #[allow(dead_code)]
-                    type BA = asset::Bitcoin;
-                    #[allow(dead_code)]
-                    type AcceptBody =
-                        crate::http_api::routes::rfc003::accept::OnlyRedeem<ethereum::Address>;
+        let swap_types: $crate::db::SwapTypes = $swap_types;
 
-                    _match_role!(role, $fn)
-                }
-                BitcoinLedgerKind::Regtest => {
-                    #[allow(dead_code)]
-                    type AL = Ethereum;
-                    #[allow(dead_code)]
-                    type BL = bitcoin::Regtest;
-                    #[allow(dead_code)]
-                    type AA = asset::Ether;
-                    #[allow(dead_code)]
-                    type BA = asset::Bitcoin;
-                    #[allow(dead_code)]
-                    type AcceptBody =
-                        crate::http_api::routes::rfc003::accept::OnlyRedeem<ethereum::Address>;
-
-                    _match_role!(role, $fn)
-                }
-            },
-            SwapTypes {
-                alpha_ledger: LedgerKind::Ethereum,
-                beta_ledger: LedgerKind::Bitcoin(bitcoin_ledger_kind),
-                alpha_asset: AssetKind::Erc20,
-                beta_asset: AssetKind::Bitcoin,
-                ..
-            } => match bitcoin_ledger_kind {
-                BitcoinLedgerKind::Mainnet => {
-                    #[allow(dead_code)]
-                    type AL = Ethereum;
-                    #[allow(dead_code)]
-                    type BL = bitcoin::Mainnet;
-                    #[allow(dead_code)]
-                    type AA = asset::Erc20;
-                    #[allow(dead_code)]
-                    type BA = asset::Bitcoin;
-                    #[allow(dead_code)]
-                    type AcceptBody =
-                        crate::http_api::routes::rfc003::accept::OnlyRedeem<ethereum::Address>;
-
-                    _match_role!(role, $fn)
-                }
-                BitcoinLedgerKind::Testnet => {
-                    #[allow(dead_code)]
-                    type AL = Ethereum;
-                    #[allow(dead_code)]
-                    type BL = bitcoin::Testnet;
-                    #[allow(dead_code)]
-                    type AA = asset::Erc20;
-                    #[allow(dead_code)]
-                    type BA = asset::Bitcoin;
-                    #[allow(dead_code)]
-                    type AcceptBody =
-                        crate::http_api::routes::rfc003::accept::OnlyRedeem<ethereum::Address>;
+        $crate::swap_registry!(_with_swap_types!(swap_types, $fn))
+    }};
+}
 
-                    _match_role!(role, $fn)
-                }
-                BitcoinLedgerKind::Regtest => {
-                    #[allow(dead_code)]
-                    type AL = Ethereum;
-                    #[allow(dead_code)]
-                    type BL = bitcoin::Regtest;
-                    #[allow(dead_code)]
-                    type AA = asset::Erc20;
-                    #[allow(dead_code)]
-                    type BA = asset::Bitcoin;
-                    #[allow(dead_code)]
-                    type AcceptBody =
-                        crate::http_api::routes::rfc003::accept::OnlyRedeem<ethereum::Address>;
+#[doc(hidden)]
+#[macro_export]
+macro_rules! _with_swap_types {
+    (($swap_types:ident, $fn:expr) [$({ $al:ty, $bl:ty, $aa:ty, $ba:ty, $accept:ty })*]) => {{
+        use $crate::swap_protocols::registry::{RegisteredAsset, RegisteredLedger};
+
+        let role = $swap_types.role;
+
+        $(
+            if <$al as RegisteredLedger>::KIND == $swap_types.alpha_ledger
+                && <$bl as RegisteredLedger>::KIND == $swap_types.beta_ledger
+                && <$aa as RegisteredAsset>::KIND == $swap_types.alpha_asset
+                && <$ba as RegisteredAsset>::KIND == $swap_types.beta_asset
+            {
+                #[allow(dead_code)]
+                type AL = $al;
+                #[allow(dead_code)]
+                type BL = $bl;
+                #[allow(dead_code)]
+                type AA = $aa;
+                #[allow(dead_code)]
+                type BA = $ba;
+                #[allow(dead_code)]
+                type AcceptBody = $accept;
 
-                    _match_role!(role, $fn)
-                }
-            },
-            _ => unimplemented!(),
+                $crate::_match_role!(role, $fn)
+            } else
+        )* {
+            unimplemented!()
         }
     }};
 }
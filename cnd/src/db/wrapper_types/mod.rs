@@ -183,6 +183,26 @@ impl_from_for_bitcoinnetwork!(Mainnet);
 impl_from_for_bitcoinnetwork!(Testnet);
 impl_from_for_bitcoinnetwork!(Regtest);
 
+impl From<::bitcoin::Network> for BitcoinNetwork {
+    fn from(network: ::bitcoin::Network) -> Self {
+        match network {
+            ::bitcoin::Network::Bitcoin => BitcoinNetwork::Mainnet,
+            ::bitcoin::Network::Testnet => BitcoinNetwork::Testnet,
+            ::bitcoin::Network::Regtest => BitcoinNetwork::Regtest,
+        }
+    }
+}
+
+impl From<BitcoinNetwork> for ::bitcoin::Network {
+    fn from(network: BitcoinNetwork) -> Self {
+        match network {
+            BitcoinNetwork::Mainnet => ::bitcoin::Network::Bitcoin,
+            BitcoinNetwork::Testnet => ::bitcoin::Network::Testnet,
+            BitcoinNetwork::Regtest => ::bitcoin::Network::Regtest,
+        }
+    }
+}
+
 impl From<BitcoinNetwork> for LedgerKind {
     fn from(network: BitcoinNetwork) -> Self {
         match network {
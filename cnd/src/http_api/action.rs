@@ -1,20 +1,27 @@
 use crate::{
     asset,
+    btsieve::lightning::{cltv_delta, LndConnector},
+    db::{ExecutedAction, ExecutedActions},
     http_api::{problem, Http, MissingQueryParameters, UnexpectedQueryParameters},
+    lightning, litecoin,
     swap_protocols::{
         actions::{
             bitcoin::{SendToAddress, SpendOutput},
-            ethereum,
+            ethereum, litecoin as litecoin_actions, lnd, DeployContractOrAddHoldInvoice,
         },
-        ledger, SwapId,
+        ledger,
+        rfc003::{actions::ActionKind, Secret, SecretHash},
+        Facade, SwapId,
     },
     timestamp::Timestamp,
+    wallet::{self, ChainTimeNotReached, WalletNotConfigured},
 };
 use anyhow::Context;
+use async_trait::async_trait;
 use blockchain_contracts::bitcoin::witness;
 use http_api_problem::HttpApiProblem;
 use serde::{Deserialize, Serialize};
-use std::convert::Infallible;
+use std::convert::{Infallible, TryFrom};
 use warp::http::StatusCode;
 
 pub trait ToSirenAction {
@@ -25,16 +32,93 @@ pub trait ListRequiredFields {
     fn list_required_fields() -> Vec<siren::Field>;
 }
 
+/// The query parameters of an action.
+///
+/// Note: This enum makes use of serde's "try_from" feature: https://serde.rs/container-attrs.html#from
 #[derive(Clone, Deserialize, Debug, PartialEq)]
-#[serde(untagged)]
+#[serde(try_from = "QueryParameters")]
 pub enum ActionExecutionParameters {
     BitcoinAddressAndFee {
         address: bitcoin::Address,
         fee_per_wu: String,
     },
+    LitecoinAddressAndFee {
+        address: litecoin::Address,
+        fee_per_wu: String,
+    },
+    Execute {
+        mode: ExecutionMode,
+    },
     None {},
 }
 
+/// Passing `mode=execute` in a POST request asks cnd to execute the action
+/// with its built-in wallet instead of returning it to the client.
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionMode {
+    Execute,
+}
+
+/// The query parameters as given by the client.
+///
+/// `mode` decides the variant of `ActionExecutionParameters`, unknown modes
+/// fail to deserialize.
+#[derive(Clone, Deserialize, Debug)]
+struct QueryParameters {
+    mode: Option<ExecutionMode>,
+    address: Option<String>,
+    fee_per_wu: Option<String>,
+}
+
+impl TryFrom<QueryParameters> for ActionExecutionParameters {
+    type Error = anyhow::Error;
+
+    fn try_from(params: QueryParameters) -> Result<Self, Self::Error> {
+        Ok(match params {
+            QueryParameters {
+                mode: Some(mode),
+                address: None,
+                fee_per_wu: None,
+            } => ActionExecutionParameters::Execute { mode },
+            QueryParameters { mode: Some(_), .. } => anyhow::bail!(
+                "mode cannot be combined with the address and fee_per_wu query parameters"
+            ),
+            QueryParameters {
+                mode: None,
+                address: Some(address),
+                fee_per_wu: Some(fee_per_wu),
+            } => match address.parse::<bitcoin::Address>() {
+                Ok(address) => ActionExecutionParameters::BitcoinAddressAndFee {
+                    address,
+                    fee_per_wu,
+                },
+                Err(_) => ActionExecutionParameters::LitecoinAddressAndFee {
+                    address: address.parse::<litecoin::Address>().with_context(|| {
+                        format!("{} is neither a bitcoin nor a litecoin address", address)
+                    })?,
+                    fee_per_wu,
+                },
+            },
+            QueryParameters {
+                mode: None,
+                address: Some(_),
+                fee_per_wu: None,
+            } => anyhow::bail!("address must be combined with the fee_per_wu query parameter"),
+            QueryParameters {
+                mode: None,
+                address: None,
+                fee_per_wu: Some(_),
+            } => anyhow::bail!("fee_per_wu must be combined with the address query parameter"),
+            QueryParameters {
+                mode: None,
+                address: None,
+                fee_per_wu: None,
+            } => ActionExecutionParameters::None {},
+        })
+    }
+}
+
 #[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "kebab-case")]
 #[serde(tag = "type", content = "payload")]
@@ -50,6 +134,17 @@ pub enum ActionResponseBody {
         #[serde(skip_serializing_if = "Option::is_none")]
         min_median_block_time: Option<Timestamp>,
     },
+    LitecoinSendAmountToAddress {
+        to: litecoin::Address,
+        amount: String,
+        network: ledger::litecoin::Network,
+    },
+    LitecoinBroadcastSignedTransaction {
+        hex: String,
+        network: ledger::litecoin::Network,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        min_median_block_time: Option<Timestamp>,
+    },
     EthereumDeployContract {
         data: crate::ethereum::Bytes,
         amount: asset::Ether,
@@ -65,6 +160,45 @@ pub enum ActionResponseBody {
         #[serde(skip_serializing_if = "Option::is_none")]
         min_block_timestamp: Option<Timestamp>,
     },
+    BitcoinTransactionSent {
+        transaction_id: bitcoin::Txid,
+        network: Http<bitcoin::Network>,
+    },
+    EthereumTransactionSent {
+        transaction_hash: crate::ethereum::H256,
+        chain_id: ledger::ethereum::ChainId,
+    },
+    LndAddHoldInvoice {
+        amount: Http<asset::Bitcoin>,
+        secret_hash: SecretHash,
+        cltv_expiry: u32,
+        network: Http<bitcoin::Network>,
+    },
+    LndSendPayment {
+        to_public_key: Http<lightning::PublicKey>,
+        amount: Http<asset::Bitcoin>,
+        secret_hash: SecretHash,
+        final_cltv_delta: u32,
+        network: Http<bitcoin::Network>,
+    },
+    LndSettleInvoice {
+        secret: Secret,
+        network: Http<bitcoin::Network>,
+    },
+    LndInvoiceAdded {
+        payment_request: String,
+        network: Http<bitcoin::Network>,
+    },
+    /// The payment was handed to lnd, it only completes once the receiver
+    /// settles the invoice.
+    LndPaymentSent {
+        secret_hash: SecretHash,
+        network: Http<bitcoin::Network>,
+    },
+    LndInvoiceSettled {
+        secret_hash: SecretHash,
+        network: Http<bitcoin::Network>,
+    },
     None,
 }
 
@@ -73,21 +207,34 @@ impl ActionResponseBody {
         transaction: &bitcoin::Transaction,
         network: bitcoin::Network,
     ) -> Self {
-        let min_median_block_time = if transaction.lock_time == 0 {
-            None
-        } else {
-            // The first time a tx with lock_time can be broadcasted is when
-            // mediantime == locktime + 1
-            let min_median_block_time = transaction.lock_time + 1;
-            Some(Timestamp::from(min_median_block_time))
-        };
-
         ActionResponseBody::BitcoinBroadcastSignedTransaction {
             hex: bitcoin::consensus::encode::serialize_hex(transaction),
             network: Http(network),
-            min_median_block_time,
+            min_median_block_time: min_median_block_time(transaction),
         }
     }
+
+    fn litecoin_broadcast_signed_transaction(
+        transaction: &bitcoin::Transaction,
+        network: ledger::litecoin::Network,
+    ) -> Self {
+        ActionResponseBody::LitecoinBroadcastSignedTransaction {
+            hex: bitcoin::consensus::encode::serialize_hex(transaction),
+            network,
+            min_median_block_time: min_median_block_time(transaction),
+        }
+    }
+}
+
+fn min_median_block_time(transaction: &bitcoin::Transaction) -> Option<Timestamp> {
+    if transaction.lock_time == 0 {
+        None
+    } else {
+        // The first time a tx with lock_time can be broadcasted is when
+        // mediantime == locktime + 1
+        let min_median_block_time = transaction.lock_time + 1;
+        Some(Timestamp::from(min_median_block_time))
+    }
 }
 
 pub trait IntoResponsePayload {
@@ -150,28 +297,10 @@ impl IntoResponsePayload for SpendOutput {
                 })?;
 
                 let network = self.network;
-                let transaction =
-                    self.spend_to(address)
-                        .sign_with_rate(&*crate::SECP, fee_per_wu)
-                        .map_err(|e| {
-                            tracing::error!("Could not sign Bitcoin transaction: {:?}", e);
-                            match e {
-                                witness::Error::FeeHigherThanInputValue => HttpApiProblem::new(
-                                    "Fee is too high.",
-                                )
-                                .set_status(StatusCode::BAD_REQUEST)
-                                .set_detail(
-                                    "The Fee per byte/WU provided makes the total fee higher than the spendable input value.",
-                                ),
-                                witness::Error::OverflowingFee => HttpApiProblem::new(
-                                    "Fee is too high.",
-                                )
-                                    .set_status(StatusCode::BAD_REQUEST)
-                                    .set_detail(
-                                        "The Fee per byte/WU provided makes the total fee higher than the system supports.",
-                                    )
-                            }
-                        })?;
+                let transaction = self
+                    .spend_to(address)
+                    .sign_with_rate(&*crate::SECP, fee_per_wu)
+                    .map_err(fee_problem)?;
 
                 Ok(ActionResponseBody::bitcoin_broadcast_signed_transaction(
                     &transaction,
@@ -198,6 +327,22 @@ impl IntoResponsePayload for SpendOutput {
     }
 }
 
+fn fee_problem(e: witness::Error) -> HttpApiProblem {
+    tracing::error!("Could not sign Bitcoin transaction: {:?}", e);
+    match e {
+        witness::Error::FeeHigherThanInputValue => HttpApiProblem::new("Fee is too high.")
+            .set_status(StatusCode::BAD_REQUEST)
+            .set_detail(
+                "The Fee per byte/WU provided makes the total fee higher than the spendable input value.",
+            ),
+        witness::Error::OverflowingFee => HttpApiProblem::new("Fee is too high.")
+            .set_status(StatusCode::BAD_REQUEST)
+            .set_detail(
+                "The Fee per byte/WU provided makes the total fee higher than the system supports.",
+            ),
+    }
+}
+
 impl ListRequiredFields for SpendOutput {
     fn list_required_fields() -> Vec<siren::Field> {
         vec![
@@ -225,6 +370,126 @@ impl ListRequiredFields for SpendOutput {
     }
 }
 
+impl IntoResponsePayload for litecoin_actions::SendToAddress {
+    fn into_response_payload(
+        self,
+        query_params: ActionExecutionParameters,
+    ) -> anyhow::Result<ActionResponseBody> {
+        match query_params {
+            ActionExecutionParameters::None {} => Ok(self.into()),
+            _ => Err(anyhow::Error::from(UnexpectedQueryParameters {
+                action: "litecoin::SendToAddress",
+                parameters: &["address", "fee_per_wu"],
+            })),
+        }
+    }
+}
+
+impl From<litecoin_actions::SendToAddress> for ActionResponseBody {
+    fn from(action: litecoin_actions::SendToAddress) -> Self {
+        let litecoin_actions::SendToAddress {
+            to,
+            amount,
+            network,
+        } = action;
+        ActionResponseBody::LitecoinSendAmountToAddress {
+            to,
+            amount: amount.as_litoshi().to_string(),
+            network,
+        }
+    }
+}
+
+impl ListRequiredFields for litecoin_actions::SendToAddress {
+    fn list_required_fields() -> Vec<siren::Field> {
+        vec![]
+    }
+}
+
+impl IntoResponsePayload for litecoin_actions::SpendOutput {
+    fn into_response_payload(
+        self,
+        query_params: ActionExecutionParameters,
+    ) -> anyhow::Result<ActionResponseBody> {
+        let (address, fee_per_wu) = match query_params {
+            ActionExecutionParameters::LitecoinAddressAndFee {
+                address,
+                fee_per_wu,
+            } => (address, fee_per_wu),
+            // Litecoin's testnet addresses share their base58 prefixes with
+            // the ones of Bitcoin's testnet.
+            ActionExecutionParameters::BitcoinAddressAndFee {
+                address,
+                fee_per_wu,
+            } => {
+                let address = address.to_string().parse::<litecoin::Address>().with_context(|| {
+                    HttpApiProblem::new("Invalid query parameter.")
+                        .set_status(StatusCode::BAD_REQUEST)
+                        .set_detail("Query parameter address is not a litecoin address.")
+                })?;
+
+                (address, fee_per_wu)
+            }
+            _ => {
+                return Err(anyhow::Error::from(MissingQueryParameters {
+                    action: "litecoin::SpendOutput",
+                    parameters: &[
+                        problem::MissingQueryParameter {
+                            name: "address",
+                            data_type: "string",
+                            description: "The litecoin address to where the funds should be sent.",
+                        },
+                        problem::MissingQueryParameter {
+                            name: "fee_per_wu",
+                            data_type: "uint",
+                            description:
+                            "The fee per weight unit you want to pay for the transaction in litoshis.",
+                        },
+                    ]
+                }))
+            }
+        };
+
+        let fee_per_wu = fee_per_wu.parse::<usize>().with_context(|| {
+            HttpApiProblem::new("Invalid query parameter.")
+                .set_status(StatusCode::BAD_REQUEST)
+                .set_detail("Query parameter fee-per-byte is not a valid unsigned integer.")
+        })?;
+
+        let network = self.network;
+        let transaction = self
+            .spend_to(address)
+            .sign_with_rate(&*crate::SECP, fee_per_wu)
+            .map_err(fee_problem)?;
+
+        Ok(ActionResponseBody::litecoin_broadcast_signed_transaction(
+            &transaction,
+            network,
+        ))
+    }
+}
+
+impl ListRequiredFields for litecoin_actions::SpendOutput {
+    fn list_required_fields() -> Vec<siren::Field> {
+        vec![
+            siren::Field {
+                name: "address".to_owned(),
+                class: vec!["litecoin".to_owned(), "address".to_owned()],
+                _type: Some("text".to_owned()),
+                value: None,
+                title: None,
+            },
+            siren::Field {
+                name: "fee_per_wu".to_owned(),
+                class: vec!["litecoin".to_owned(), "feePerWU".to_owned()],
+                _type: Some("number".to_owned()),
+                value: None,
+                title: None,
+            },
+        ]
+    }
+}
+
 impl IntoResponsePayload for ethereum::DeployContract {
     fn into_response_payload(
         self,
@@ -291,6 +556,131 @@ impl ListRequiredFields for ethereum::CallContract {
     }
 }
 
+impl IntoResponsePayload for lnd::AddHoldInvoice {
+    fn into_response_payload(
+        self,
+        query_params: ActionExecutionParameters,
+    ) -> anyhow::Result<ActionResponseBody> {
+        match query_params {
+            ActionExecutionParameters::None {} => Ok(self.into()),
+            _ => Err(anyhow::Error::from(UnexpectedQueryParameters {
+                action: "lnd::AddHoldInvoice",
+                parameters: &["address", "fee_per_wu"],
+            })),
+        }
+    }
+}
+
+impl From<lnd::AddHoldInvoice> for ActionResponseBody {
+    fn from(action: lnd::AddHoldInvoice) -> Self {
+        let lnd::AddHoldInvoice {
+            amount,
+            secret_hash,
+            expiry,
+            network,
+        } = action;
+        ActionResponseBody::LndAddHoldInvoice {
+            amount: Http(amount),
+            secret_hash,
+            cltv_expiry: cltv_delta(expiry),
+            network: Http(network),
+        }
+    }
+}
+
+impl ListRequiredFields for lnd::AddHoldInvoice {
+    fn list_required_fields() -> Vec<siren::Field> {
+        vec![]
+    }
+}
+
+impl IntoResponsePayload for lnd::SendPayment {
+    fn into_response_payload(
+        self,
+        query_params: ActionExecutionParameters,
+    ) -> anyhow::Result<ActionResponseBody> {
+        match query_params {
+            ActionExecutionParameters::None {} => Ok(self.into()),
+            _ => Err(anyhow::Error::from(UnexpectedQueryParameters {
+                action: "lnd::SendPayment",
+                parameters: &["address", "fee_per_wu"],
+            })),
+        }
+    }
+}
+
+impl From<lnd::SendPayment> for ActionResponseBody {
+    fn from(action: lnd::SendPayment) -> Self {
+        let lnd::SendPayment {
+            to_public_key,
+            amount,
+            secret_hash,
+            expiry,
+            network,
+        } = action;
+        ActionResponseBody::LndSendPayment {
+            to_public_key: Http(to_public_key),
+            amount: Http(amount),
+            secret_hash,
+            final_cltv_delta: cltv_delta(expiry),
+            network: Http(network),
+        }
+    }
+}
+
+impl ListRequiredFields for lnd::SendPayment {
+    fn list_required_fields() -> Vec<siren::Field> {
+        vec![]
+    }
+}
+
+impl IntoResponsePayload for lnd::SettleInvoice {
+    fn into_response_payload(
+        self,
+        query_params: ActionExecutionParameters,
+    ) -> anyhow::Result<ActionResponseBody> {
+        let lnd::SettleInvoice { secret, network } = self;
+        match query_params {
+            ActionExecutionParameters::None {} => Ok(ActionResponseBody::LndSettleInvoice {
+                secret,
+                network: Http(network),
+            }),
+            _ => Err(anyhow::Error::from(UnexpectedQueryParameters {
+                action: "lnd::SettleInvoice",
+                parameters: &["address", "fee_per_wu"],
+            })),
+        }
+    }
+}
+
+impl ListRequiredFields for lnd::SettleInvoice {
+    fn list_required_fields() -> Vec<siren::Field> {
+        vec![]
+    }
+}
+
+impl IntoResponsePayload for DeployContractOrAddHoldInvoice {
+    fn into_response_payload(
+        self,
+        query_params: ActionExecutionParameters,
+    ) -> anyhow::Result<ActionResponseBody> {
+        match self {
+            DeployContractOrAddHoldInvoice::DeployContract(action) => {
+                action.into_response_payload(query_params)
+            }
+            DeployContractOrAddHoldInvoice::AddHoldInvoice(action) => {
+                action.into_response_payload(query_params)
+            }
+        }
+    }
+}
+
+impl ListRequiredFields for DeployContractOrAddHoldInvoice {
+    fn list_required_fields() -> Vec<siren::Field> {
+        vec![]
+    }
+}
+
 impl ListRequiredFields for Infallible {
     fn list_required_fields() -> Vec<siren::Field> {
         unreachable!("how did you manage to construct Infallible?")
@@ -306,6 +696,367 @@ impl IntoResponsePayload for Infallible {
     }
 }
 
+/// Executes an action with one of the built-in wallets instead of returning
+/// it to the client.
+#[async_trait]
+pub trait ExecuteAction<A> {
+    async fn execute_action(&self, action: A) -> anyhow::Result<ActionResponseBody>;
+
+    /// Whether a wallet that can execute the action is configured, i.e. one
+    /// for the ledger and network of the action.
+    fn can_execute(&self, action: &A) -> bool;
+}
+
+/// The action was already executed for this swap, executing it again would
+/// send another transaction.
+#[derive(Debug, Clone, thiserror::Error, PartialEq)]
+#[error("{action_kind} action of swap {swap_id} was already executed")]
+pub struct ActionAlreadyExecuted {
+    pub swap_id: SwapId,
+    pub action_kind: ActionKind,
+    /// The outcome of the earlier execution, e.g. the id of its transaction.
+    pub outcome: serde_json::Value,
+}
+
+/// Executes the action unless it was already executed successfully for this
+/// swap and records the outcome in the database, failed attempts too. Attempts
+/// that were too early for the chain time did not send anything and are not
+/// recorded.
+///
+/// Executions are serialized so that two concurrent requests cannot both find
+/// the action not executed yet.
+pub async fn execute_once<A>(
+    facade: &Facade,
+    swap_id: SwapId,
+    action_kind: ActionKind,
+    action: A,
+) -> anyhow::Result<ActionResponseBody>
+where
+    A: Send + 'static,
+    Facade: ExecuteAction<A>,
+{
+    let _guard = facade.execution_lock.lock().await;
+
+    let executed = facade
+        .load_executed_actions(&swap_id)
+        .await?
+        .into_iter()
+        .find(|executed| executed.action == action_kind && !executed.failed());
+    if let Some(executed) = executed {
+        return Err(anyhow::Error::from(ActionAlreadyExecuted {
+            swap_id,
+            action_kind,
+            outcome: executed.outcome,
+        }));
+    }
+
+    match facade.execute_action(action).await {
+        Ok(outcome) => {
+            facade
+                .save_executed_action(&swap_id, action_kind, serde_json::to_value(&outcome)?)
+                .await?;
+
+            Ok(outcome)
+        }
+        Err(e) if e.is::<ChainTimeNotReached>() => Err(e),
+        Err(e) => {
+            facade
+                .save_executed_action(&swap_id, action_kind, ExecutedAction::failure(&e))
+                .await?;
+
+            Err(e)
+        }
+    }
+}
+
+fn bitcoin_wallet(
+    facade: &Facade,
+    network: bitcoin::Network,
+) -> anyhow::Result<&wallet::bitcoin::Wallet> {
+    let wallet = facade
+        .bitcoin_wallet
+        .as_ref()
+        .ok_or(WalletNotConfigured { ledger: "bitcoin" })?;
+
+    if wallet.network() != network {
+        anyhow::bail!(
+            "action is for bitcoin network {} but the wallet uses {}",
+            network,
+            wallet.network()
+        )
+    }
+
+    Ok(wallet)
+}
+
+#[async_trait]
+impl ExecuteAction<SendToAddress> for Facade {
+    async fn execute_action(&self, action: SendToAddress) -> anyhow::Result<ActionResponseBody> {
+        let SendToAddress {
+            to,
+            amount,
+            network,
+        } = action;
+        let wallet = bitcoin_wallet(self, network)?;
+
+        let transaction_id = wallet.send_to_address(&to, amount).await?;
+
+        Ok(ActionResponseBody::BitcoinTransactionSent {
+            transaction_id,
+            network: Http(network),
+        })
+    }
+
+    fn can_execute(&self, action: &SendToAddress) -> bool {
+        bitcoin_wallet(self, action.network).is_ok()
+    }
+}
+
+#[async_trait]
+impl ExecuteAction<SpendOutput> for Facade {
+    async fn execute_action(&self, action: SpendOutput) -> anyhow::Result<ActionResponseBody> {
+        let network = action.network;
+        let wallet = bitcoin_wallet(self, network)?;
+
+        let fee_per_wu = wallet.fee_per_wu().await;
+        let transaction = action
+            .spend_to(wallet.address())
+            .sign_with_rate(&*crate::SECP, fee_per_wu)
+            .map_err(fee_problem)?;
+
+        if let Some(min_median_block_time) = min_median_block_time(&transaction) {
+            if wallet.median_time_past().await? < min_median_block_time {
+                return Err(anyhow::Error::from(ChainTimeNotReached {
+                    ledger: "bitcoin",
+                    min_time: u32::from(min_median_block_time),
+                }));
+            }
+        }
+
+        let transaction_id = wallet.broadcast(&transaction).await?;
+
+        Ok(ActionResponseBody::BitcoinTransactionSent {
+            transaction_id,
+            network: Http(network),
+        })
+    }
+
+    fn can_execute(&self, action: &SpendOutput) -> bool {
+        bitcoin_wallet(self, action.network).is_ok()
+    }
+}
+
+#[async_trait]
+impl ExecuteAction<litecoin_actions::SendToAddress> for Facade {
+    async fn execute_action(
+        &self,
+        _: litecoin_actions::SendToAddress,
+    ) -> anyhow::Result<ActionResponseBody> {
+        Err(anyhow::Error::from(WalletNotConfigured {
+            ledger: "litecoin",
+        }))
+    }
+
+    fn can_execute(&self, _: &litecoin_actions::SendToAddress) -> bool {
+        false
+    }
+}
+
+#[async_trait]
+impl ExecuteAction<litecoin_actions::SpendOutput> for Facade {
+    async fn execute_action(
+        &self,
+        _: litecoin_actions::SpendOutput,
+    ) -> anyhow::Result<ActionResponseBody> {
+        Err(anyhow::Error::from(WalletNotConfigured {
+            ledger: "litecoin",
+        }))
+    }
+
+    fn can_execute(&self, _: &litecoin_actions::SpendOutput) -> bool {
+        false
+    }
+}
+
+fn ethereum_wallet(facade: &Facade) -> anyhow::Result<&wallet::ethereum::Wallet> {
+    facade
+        .ethereum_wallet
+        .as_ref()
+        .ok_or_else(|| anyhow::Error::from(WalletNotConfigured { ledger: "ethereum" }))
+}
+
+#[async_trait]
+impl ExecuteAction<ethereum::DeployContract> for Facade {
+    async fn execute_action(
+        &self,
+        action: ethereum::DeployContract,
+    ) -> anyhow::Result<ActionResponseBody> {
+        let chain_id = action.chain_id;
+        let transaction_hash = ethereum_wallet(self)?.deploy_contract(action).await?;
+
+        Ok(ActionResponseBody::EthereumTransactionSent {
+            transaction_hash,
+            chain_id,
+        })
+    }
+
+    fn can_execute(&self, action: &ethereum::DeployContract) -> bool {
+        ethereum_wallet(self).map_or(false, |wallet| wallet.chain_id() == action.chain_id)
+    }
+}
+
+#[async_trait]
+impl ExecuteAction<ethereum::CallContract> for Facade {
+    async fn execute_action(
+        &self,
+        action: ethereum::CallContract,
+    ) -> anyhow::Result<ActionResponseBody> {
+        let chain_id = action.chain_id;
+        let transaction_hash = ethereum_wallet(self)?.call_contract(action).await?;
+
+        Ok(ActionResponseBody::EthereumTransactionSent {
+            transaction_hash,
+            chain_id,
+        })
+    }
+
+    fn can_execute(&self, action: &ethereum::CallContract) -> bool {
+        ethereum_wallet(self).map_or(false, |wallet| wallet.chain_id() == action.chain_id)
+    }
+}
+
+fn lnd_connector(facade: &Facade, network: bitcoin::Network) -> anyhow::Result<&LndConnector> {
+    let lnd = facade.lnd_connector()?;
+
+    if lnd.network() != network {
+        anyhow::bail!(
+            "action is for lightning network {} but lnd uses {}",
+            network,
+            lnd.network()
+        )
+    }
+
+    Ok(lnd)
+}
+
+#[async_trait]
+impl ExecuteAction<lnd::AddHoldInvoice> for Facade {
+    async fn execute_action(
+        &self,
+        action: lnd::AddHoldInvoice,
+    ) -> anyhow::Result<ActionResponseBody> {
+        let lnd::AddHoldInvoice {
+            amount,
+            secret_hash,
+            expiry,
+            network,
+        } = action;
+
+        let payment_request = lnd_connector(self, network)?
+            .add_hold_invoice(secret_hash, amount, cltv_delta(expiry))
+            .await?;
+
+        Ok(ActionResponseBody::LndInvoiceAdded {
+            payment_request,
+            network: Http(network),
+        })
+    }
+
+    fn can_execute(&self, action: &lnd::AddHoldInvoice) -> bool {
+        lnd_connector(self, action.network).is_ok()
+    }
+}
+
+#[async_trait]
+impl ExecuteAction<lnd::SendPayment> for Facade {
+    async fn execute_action(&self, action: lnd::SendPayment) -> anyhow::Result<ActionResponseBody> {
+        let lnd::SendPayment {
+            to_public_key,
+            amount,
+            secret_hash,
+            expiry,
+            network,
+        } = action;
+        let lnd = lnd_connector(self, network)?.clone();
+
+        // lnd only responds once the hold invoice is settled or canceled, the
+        // outcome is picked up by watching the payment instead.
+        tokio::task::spawn(async move {
+            if let Err(e) = lnd
+                .send_payment(to_public_key, amount, secret_hash, cltv_delta(expiry))
+                .await
+            {
+                tracing::error!("payment for {} failed: {:?}", secret_hash, e);
+            }
+        });
+
+        Ok(ActionResponseBody::LndPaymentSent {
+            secret_hash,
+            network: Http(network),
+        })
+    }
+
+    fn can_execute(&self, action: &lnd::SendPayment) -> bool {
+        lnd_connector(self, action.network).is_ok()
+    }
+}
+
+#[async_trait]
+impl ExecuteAction<lnd::SettleInvoice> for Facade {
+    async fn execute_action(
+        &self,
+        action: lnd::SettleInvoice,
+    ) -> anyhow::Result<ActionResponseBody> {
+        let lnd::SettleInvoice { secret, network } = action;
+
+        lnd_connector(self, network)?.settle_invoice(secret).await?;
+
+        Ok(ActionResponseBody::LndInvoiceSettled {
+            secret_hash: secret.hash(),
+            network: Http(network),
+        })
+    }
+
+    fn can_execute(&self, action: &lnd::SettleInvoice) -> bool {
+        lnd_connector(self, action.network).is_ok()
+    }
+}
+
+#[async_trait]
+impl ExecuteAction<DeployContractOrAddHoldInvoice> for Facade {
+    async fn execute_action(
+        &self,
+        action: DeployContractOrAddHoldInvoice,
+    ) -> anyhow::Result<ActionResponseBody> {
+        match action {
+            DeployContractOrAddHoldInvoice::DeployContract(action) => {
+                self.execute_action(action).await
+            }
+            DeployContractOrAddHoldInvoice::AddHoldInvoice(action) => {
+                self.execute_action(action).await
+            }
+        }
+    }
+
+    fn can_execute(&self, action: &DeployContractOrAddHoldInvoice) -> bool {
+        match action {
+            DeployContractOrAddHoldInvoice::DeployContract(action) => self.can_execute(action),
+            DeployContractOrAddHoldInvoice::AddHoldInvoice(action) => self.can_execute(action),
+        }
+    }
+}
+
+#[async_trait]
+impl ExecuteAction<Infallible> for Facade {
+    async fn execute_action(&self, _: Infallible) -> anyhow::Result<ActionResponseBody> {
+        unreachable!("how did you manage to construct Infallible?")
+    }
+
+    fn can_execute(&self, _: &Infallible) -> bool {
+        unreachable!("how did you manage to construct Infallible?")
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -338,6 +1089,96 @@ mod test {
         );
     }
 
+    #[test]
+    fn given_execute_mode_deserialize_to_execute() {
+        let s = "mode=execute";
+
+        let res = serde_urlencoded::from_str::<ActionExecutionParameters>(s);
+        assert_eq!(
+            res,
+            Ok(ActionExecutionParameters::Execute {
+                mode: ExecutionMode::Execute
+            })
+        );
+    }
+
+    #[test]
+    fn given_unknown_mode_fails_to_deserialize() {
+        let s = "mode=exceute";
+
+        let res = serde_urlencoded::from_str::<ActionExecutionParameters>(s);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn given_execute_mode_with_address_and_fee_fails_to_deserialize() {
+        let s = "mode=execute&address=1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa&fee_per_wu=10";
+
+        let res = serde_urlencoded::from_str::<ActionExecutionParameters>(s);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn given_address_without_fee_fails_to_deserialize() {
+        let s = "address=1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa";
+
+        let res = serde_urlencoded::from_str::<ActionExecutionParameters>(s);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn given_fee_without_address_fails_to_deserialize() {
+        let s = "fee_per_wu=10";
+
+        let res = serde_urlencoded::from_str::<ActionExecutionParameters>(s);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn bitcoin_transaction_sent_serializes_correctly_to_json() {
+        let transaction_id = "0101010101010101010101010101010101010101010101010101010101010101"
+            .parse()
+            .unwrap();
+        let body = ActionResponseBody::BitcoinTransactionSent {
+            transaction_id,
+            network: Http(bitcoin::Network::Regtest),
+        };
+
+        let serialized = serde_json::to_string(&body).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"bitcoin-transaction-sent","payload":{"transaction_id":"0101010101010101010101010101010101010101010101010101010101010101","network":"regtest"}}"#
+        );
+    }
+
+    #[test]
+    fn ethereum_transaction_sent_serializes_correctly_to_json() {
+        let body = ActionResponseBody::EthereumTransactionSent {
+            transaction_hash: crate::ethereum::H256::repeat_byte(0x01),
+            chain_id: ChainId::regtest(),
+        };
+
+        let serialized = serde_json::to_string(&body).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"ethereum-transaction-sent","payload":{"transaction_hash":"0x0101010101010101010101010101010101010101010101010101010101010101","chain_id":17}}"#
+        );
+    }
+
+    #[test]
+    fn lnd_settle_invoice_serializes_correctly_to_json() {
+        let body = ActionResponseBody::LndSettleInvoice {
+            secret: Secret::from(*b"hello world, you are beautiful!!"),
+            network: Http(bitcoin::Network::Regtest),
+        };
+
+        let serialized = serde_json::to_string(&body).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"lnd-settle-invoice","payload":{"secret":"68656c6c6f20776f726c642c20796f75206172652062656175746966756c2121","network":"regtest"}}"#
+        );
+    }
+
     #[test]
     fn call_contract_serializes_correctly_to_json_with_none() {
         let addr = EthereumAddress::from_str("0A81e8be41b21f651a71aaB1A85c6813b8bBcCf8").unwrap();
@@ -393,4 +1234,37 @@ mod test {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn given_litecoin_address_and_fee_deserialize_to_ditto() {
+        let s = "address=ltc1qw508d6qejxtdg4y5r3zarvary0c5xw7kgmn4n9&fee_per_wu=10";
+
+        let res = serde_urlencoded::from_str::<ActionExecutionParameters>(s);
+        assert_eq!(
+            res,
+            Ok(ActionExecutionParameters::LitecoinAddressAndFee {
+                address: "ltc1qw508d6qejxtdg4y5r3zarvary0c5xw7kgmn4n9"
+                    .parse()
+                    .unwrap(),
+                fee_per_wu: "10".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn litecoin_send_amount_to_address_serializes_correctly_to_json() {
+        let body = ActionResponseBody::from(litecoin_actions::SendToAddress {
+            to: "rltc1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q22ldvf"
+                .parse()
+                .unwrap(),
+            amount: asset::Litecoin::from_litoshi(100_000_000),
+            network: ledger::litecoin::Network::Regtest,
+        });
+
+        let serialized = serde_json::to_string(&body).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"litecoin-send-amount-to-address","payload":{"to":"rltc1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q22ldvf","amount":"100000000","network":"regtest"}}"#
+        );
+    }
 }
@@ -14,10 +14,13 @@ pub use self::{
 pub const PATH: &str = "swaps";
 
 use crate::{
-    asset, ethereum,
+    asset::{self, AssetKind},
+    comit_api::LedgerKind,
+    ethereum,
     network::DialInformation,
     swap_protocols::{
         ledger::{self, ethereum::ChainId},
+        rfc003::SecretHash,
         SwapId, SwapProtocol,
     },
 };
@@ -95,9 +98,28 @@ impl Serialize for Http<crate::bitcoin::PublicKey> {
     }
 }
 
+impl Serialize for Http<crate::lightning::PublicKey> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0.to_string())
+    }
+}
+
+impl Serialize for Http<crate::lightning::Transaction> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0.payment_hash.to_string())
+    }
+}
+
 impl_serialize_type_with_fields!(bitcoin::OutPoint { "txid" => txid, "vout" => vout });
 impl_serialize_http!(crate::ethereum::H160);
 impl_serialize_http!(SwapId);
+impl_serialize_http!(SecretHash);
 
 impl Serialize for Http<SwapProtocol> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -243,6 +265,8 @@ pub enum HttpLedger {
     BitcoinTestnet,
     BitcoinRegtest,
     Ethereum(ledger::Ethereum),
+    Lightning(ledger::Lightning),
+    Litecoin(ledger::Litecoin),
 }
 
 /// An enum describing all the possible values of `alpha_asset` and
@@ -256,6 +280,7 @@ pub enum HttpAsset {
     Bitcoin(asset::Bitcoin),
     Ether(asset::Ether),
     Erc20(asset::Erc20),
+    Litecoin(asset::Litecoin),
 }
 
 /// The actual enum that is used by serde to deserialize the `alpha_ledger` and
@@ -270,6 +295,8 @@ pub enum HttpAsset {
 pub enum HttpLedgerParams {
     Bitcoin(BitcoinLedgerParams),
     Ethereum(EthereumLedgerParams),
+    Lightning(BitcoinLedgerParams),
+    Litecoin(LitecoinLedgerParams),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -285,6 +312,11 @@ impl From<bitcoin::Network> for BitcoinLedgerParams {
     }
 }
 
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub struct LitecoinLedgerParams {
+    network: ledger::litecoin::Network,
+}
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
 pub struct EthereumLedgerParams {
     chain_id: Option<ChainId>,
@@ -303,6 +335,7 @@ pub enum HttpAssetParams {
     Bitcoin(BitcoinAssetParams),
     Ether(EtherAssetParams),
     Erc20(Erc20AssetParams),
+    Litecoin(LitecoinAssetParams),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -321,6 +354,11 @@ pub struct Erc20AssetParams {
     token_contract: ethereum::Address,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct LitecoinAssetParams {
+    quantity: asset::Litecoin,
+}
+
 impl TryFrom<HttpLedgerParams> for HttpLedger {
     type Error = anyhow::Error;
 
@@ -332,6 +370,12 @@ impl TryFrom<HttpLedgerParams> for HttpLedger {
                 bitcoin::Network::Regtest => HttpLedger::BitcoinRegtest,
             },
             HttpLedgerParams::Ethereum(params) => HttpLedger::Ethereum(params.try_into()?),
+            HttpLedgerParams::Lightning(BitcoinLedgerParams { network }) => {
+                HttpLedger::Lightning(ledger::Lightning::new(*network))
+            }
+            HttpLedgerParams::Litecoin(LitecoinLedgerParams { network }) => {
+                HttpLedger::Litecoin(ledger::Litecoin::new(network))
+            }
         })
     }
 }
@@ -349,6 +393,10 @@ impl From<HttpLedger> for HttpLedgerParams {
                 HttpLedgerParams::Bitcoin(bitcoin::Network::Regtest.into())
             }
             HttpLedger::Ethereum(ledger) => HttpLedgerParams::Ethereum(ledger.into()),
+            HttpLedger::Lightning(ledger) => HttpLedgerParams::Lightning(ledger.network.into()),
+            HttpLedger::Litecoin(ledger) => HttpLedgerParams::Litecoin(LitecoinLedgerParams {
+                network: ledger.network,
+            }),
         }
     }
 }
@@ -388,6 +436,7 @@ impl From<HttpAssetParams> for HttpAsset {
             HttpAssetParams::Bitcoin(params) => HttpAsset::Bitcoin(params.into()),
             HttpAssetParams::Ether(params) => HttpAsset::Ether(params.into()),
             HttpAssetParams::Erc20(params) => HttpAsset::Erc20(params.into()),
+            HttpAssetParams::Litecoin(params) => HttpAsset::Litecoin(params.into()),
         }
     }
 }
@@ -398,6 +447,7 @@ impl From<HttpAsset> for HttpAssetParams {
             HttpAsset::Bitcoin(asset) => HttpAssetParams::Bitcoin(asset.into()),
             HttpAsset::Ether(asset) => HttpAssetParams::Ether(asset.into()),
             HttpAsset::Erc20(asset) => HttpAssetParams::Erc20(asset.into()),
+            HttpAsset::Litecoin(asset) => HttpAssetParams::Litecoin(asset.into()),
         }
     }
 }
@@ -446,6 +496,18 @@ impl From<asset::Erc20> for Erc20AssetParams {
     }
 }
 
+impl From<LitecoinAssetParams> for asset::Litecoin {
+    fn from(params: LitecoinAssetParams) -> Self {
+        params.quantity
+    }
+}
+
+impl From<asset::Litecoin> for LitecoinAssetParams {
+    fn from(litecoin: asset::Litecoin) -> Self {
+        Self { quantity: litecoin }
+    }
+}
+
 impl From<ledger::bitcoin::Mainnet> for HttpLedger {
     fn from(_: ledger::bitcoin::Mainnet) -> Self {
         HttpLedger::BitcoinMainnet
@@ -470,6 +532,18 @@ impl From<ledger::Ethereum> for HttpLedger {
     }
 }
 
+impl From<ledger::Lightning> for HttpLedger {
+    fn from(lightning: ledger::Lightning) -> Self {
+        HttpLedger::Lightning(lightning)
+    }
+}
+
+impl From<ledger::Litecoin> for HttpLedger {
+    fn from(litecoin: ledger::Litecoin) -> Self {
+        HttpLedger::Litecoin(litecoin)
+    }
+}
+
 impl From<asset::Bitcoin> for HttpAsset {
     fn from(bitcoin: asset::Bitcoin) -> Self {
         HttpAsset::Bitcoin(bitcoin)
@@ -488,6 +562,36 @@ impl From<asset::Erc20> for HttpAsset {
     }
 }
 
+impl From<asset::Litecoin> for HttpAsset {
+    fn from(litecoin: asset::Litecoin) -> Self {
+        HttpAsset::Litecoin(litecoin)
+    }
+}
+
+impl From<HttpLedger> for LedgerKind {
+    fn from(ledger: HttpLedger) -> Self {
+        match ledger {
+            HttpLedger::BitcoinMainnet => LedgerKind::BitcoinMainnet,
+            HttpLedger::BitcoinTestnet => LedgerKind::BitcoinTestnet,
+            HttpLedger::BitcoinRegtest => LedgerKind::BitcoinRegtest,
+            HttpLedger::Ethereum(ethereum) => LedgerKind::Ethereum(ethereum),
+            HttpLedger::Lightning(lightning) => LedgerKind::Lightning(lightning),
+            HttpLedger::Litecoin(litecoin) => LedgerKind::Litecoin(litecoin),
+        }
+    }
+}
+
+impl From<HttpAsset> for AssetKind {
+    fn from(asset: HttpAsset) -> Self {
+        match asset {
+            HttpAsset::Bitcoin(bitcoin) => AssetKind::Bitcoin(bitcoin),
+            HttpAsset::Ether(ether) => AssetKind::Ether(ether),
+            HttpAsset::Erc20(erc20) => AssetKind::Erc20(erc20),
+            HttpAsset::Litecoin(litecoin) => AssetKind::Litecoin(litecoin),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -497,7 +601,7 @@ mod tests {
         ethereum::{H160, H256, U256},
         http_api::{Http, HttpAsset, HttpLedger},
         swap_protocols::{
-            ledger::{bitcoin, ethereum, Ethereum},
+            ledger::{bitcoin, ethereum, litecoin, Ethereum, Lightning, Litecoin},
             HashFunction, SwapId, SwapProtocol,
         },
     };
@@ -516,10 +620,12 @@ mod tests {
             "B97048628DB6B661D4C2aA833e95Dbe1A905B280".parse().unwrap(),
             asset::Erc20Quantity::from_wei(U256::from(100_000_000_000u64)),
         ));
+        let litecoin = HttpAsset::from(asset::Litecoin::from_litoshi(100_000_000));
 
         let bitcoin_serialized = serde_json::to_string(&bitcoin).unwrap();
         let ether_serialized = serde_json::to_string(&ether).unwrap();
         let pay_serialized = serde_json::to_string(&pay).unwrap();
+        let litecoin_serialized = serde_json::to_string(&litecoin).unwrap();
 
         assert_eq!(
             &bitcoin_serialized,
@@ -533,6 +639,10 @@ mod tests {
             &pay_serialized,
             r#"{"name":"erc20","quantity":"100000000000","token_contract":"0xb97048628db6b661d4c2aa833e95dbe1a905b280"}"#
         );
+        assert_eq!(
+            &litecoin_serialized,
+            r#"{"name":"litecoin","quantity":"100000000"}"#
+        );
     }
 
     #[test]
@@ -581,6 +691,48 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn lightning_http_ledger_serializes_correctly_to_json() {
+        let input = &[
+            HttpLedger::from(Lightning::new(::bitcoin::Network::Bitcoin)),
+            HttpLedger::from(Lightning::new(::bitcoin::Network::Regtest)),
+        ];
+
+        let expected = &[
+            r#"{"name":"lightning","network":"mainnet"}"#,
+            r#"{"name":"lightning","network":"regtest"}"#,
+        ];
+
+        let actual = input
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<String>, serde_json::Error>>()
+            .unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn litecoin_http_ledger_serializes_correctly_to_json() {
+        let input = &[
+            HttpLedger::from(Litecoin::new(litecoin::Network::Mainnet)),
+            HttpLedger::from(Litecoin::new(litecoin::Network::Regtest)),
+        ];
+
+        let expected = &[
+            r#"{"name":"litecoin","network":"mainnet"}"#,
+            r#"{"name":"litecoin","network":"regtest"}"#,
+        ];
+
+        let actual = input
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<String>, serde_json::Error>>()
+            .unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn http_transaction_serializes_correctly_to_json() {
         let bitcoin_tx = ::bitcoin::Transaction {
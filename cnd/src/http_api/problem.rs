@@ -1,8 +1,13 @@
 use crate::{
     db,
-    http_api::routes::rfc003::handlers::{
-        post_swap::UnsupportedSwap, InvalidAction, InvalidActionInvocation,
+    http_api::{
+        action::ActionAlreadyExecuted,
+        routes::rfc003::handlers::{
+            post_swap::UnsupportedSwap, InvalidAction, InvalidActionInvocation,
+        },
     },
+    swap_protocols::{ledger::UnsupportedLedger, rfc003::expiries::UnsafeExpiries},
+    wallet::{bitcoin::InsufficientFunds, ChainTimeNotReached, WalletNotConfigured},
 };
 use http_api_problem::HttpApiProblem;
 use warp::{
@@ -102,6 +107,63 @@ pub fn from_anyhow(e: anyhow::Error) -> HttpApiProblem {
             .set_detail("The requested combination of ledgers and assets is not supported.");
     }
 
+    if let Some(e) = e.downcast_ref::<UnsupportedLedger>() {
+        tracing::warn!("{}", e);
+
+        let mut problem = HttpApiProblem::new("Ledger not supported.")
+            .set_status(StatusCode::BAD_REQUEST)
+            .set_detail(format!("The swap cannot be watched because {}.", e));
+
+        problem
+            .set_value("unsupported_ledger", &format!("{:?}", e.ledger))
+            .expect("ledger will never fail to serialize");
+
+        return problem;
+    }
+
+    if let Some(e) = e.downcast_ref::<UnsafeExpiries>() {
+        tracing::warn!("{}", e);
+
+        return HttpApiProblem::new("Unsafe expiries.")
+            .set_status(StatusCode::BAD_REQUEST)
+            .set_detail(format!("The swap is not safe to execute because {}.", e));
+    }
+
+    if let Some(e) = e.downcast_ref::<WalletNotConfigured>() {
+        tracing::warn!("{}", e);
+
+        return HttpApiProblem::new("Wallet not configured.")
+            .set_status(StatusCode::BAD_REQUEST)
+            .set_detail(format!("This action cannot be executed because {}.", e));
+    }
+
+    if let Some(e) = e.downcast_ref::<ActionAlreadyExecuted>() {
+        tracing::warn!("{}", e);
+
+        return HttpApiProblem::new("Action already executed.")
+            .set_status(StatusCode::CONFLICT)
+            .set_detail(format!(
+                "The {} action was already executed with the outcome {}.",
+                e.action_kind, e.outcome
+            ));
+    }
+
+    if let Some(e) = e.downcast_ref::<ChainTimeNotReached>() {
+        tracing::warn!("{}", e);
+
+        return HttpApiProblem::new("Action not executable yet.")
+            .set_status(StatusCode::CONFLICT)
+            .set_detail(format!("This action cannot be executed yet because {}.", e));
+    }
+
+    if let Some(e) = e.downcast_ref::<InsufficientFunds>() {
+        tracing::warn!("{}", e);
+
+        return HttpApiProblem::new("Insufficient funds.")
+            .set_status(StatusCode::BAD_REQUEST)
+            .set_detail(format!("The built-in {}.", e));
+    }
+
     tracing::error!("internal error occurred: {:#}", e);
 
     HttpApiProblem::with_title_and_type_from_status(StatusCode::INTERNAL_SERVER_ERROR)
@@ -1,5 +1,5 @@
 use crate::{
-    db::{DetermineTypes, Retrieve},
+    db::{DetermineTypes, ExecutedActions, Retrieve},
     http_api::swap_resource::{build_rfc003_siren_entity, IncludeState, OnFail},
     swap_protocols::Facade,
 };
@@ -9,11 +9,13 @@ pub async fn handle_get_swaps(dependencies: Facade) -> anyhow::Result<siren::Ent
 
     for swap in Retrieve::all(&dependencies).await?.into_iter() {
         let types = dependencies.determine_types(&swap.swap_id).await?;
+        let executed_actions = dependencies.load_executed_actions(&swap.swap_id).await?;
 
         let sub_entity = build_rfc003_siren_entity(
             &dependencies,
             swap,
             types,
+            executed_actions,
             IncludeState::No,
             OnFail::NoAction,
         )?;
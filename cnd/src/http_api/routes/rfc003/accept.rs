@@ -1,8 +1,10 @@
 use crate::{
+    btsieve::lightning::LndNotConnected,
     ethereum,
     http_api::action::ListRequiredFields,
+    lightning,
     swap_protocols::{
-        ledger::{bitcoin, Ethereum},
+        ledger::{bitcoin, Ethereum, Lightning, Litecoin},
         rfc003::{
             actions::Accept,
             messages::{self, IntoAcceptMessage},
@@ -18,6 +20,14 @@ pub struct OnlyRedeem<I> {
     pub alpha_ledger_redeem_identity: I,
 }
 
+impl From<ethereum::Address> for OnlyRedeem<ethereum::Address> {
+    fn from(alpha_ledger_redeem_identity: ethereum::Address) -> Self {
+        Self {
+            alpha_ledger_redeem_identity,
+        }
+    }
+}
+
 impl ListRequiredFields for Accept<Ethereum, bitcoin::Mainnet> {
     fn list_required_fields() -> Vec<siren::Field> {
         ethereum_bitcoin_accept_required_fields()
@@ -36,6 +46,12 @@ impl ListRequiredFields for Accept<Ethereum, bitcoin::Regtest> {
     }
 }
 
+impl ListRequiredFields for Accept<Ethereum, Litecoin> {
+    fn list_required_fields() -> Vec<siren::Field> {
+        ethereum_bitcoin_accept_required_fields()
+    }
+}
+
 fn ethereum_bitcoin_accept_required_fields() -> Vec<siren::Field> {
     vec![siren::Field {
         name: "alpha_ledger_redeem_identity".to_owned(),
@@ -53,16 +69,40 @@ impl IntoAcceptMessage<ethereum::Address, crate::bitcoin::PublicKey>
         self,
         id: SwapId,
         secret_source: &dyn DeriveIdentities,
-    ) -> messages::Accept<ethereum::Address, crate::bitcoin::PublicKey> {
+        _: Option<lightning::PublicKey>,
+    ) -> anyhow::Result<messages::Accept<ethereum::Address, crate::bitcoin::PublicKey>> {
         let beta_ledger_refund_identity = crate::bitcoin::PublicKey::from_secret_key(
             &*crate::SECP,
             &secret_source.derive_refund_identity(),
         );
-        messages::Accept {
+        Ok(messages::Accept {
             swap_id: id,
             alpha_ledger_redeem_identity: self.alpha_ledger_redeem_identity,
             beta_ledger_refund_identity,
-        }
+        })
+    }
+}
+
+impl ListRequiredFields for Accept<Ethereum, Lightning> {
+    fn list_required_fields() -> Vec<siren::Field> {
+        ethereum_bitcoin_accept_required_fields()
+    }
+}
+
+impl IntoAcceptMessage<ethereum::Address, lightning::PublicKey> for OnlyRedeem<ethereum::Address> {
+    fn into_accept_message(
+        self,
+        id: SwapId,
+        _: &dyn DeriveIdentities,
+        lnd_identity: Option<lightning::PublicKey>,
+    ) -> anyhow::Result<messages::Accept<ethereum::Address, lightning::PublicKey>> {
+        let beta_ledger_refund_identity = lnd_identity.ok_or(LndNotConnected)?;
+
+        Ok(messages::Accept {
+            swap_id: id,
+            alpha_ledger_redeem_identity: self.alpha_ledger_redeem_identity,
+            beta_ledger_refund_identity,
+        })
     }
 }
 
@@ -71,6 +111,14 @@ pub struct OnlyRefund<I> {
     pub beta_ledger_refund_identity: I,
 }
 
+impl From<ethereum::Address> for OnlyRefund<ethereum::Address> {
+    fn from(beta_ledger_refund_identity: ethereum::Address) -> Self {
+        Self {
+            beta_ledger_refund_identity,
+        }
+    }
+}
+
 impl ListRequiredFields for Accept<bitcoin::Mainnet, Ethereum> {
     fn list_required_fields() -> Vec<siren::Field> {
         bitcoin_ethereum_accept_required_fields()
@@ -89,6 +137,12 @@ impl ListRequiredFields for Accept<bitcoin::Regtest, Ethereum> {
     }
 }
 
+impl ListRequiredFields for Accept<Litecoin, Ethereum> {
+    fn list_required_fields() -> Vec<siren::Field> {
+        bitcoin_ethereum_accept_required_fields()
+    }
+}
+
 fn bitcoin_ethereum_accept_required_fields() -> Vec<siren::Field> {
     vec![siren::Field {
         name: "beta_ledger_refund_identity".to_owned(),
@@ -106,15 +160,80 @@ impl IntoAcceptMessage<crate::bitcoin::PublicKey, crate::ethereum::Address>
         self,
         id: SwapId,
         secret_source: &dyn DeriveIdentities,
-    ) -> messages::Accept<crate::bitcoin::PublicKey, crate::ethereum::Address> {
+        _: Option<lightning::PublicKey>,
+    ) -> anyhow::Result<messages::Accept<crate::bitcoin::PublicKey, crate::ethereum::Address>> {
         let alpha_ledger_redeem_identity = crate::bitcoin::PublicKey::from_secret_key(
             &*crate::SECP,
             &secret_source.derive_redeem_identity(),
         );
-        messages::Accept {
+        Ok(messages::Accept {
+            swap_id: id,
+            beta_ledger_refund_identity: self.beta_ledger_refund_identity,
+            alpha_ledger_redeem_identity,
+        })
+    }
+}
+
+impl ListRequiredFields for Accept<Lightning, Ethereum> {
+    fn list_required_fields() -> Vec<siren::Field> {
+        bitcoin_ethereum_accept_required_fields()
+    }
+}
+
+impl IntoAcceptMessage<lightning::PublicKey, ethereum::Address> for OnlyRefund<ethereum::Address> {
+    fn into_accept_message(
+        self,
+        id: SwapId,
+        _: &dyn DeriveIdentities,
+        lnd_identity: Option<lightning::PublicKey>,
+    ) -> anyhow::Result<messages::Accept<lightning::PublicKey, ethereum::Address>> {
+        let alpha_ledger_redeem_identity = lnd_identity.ok_or(LndNotConnected)?;
+
+        Ok(messages::Accept {
             swap_id: id,
             beta_ledger_refund_identity: self.beta_ledger_refund_identity,
             alpha_ledger_redeem_identity,
+        })
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct RedeemAndRefund<I> {
+    pub alpha_ledger_redeem_identity: I,
+    pub beta_ledger_refund_identity: I,
+}
+
+impl From<ethereum::Address> for RedeemAndRefund<ethereum::Address> {
+    fn from(identity: ethereum::Address) -> Self {
+        Self {
+            alpha_ledger_redeem_identity: identity,
+            beta_ledger_refund_identity: identity,
         }
     }
 }
+
+impl ListRequiredFields for Accept<Ethereum, Ethereum> {
+    fn list_required_fields() -> Vec<siren::Field> {
+        let mut fields = ethereum_bitcoin_accept_required_fields();
+        fields.extend(bitcoin_ethereum_accept_required_fields());
+
+        fields
+    }
+}
+
+impl IntoAcceptMessage<ethereum::Address, ethereum::Address>
+    for RedeemAndRefund<ethereum::Address>
+{
+    fn into_accept_message(
+        self,
+        id: SwapId,
+        _: &dyn DeriveIdentities,
+        _: Option<lightning::PublicKey>,
+    ) -> anyhow::Result<messages::Accept<ethereum::Address, ethereum::Address>> {
+        Ok(messages::Accept {
+            swap_id: id,
+            alpha_ledger_redeem_identity: self.alpha_ledger_redeem_identity,
+            beta_ledger_refund_identity: self.beta_ledger_refund_identity,
+        })
+    }
+}
@@ -26,10 +26,14 @@ pub fn to_swap_decline_reason(
 ) -> Option<SwapDeclineReason> {
     reason.map(|reason| match reason {
         HttpApiSwapDeclineReason::UnsatisfactoryRate => SwapDeclineReason::UnsatisfactoryRate,
+        HttpApiSwapDeclineReason::UnsatisfactoryQuantity => {
+            SwapDeclineReason::UnsatisfactoryQuantity
+        }
     })
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
 pub enum HttpApiSwapDeclineReason {
     UnsatisfactoryRate,
+    UnsatisfactoryQuantity,
 }
@@ -1,9 +1,10 @@
 use crate::{
-    db::{DetermineTypes, LoadAcceptedSwap, Save},
+    asset::Asset,
+    db::{DetermineTypes, LoadAcceptedSwap, Save, SwapEvents},
     http_api::{
         action::{
-            ActionExecutionParameters, ActionResponseBody, IntoResponsePayload, ListRequiredFields,
-            ToSirenAction,
+            execute_once, ActionExecutionParameters, ActionResponseBody, ExecuteAction,
+            IntoResponsePayload, ListRequiredFields, ToSirenAction,
         },
         route_factory::new_action_link,
         routes::rfc003::decline::{to_swap_decline_reason, DeclineBody},
@@ -18,10 +19,15 @@ use crate::{
             self,
             actions::{Action, ActionKind},
             bob::State,
-            messages::{Decision, IntoAcceptMessage},
+            create_swap::SwapEventOnLedger,
+            events::{
+                HtlcConfirmed, HtlcDeployed, HtlcFunded, HtlcPending, HtlcRedeemed, HtlcRefunded,
+            },
+            messages::{Decision, IntoAcceptMessage, SwapDeclineReason},
             state_store::StateStore,
+            Ledger,
         },
-        Facade, SwapId,
+        Facade, Role, SwapId,
     },
 };
 use anyhow::Context;
@@ -49,99 +55,179 @@ pub async fn handle_action(
             anyhow::anyhow!("state store did not contain an entry for {}", swap_id)
         })?;
 
-        let action = state
-            .actions()
-            .into_iter()
-            .select_action(action_kind, method)?;
+        let actions = state.actions().into_iter();
+        let action = match query_params {
+            ActionExecutionParameters::Execute { .. } => {
+                actions.select_action_to_execute(action_kind, method)?
+            }
+            _ => actions.select_action(action_kind, method)?,
+        };
 
         match action {
             Action::Accept(_) => {
                 let body = serde_json::from_value::<AcceptBody>(body)
                     .context("failed to deserialize accept body")?;
 
-                let channel = dependencies
-                    .pending_request_for(swap_id)
-                    .await
-                    .with_context(|| {
-                        format!("unable to find response channel for swap {}", swap_id)
-                    })?;
-
-                let accept_message =
-                    body.into_accept_message(swap_id, &dependencies.derive_swap_seed(swap_id));
-
-                Save::save(&dependencies, accept_message).await?;
+                let accept_message = body.into_accept_message(
+                    swap_id,
+                    &dependencies.derive_swap_seed(swap_id),
+                    dependencies.lnd_identity(),
+                )?;
 
                 tracing::trace!("received accept action: {}", swap_id);
 
-                let response = rfc003_accept_response(accept_message);
-                channel.send(response).map_err(|_| {
-                    anyhow::anyhow!(
-                        "failed to send response through channel for swap {}",
-                        swap_id
-                    )
-                })?;
-
-                let accepted =
-                    LoadAcceptedSwap::<AL, BL, AA, BA>::load_accepted_swap(&dependencies, &swap_id)
-                        .await?;
-                init_accepted_swap(&dependencies, accepted, types.role)?;
+                accept_swap::<AL, BL, AA, BA>(&dependencies, accept_message, types.role).await?;
 
                 Ok(ActionResponseBody::None)
             }
             Action::Decline(_) => {
                 let body = serde_json::from_value::<DeclineBody>(body)?;
 
-                let channel = dependencies
-                    .pending_request_for(swap_id)
-                    .await
-                    .with_context(|| {
-                        format!("unable to find response channel for swap {}", swap_id)
-                    })?;
-
-                let decline_message = rfc003::Decline {
-                    swap_id,
-                    reason: to_swap_decline_reason(body.reason),
-                };
-
-                Save::save(&dependencies, decline_message).await?;
-
                 tracing::trace!("received decline action: {}", swap_id);
 
-                let response = rfc003_decline_response(decline_message);
-                channel.send(response).map_err(|_| {
-                    anyhow::anyhow!(
-                        "failed to send response through channel for swap {}",
-                        swap_id
-                    )
-                })?;
-
-                let swap_request = state.request();
-                let seed = dependencies.derive_swap_seed(swap_id);
-                let state = State::declined(swap_request, decline_message, seed);
-                StateStore::insert(&dependencies, swap_id, state);
+                decline_swap(
+                    &dependencies,
+                    state.request(),
+                    to_swap_decline_reason(body.reason),
+                )
+                .await?;
 
                 Ok(ActionResponseBody::None)
             }
             Action::Deploy(action) => {
                 tracing::trace!("received deploy action");
-                action.into_response_payload(query_params)
+                execute_or_respond(&dependencies, swap_id, action_kind, action, query_params).await
             }
             Action::Fund(action) => {
                 tracing::trace!("received fund action");
-                action.into_response_payload(query_params)
+                execute_or_respond(&dependencies, swap_id, action_kind, action, query_params).await
             }
             Action::Redeem(action) => {
                 tracing::trace!("received redeem action");
-                action.into_response_payload(query_params)
+                execute_or_respond(&dependencies, swap_id, action_kind, action, query_params).await
             }
             Action::Refund(action) => {
                 tracing::trace!("received refund action");
-                action.into_response_payload(query_params)
+                execute_or_respond(&dependencies, swap_id, action_kind, action, query_params).await
             }
         }
     })
 }
 
+/// Answers the pending request of a swap with the given accept message and
+/// starts watching the ledgers.
+pub async fn accept_swap<AL, BL, AA, BA>(
+    dependencies: &Facade,
+    accept_message: rfc003::Accept<AL::Identity, BL::Identity>,
+    role: Role,
+) -> anyhow::Result<()>
+where
+    AL: Ledger,
+    BL: Ledger,
+    AA: Asset,
+    BA: Asset,
+    Facade: Save<rfc003::Accept<AL::Identity, BL::Identity>>
+        + LoadAcceptedSwap<AL, BL, AA, BA>
+        + SwapEvents<SwapEventOnLedger<AL, BL, AA, BA>>
+        + HtlcFunded<AL, AA>
+        + HtlcFunded<BL, BA>
+        + HtlcDeployed<AL, AA>
+        + HtlcDeployed<BL, BA>
+        + HtlcRedeemed<AL, AA>
+        + HtlcRedeemed<BL, BA>
+        + HtlcRefunded<AL, AA>
+        + HtlcRefunded<BL, BA>
+        + HtlcConfirmed<AL>
+        + HtlcConfirmed<BL>
+        + HtlcPending<AL, AA>
+        + HtlcPending<BL, BA>,
+{
+    let swap_id = accept_message.swap_id;
+
+    let channel = dependencies
+        .pending_request_for(swap_id)
+        .await
+        .with_context(|| format!("unable to find response channel for swap {}", swap_id))?;
+
+    Save::save(dependencies, accept_message).await?;
+
+    let response = rfc003_accept_response(accept_message);
+    channel.send(response).map_err(|_| {
+        anyhow::anyhow!(
+            "failed to send response through channel for swap {}",
+            swap_id
+        )
+    })?;
+
+    let accepted =
+        LoadAcceptedSwap::<AL, BL, AA, BA>::load_accepted_swap(dependencies, &swap_id).await?;
+    init_accepted_swap(dependencies, accepted, role)?;
+
+    Ok(())
+}
+
+/// Answers the pending request of a swap with a decline message.
+pub async fn decline_swap<AL, BL, AA, BA>(
+    dependencies: &Facade,
+    swap_request: rfc003::Request<AL, BL, AA, BA>,
+    reason: Option<SwapDeclineReason>,
+) -> anyhow::Result<()>
+where
+    AL: Ledger,
+    BL: Ledger,
+    AA: Asset,
+    BA: Asset,
+{
+    let swap_id = swap_request.swap_id;
+
+    let channel = dependencies
+        .pending_request_for(swap_id)
+        .await
+        .with_context(|| format!("unable to find response channel for swap {}", swap_id))?;
+
+    let decline_message = rfc003::Decline { swap_id, reason };
+
+    Save::save(dependencies, decline_message).await?;
+
+    let response = rfc003_decline_response(decline_message);
+    channel.send(response).map_err(|_| {
+        anyhow::anyhow!(
+            "failed to send response through channel for swap {}",
+            swap_id
+        )
+    })?;
+
+    let seed = dependencies.derive_swap_seed(swap_id);
+    let state = State::declined(swap_request, decline_message, seed);
+    StateStore::insert(dependencies, swap_id, state);
+
+    Ok(())
+}
+
+/// Executes the action with a built-in wallet if the client asked for it,
+/// otherwise returns the action to the client.
+///
+/// An action is only executed once per swap, repeating the request fails with
+/// the outcome of the first execution.
+async fn execute_or_respond<A>(
+    dependencies: &Facade,
+    swap_id: SwapId,
+    action_kind: ActionKind,
+    action: A,
+    query_params: ActionExecutionParameters,
+) -> anyhow::Result<ActionResponseBody>
+where
+    A: IntoResponsePayload + Send + 'static,
+    Facade: ExecuteAction<A>,
+{
+    match query_params {
+        ActionExecutionParameters::Execute { .. } => {
+            execute_once(dependencies, swap_id, action_kind, action).await
+        }
+        query_params => action.into_response_payload(query_params),
+    }
+}
+
 #[derive(Debug, thiserror::Error, PartialEq)]
 #[error("attempt to invoke {action_kind} action with http method {method}, which is an invalid combination")]
 pub struct InvalidActionInvocation {
@@ -179,6 +265,35 @@ trait SelectAction<Accept, Decline, Deploy, Fund, Redeem, Refund>:
 
         Ok(action)
     }
+
+    /// Selects an action to execute with a built-in wallet. Executing has
+    /// side effects, hence it is only possible with POST and only for the
+    /// actions that send a transaction.
+    fn select_action_to_execute(
+        mut self,
+        action_kind: ActionKind,
+        method: http::Method,
+    ) -> anyhow::Result<Self::Item>
+    where
+        Self: Sized,
+    {
+        let action = self
+            .find(|action| ActionKind::from(action) == action_kind)
+            .ok_or_else(|| anyhow::Error::from(InvalidAction { action_kind }))?;
+
+        let executable = match action_kind {
+            ActionKind::Deploy | ActionKind::Fund | ActionKind::Redeem | ActionKind::Refund => true,
+            ActionKind::Accept | ActionKind::Decline => false,
+        };
+        if !executable || method != http::Method::POST {
+            return Err(anyhow::Error::from(InvalidActionInvocation {
+                action_kind,
+                method,
+            }));
+        }
+
+        Ok(action)
+    }
 }
 
 fn rfc003_accept_response<AI, BI>(message: rfc003::messages::Accept<AI, BI>) -> Response
@@ -355,6 +470,52 @@ mod tests {
                 method: http::Method::POST,
             });
     }
+
+    #[test]
+    fn fund_action_can_be_executed_with_http_post() {
+        let mut given_actions = actions();
+        given_actions.extend(vec![Action::Fund(())]);
+
+        let result = given_actions
+            .into_iter()
+            .select_action_to_execute(ActionKind::Fund, http::Method::POST);
+
+        assert_that(&result).is_ok_containing(Action::Fund(()));
+    }
+
+    #[test]
+    fn fund_action_cannot_be_executed_with_http_get() {
+        let mut given_actions = actions();
+        given_actions.extend(vec![Action::Fund(())]);
+
+        let result = given_actions
+            .into_iter()
+            .select_action_to_execute(ActionKind::Fund, http::Method::GET);
+
+        assert_that(&result)
+            .is_inner_err::<InvalidActionInvocation>()
+            .is_equal_to(&InvalidActionInvocation {
+                action_kind: ActionKind::Fund,
+                method: http::Method::GET,
+            });
+    }
+
+    #[test]
+    fn accept_action_cannot_be_executed() {
+        let mut given_actions = actions();
+        given_actions.extend(vec![Action::Accept(())]);
+
+        let result = given_actions
+            .into_iter()
+            .select_action_to_execute(ActionKind::Accept, http::Method::POST);
+
+        assert_that(&result)
+            .is_inner_err::<InvalidActionInvocation>()
+            .is_equal_to(&InvalidActionInvocation {
+                action_kind: ActionKind::Accept,
+                method: http::Method::POST,
+            });
+    }
 }
 
 impl From<ActionKind> for http::Method {
@@ -1,5 +1,5 @@
 use crate::{
-    db::{DetermineTypes, Retrieve},
+    db::{DetermineTypes, ExecutedActions, Retrieve},
     http_api::swap_resource::{build_rfc003_siren_entity, IncludeState, OnFail},
     swap_protocols::{Facade, SwapId},
 };
@@ -7,6 +7,14 @@ use crate::{
 pub async fn handle_get_swap(dependencies: Facade, id: SwapId) -> anyhow::Result<siren::Entity> {
     let swap = Retrieve::get(&dependencies, &id).await?;
     let types = dependencies.determine_types(&id).await?;
+    let executed_actions = dependencies.load_executed_actions(&id).await?;
 
-    build_rfc003_siren_entity(&dependencies, swap, types, IncludeState::Yes, OnFail::Error)
+    build_rfc003_siren_entity(
+        &dependencies,
+        swap,
+        types,
+        executed_actions,
+        IncludeState::Yes,
+        OnFail::Error,
+    )
 }
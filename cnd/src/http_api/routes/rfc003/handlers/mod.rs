@@ -3,7 +3,7 @@ mod get_swap;
 pub mod post_swap;
 
 pub use self::{
-    action::{handle_action, InvalidAction, InvalidActionInvocation},
+    action::{accept_swap, decline_swap, handle_action, InvalidAction, InvalidActionInvocation},
     get_swap::handle_get_swap,
     post_swap::handle_post_swap,
 };
@@ -1,9 +1,11 @@
 use crate::{
     asset::Asset,
+    btsieve::lightning::LndNotConnected,
     db::{LoadAcceptedSwap, Save, Sqlite, Swap},
     ethereum,
     http_api::{HttpAsset, HttpLedger},
     init_swap::init_accepted_swap,
+    lightning,
     network::{DialInformation, SendRequest},
     seed::DeriveSwapSeed,
     swap_protocols::{
@@ -11,7 +13,9 @@ use crate::{
         rfc003::{
             self,
             alice::State,
-            events::{HtlcDeployed, HtlcFunded, HtlcRedeemed, HtlcRefunded},
+            events::{
+                HtlcConfirmed, HtlcDeployed, HtlcFunded, HtlcPending, HtlcRedeemed, HtlcRefunded,
+            },
             state_store::StateStore,
             Accept, Decline, DeriveIdentities, DeriveSecret, Ledger, Request, SecretHash,
         },
@@ -47,10 +51,26 @@ where
         + HtlcRedeemed<AL, AA>
         + HtlcRedeemed<BL, BA>
         + HtlcRefunded<AL, AA>
-        + HtlcRefunded<BL, BA>,
+        + HtlcRefunded<BL, BA>
+        + HtlcConfirmed<AL>
+        + HtlcConfirmed<BL>
+        + HtlcPending<AL, AA>
+        + HtlcPending<BL, BA>,
 {
     tracing::trace!("initiating new request: {}", swap_request.swap_id);
 
+    dependencies
+        .connected_ledgers
+        .ensure_connected(swap_request.alpha_ledger.into())?;
+    dependencies
+        .connected_ledgers
+        .ensure_connected(swap_request.beta_ledger.into())?;
+    dependencies.expiry_margins.validate(
+        swap_request.alpha_expiry,
+        swap_request.beta_expiry,
+        Timestamp::now(),
+    )?;
+
     let counterparty = peer.peer_id.clone();
     let seed = dependencies.derive_swap_seed(id);
 
@@ -101,215 +121,31 @@ pub async fn handle_post_swap(
     let seed = dependencies.derive_swap_seed(id);
     let secret_hash = seed.derive_secret().hash();
 
-    let body = serde_json::from_value(body)?;
-
-    match body {
-        SwapRequestBody {
-            alpha_ledger: HttpLedger::BitcoinMainnet,
-            beta_ledger: HttpLedger::Ethereum(beta_ledger),
-            alpha_asset: HttpAsset::Bitcoin(alpha_asset),
-            beta_asset: HttpAsset::Ether(beta_asset),
-            alpha_expiry,
-            beta_expiry,
-            identities,
-            peer,
-        } => {
-            let identities = identities.into_bitcoin_ethereum_identities(&seed)?;
-            let request = new_request(
-                id,
-                ledger::bitcoin::Mainnet,
-                beta_ledger,
-                alpha_asset,
-                beta_asset,
-                alpha_expiry,
-                beta_expiry,
-                identities,
-                secret_hash,
-            );
-            initiate_request(dependencies, id, peer, request).await?;
-        }
-        SwapRequestBody {
-            alpha_ledger: HttpLedger::BitcoinTestnet,
-            beta_ledger: HttpLedger::Ethereum(beta_ledger),
-            alpha_asset: HttpAsset::Bitcoin(alpha_asset),
-            beta_asset: HttpAsset::Ether(beta_asset),
-            alpha_expiry,
-            beta_expiry,
-            identities,
-            peer,
-        } => {
-            let identities = identities.into_bitcoin_ethereum_identities(&seed)?;
-            let request = new_request(
-                id,
-                ledger::bitcoin::Testnet,
-                beta_ledger,
-                alpha_asset,
-                beta_asset,
-                alpha_expiry,
-                beta_expiry,
-                identities,
-                secret_hash,
-            );
-            initiate_request(dependencies, id, peer, request).await?;
-        }
-        SwapRequestBody {
-            alpha_ledger: HttpLedger::BitcoinRegtest,
-            beta_ledger: HttpLedger::Ethereum(beta_ledger),
-            alpha_asset: HttpAsset::Bitcoin(alpha_asset),
-            beta_asset: HttpAsset::Ether(beta_asset),
-            alpha_expiry,
-            beta_expiry,
-            identities,
-            peer,
-        } => {
-            let identities = identities.into_bitcoin_ethereum_identities(&seed)?;
-            let request = new_request(
-                id,
-                ledger::bitcoin::Regtest,
-                beta_ledger,
-                alpha_asset,
-                beta_asset,
-                alpha_expiry,
-                beta_expiry,
-                identities,
-                secret_hash,
-            );
-            initiate_request(dependencies, id, peer, request).await?;
-        }
-        SwapRequestBody {
-            alpha_ledger: HttpLedger::Ethereum(alpha_ledger),
-            beta_ledger: HttpLedger::BitcoinMainnet,
-            alpha_asset: HttpAsset::Ether(alpha_asset),
-            beta_asset: HttpAsset::Bitcoin(beta_asset),
-            alpha_expiry,
-            beta_expiry,
-            identities,
-            peer,
-        } => {
-            let identities = identities.into_ethereum_bitcoin_identities(&seed)?;
-            let request = new_request(
-                id,
-                alpha_ledger,
-                ledger::bitcoin::Mainnet,
-                alpha_asset,
-                beta_asset,
-                alpha_expiry,
-                beta_expiry,
-                identities,
-                secret_hash,
-            );
-            initiate_request(dependencies, id, peer, request).await?;
-        }
-        SwapRequestBody {
-            alpha_ledger: HttpLedger::Ethereum(alpha_ledger),
-            beta_ledger: HttpLedger::BitcoinTestnet,
-            alpha_asset: HttpAsset::Ether(alpha_asset),
-            beta_asset: HttpAsset::Bitcoin(beta_asset),
-            alpha_expiry,
-            beta_expiry,
-            identities,
-            peer,
-        } => {
-            let identities = identities.into_ethereum_bitcoin_identities(&seed)?;
-            let request = new_request(
-                id,
-                alpha_ledger,
-                ledger::bitcoin::Testnet,
-                alpha_asset,
-                beta_asset,
-                alpha_expiry,
-                beta_expiry,
-                identities,
-                secret_hash,
-            );
-            initiate_request(dependencies, id, peer, request).await?;
-        }
-        SwapRequestBody {
-            alpha_ledger: HttpLedger::Ethereum(alpha_ledger),
-            beta_ledger: HttpLedger::BitcoinRegtest,
-            alpha_asset: HttpAsset::Ether(alpha_asset),
-            beta_asset: HttpAsset::Bitcoin(beta_asset),
-            alpha_expiry,
-            beta_expiry,
-            identities,
-            peer,
-        } => {
-            let identities = identities.into_ethereum_bitcoin_identities(&seed)?;
+    let SwapRequestBody {
+        alpha_ledger,
+        beta_ledger,
+        alpha_asset,
+        beta_asset,
+        alpha_expiry,
+        beta_expiry,
+        identities,
+        peer,
+    } = serde_json::from_value(body)?;
+    let kinds = (
+        alpha_ledger.into(),
+        beta_ledger.into(),
+        alpha_asset.clone().into(),
+        beta_asset.clone().into(),
+    );
+
+    crate::with_swap!(
+        (alpha_ledger, beta_ledger, alpha_asset, beta_asset) = kinds,
+        {
+            let identities =
+                identities.into_identities::<AL, BL>(&seed, dependencies.lnd_identity())?;
             let request = new_request(
                 id,
                 alpha_ledger,
-                ledger::bitcoin::Regtest,
-                alpha_asset,
-                beta_asset,
-                alpha_expiry,
-                beta_expiry,
-                identities,
-                secret_hash,
-            );
-            initiate_request(dependencies, id, peer, request).await?;
-        }
-        SwapRequestBody {
-            alpha_ledger: HttpLedger::BitcoinMainnet,
-            beta_ledger: HttpLedger::Ethereum(beta_ledger),
-            alpha_asset: HttpAsset::Bitcoin(alpha_asset),
-            beta_asset: HttpAsset::Erc20(beta_asset),
-            alpha_expiry,
-            beta_expiry,
-            identities,
-            peer,
-        } => {
-            let identities = identities.into_bitcoin_ethereum_identities(&seed)?;
-            let request = new_request(
-                id,
-                ledger::bitcoin::Mainnet,
-                beta_ledger,
-                alpha_asset,
-                beta_asset,
-                alpha_expiry,
-                beta_expiry,
-                identities,
-                secret_hash,
-            );
-            initiate_request(dependencies, id, peer, request).await?;
-        }
-        SwapRequestBody {
-            alpha_ledger: HttpLedger::BitcoinTestnet,
-            beta_ledger: HttpLedger::Ethereum(beta_ledger),
-            alpha_asset: HttpAsset::Bitcoin(alpha_asset),
-            beta_asset: HttpAsset::Erc20(beta_asset),
-            alpha_expiry,
-            beta_expiry,
-            identities,
-            peer,
-        } => {
-            let identities = identities.into_bitcoin_ethereum_identities(&seed)?;
-            let request = new_request(
-                id,
-                ledger::bitcoin::Testnet,
-                beta_ledger,
-                alpha_asset,
-                beta_asset,
-                alpha_expiry,
-                beta_expiry,
-                identities,
-                secret_hash,
-            );
-            initiate_request(dependencies, id, peer, request).await?;
-        }
-        SwapRequestBody {
-            alpha_ledger: HttpLedger::BitcoinRegtest,
-            beta_ledger: HttpLedger::Ethereum(beta_ledger),
-            alpha_asset: HttpAsset::Bitcoin(alpha_asset),
-            beta_asset: HttpAsset::Erc20(beta_asset),
-            alpha_expiry,
-            beta_expiry,
-            identities,
-            peer,
-        } => {
-            let identities = identities.into_bitcoin_ethereum_identities(&seed)?;
-            let request = new_request(
-                id,
-                ledger::bitcoin::Regtest,
                 beta_ledger,
                 alpha_asset,
                 beta_asset,
@@ -319,89 +155,16 @@ pub async fn handle_post_swap(
                 secret_hash,
             );
             initiate_request(dependencies, id, peer, request).await?;
-        }
-        SwapRequestBody {
-            alpha_ledger: HttpLedger::Ethereum(alpha_ledger),
-            beta_ledger: HttpLedger::BitcoinMainnet,
-            alpha_asset: HttpAsset::Erc20(alpha_asset),
-            beta_asset: HttpAsset::Bitcoin(beta_asset),
-            alpha_expiry,
-            beta_expiry,
-            identities,
-            peer,
-        } => {
-            let identities = identities.into_ethereum_bitcoin_identities(&seed)?;
-            let request = new_request(
-                id,
-                alpha_ledger,
-                ledger::bitcoin::Mainnet,
-                alpha_asset,
-                beta_asset,
-                alpha_expiry,
-                beta_expiry,
-                identities,
-                secret_hash,
-            );
-            initiate_request(dependencies, id, peer, request).await?;
-        }
-        SwapRequestBody {
-            alpha_ledger: HttpLedger::Ethereum(alpha_ledger),
-            beta_ledger: HttpLedger::BitcoinTestnet,
-            alpha_asset: HttpAsset::Erc20(alpha_asset),
-            beta_asset: HttpAsset::Bitcoin(beta_asset),
-            alpha_expiry,
-            beta_expiry,
-            identities,
-            peer,
-        } => {
-            let identities = identities.into_ethereum_bitcoin_identities(&seed)?;
-            let request = new_request(
-                id,
-                alpha_ledger,
-                ledger::bitcoin::Testnet,
-                alpha_asset,
-                beta_asset,
-                alpha_expiry,
-                beta_expiry,
-                identities,
-                secret_hash,
-            );
-            initiate_request(dependencies, id, peer, request).await?;
-        }
-        SwapRequestBody {
-            alpha_ledger: HttpLedger::Ethereum(alpha_ledger),
-            beta_ledger: HttpLedger::BitcoinRegtest,
-            alpha_asset: HttpAsset::Erc20(alpha_asset),
-            beta_asset: HttpAsset::Bitcoin(beta_asset),
-            alpha_expiry,
-            beta_expiry,
-            identities,
-            peer,
-        } => {
-            let identities = identities.into_ethereum_bitcoin_identities(&seed)?;
-            let request = new_request(
-                id,
+        },
+        {
+            return Err(anyhow::Error::from(UnsupportedSwap {
                 alpha_ledger,
-                ledger::bitcoin::Regtest,
+                beta_ledger,
                 alpha_asset,
                 beta_asset,
-                alpha_expiry,
-                beta_expiry,
-                identities,
-                secret_hash,
-            );
-            initiate_request(dependencies, id, peer, request).await?;
-        }
-
-        _ => {
-            return Err(anyhow::Error::from(UnsupportedSwap {
-                alpha_ledger: body.alpha_ledger,
-                beta_ledger: body.beta_ledger,
-                alpha_asset: body.alpha_asset,
-                beta_asset: body.beta_asset,
-            }))
+            }));
         }
-    }
+    );
 
     Ok(SwapCreated { id })
 }
@@ -485,74 +248,98 @@ struct HttpIdentities {
 }
 
 impl HttpIdentities {
-    fn into_bitcoin_ethereum_identities(
+    fn into_identities<AL, BL>(
         self,
         secret_source: &dyn DeriveIdentities,
-    ) -> anyhow::Result<Identities<crate::bitcoin::PublicKey, ethereum::Address>> {
-        let HttpIdentities {
-            alpha_ledger_refund_identity,
-            beta_ledger_redeem_identity,
-        } = self;
-
-        let beta_ledger_redeem_identity =
-            match (alpha_ledger_refund_identity, beta_ledger_redeem_identity) {
-                (None, Some(beta_ledger_redeem_identity)) => beta_ledger_redeem_identity,
-                (_, None) => {
-                    return Err(anyhow::Error::from(MissingIdentity {
-                        kind: IdentityKind::BetaLedgerRedeemIdentity,
-                    }))
-                }
-                (Some(_), _) => {
-                    return Err(anyhow::Error::from(UnexpectedIdentity {
-                        kind: IdentityKind::AlphaLedgerRefundIdentity,
-                    }))
-                }
-            };
-
-        let alpha_ledger_refund_identity = crate::bitcoin::PublicKey::from_secret_key(
-            &*crate::SECP,
-            &secret_source.derive_refund_identity(),
-        );
+        lnd_identity: Option<lightning::PublicKey>,
+    ) -> anyhow::Result<Identities<AL::Identity, BL::Identity>>
+    where
+        AL: HttpIdentity,
+        BL: HttpIdentity,
+    {
+        let alpha_ledger_refund_identity = AL::http_identity(
+            self.alpha_ledger_refund_identity,
+            IdentityKind::AlphaLedgerRefundIdentity,
+            secret_source,
+            lnd_identity,
+        )?;
+        let beta_ledger_redeem_identity = BL::http_identity(
+            self.beta_ledger_redeem_identity,
+            IdentityKind::BetaLedgerRedeemIdentity,
+            secret_source,
+            lnd_identity,
+        )?;
 
         Ok(Identities {
             alpha_ledger_refund_identity,
             beta_ledger_redeem_identity,
         })
     }
+}
 
-    fn into_ethereum_bitcoin_identities(
-        self,
+/// How a ledger gets the identity of the party creating a swap.
+///
+/// Ethereum addresses are provided in the body of the request, the keys of
+/// Bitcoin-like ledgers are derived from the swap's seed and on Lightning the
+/// identity is the one of the connected lnd node.
+trait HttpIdentity: Ledger {
+    fn http_identity(
+        provided: Option<ethereum::Address>,
+        kind: IdentityKind,
         secret_source: &dyn DeriveIdentities,
-    ) -> anyhow::Result<Identities<ethereum::Address, crate::bitcoin::PublicKey>> {
-        let HttpIdentities {
-            alpha_ledger_refund_identity,
-            beta_ledger_redeem_identity,
-        } = self;
-
-        let alpha_ledger_refund_identity =
-            match (alpha_ledger_refund_identity, beta_ledger_redeem_identity) {
-                (Some(alpha_ledger_refund_identity), None) => alpha_ledger_refund_identity,
-                (_, Some(_)) => {
-                    return Err(anyhow::Error::from(UnexpectedIdentity {
-                        kind: IdentityKind::BetaLedgerRedeemIdentity,
-                    }))
-                }
-                (None, _) => {
-                    return Err(anyhow::Error::from(MissingIdentity {
-                        kind: IdentityKind::AlphaLedgerRefundIdentity,
-                    }))
-                }
-            };
+        lnd_identity: Option<lightning::PublicKey>,
+    ) -> anyhow::Result<Self::Identity>;
+}
+
+impl<B> HttpIdentity for B
+where
+    B: ledger::Bitcoin,
+{
+    fn http_identity(
+        provided: Option<ethereum::Address>,
+        kind: IdentityKind,
+        secret_source: &dyn DeriveIdentities,
+        _: Option<lightning::PublicKey>,
+    ) -> anyhow::Result<crate::bitcoin::PublicKey> {
+        if provided.is_some() {
+            return Err(anyhow::Error::from(UnexpectedIdentity { kind }));
+        }
+
+        let secret_key = match kind {
+            IdentityKind::AlphaLedgerRefundIdentity => secret_source.derive_refund_identity(),
+            IdentityKind::BetaLedgerRedeemIdentity => secret_source.derive_redeem_identity(),
+        };
 
-        let beta_ledger_redeem_identity = crate::bitcoin::PublicKey::from_secret_key(
+        Ok(crate::bitcoin::PublicKey::from_secret_key(
             &*crate::SECP,
-            &secret_source.derive_redeem_identity(),
-        );
+            &secret_key,
+        ))
+    }
+}
 
-        Ok(Identities {
-            alpha_ledger_refund_identity,
-            beta_ledger_redeem_identity,
-        })
+impl HttpIdentity for ledger::Ethereum {
+    fn http_identity(
+        provided: Option<ethereum::Address>,
+        kind: IdentityKind,
+        _: &dyn DeriveIdentities,
+        _: Option<lightning::PublicKey>,
+    ) -> anyhow::Result<ethereum::Address> {
+        provided.ok_or_else(|| anyhow::Error::from(MissingIdentity { kind }))
+    }
+}
+
+impl HttpIdentity for ledger::Lightning {
+    fn http_identity(
+        provided: Option<ethereum::Address>,
+        kind: IdentityKind,
+        _: &dyn DeriveIdentities,
+        lnd_identity: Option<lightning::PublicKey>,
+    ) -> anyhow::Result<lightning::PublicKey> {
+        if provided.is_some() {
+            return Err(anyhow::Error::from(UnexpectedIdentity { kind }));
+        }
+
+        lnd_identity.ok_or_else(|| anyhow::Error::from(LndNotConnected))
     }
 }
 
@@ -599,9 +386,18 @@ fn default_beta_expiry() -> Timestamp {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{network::DialInformation, swap_protocols::ledger::ethereum::ChainId};
+    use crate::{
+        asset,
+        network::DialInformation,
+        seed::{RootSeed, SwapSeed},
+        swap_protocols::ledger::ethereum::ChainId,
+    };
     use spectral::prelude::*;
 
+    fn seed() -> SwapSeed {
+        RootSeed::from(*b"hello world, you are beautiful!!").derive_swap_seed(SwapId::default())
+    }
+
     #[test]
     fn can_deserialize_swap_request_body() {
         let body = r#"{
@@ -676,4 +472,131 @@ mod tests {
                 chain_id: ChainId::from(17),
             }));
     }
+
+    #[test]
+    fn can_deserialize_lightning_swap_request_body() {
+        let body = r#"{
+                "alpha_ledger": {
+                    "name": "lightning",
+                    "network": "regtest"
+                },
+                "beta_ledger": {
+                    "name": "ethereum",
+                    "chain_id": 17
+                },
+                "alpha_asset": {
+                    "name": "bitcoin",
+                    "quantity": "100000"
+                },
+                "beta_asset": {
+                    "name": "ether",
+                    "quantity": "10000000000000000000"
+                },
+                "beta_ledger_redeem_identity": "0x00a329c0648769a73afac7f9381e08fb43dbea72",
+                "peer": "Qma9T5YraSnpRDZqRR4krcSJabThc8nwZuJV3LercPHufi"
+            }"#;
+
+        let body = serde_json::from_str::<SwapRequestBody>(body);
+
+        assert_that(&body)
+            .is_ok()
+            .map(|b| &b.alpha_ledger)
+            .is_equal_to(&HttpLedger::Lightning(ledger::Lightning::new(
+                ::bitcoin::Network::Regtest,
+            )));
+    }
+
+    #[test]
+    fn can_deserialize_litecoin_swap_request_body() {
+        let body = r#"{
+                "alpha_ledger": {
+                    "name": "litecoin",
+                    "network": "regtest"
+                },
+                "beta_ledger": {
+                    "name": "ethereum",
+                    "chain_id": 17
+                },
+                "alpha_asset": {
+                    "name": "litecoin",
+                    "quantity": "100000000"
+                },
+                "beta_asset": {
+                    "name": "ether",
+                    "quantity": "10000000000000000000"
+                },
+                "beta_ledger_redeem_identity": "0x00a329c0648769a73afac7f9381e08fb43dbea72",
+                "peer": "Qma9T5YraSnpRDZqRR4krcSJabThc8nwZuJV3LercPHufi"
+            }"#;
+
+        let body = serde_json::from_str::<SwapRequestBody>(body);
+
+        assert_that(&body)
+            .is_ok()
+            .map(|b| &b.alpha_ledger)
+            .is_equal_to(&HttpLedger::Litecoin(ledger::Litecoin::new(
+                ledger::litecoin::Network::Regtest,
+            )));
+        assert_that(&body)
+            .is_ok()
+            .map(|b| &b.alpha_asset)
+            .is_equal_to(&HttpAsset::Litecoin(asset::Litecoin::from_litoshi(
+                100_000_000,
+            )));
+    }
+
+    #[test]
+    fn can_deserialize_ethereum_ethereum_swap_request_body() {
+        let body = r#"{
+                "alpha_ledger": {
+                    "name": "ethereum",
+                    "chain_id": 17
+                },
+                "beta_ledger": {
+                    "name": "ethereum",
+                    "chain_id": 3
+                },
+                "alpha_asset": {
+                    "name": "ether",
+                    "quantity": "10000000000000000000"
+                },
+                "beta_asset": {
+                    "name": "erc20",
+                    "quantity": "10000000000000000000",
+                    "token_contract": "0xB97048628DB6B661D4C2aA833e95Dbe1A905B280"
+                },
+                "alpha_ledger_refund_identity": "0x00a329c0648769a73afac7f9381e08fb43dbea72",
+                "beta_ledger_redeem_identity": "0x00a329c0648769a73afac7f9381e08fb43dbea72",
+                "peer": "Qma9T5YraSnpRDZqRR4krcSJabThc8nwZuJV3LercPHufi"
+            }"#;
+
+        let body = serde_json::from_str::<SwapRequestBody>(body).unwrap();
+
+        assert_that(&body.beta_ledger).is_equal_to(&HttpLedger::Ethereum(ledger::Ethereum {
+            chain_id: ChainId::ropsten(),
+        }));
+        assert_that(
+            &body
+                .identities
+                .into_identities::<ledger::Ethereum, ledger::Ethereum>(&seed(), None),
+        )
+        .is_ok();
+    }
+
+    #[test]
+    fn ethereum_ethereum_swaps_require_both_identities() {
+        let identities = HttpIdentities {
+            alpha_ledger_refund_identity: Some(
+                "0x00a329c0648769a73afac7f9381e08fb43dbea72"
+                    .parse()
+                    .unwrap(),
+            ),
+            beta_ledger_redeem_identity: None,
+        };
+
+        assert_that(
+            &identities.into_identities::<ledger::Ethereum, ledger::Ethereum>(&seed(), None),
+        )
+        .is_err();
+    }
 }
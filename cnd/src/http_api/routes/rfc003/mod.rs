@@ -19,7 +19,9 @@ use warp::{
     Rejection, Reply,
 };
 
-pub use self::swap_state::{LedgerState, SwapCommunication, SwapCommunicationState, SwapState};
+pub use self::swap_state::{
+    LedgerState, PendingTransaction, SwapCommunication, SwapCommunicationState, SwapState,
+};
 use crate::http_api::problem;
 
 #[allow(clippy::needless_pass_by_value)]
@@ -2,7 +2,11 @@
 use crate::{
     asset::Asset,
     http_api::{Http, SwapStatus},
-    swap_protocols::rfc003::{self, Ledger, SecretHash},
+    swap_protocols::rfc003::{
+        self,
+        events::{Pending, PendingState},
+        Ledger, SecretHash,
+    },
     timestamp::Timestamp,
 };
 use serde::Serialize;
@@ -36,11 +40,33 @@ pub struct SwapCommunication<AI, BI> {
 #[derivative(Default(bound = ""))]
 pub struct LedgerState<H, T> {
     pub status: rfc003::HtlcState,
+    /// Whether the transaction that led to `status` is final. Until then, a
+    /// reorg may still roll the status back.
+    pub confirmed: bool,
     pub htlc_location: Option<Http<H>>,
     pub deploy_tx: Option<Http<T>>,
     pub fund_tx: Option<Http<T>>,
     pub redeem_tx: Option<Http<T>>,
     pub refund_tx: Option<Http<T>>,
+    /// The transaction of the HTLC that was broadcast but is not mined yet.
+    /// It does not affect `status` until it is mined.
+    pub pending: Option<PendingTransaction<T>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(bound = "Http<T>: Serialize")]
+pub struct PendingTransaction<T> {
+    pub status: PendingState,
+    pub tx: Http<T>,
+}
+
+impl<T> From<Pending<T>> for PendingTransaction<T> {
+    fn from(pending: Pending<T>) -> Self {
+        Self {
+            status: pending.state,
+            tx: Http(pending.transaction),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Copy, Serialize)]
@@ -98,18 +124,22 @@ where
     fn from(ledger_state: rfc003::LedgerState<H, T, A>) -> Self {
         use self::rfc003::LedgerState::*;
         let status = ledger_state.clone().into();
+        let confirmed = ledger_state.is_confirmed();
         match ledger_state {
             NotDeployed => Self::default(),
             Deployed {
                 htlc_location,
                 deploy_transaction,
+                ..
             } => Self {
                 status,
+                confirmed,
                 htlc_location: Some(Http(htlc_location)),
                 deploy_tx: Some(Http(deploy_transaction)),
                 fund_tx: None,
                 refund_tx: None,
                 redeem_tx: None,
+                pending: None,
             },
             IncorrectlyFunded {
                 htlc_location,
@@ -118,11 +148,13 @@ where
                 ..
             } => Self {
                 status,
+                confirmed,
                 htlc_location: Some(Http(htlc_location)),
                 deploy_tx: Some(Http(deploy_transaction)),
                 fund_tx: Some(Http(fund_transaction)),
                 redeem_tx: None,
                 refund_tx: None,
+                pending: None,
             },
             Funded {
                 htlc_location,
@@ -131,11 +163,13 @@ where
                 ..
             } => Self {
                 status,
+                confirmed,
                 htlc_location: Some(Http(htlc_location)),
                 deploy_tx: Some(Http(deploy_transaction)),
                 fund_tx: Some(Http(fund_transaction)),
                 refund_tx: None,
                 redeem_tx: None,
+                pending: None,
             },
             Redeemed {
                 htlc_location,
@@ -145,11 +179,13 @@ where
                 ..
             } => Self {
                 status,
+                confirmed,
                 htlc_location: Some(Http(htlc_location)),
                 deploy_tx: Some(Http(deploy_transaction)),
                 fund_tx: Some(Http(fund_transaction)),
                 redeem_tx: Some(Http(redeem_transaction)),
                 refund_tx: None,
+                pending: None,
             },
             Refunded {
                 htlc_location,
@@ -159,11 +195,13 @@ where
                 ..
             } => Self {
                 status,
+                confirmed,
                 htlc_location: Some(Http(htlc_location)),
                 deploy_tx: Some(Http(deploy_transaction)),
                 fund_tx: Some(Http(fund_transaction)),
                 refund_tx: Some(Http(refund_transaction)),
                 redeem_tx: None,
+                pending: None,
             },
         }
     }
@@ -191,6 +229,18 @@ impl SwapStatus {
     }
 }
 
+impl From<rfc003::SwapOutcome> for SwapStatus {
+    fn from(outcome: rfc003::SwapOutcome) -> Self {
+        match outcome {
+            rfc003::SwapOutcome::Swapped => SwapStatus::Swapped,
+            rfc003::SwapOutcome::AlphaRedeemedOnly
+            | rfc003::SwapOutcome::BetaRedeemedOnly
+            | rfc003::SwapOutcome::Refunded
+            | rfc003::SwapOutcome::Expired => SwapStatus::NotSwapped,
+        }
+    }
+}
+
 #[cfg(test)]
 impl quickcheck::Arbitrary for SwapCommunicationState {
     fn arbitrary<G: quickcheck::Gen>(g: &mut G) -> Self {
@@ -2,11 +2,11 @@
 
 use crate::{
     asset::Asset,
-    db::{Swap, SwapTypes},
+    db::{self, Swap, SwapTypes},
     http_api::{
         action::ToSirenAction,
         route_factory::swap_path,
-        routes::rfc003::{LedgerState, SwapCommunication, SwapState},
+        routes::rfc003::{LedgerState, PendingTransaction, SwapCommunication, SwapState},
         Http, HttpAsset, HttpLedger,
     },
     swap_protocols::{
@@ -16,6 +16,7 @@ use crate::{
     },
 };
 use anyhow::anyhow;
+use chrono::NaiveDateTime;
 use http_api_problem::HttpApiProblem;
 use libp2p::PeerId;
 use serde::Serialize;
@@ -28,9 +29,31 @@ pub struct SwapResource<S> {
     pub counterparty: Http<PeerId>,
     pub protocol: Http<SwapProtocol>,
     pub status: SwapStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<rfc003::SwapOutcome>,
     pub parameters: SwapParameters,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub state: Option<S>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub executed_actions: Vec<ExecutedAction>,
+}
+
+/// An action that was executed by cnd's autopilot.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutedAction {
+    pub action: String,
+    pub outcome: serde_json::Value,
+    pub at: NaiveDateTime,
+}
+
+impl From<db::ExecutedAction> for ExecutedAction {
+    fn from(executed_action: db::ExecutedAction) -> Self {
+        Self {
+            action: executed_action.action.to_string(),
+            outcome: executed_action.outcome,
+            at: executed_action.at,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -89,6 +112,7 @@ pub fn build_rfc003_siren_entity<S: StateStore>(
     state_store: &S,
     swap: Swap,
     types: SwapTypes,
+    executed_actions: Vec<db::ExecutedAction>,
     include_state: IncludeState,
     on_fail: OnFail,
 ) -> anyhow::Result<siren::Entity> {
@@ -106,20 +130,31 @@ pub fn build_rfc003_siren_entity<S: StateStore>(
         }
 
         let communication = SwapCommunication::from(state.swap_communication.clone());
-        let alpha_ledger = LedgerState::from(state.alpha_ledger_state.clone());
-        let beta_ledger = LedgerState::from(state.beta_ledger_state.clone());
+        let alpha_ledger = LedgerState {
+            pending: state.alpha_pending().cloned().map(PendingTransaction::from),
+            ..LedgerState::from(state.alpha_ledger_state.clone())
+        };
+        let beta_ledger = LedgerState {
+            pending: state.beta_pending().cloned().map(PendingTransaction::from),
+            ..LedgerState::from(state.beta_ledger_state.clone())
+        };
         let parameters = SwapParameters::from(state.clone().request());
         let actions = state.actions();
 
-        let status = SwapStatus::new(
-            communication.status,
-            alpha_ledger.status,
-            beta_ledger.status,
-        );
+        let outcome = state.swap_outcome();
+        let status = match outcome {
+            Some(outcome) => SwapStatus::from(outcome),
+            None => SwapStatus::new(
+                communication.status,
+                alpha_ledger.status,
+                beta_ledger.status,
+            ),
+        };
 
         let swap = SwapResource {
             id: Http(id),
             status,
+            outcome,
             protocol: Http(SwapProtocol::Rfc003(HashFunction::Sha256)),
             parameters,
             role: swap.role.to_string(),
@@ -139,6 +174,10 @@ pub fn build_rfc003_siren_entity<S: StateStore>(
                 }),
                 IncludeState::No => None,
             },
+            executed_actions: executed_actions
+                .into_iter()
+                .map(ExecutedAction::from)
+                .collect(),
         };
 
         let entity = siren::Entity::default()
@@ -1,11 +1,14 @@
 use crate::{
     asset::Asset,
-    db::AcceptedSwap,
+    db::{AcceptedSwap, SwapEvents},
     seed::DeriveSwapSeed,
     swap_protocols::{
         rfc003::{
-            alice, bob, create_swap,
-            events::{HtlcDeployed, HtlcFunded, HtlcRedeemed, HtlcRefunded},
+            alice, bob,
+            create_swap::{create_swap, SwapEventOnLedger},
+            events::{
+                HtlcConfirmed, HtlcDeployed, HtlcFunded, HtlcPending, HtlcRedeemed, HtlcRefunded,
+            },
             state_store::StateStore,
             Ledger,
         },
@@ -23,6 +26,7 @@ where
     D: StateStore
         + Clone
         + DeriveSwapSeed
+        + SwapEvents<SwapEventOnLedger<AL, BL, AA, BA>>
         + HtlcFunded<AL, AA>
         + HtlcFunded<BL, BA>
         + HtlcDeployed<AL, AA>
@@ -30,7 +34,11 @@ where
         + HtlcRedeemed<AL, AA>
         + HtlcRedeemed<BL, BA>
         + HtlcRefunded<AL, AA>
-        + HtlcRefunded<BL, BA>,
+        + HtlcRefunded<BL, BA>
+        + HtlcConfirmed<AL>
+        + HtlcConfirmed<BL>
+        + HtlcPending<AL, AA>
+        + HtlcPending<BL, BA>,
 {
     let (request, accept, _at) = accepted.clone();
 
@@ -30,6 +30,7 @@ pub mod libp2p_comit_ext;
 pub mod db;
 
 pub mod asset;
+pub mod autopilot;
 pub mod bitcoin;
 pub mod btsieve;
 pub mod comit_api;
@@ -37,9 +38,12 @@ pub mod config;
 pub mod ethereum;
 pub mod http_api;
 pub mod init_swap;
+pub mod lightning;
+pub mod litecoin;
 pub mod load_swaps;
 #[macro_use]
 pub mod network;
+pub mod policy;
 #[cfg(test)]
 pub mod quickcheck;
 #[macro_use]
@@ -48,6 +52,7 @@ pub mod seed;
 pub mod spectral_ext;
 pub mod swap_protocols;
 pub mod timestamp;
+pub mod wallet;
 
 use anyhow::Context;
 use directories::ProjectDirs;
@@ -0,0 +1,123 @@
+//! This module is the home of lightning-specific types that are needed across
+//! several places in cnd.
+//!
+//! On the Lightning Network an HTLC is a hold invoice on the node of the
+//! redeemer that is paid by the node of the funder. Both are identified by
+//! the public key of the respective node and the invoice is looked up through
+//! the payment hash, which is the `SecretHash` of the swap.
+
+use crate::{asset, swap_protocols::rfc003::SecretHash};
+use bitcoin::secp256k1;
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{fmt, str::FromStr};
+
+/// The public key of a Lightning node.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PublicKey(secp256k1::PublicKey);
+
+impl From<secp256k1::PublicKey> for PublicKey {
+    fn from(key: secp256k1::PublicKey) -> Self {
+        Self(key)
+    }
+}
+
+impl From<PublicKey> for secp256k1::PublicKey {
+    fn from(public_key: PublicKey) -> Self {
+        public_key.0
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = secp256k1::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        secp256k1::PublicKey::from_str(s).map(Self)
+    }
+}
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Serialize for PublicKey {
+    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for PublicKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, <D as Deserializer<'de>>::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct PublicKeyVisitor;
+
+        impl<'de> Visitor<'de> for PublicKeyVisitor {
+            type Value = PublicKey;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(formatter, "a hex-encoded, compressed node public key")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                v.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(PublicKeyVisitor)
+    }
+}
+
+/// A snapshot of the hold invoice or payment that makes up an HTLC on the
+/// Lightning Network.
+///
+/// Lightning has no transactions that could be referred to later on, hence
+/// this records what the node reported at the time the event was observed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Transaction {
+    pub payment_hash: SecretHash,
+    pub amount: asset::Bitcoin,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_lightning_identity() {
+        let public_key: PublicKey =
+            "02c2a8efce029526d364c2cf39d89e3cdda05e5df7b2cbfc098b4e3d02b70b5275"
+                .parse()
+                .unwrap();
+
+        let str = serde_json::to_string(&public_key).unwrap();
+        assert_eq!(
+            str,
+            "\"02c2a8efce029526d364c2cf39d89e3cdda05e5df7b2cbfc098b4e3d02b70b5275\""
+        )
+    }
+
+    #[test]
+    fn lightning_identity_roundtrips_through_json() {
+        let public_key: PublicKey =
+            "02c2a8efce029526d364c2cf39d89e3cdda05e5df7b2cbfc098b4e3d02b70b5275"
+                .parse()
+                .unwrap();
+
+        let str = serde_json::to_string(&public_key).unwrap();
+        let deserialized = serde_json::from_str::<PublicKey>(&str).unwrap();
+
+        assert_eq!(deserialized, public_key);
+    }
+}
@@ -0,0 +1,278 @@
+//! This module is the home of litecoin-specific types that are needed across
+//! several places in cnd.
+//!
+//! Litecoin shares the script and transaction format of Bitcoin, so cnd
+//! handles Litecoin transactions with the types of rust-bitcoin. Only
+//! addresses are encoded with different prefixes, which is what `Address`
+//! takes care of.
+
+use crate::swap_protocols::ledger::litecoin::Network;
+use bitcoin::{
+    bech32::{self, u5, FromBase32, ToBase32},
+    blockdata::{
+        opcodes::all::{OP_CHECKSIG, OP_DUP, OP_EQUAL, OP_EQUALVERIFY, OP_HASH160},
+        script::Builder,
+    },
+    util::base58,
+    Script,
+};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+/// An address on the Litecoin blockchain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Address {
+    script_pubkey: Script,
+    network: Network,
+}
+
+impl Address {
+    /// The address that pays to `script_pubkey` on `network`.
+    ///
+    /// Returns `None` if the script is not one of the standard output
+    /// scripts that have an address.
+    pub fn from_script(script_pubkey: Script, network: Network) -> Option<Self> {
+        if script_pubkey.is_p2pkh()
+            || script_pubkey.is_p2sh()
+            || script_pubkey.is_v0_p2wpkh()
+            || script_pubkey.is_v0_p2wsh()
+        {
+            Some(Address {
+                script_pubkey,
+                network,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn script_pubkey(&self) -> &Script {
+        &self.script_pubkey
+    }
+
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    /// The same output as an address of rust-bitcoin, which signing and
+    /// watching transactions work with.
+    pub fn to_bitcoin_address(&self) -> bitcoin::Address {
+        bitcoin::Address::from_script(&self.script_pubkey, self.network.bitcoin_network())
+            .expect("only standard output scripts are represented")
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let script = self.script_pubkey.as_bytes();
+
+        if self.script_pubkey.is_p2pkh() {
+            let mut payload = vec![self.network.p2pkh_prefix()];
+            payload.extend_from_slice(&script[3..23]);
+
+            return f.write_str(&base58::check_encode_slice(&payload));
+        }
+
+        if self.script_pubkey.is_p2sh() {
+            let mut payload = vec![self.network.p2sh_prefix()];
+            payload.extend_from_slice(&script[2..22]);
+
+            return f.write_str(&base58::check_encode_slice(&payload));
+        }
+
+        let mut data = vec![u5::try_from_u8(0).expect("0 is a valid u5")];
+        data.extend(script[2..].to_base32());
+        let address = bech32::encode(self.network.bech32_hrp(), data).map_err(|_| fmt::Error)?;
+
+        f.write_str(&address)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("invalid base58 encoding")]
+    Base58(#[from] base58::Error),
+    #[error("invalid bech32 encoding")]
+    Bech32(#[from] bech32::Error),
+    #[error("unknown address prefix: {0}")]
+    UnknownPrefix(String),
+    #[error("unsupported witness program")]
+    UnsupportedWitnessProgram,
+    #[error("invalid address length")]
+    InvalidLength,
+}
+
+impl FromStr for Address {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let segwit_network = [Network::Mainnet, Network::Testnet, Network::Regtest]
+            .iter()
+            .find(|network| {
+                s.to_lowercase()
+                    .starts_with(&format!("{}1", network.bech32_hrp()))
+            });
+
+        if let Some(network) = segwit_network {
+            let (_, data) = bech32::decode(s)?;
+            let (version, program) = data.split_first().ok_or(Error::InvalidLength)?;
+            if version.to_u8() != 0 {
+                return Err(Error::UnsupportedWitnessProgram);
+            }
+            let program = Vec::<u8>::from_base32(program)?;
+            if program.len() != 20 && program.len() != 32 {
+                return Err(Error::UnsupportedWitnessProgram);
+            }
+
+            let script_pubkey = Builder::new()
+                .push_int(0)
+                .push_slice(&program)
+                .into_script();
+
+            return Ok(Address {
+                script_pubkey,
+                network: *network,
+            });
+        }
+
+        let payload = base58::from_check(s)?;
+        if payload.len() != 21 {
+            return Err(Error::InvalidLength);
+        }
+        let (prefix, hash) = (payload[0], &payload[1..]);
+
+        // Testnet and regtest share their base58 prefixes.
+        let (script_pubkey, network) = match prefix {
+            0x30 => (p2pkh_script(hash), Network::Mainnet),
+            0x32 => (p2sh_script(hash), Network::Mainnet),
+            0x6f => (p2pkh_script(hash), Network::Testnet),
+            0x3a => (p2sh_script(hash), Network::Testnet),
+            _ => return Err(Error::UnknownPrefix(s.to_owned())),
+        };
+
+        Ok(Address {
+            script_pubkey,
+            network,
+        })
+    }
+}
+
+fn p2pkh_script(pubkey_hash: &[u8]) -> Script {
+    Builder::new()
+        .push_opcode(OP_DUP)
+        .push_opcode(OP_HASH160)
+        .push_slice(pubkey_hash)
+        .push_opcode(OP_EQUALVERIFY)
+        .push_opcode(OP_CHECKSIG)
+        .into_script()
+}
+
+fn p2sh_script(script_hash: &[u8]) -> Script {
+    Builder::new()
+        .push_opcode(OP_HASH160)
+        .push_slice(script_hash)
+        .push_opcode(OP_EQUAL)
+        .into_script()
+}
+
+impl Serialize for Address {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let address = String::deserialize(deserializer)?;
+
+        address.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUBKEY_HASH: &str = "751e76e8199196d454941c45d1b3a323f1433bd6";
+
+    fn pubkey_hash() -> Vec<u8> {
+        hex::decode(PUBKEY_HASH).unwrap()
+    }
+
+    #[test]
+    fn encodes_p2pkh_with_litecoin_prefix() {
+        let address = Address::from_script(p2pkh_script(&pubkey_hash()), Network::Mainnet).unwrap();
+
+        assert_eq!(address.to_string(), "LVuDpNCSSj6pQ7t9Pv6d6sUkLKoqDEVUnJ");
+    }
+
+    #[test]
+    fn encodes_p2sh_with_litecoin_prefix() {
+        let address = Address::from_script(p2sh_script(&pubkey_hash()), Network::Mainnet).unwrap();
+
+        assert_eq!(address.to_string(), "MJaRnao1s62a2zAKSkmG582KbLKianqb7v");
+    }
+
+    #[test]
+    fn encodes_segwit_with_litecoin_hrp() {
+        let script = Builder::new()
+            .push_int(0)
+            .push_slice(&pubkey_hash())
+            .into_script();
+
+        let mainnet = Address::from_script(script.clone(), Network::Mainnet).unwrap();
+        let regtest = Address::from_script(script, Network::Regtest).unwrap();
+
+        assert_eq!(
+            mainnet.to_string(),
+            "ltc1qw508d6qejxtdg4y5r3zarvary0c5xw7kgmn4n9"
+        );
+        assert_eq!(
+            regtest.to_string(),
+            "rltc1qw508d6qejxtdg4y5r3zarvary0c5xw7k693xs3"
+        );
+    }
+
+    #[test]
+    fn roundtrips_through_string() {
+        let addresses = [
+            "LVuDpNCSSj6pQ7t9Pv6d6sUkLKoqDEVUnJ",
+            "MJaRnao1s62a2zAKSkmG582KbLKianqb7v",
+            "mrCDrCybB6J1vRfbwM5hemdJz73FwDBC8r",
+            "QXHFfTBKYXjaaTH1e7Rox8CcdNPGHVhM59",
+            "ltc1qw508d6qejxtdg4y5r3zarvary0c5xw7kgmn4n9",
+            "tltc1qw508d6qejxtdg4y5r3zarvary0c5xw7klfsuq0",
+            "rltc1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q22ldvf",
+        ];
+
+        for address in addresses.iter() {
+            let parsed = Address::from_str(address).unwrap();
+
+            assert_eq!(&parsed.to_string(), address);
+        }
+    }
+
+    #[test]
+    fn rejects_bitcoin_addresses() {
+        assert!(Address::from_str("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH").is_err());
+        assert!(Address::from_str("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4").is_err());
+    }
+
+    #[test]
+    fn bitcoin_address_pays_to_the_same_script() {
+        let address =
+            Address::from_str("rltc1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q22ldvf")
+                .unwrap();
+
+        assert_eq!(
+            &address.to_bitcoin_address().script_pubkey(),
+            address.script_pubkey()
+        );
+    }
+}
@@ -13,25 +13,44 @@
 )]
 #![forbid(unsafe_code)]
 use crate::cli::Options;
-use anyhow::Context;
+use anyhow::{bail, Context};
 use cnd::{
-    btsieve::{bitcoin, bitcoin::BitcoindConnector, ethereum, ethereum::Web3Connector},
+    autopilot,
+    btsieve::{
+        bitcoin,
+        bitcoin::{
+            subscribe_zmq_blocks, BitcoinBackend, BitcoindConnector, BitcoindRpcClient,
+            BitcoindRpcConnector, EsploraConnector, RpcAuth,
+        },
+        disk_cache::DiskCache,
+        ethereum,
+        ethereum::{subscribe_new_heads, Web3Connector},
+        failover::Failover,
+        lightning::{LndApi, LndConnector},
+        Mempool, Scanner,
+    },
     config::{self, Settings},
     db::Sqlite,
     http_api::route_factory,
     load_swaps,
     network::Swarm,
+    policy,
     seed::RootSeed,
-    swap_protocols::{rfc003::state_store::InMemoryStateStore, Facade},
+    swap_protocols::{ledger::ethereum::ChainId, rfc003::state_store::InMemoryStateStore, Facade},
+    wallet,
 };
+use futures_core::channel::mpsc;
 use rand::rngs::OsRng;
-use std::{net::SocketAddr, process, sync::Arc};
+use std::{collections::HashMap, net::SocketAddr, process, sync::Arc};
 use structopt::StructOpt;
+use tokio::sync::Mutex;
 use tokio_compat::runtime;
 
 mod cli;
 mod trace;
 
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
 fn main() -> anyhow::Result<()> {
     let options = cli::Options::from_args();
 
@@ -55,47 +74,243 @@ fn main() -> anyhow::Result<()> {
         .stack_size(1024 * 1024 * 4) // the default is 2MB but that causes a segfault for some reason
         .build()?;
 
+    let rpc_auth = bitcoind_rpc_auth(settings.bitcoin.bitcoind.rpc.clone().unwrap_or_default())?;
+    // The wallet talks to the primary node only.
+    let bitcoind_rpc_client =
+        BitcoindRpcClient::new(settings.bitcoin.bitcoind.node_url.clone(), rpc_auth.clone());
+
     const BITCOIN_BLOCK_CACHE_CAPACITY: usize = 144;
+    const BITCOIN_MAX_TIP_LAG: u64 = 2;
     let bitcoin_connector = {
-        let config::Bitcoin { network, bitcoind } = settings.clone().bitcoin;
-        bitcoin::Cache::new(
-            BitcoindConnector::new(bitcoind.node_url, network)?,
-            BITCOIN_BLOCK_CACHE_CAPACITY,
-        )
+        let config::Bitcoin {
+            network,
+            bitcoind,
+            esplora,
+            ..
+        } = settings.clone().bitcoin;
+        let node_urls = std::iter::once(bitcoind.node_url).chain(bitcoind.fallback_node_urls);
+
+        let mut endpoints = match (esplora, bitcoind.rpc) {
+            (Some(config::Esplora { url }), _) => {
+                vec![(
+                    url.clone(),
+                    BitcoinBackend::from(EsploraConnector::new(url)),
+                )]
+            }
+            (None, Some(_)) => node_urls
+                .map(|url| {
+                    let client = BitcoindRpcClient::new(url.clone(), rpc_auth.clone());
+                    (url, BitcoinBackend::from(BitcoindRpcConnector::new(client)))
+                })
+                .collect(),
+            (None, None) => node_urls
+                .map(|url| {
+                    let connector = BitcoindConnector::new(url.clone(), network)?;
+                    Ok((url, BitcoinBackend::from(connector)))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?,
+        };
+        // Notifications only come from the primary node.
+        if let Some(zmq_url) = bitcoind.zmq_url {
+            let (url, connector) = endpoints.remove(0);
+            let connector = connector.with_new_blocks(subscribe_zmq_blocks(zmq_url.as_str())?);
+            endpoints.insert(0, (url, connector));
+        }
+
+        let (connector, health_check) = Failover::new(endpoints, network, BITCOIN_MAX_TIP_LAG)?;
+        runtime.spawn_std(health_check);
+
+        let cache = bitcoin::Cache::new(connector, BITCOIN_BLOCK_CACHE_CAPACITY);
+        match settings.data.block_cache {
+            Some(config::BlockCache { max_size_mb }) => {
+                let dir = settings
+                    .data
+                    .dir
+                    .join("cache")
+                    .join("bitcoin")
+                    .join(network.to_string());
+
+                cache.with_disk_cache(DiskCache::open(
+                    dir.join("blocks"),
+                    max_size_mb * BYTES_PER_MB,
+                )?)
+            }
+            None => cache,
+        }
     };
 
-    const ETHEREUM_BLOCK_CACHE_CAPACITY: usize = 720;
-    const ETHEREUM_RECEIPT_CACHE_CAPACITY: usize = 720;
-    let ethereum_connector = ethereum::Cache::new(
-        Web3Connector::new(settings.clone().ethereum.parity.node_url),
-        ETHEREUM_BLOCK_CACHE_CAPACITY,
-        ETHEREUM_RECEIPT_CACHE_CAPACITY,
-    );
+    let litecoin_connector = match &settings.litecoin {
+        Some(config::Litecoin {
+            network, litecoind, ..
+        }) => {
+            let network = network.bitcoin_network();
+            let connector = BitcoindConnector::new(litecoind.node_url.clone(), network)?;
+            let (connector, health_check) = Failover::new(
+                vec![(litecoind.node_url.clone(), BitcoinBackend::from(connector))],
+                network,
+                BITCOIN_MAX_TIP_LAG,
+            )?;
+            runtime.spawn_std(health_check);
+
+            Some(bitcoin::Cache::new(connector, BITCOIN_BLOCK_CACHE_CAPACITY))
+        }
+        None => None,
+    };
+
+    let mut ethereum_connectors = HashMap::new();
+    let ethereum_chains = std::iter::once((settings.ethereum.chain_id, &settings.ethereum.parity))
+        .chain(
+            settings
+                .ethereum
+                .other_chains
+                .iter()
+                .map(|chain| (chain.chain_id, &chain.parity)),
+        );
+    for (chain_id, parity) in ethereum_chains {
+        let connector = ethereum_connector(&mut runtime, &settings.data, chain_id, parity.clone())?;
+
+        if ethereum_connectors.insert(chain_id, connector).is_some() {
+            bail!(
+                "ethereum chain {} is configured more than once",
+                u32::from(chain_id)
+            )
+        }
+    }
+
+    let bitcoin_wallet = match settings.bitcoin.wallet {
+        Some(config::BitcoinWallet {
+            fallback_fee_per_wu,
+        }) => {
+            let wallet = wallet::bitcoin::Wallet::new(
+                seed,
+                settings.bitcoin.network,
+                bitcoind_rpc_client,
+                fallback_fee_per_wu,
+            )?;
+            tracing::info!("Built-in bitcoin wallet uses address {}", wallet.address());
+
+            Some(wallet)
+        }
+        None => None,
+    };
+
+    let ethereum_wallet = match settings.ethereum.wallet {
+        Some(config::EthereumWallet { private_key }) => {
+            let chain_id = settings.ethereum.chain_id;
+            let (_, connector) = primary_ethereum_node(&settings.ethereum.parity)?;
+
+            let wallet = match private_key {
+                Some(private_key) => {
+                    wallet::ethereum::Wallet::new(private_key, chain_id, connector)
+                }
+                None => wallet::ethereum::Wallet::from_seed(seed, chain_id, connector)?,
+            };
+            tracing::info!(
+                "Built-in ethereum wallet uses address {:x}",
+                wallet.address()
+            );
+
+            Some(wallet)
+        }
+        None => None,
+    };
+
+    let lnd_connector = match &settings.lightning {
+        Some(config::Lightning { network, lnd }) => {
+            let connector = runtime.block_on_std(LndConnector::connect(
+                lnd.rest_url.clone(),
+                &lnd.tls_cert_path,
+                &lnd.macaroon_path,
+            ))?;
+            if connector.network() != *network {
+                anyhow::bail!(
+                    "lnd runs on {} but lightning is configured for {}",
+                    connector.network(),
+                    network
+                )
+            }
+            tracing::info!("Connected to lnd node {}", connector.identity());
+
+            Some(connector)
+        }
+        None => None,
+    };
 
     let state_store = Arc::new(InMemoryStateStore::default());
 
     let database = Sqlite::new_in_dir(&settings.data.dir)?;
 
+    let (inbound_requests, policy_receiver) = if settings.policy.is_enabled() {
+        let (sender, receiver) = mpsc::unbounded();
+        (Some(sender), Some(receiver))
+    } else {
+        (None, None)
+    };
+
     let swarm = Swarm::new(
         &settings,
         seed,
         &mut runtime,
         &bitcoin_connector,
-        &ethereum_connector,
+        &ethereum_connectors,
         &state_store,
         &database,
+        inbound_requests,
     )?;
 
+    let bitcoin_mempool = if settings.bitcoin.mempool {
+        Some(Mempool::new(bitcoin_connector.connector.clone()))
+    } else {
+        None
+    };
+    let ethereum_mempools = if settings.ethereum.mempool {
+        ethereum_connectors
+            .iter()
+            .map(|(chain_id, connector)| (*chain_id, Mempool::new(connector.connector.clone())))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
     let deps = Facade {
-        bitcoin_connector,
-        ethereum_connector,
+        bitcoin_scanner: Scanner::new(bitcoin_connector),
+        ethereum_scanners: ethereum_connectors
+            .into_iter()
+            .map(|(chain_id, connector)| (chain_id, Scanner::new(connector)))
+            .collect(),
+        bitcoin_confirmations: settings.bitcoin.confirmations,
+        ethereum_confirmations: settings.ethereum.confirmations,
+        bitcoin_mempool,
+        ethereum_mempools,
+        litecoin_scanner: litecoin_connector.map(Scanner::new),
+        litecoin_confirmations: settings
+            .litecoin
+            .as_ref()
+            .and_then(|litecoin| litecoin.confirmations)
+            .unwrap_or(config::DEFAULT_CONFIRMATIONS),
         state_store: Arc::clone(&state_store),
         seed,
         swarm,
         db: database,
+        connected_ledgers: settings.connected_ledgers(),
+        expiry_margins: settings.expiry_margins,
+        bitcoin_wallet,
+        ethereum_wallet,
+        lnd_connector,
+        execution_lock: Arc::new(Mutex::new(())),
     };
 
     runtime.block_on_std(load_swaps::load_swaps_from_database(deps.clone()))?;
+    if settings.autopilot.is_enabled() {
+        runtime.spawn_std(autopilot::run(deps.clone(), settings.autopilot));
+    }
+    if let Some(policy_receiver) = policy_receiver {
+        runtime.spawn_std(policy::run(
+            deps.clone(),
+            settings.policy.clone(),
+            policy_receiver,
+        ));
+    }
     runtime.spawn_std(spawn_warp_instance(settings, deps));
 
     // Block the current thread.
@@ -127,6 +342,99 @@ async fn spawn_warp_instance(settings: Settings, dependencies: Facade) {
     warp::serve(routes).bind(listen_addr).await
 }
 
+/// Follows the Ethereum chain with `chain_id` through the nodes of `parity`.
+fn ethereum_connector(
+    runtime: &mut runtime::Runtime,
+    data: &config::Data,
+    chain_id: ChainId,
+    parity: config::Parity,
+) -> anyhow::Result<ethereum::Cache<Failover<Web3Connector>>> {
+    const ETHEREUM_BLOCK_CACHE_CAPACITY: usize = 720;
+    const ETHEREUM_RECEIPT_CACHE_CAPACITY: usize = 720;
+    const ETHEREUM_MAX_TIP_LAG: u64 = 10;
+
+    let mut endpoints = vec![primary_ethereum_node(&parity)?];
+    endpoints.extend(
+        parity
+            .fallback_node_urls
+            .into_iter()
+            .map(|url| (url.clone(), Web3Connector::new(url))),
+    );
+    // The subscription is only made with the primary node.
+    if let Some(ws_url) = parity.ws_url {
+        let (url, connector) = endpoints.remove(0);
+        let (new_heads, subscription) = subscribe_new_heads(ws_url, connector.clone());
+        runtime.spawn_std(subscription);
+        endpoints.insert(0, (url, connector.with_new_heads(new_heads)));
+    }
+
+    let (connector, health_check) = Failover::new(endpoints, chain_id, ETHEREUM_MAX_TIP_LAG)?;
+    runtime.spawn_std(health_check);
+
+    let cache = ethereum::Cache::new(
+        connector,
+        ETHEREUM_BLOCK_CACHE_CAPACITY,
+        ETHEREUM_RECEIPT_CACHE_CAPACITY,
+    );
+    let cache = match data.block_cache {
+        Some(config::BlockCache { max_size_mb }) => {
+            let dir = data
+                .dir
+                .join("cache")
+                .join("ethereum")
+                .join(u32::from(chain_id).to_string());
+            // The blocks and receipts of a chain share the budget.
+            let max_size = max_size_mb * BYTES_PER_MB / 2;
+
+            cache.with_disk_cache(
+                DiskCache::open(dir.join("blocks"), max_size)?,
+                DiskCache::open(dir.join("receipts"), max_size)?,
+            )
+        }
+        None => cache,
+    };
+
+    Ok(cache)
+}
+
+/// The node at `node_url`, or behind `ipc_path` if set, along with the URL it
+/// is reported under.
+fn primary_ethereum_node(parity: &config::Parity) -> anyhow::Result<(reqwest::Url, Web3Connector)> {
+    match &parity.ipc_path {
+        Some(ipc_path) => {
+            let url = reqwest::Url::from_file_path(ipc_path)
+                .map_err(|_| anyhow::anyhow!("ipc_path {} is not absolute", ipc_path.display()))?;
+
+            Ok((url, Web3Connector::with_ipc_path(ipc_path.clone())))
+        }
+        None => Ok((
+            parity.node_url.clone(),
+            Web3Connector::new(parity.node_url.clone()),
+        )),
+    }
+}
+
+fn bitcoind_rpc_auth(rpc: config::BitcoindRpc) -> anyhow::Result<RpcAuth> {
+    match rpc {
+        config::BitcoindRpc {
+            cookie_file: None,
+            user: None,
+            password: None,
+        } => Ok(RpcAuth::Url),
+        config::BitcoindRpc {
+            cookie_file: Some(cookie_file),
+            user: None,
+            password: None,
+        } => Ok(RpcAuth::CookieFile(cookie_file)),
+        config::BitcoindRpc {
+            cookie_file: None,
+            user: Some(user),
+            password: Some(password),
+        } => Ok(RpcAuth::UserPassword { user, password }),
+        _ => bail!("set either cookie_file or user and password in [bitcoin.bitcoind.rpc]"),
+    }
+}
+
 #[allow(clippy::print_stdout)] // We cannot use `log` before we have the config file
 fn read_config(options: &Options) -> anyhow::Result<config::File> {
     // if the user specifies a config path, use it
@@ -4,26 +4,33 @@ pub use transport::ComitTransport;
 
 use crate::{
     asset::{Asset, AssetKind},
-    btsieve::{bitcoin, bitcoin::BitcoindConnector, ethereum, ethereum::Web3Connector},
+    btsieve::{
+        bitcoin, bitcoin::BitcoinBackend, ethereum, ethereum::Web3Connector, failover::Failover,
+    },
     comit_api::LedgerKind,
     config::Settings,
     db::{Save, Sqlite, Swap},
     libp2p_comit_ext::{FromHeader, ToHeader},
     seed::{DeriveSwapSeed, RootSeed},
     swap_protocols::{
-        ledger,
+        ledger::{ethereum::ChainId, ConnectedLedgers},
         rfc003::{
             self, bob,
+            expiries::ExpiryMargins,
             messages::{Decision, DeclineResponseBody, Request, SwapDeclineReason},
             state_store::{InMemoryStateStore, StateStore},
             Ledger,
         },
         HashFunction, Role, SwapId, SwapProtocol,
     },
+    timestamp::Timestamp,
 };
 use async_trait::async_trait;
 use futures_core::{
-    channel::oneshot::{self, Sender},
+    channel::{
+        mpsc,
+        oneshot::{self, Sender},
+    },
     stream::StreamExt,
     Future as _,
 };
@@ -72,14 +79,16 @@ pub struct Swarm {
 }
 
 impl Swarm {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         settings: &Settings,
         seed: RootSeed,
         runtime: &mut Runtime,
-        bitcoin_connector: &bitcoin::Cache<BitcoindConnector>,
-        ethereum_connector: &ethereum::Cache<Web3Connector>,
+        bitcoin_connector: &bitcoin::Cache<Failover<BitcoinBackend>>,
+        ethereum_connectors: &HashMap<ChainId, ethereum::Cache<Failover<Web3Connector>>>,
         state_store: &Arc<InMemoryStateStore>,
         database: &Sqlite,
+        inbound_requests: Option<mpsc::UnboundedSender<SwapId>>,
     ) -> anyhow::Result<Self> {
         let local_key_pair = derive_key_pair(&seed);
         let local_peer_id = PeerId::from(local_key_pair.clone().public());
@@ -88,11 +97,14 @@ impl Swarm {
         let transport = transport::build_comit_transport(local_key_pair)?;
         let behaviour = ComitNode::new(
             bitcoin_connector.clone(),
-            ethereum_connector.clone(),
+            ethereum_connectors.clone(),
             Arc::clone(&state_store),
             seed,
             database.clone(),
             runtime.executor(),
+            settings.connected_ledgers(),
+            settings.expiry_margins,
+            inbound_requests,
         )?;
 
         let mut swarm = SwarmBuilder::new(transport, behaviour, local_peer_id.clone())
@@ -141,9 +153,9 @@ pub struct ComitNode {
     mdns: Mdns,
 
     #[behaviour(ignore)]
-    pub bitcoin_connector: bitcoin::Cache<BitcoindConnector>,
+    pub bitcoin_connector: bitcoin::Cache<Failover<BitcoinBackend>>,
     #[behaviour(ignore)]
-    pub ethereum_connector: ethereum::Cache<Web3Connector>,
+    pub ethereum_connectors: HashMap<ChainId, ethereum::Cache<Failover<Web3Connector>>>,
     #[behaviour(ignore)]
     pub state_store: Arc<InMemoryStateStore>,
     #[behaviour(ignore)]
@@ -154,6 +166,13 @@ pub struct ComitNode {
     response_channels: Arc<Mutex<HashMap<SwapId, oneshot::Sender<Response>>>>,
     #[behaviour(ignore)]
     task_executor: TaskExecutor,
+    #[behaviour(ignore)]
+    connected_ledgers: ConnectedLedgers,
+    #[behaviour(ignore)]
+    expiry_margins: ExpiryMargins,
+    /// Notified about every inbound request that awaits an answer.
+    #[behaviour(ignore)]
+    inbound_requests: Option<mpsc::UnboundedSender<SwapId>>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -189,13 +208,17 @@ pub struct Reason {
 }
 
 impl ComitNode {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        bitcoin_connector: bitcoin::Cache<BitcoindConnector>,
-        ethereum_connector: ethereum::Cache<Web3Connector>,
+        bitcoin_connector: bitcoin::Cache<Failover<BitcoinBackend>>,
+        ethereum_connectors: HashMap<ChainId, ethereum::Cache<Failover<Web3Connector>>>,
         state_store: Arc<InMemoryStateStore>,
         seed: RootSeed,
         db: Sqlite,
         task_executor: TaskExecutor,
+        connected_ledgers: ConnectedLedgers,
+        expiry_margins: ExpiryMargins,
+        inbound_requests: Option<mpsc::UnboundedSender<SwapId>>,
     ) -> Result<Self, io::Error> {
         let mut swap_headers = HashSet::new();
         swap_headers.insert("id".into());
@@ -212,12 +235,15 @@ impl ComitNode {
             comit: Comit::new(known_headers),
             mdns: Mdns::new()?,
             bitcoin_connector,
-            ethereum_connector,
+            ethereum_connectors,
             state_store,
             seed,
             db,
             response_channels: Arc::new(Mutex::new(HashMap::new())),
             task_executor,
+            connected_ledgers,
+            expiry_margins,
+            inbound_requests,
         })
     }
 
@@ -231,13 +257,15 @@ impl ComitNode {
     }
 }
 
-// This is due to the introduction of a struct per Bitcoin network and can be
-// iteratively improved
+// Dispatching through every registered combination trips clippy's complexity
+// lint, just like `with_swap_types!` does.
 #[allow(clippy::cognitive_complexity)]
 async fn handle_request(
     db: Sqlite,
     seed: RootSeed,
     state_store: Arc<InMemoryStateStore>,
+    connected_ledgers: ConnectedLedgers,
+    expiry_margins: ExpiryMargins,
     counterparty: PeerId,
     mut request: ValidatedInboundRequest,
 ) -> Result<SwapId, Response> {
@@ -255,6 +283,16 @@ async fn handle_request(
                     let beta_ledger = header!(request
                         .take_header("beta_ledger")
                         .map(LedgerKind::from_header));
+
+                    if let Err(e) = connected_ledgers.ensure_connected(alpha_ledger) {
+                        tracing::warn!("declining swap {}: {}", swap_id, e);
+                        return Err(decline_response(SwapDeclineReason::UnsupportedAlphaLedger));
+                    }
+                    if let Err(e) = connected_ledgers.ensure_connected(beta_ledger) {
+                        tracing::warn!("declining swap {}: {}", swap_id, e);
+                        return Err(decline_response(SwapDeclineReason::UnsupportedBetaLedger));
+                    }
+
                     let alpha_asset = header!(request
                         .take_header("alpha_asset")
                         .map(AssetKind::from_header));
@@ -262,259 +300,26 @@ async fn handle_request(
                         .take_header("beta_asset")
                         .map(AssetKind::from_header));
 
-                    match (alpha_ledger, beta_ledger, alpha_asset, beta_asset) {
-                        (
-                            LedgerKind::BitcoinRegtest,
-                            LedgerKind::Ethereum(beta_ledger),
-                            AssetKind::Bitcoin(alpha_asset),
-                            AssetKind::Ether(beta_asset),
-                        ) => {
-                            let request = rfc003_swap_request(
-                                swap_id,
-                                ledger::bitcoin::Regtest,
-                                beta_ledger,
-                                alpha_asset,
-                                beta_asset,
-                                hash_function,
-                                body!(request.take_body_as()),
-                            );
-                            insert_state_for_bob(
-                                db.clone(),
-                                seed,
-                                state_store.clone(),
-                                counterparty,
-                                request,
-                            )
-                            .await
-                            .expect("Could not save state to db");
-                            Ok(swap_id)
-                        }
-                        (
-                            LedgerKind::BitcoinTestnet,
-                            LedgerKind::Ethereum(beta_ledger),
-                            AssetKind::Bitcoin(alpha_asset),
-                            AssetKind::Ether(beta_asset),
-                        ) => {
-                            let request = rfc003_swap_request(
-                                swap_id,
-                                ledger::bitcoin::Testnet,
-                                beta_ledger,
-                                alpha_asset,
-                                beta_asset,
-                                hash_function,
-                                body!(request.take_body_as()),
-                            );
-                            insert_state_for_bob(
-                                db.clone(),
-                                seed,
-                                state_store.clone(),
-                                counterparty,
-                                request,
-                            )
-                            .await
-                            .expect("Could not save state to db");
-                            Ok(swap_id)
-                        }
-                        (
-                            LedgerKind::BitcoinMainnet,
-                            LedgerKind::Ethereum(beta_ledger),
-                            AssetKind::Bitcoin(alpha_asset),
-                            AssetKind::Ether(beta_asset),
-                        ) => {
-                            let request = rfc003_swap_request(
-                                swap_id,
-                                ledger::bitcoin::Mainnet,
-                                beta_ledger,
-                                alpha_asset,
-                                beta_asset,
-                                hash_function,
-                                body!(request.take_body_as()),
-                            );
-                            insert_state_for_bob(
-                                db.clone(),
-                                seed,
-                                state_store.clone(),
-                                counterparty,
-                                request,
-                            )
-                            .await
-                            .expect("Could not save state to db");
-                            Ok(swap_id)
-                        }
-                        (
-                            LedgerKind::Ethereum(alpha_ledger),
-                            LedgerKind::BitcoinRegtest,
-                            AssetKind::Ether(alpha_asset),
-                            AssetKind::Bitcoin(beta_asset),
-                        ) => {
-                            let request = rfc003_swap_request(
-                                swap_id,
-                                alpha_ledger,
-                                ledger::bitcoin::Regtest,
-                                alpha_asset,
-                                beta_asset,
-                                hash_function,
-                                body!(request.take_body_as()),
-                            );
-                            insert_state_for_bob(
-                                db.clone(),
-                                seed,
-                                state_store.clone(),
-                                counterparty,
-                                request,
-                            )
-                            .await
-                            .expect("Could not save state to db");
-                            Ok(swap_id)
-                        }
-                        (
-                            LedgerKind::Ethereum(alpha_ledger),
-                            LedgerKind::BitcoinTestnet,
-                            AssetKind::Ether(alpha_asset),
-                            AssetKind::Bitcoin(beta_asset),
-                        ) => {
-                            let request = rfc003_swap_request(
-                                swap_id,
-                                alpha_ledger,
-                                ledger::bitcoin::Testnet,
-                                alpha_asset,
-                                beta_asset,
-                                hash_function,
-                                body!(request.take_body_as()),
-                            );
-                            insert_state_for_bob(
-                                db.clone(),
-                                seed,
-                                state_store.clone(),
-                                counterparty,
-                                request,
-                            )
-                            .await
-                            .expect("Could not save state to db");
-                            Ok(swap_id)
-                        }
-                        (
-                            LedgerKind::Ethereum(alpha_ledger),
-                            LedgerKind::BitcoinMainnet,
-                            AssetKind::Ether(alpha_asset),
-                            AssetKind::Bitcoin(beta_asset),
-                        ) => {
-                            let request = rfc003_swap_request(
-                                swap_id,
-                                alpha_ledger,
-                                ledger::bitcoin::Mainnet,
-                                alpha_asset,
-                                beta_asset,
-                                hash_function,
-                                body!(request.take_body_as()),
-                            );
-                            insert_state_for_bob(
-                                db.clone(),
-                                seed,
-                                state_store.clone(),
-                                counterparty,
-                                request,
-                            )
-                            .await
-                            .expect("Could not save state to db");
-                            Ok(swap_id)
-                        }
-                        (
-                            LedgerKind::BitcoinRegtest,
-                            LedgerKind::Ethereum(beta_ledger),
-                            AssetKind::Bitcoin(alpha_asset),
-                            AssetKind::Erc20(beta_asset),
-                        ) => {
-                            let request = rfc003_swap_request(
-                                swap_id,
-                                ledger::bitcoin::Regtest,
-                                beta_ledger,
-                                alpha_asset,
-                                beta_asset,
-                                hash_function,
-                                body!(request.take_body_as()),
-                            );
-                            insert_state_for_bob(
-                                db.clone(),
-                                seed,
-                                state_store.clone(),
-                                counterparty,
-                                request,
-                            )
-                            .await
-                            .expect("Could not save state to db");
-
-                            Ok(swap_id)
-                        }
-                        (
-                            LedgerKind::BitcoinTestnet,
-                            LedgerKind::Ethereum(beta_ledger),
-                            AssetKind::Bitcoin(alpha_asset),
-                            AssetKind::Erc20(beta_asset),
-                        ) => {
-                            let request = rfc003_swap_request(
-                                swap_id,
-                                ledger::bitcoin::Testnet,
-                                beta_ledger,
-                                alpha_asset,
-                                beta_asset,
-                                hash_function,
-                                body!(request.take_body_as()),
-                            );
-                            insert_state_for_bob(
-                                db.clone(),
-                                seed,
-                                state_store.clone(),
-                                counterparty,
-                                request,
-                            )
-                            .await
-                            .expect("Could not save state to db");
-
-                            Ok(swap_id)
-                        }
-                        (
-                            LedgerKind::BitcoinMainnet,
-                            LedgerKind::Ethereum(beta_ledger),
-                            AssetKind::Bitcoin(alpha_asset),
-                            AssetKind::Erc20(beta_asset),
-                        ) => {
-                            let request = rfc003_swap_request(
-                                swap_id,
-                                ledger::bitcoin::Mainnet,
-                                beta_ledger,
-                                alpha_asset,
-                                beta_asset,
-                                hash_function,
-                                body!(request.take_body_as()),
-                            );
-                            insert_state_for_bob(
-                                db.clone(),
-                                seed,
-                                state_store.clone(),
-                                counterparty,
-                                request,
-                            )
-                            .await
-                            .expect("Could not save state to db");
+                    let kinds = (
+                        alpha_ledger,
+                        beta_ledger,
+                        alpha_asset.clone(),
+                        beta_asset.clone(),
+                    );
 
-                            Ok(swap_id)
-                        }
-                        (
-                            LedgerKind::Ethereum(alpha_ledger),
-                            LedgerKind::BitcoinRegtest,
-                            AssetKind::Erc20(alpha_asset),
-                            AssetKind::Bitcoin(beta_asset),
-                        ) => {
+                    crate::with_swap!(
+                        (alpha_ledger, beta_ledger, alpha_asset, beta_asset) = kinds,
+                        {
                             let request = rfc003_swap_request(
                                 swap_id,
                                 alpha_ledger,
-                                ledger::bitcoin::Regtest,
+                                beta_ledger,
                                 alpha_asset,
                                 beta_asset,
                                 hash_function,
                                 body!(request.take_body_as()),
                             );
+                            ensure_safe_expiries(expiry_margins, &request)?;
                             insert_state_for_bob(
                                 db.clone(),
                                 seed,
@@ -525,80 +330,19 @@ async fn handle_request(
                             .await
                             .expect("Could not save state to db");
                             Ok(swap_id)
-                        }
-                        (
-                            LedgerKind::Ethereum(alpha_ledger),
-                            LedgerKind::BitcoinTestnet,
-                            AssetKind::Erc20(alpha_asset),
-                            AssetKind::Bitcoin(beta_asset),
-                        ) => {
-                            let request = rfc003_swap_request(
-                                swap_id,
-                                alpha_ledger,
-                                ledger::bitcoin::Testnet,
+                        },
+                        {
+                            tracing::warn!(
+                                "swapping {:?} to {:?} from {:?} to {:?} is currently not supported",
                                 alpha_asset,
                                 beta_asset,
-                                hash_function,
-                                body!(request.take_body_as()),
-                            );
-                            insert_state_for_bob(
-                                db.clone(),
-                                seed,
-                                state_store.clone(),
-                                counterparty,
-                                request,
-                            )
-                            .await
-                            .expect("Could not save state to db");
-                            Ok(swap_id)
-                        }
-                        (
-                            LedgerKind::Ethereum(alpha_ledger),
-                            LedgerKind::BitcoinMainnet,
-                            AssetKind::Erc20(alpha_asset),
-                            AssetKind::Bitcoin(beta_asset),
-                        ) => {
-                            let request = rfc003_swap_request(
-                                swap_id,
                                 alpha_ledger,
-                                ledger::bitcoin::Mainnet,
-                                alpha_asset,
-                                beta_asset,
-                                hash_function,
-                                body!(request.take_body_as()),
+                                beta_ledger
                             );
-                            insert_state_for_bob(
-                                db.clone(),
-                                seed,
-                                state_store.clone(),
-                                counterparty,
-                                request,
-                            )
-                            .await
-                            .expect("Could not save state to db");
-                            Ok(swap_id)
-                        }
-                        (alpha_ledger, beta_ledger, alpha_asset, beta_asset) => {
-                            tracing::warn!(
-                                    "swapping {:?} to {:?} from {:?} to {:?} is currently not supported", alpha_asset, beta_asset, alpha_ledger, beta_ledger
-                                );
-
-                            let decline_body = DeclineResponseBody {
-                                reason: Some(SwapDeclineReason::UnsupportedSwap),
-                            };
-
-                            Err(Response::empty()
-                                .with_header(
-                                    "decision",
-                                    Decision::Declined
-                                        .to_header()
-                                        .expect("Decision should not fail to serialize"),
-                                )
-                                .with_body(serde_json::to_value(decline_body).expect(
-                                    "decline body should always serialize into serde_json::Value",
-                                )))
+
+                            Err(decline_response(SwapDeclineReason::UnsupportedSwap))
                         }
-                    }
+                    )
                 }
             }
         }
@@ -620,6 +364,36 @@ async fn handle_request(
     }
 }
 
+fn ensure_safe_expiries<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset>(
+    expiry_margins: ExpiryMargins,
+    request: &Request<AL, BL, AA, BA>,
+) -> Result<(), Response> {
+    expiry_margins
+        .validate(request.alpha_expiry, request.beta_expiry, Timestamp::now())
+        .map_err(|e| {
+            tracing::warn!("declining swap {}: {}", request.swap_id, e);
+            decline_response(SwapDeclineReason::UnsafeExpiries)
+        })
+}
+
+fn decline_response(reason: SwapDeclineReason) -> Response {
+    let decline_body = DeclineResponseBody {
+        reason: Some(reason),
+    };
+
+    Response::empty()
+        .with_header(
+            "decision",
+            Decision::Declined
+                .to_header()
+                .expect("Decision should not fail to serialize"),
+        )
+        .with_body(
+            serde_json::to_value(decline_body)
+                .expect("decline body should always serialize into serde_json::Value"),
+        )
+}
+
 #[allow(clippy::type_complexity)]
 async fn insert_state_for_bob<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset, DB>(
     db: DB,
@@ -812,12 +586,32 @@ impl NetworkBehaviourEventProcess<BehaviourOutEvent> for ComitNode {
                 let db = self.db.clone();
                 let state_store = self.state_store.clone();
                 let seed = self.seed;
+                let connected_ledgers = self.connected_ledgers.clone();
+                let expiry_margins = self.expiry_margins;
+                let inbound_requests = self.inbound_requests.clone();
 
                 self.task_executor.spawn_std(async move {
-                    match handle_request(db, seed, state_store, peer_id, request).await {
+                    match handle_request(
+                        db,
+                        seed,
+                        state_store,
+                        connected_ledgers,
+                        expiry_margins,
+                        peer_id,
+                        request,
+                    )
+                    .await {
                         Ok(id) => {
-                            let mut response_channels = response_channels.lock().await;
-                            response_channels.insert(id, channel);
+                            response_channels.lock().await.insert(id, channel);
+
+                            if let Some(inbound_requests) = inbound_requests {
+                                inbound_requests.unbounded_send(id).unwrap_or_else(|_| {
+                                    tracing::warn!(
+                                        "policy is not running, request for swap {} has to be answered manually",
+                                        id
+                                    )
+                                });
+                            }
                         }
                         Err(response) => channel.send(response).unwrap_or_else(|_| {
                             tracing::debug!("failed to send response through channel")
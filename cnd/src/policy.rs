@@ -0,0 +1,394 @@
+use crate::{
+    asset::{Asset, AssetKind},
+    comit_api::LedgerKind,
+    config::{Policy, PolicyAsset, PolicyLedger, PolicyRule},
+    db::{DetermineTypes, Retrieve},
+    ethereum::U256,
+    http_api::routes::rfc003::handlers::{accept_swap, decline_swap},
+    seed::DeriveSwapSeed,
+    swap_protocols::{
+        rfc003::{
+            messages::{IntoAcceptMessage, SwapDeclineReason},
+            state_store::StateStore,
+            Ledger, Request,
+        },
+        Facade, SwapId,
+    },
+    timestamp::Timestamp,
+};
+use bigdecimal::BigDecimal;
+use futures_core::{channel::mpsc, stream::StreamExt};
+use libp2p::PeerId;
+use num::{BigInt, BigUint};
+
+/// What a policy rule decided about a swap request.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Verdict {
+    Accept,
+    Decline(Option<SwapDeclineReason>),
+}
+
+/// Answers every inbound request announced through `inbound_requests` as
+/// decided by `policy`.
+///
+/// Requests that no rule applies to, or that cannot be answered, are left for
+/// manual handling through the HTTP API.
+pub async fn run(
+    facade: Facade,
+    policy: Policy,
+    mut inbound_requests: mpsc::UnboundedReceiver<SwapId>,
+) {
+    tracing::info!("policy started with {} rules", policy.rules.len());
+
+    while let Some(swap_id) = inbound_requests.next().await {
+        if let Err(e) = answer_request(&facade, &policy, swap_id).await {
+            tracing::warn!(
+                "policy could not answer request for swap {}: {:#}",
+                swap_id,
+                e
+            );
+        }
+    }
+}
+
+#[allow(clippy::cognitive_complexity)]
+async fn answer_request(facade: &Facade, policy: &Policy, swap_id: SwapId) -> anyhow::Result<()> {
+    let swap = Retrieve::get(facade, &swap_id).await?;
+    let types = facade.determine_types(&swap_id).await?;
+
+    with_swap_types!(types, {
+        let state = StateStore::get::<ROLE>(facade, &swap_id)?.ok_or_else(|| {
+            anyhow::anyhow!("state store did not contain an entry for {}", swap_id)
+        })?;
+        let request = state.request();
+
+        match evaluate(policy, &swap.counterparty, &request, Timestamp::now()) {
+            Some(Verdict::Accept) => {
+                let identity = policy.ethereum_identity.or_else(|| {
+                    facade
+                        .ethereum_wallet
+                        .as_ref()
+                        .map(|wallet| wallet.address())
+                });
+                let identity = match identity {
+                    Some(identity) => identity,
+                    None => anyhow::bail!("no ethereum identity to accept the swap with"),
+                };
+
+                let accept_message = AcceptBody::from(identity).into_accept_message(
+                    swap_id,
+                    &facade.derive_swap_seed(swap_id),
+                    facade.lnd_identity(),
+                )?;
+                accept_swap::<AL, BL, AA, BA>(facade, accept_message, types.role).await?;
+
+                tracing::info!("policy accepted swap {}", swap_id);
+            }
+            Some(Verdict::Decline(reason)) => {
+                decline_swap(facade, request, reason).await?;
+
+                tracing::info!("policy declined swap {}", swap_id);
+            }
+            None => tracing::info!(
+                "no policy rule applies to swap {}, leaving it for manual handling",
+                swap_id
+            ),
+        }
+
+        Ok(())
+    })
+}
+
+/// Returns the verdict of the first rule in `policy` that applies to the
+/// request, if any.
+pub fn evaluate<AL, BL, AA, BA>(
+    policy: &Policy,
+    counterparty: &PeerId,
+    request: &Request<AL, BL, AA, BA>,
+    now: Timestamp,
+) -> Option<Verdict>
+where
+    AL: Ledger,
+    BL: Ledger,
+    AA: Asset,
+    BA: Asset,
+{
+    let alpha_ledger: LedgerKind = request.alpha_ledger.into();
+    let beta_ledger: LedgerKind = request.beta_ledger.into();
+    let alpha_asset: AssetKind = request.alpha_asset.clone().into();
+    let beta_asset: AssetKind = request.beta_asset.clone().into();
+    let earliest_expiry = std::cmp::min(request.alpha_expiry, request.beta_expiry);
+
+    policy
+        .rules
+        .iter()
+        .find(|rule| {
+            ledger_matches(rule.alpha_ledger, alpha_ledger)
+                && ledger_matches(rule.beta_ledger, beta_ledger)
+                && asset_matches(rule.alpha_asset, rule.alpha_token_contract, &alpha_asset)
+                && asset_matches(rule.beta_asset, rule.beta_token_contract, &beta_asset)
+                && peer_matches(rule, counterparty)
+        })
+        .map(|rule| {
+            decide(
+                rule,
+                quantity(&alpha_asset),
+                quantity(&beta_asset),
+                earliest_expiry,
+                now,
+            )
+        })
+}
+
+/// Declines requests whose quantities or exchange rate, both compared in the
+/// smallest unit of the assets, are outside the bounds of `rule`, or that
+/// expire too soon.
+fn decide(
+    rule: &PolicyRule,
+    alpha_quantity: BigUint,
+    beta_quantity: BigUint,
+    earliest_expiry: Timestamp,
+    now: Timestamp,
+) -> Verdict {
+    let quantities_within_bounds = within_bounds(
+        &alpha_quantity,
+        &rule.min_alpha_quantity,
+        &rule.max_alpha_quantity,
+    ) && within_bounds(
+        &beta_quantity,
+        &rule.min_beta_quantity,
+        &rule.max_beta_quantity,
+    );
+
+    // The rate is compared between the quantities in the smallest unit of their
+    // asset, e.g. satoshi per wei, not between whole coins. Compare alpha
+    // against rate * beta so that a zero beta quantity does not need special
+    // treatment.
+    let alpha = BigDecimal::from((BigInt::from(alpha_quantity), 0));
+    let beta = BigDecimal::from((BigInt::from(beta_quantity), 0));
+    let rate_within_bounds = rule
+        .min_rate
+        .as_ref()
+        .map_or(true, |min_rate| alpha >= min_rate.clone() * beta.clone())
+        && rule
+            .max_rate
+            .as_ref()
+            .map_or(true, |max_rate| alpha <= max_rate.clone() * beta.clone());
+
+    if !quantities_within_bounds {
+        return Verdict::Decline(Some(SwapDeclineReason::UnsatisfactoryQuantity));
+    }
+
+    if !rate_within_bounds {
+        return Verdict::Decline(Some(SwapDeclineReason::UnsatisfactoryRate));
+    }
+
+    let expiry_far_enough = rule
+        .min_expiry_distance_secs
+        .map_or(true, |distance| now.plus(distance) <= earliest_expiry);
+
+    if !expiry_far_enough {
+        return Verdict::Decline(Some(SwapDeclineReason::UnsafeExpiries));
+    }
+
+    Verdict::Accept
+}
+
+fn ledger_matches(rule: PolicyLedger, ledger: LedgerKind) -> bool {
+    match (rule, ledger) {
+        (PolicyLedger::Bitcoin, LedgerKind::BitcoinMainnet)
+        | (PolicyLedger::Bitcoin, LedgerKind::BitcoinTestnet)
+        | (PolicyLedger::Bitcoin, LedgerKind::BitcoinRegtest)
+        | (PolicyLedger::Ethereum, LedgerKind::Ethereum(_))
+        | (PolicyLedger::Lightning, LedgerKind::Lightning(_))
+        | (PolicyLedger::Litecoin, LedgerKind::Litecoin(_)) => true,
+        _ => false,
+    }
+}
+
+fn asset_matches(
+    rule: PolicyAsset,
+    token_contract: Option<crate::ethereum::Address>,
+    asset: &AssetKind,
+) -> bool {
+    match (rule, asset) {
+        (PolicyAsset::Bitcoin, AssetKind::Bitcoin(_))
+        | (PolicyAsset::Litecoin, AssetKind::Litecoin(_))
+        | (PolicyAsset::Ether, AssetKind::Ether(_)) => true,
+        (PolicyAsset::Erc20, AssetKind::Erc20(erc20)) => token_contract
+            .map_or(true, |token_contract| {
+                token_contract == erc20.token_contract
+            }),
+        _ => false,
+    }
+}
+
+fn peer_matches(rule: &PolicyRule, counterparty: &PeerId) -> bool {
+    let counterparty = counterparty.to_base58();
+
+    rule.peers
+        .as_ref()
+        .map_or(true, |peers| peers.iter().any(|peer| *peer == counterparty))
+}
+
+fn within_bounds<T: PartialOrd>(value: &T, min: &Option<T>, max: &Option<T>) -> bool {
+    min.as_ref().map_or(true, |min| value >= min) && max.as_ref().map_or(true, |max| value <= max)
+}
+
+/// The quantity of the asset in its smallest unit.
+fn quantity(asset: &AssetKind) -> BigUint {
+    match asset {
+        AssetKind::Bitcoin(bitcoin) => BigUint::from(bitcoin.as_sat()),
+        AssetKind::Litecoin(litecoin) => BigUint::from(litecoin.as_litoshi()),
+        AssetKind::Ether(ether) => biguint_from_u256(ether.to_u256()),
+        AssetKind::Erc20(erc20) => biguint_from_u256(erc20.quantity.to_u256()),
+    }
+}
+
+fn biguint_from_u256(value: U256) -> BigUint {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+
+    BigUint::from_bytes_be(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        asset::{self, ethereum::FromWei},
+        ethereum::Address,
+        swap_protocols::{
+            ledger::{bitcoin, Ethereum},
+            rfc003::Secret,
+            HashFunction,
+        },
+    };
+    use spectral::prelude::*;
+
+    const NOW: u32 = 1_500_000_000;
+
+    fn request(
+        sat: u64,
+        wei: u64,
+        beta_expiry: u32,
+    ) -> Request<bitcoin::Regtest, Ethereum, asset::Bitcoin, asset::Ether> {
+        Request {
+            swap_id: SwapId::default(),
+            alpha_ledger: bitcoin::Regtest {},
+            beta_ledger: Ethereum::default(),
+            alpha_asset: asset::Bitcoin::from_sat(sat),
+            beta_asset: asset::Ether::from_wei(wei),
+            hash_function: HashFunction::Sha256,
+            alpha_ledger_refund_identity:
+                "02c2a8efce029526d364c2cf39d89e3cdda05e5df7b2cbfc098b4e3d02b70b5275"
+                    .parse()
+                    .unwrap(),
+            beta_ledger_redeem_identity: "8457037fcd80a8650c4692d7fcfc1d0a96b92867"
+                .parse::<Address>()
+                .unwrap(),
+            alpha_expiry: Timestamp::from(NOW + 7200),
+            beta_expiry: Timestamp::from(beta_expiry),
+            secret_hash: Secret::from(*b"hello world, you are beautiful!!").hash(),
+        }
+    }
+
+    fn bitcoin_ether_rule() -> PolicyRule {
+        PolicyRule {
+            alpha_ledger: PolicyLedger::Bitcoin,
+            beta_ledger: PolicyLedger::Ethereum,
+            alpha_asset: PolicyAsset::Bitcoin,
+            beta_asset: PolicyAsset::Ether,
+            alpha_token_contract: None,
+            beta_token_contract: None,
+            peers: None,
+            min_alpha_quantity: None,
+            max_alpha_quantity: None,
+            min_beta_quantity: None,
+            max_beta_quantity: None,
+            min_rate: None,
+            max_rate: None,
+            min_expiry_distance_secs: None,
+        }
+    }
+
+    fn evaluate_rule(rule: PolicyRule, sat: u64, wei: u64, beta_expiry: u32) -> Option<Verdict> {
+        let policy = Policy {
+            ethereum_identity: None,
+            rules: vec![rule],
+        };
+
+        evaluate(
+            &policy,
+            &PeerId::random(),
+            &request(sat, wei, beta_expiry),
+            Timestamp::from(NOW),
+        )
+    }
+
+    #[test]
+    fn leaves_request_for_manual_handling_if_no_rule_applies() {
+        let rule = PolicyRule {
+            beta_asset: PolicyAsset::Erc20,
+            ..bitcoin_ether_rule()
+        };
+
+        assert_that(&evaluate_rule(rule, 100, 100, NOW + 3600)).is_none();
+
+        let rule = PolicyRule {
+            peers: Some(vec![PeerId::random().to_base58()]),
+            ..bitcoin_ether_rule()
+        };
+
+        assert_that(&evaluate_rule(rule, 100, 100, NOW + 3600)).is_none();
+    }
+
+    #[test]
+    fn accepts_request_within_bounds() {
+        let rule = PolicyRule {
+            min_alpha_quantity: Some(BigUint::from(100u32)),
+            max_beta_quantity: Some(BigUint::from(1_000u32)),
+            min_rate: Some("0.5".parse().unwrap()),
+            max_rate: Some("2".parse().unwrap()),
+            min_expiry_distance_secs: Some(3600),
+            ..bitcoin_ether_rule()
+        };
+
+        assert_that(&evaluate_rule(rule, 100, 100, NOW + 3600)).contains_value(Verdict::Accept);
+    }
+
+    #[test]
+    fn declines_request_with_unsatisfactory_quantities() {
+        let rule = PolicyRule {
+            min_alpha_quantity: Some(BigUint::from(100u32)),
+            ..bitcoin_ether_rule()
+        };
+
+        assert_that(&evaluate_rule(rule, 99, 100, NOW + 3600)).contains_value(Verdict::Decline(
+            Some(SwapDeclineReason::UnsatisfactoryQuantity),
+        ));
+    }
+
+    #[test]
+    fn declines_request_with_unsatisfactory_rate() {
+        let rule = PolicyRule {
+            min_rate: Some("0.5".parse().unwrap()),
+            ..bitcoin_ether_rule()
+        };
+
+        assert_that(&evaluate_rule(rule, 49, 100, NOW + 3600)).contains_value(Verdict::Decline(
+            Some(SwapDeclineReason::UnsatisfactoryRate),
+        ));
+    }
+
+    #[test]
+    fn declines_request_that_expires_too_soon() {
+        let rule = PolicyRule {
+            min_expiry_distance_secs: Some(3600),
+            ..bitcoin_ether_rule()
+        };
+
+        assert_that(&evaluate_rule(rule, 100, 100, NOW + 3599))
+            .contains_value(Verdict::Decline(Some(SwapDeclineReason::UnsafeExpiries)));
+    }
+}
@@ -90,6 +90,14 @@ impl Arbitrary for Quickcheck<asset::Bitcoin> {
     }
 }
 
+impl Arbitrary for Quickcheck<asset::Litecoin> {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        let amount = asset::Litecoin::from_litoshi(g.next_u64());
+
+        Quickcheck(amount)
+    }
+}
+
 impl Arbitrary for Quickcheck<crate::ethereum::U256> {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
         let bytes = *Quickcheck::<[u8; 32]>::arbitrary(g);
@@ -238,6 +246,18 @@ impl Arbitrary for Quickcheck<ledger::Ethereum> {
     }
 }
 
+impl Arbitrary for Quickcheck<ledger::Litecoin> {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        let network = match g.next_u32() % 3 {
+            0 => ledger::litecoin::Network::Mainnet,
+            1 => ledger::litecoin::Network::Testnet,
+            _ => ledger::litecoin::Network::Regtest,
+        };
+
+        Quickcheck(ledger::Litecoin::new(network))
+    }
+}
+
 impl<AL, BL, AA, BA> Arbitrary for Quickcheck<Request<AL, BL, AA, BA>>
 where
     AL: Ledger,
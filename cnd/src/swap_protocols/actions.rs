@@ -39,6 +39,33 @@ pub mod bitcoin {
     }
 }
 
+pub mod litecoin {
+    use crate::{asset, litecoin::Address, swap_protocols::ledger::litecoin::Network};
+    use blockchain_contracts::bitcoin::witness::{PrimedInput, PrimedTransaction};
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct SendToAddress {
+        pub to: Address,
+        pub amount: asset::Litecoin,
+        pub network: Network,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct SpendOutput {
+        pub output: PrimedInput,
+        pub network: Network,
+    }
+
+    impl SpendOutput {
+        pub fn spend_to(self, to_address: Address) -> PrimedTransaction {
+            PrimedTransaction {
+                inputs: vec![self.output],
+                output_address: to_address.to_bitcoin_address(),
+            }
+        }
+    }
+}
+
 pub mod ethereum {
     use crate::{
         asset,
@@ -64,3 +91,43 @@ pub mod ethereum {
         pub min_block_timestamp: Option<Timestamp>,
     }
 }
+
+pub mod lnd {
+    use crate::{
+        asset,
+        lightning::PublicKey,
+        swap_protocols::rfc003::{Secret, SecretHash},
+        timestamp::Timestamp,
+    };
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct AddHoldInvoice {
+        pub amount: asset::Bitcoin,
+        pub secret_hash: SecretHash,
+        pub expiry: Timestamp,
+        pub network: bitcoin::Network,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct SettleInvoice {
+        pub secret: Secret,
+        pub network: bitcoin::Network,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct SendPayment {
+        pub to_public_key: PublicKey,
+        pub amount: asset::Bitcoin,
+        pub secret_hash: SecretHash,
+        pub expiry: Timestamp,
+        pub network: bitcoin::Network,
+    }
+}
+
+/// Swapping ERC20 against Lightning needs to deploy the ERC20 HTLC as well as
+/// add the hold invoice, hence the deploy action can be either of them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeployContractOrAddHoldInvoice {
+    DeployContract(ethereum::DeployContract),
+    AddHoldInvoice(lnd::AddHoldInvoice),
+}
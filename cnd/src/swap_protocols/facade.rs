@@ -1,34 +1,63 @@
 use crate::{
     asset::{self, Asset},
-    btsieve::{self, bitcoin::BitcoindConnector, ethereum, ethereum::Web3Connector},
-    db::{AcceptedSwap, DetermineTypes, LoadAcceptedSwap, Retrieve, Save, Sqlite, Swap, SwapTypes},
+    btsieve::{
+        self,
+        bitcoin::BitcoinBackend,
+        ethereum,
+        ethereum::Web3Connector,
+        failover::Failover,
+        lightning::{LndApi, LndConnector, LndNotConnected},
+        Confirmation, Mempool, PendingChange, Scanner, Watch,
+    },
+    db::{
+        AcceptedSwap, DetermineTypes, ExecutedActions, LoadAcceptedSwap, Retrieve, Save, Sqlite,
+        Swap, SwapEvents, SwapTypes,
+    },
     network::{
         ComitPeers, DialInformation, ListenAddresses, LocalPeerId, PendingRequestFor, RequestError,
         SendRequest, Swarm,
     },
     seed::{DeriveSwapSeed, RootSeed, SwapSeed},
     swap_protocols::{
-        ledger::{bitcoin, Ethereum},
+        ledger::{
+            bitcoin, ethereum::ChainId, ConnectedLedgers, Ethereum, Lightning, Litecoin,
+            UnsupportedLedger,
+        },
         rfc003::{
             self,
             create_swap::{HtlcParams, SwapEventOnLedger},
             events::{
-                Deployed, Funded, HtlcDeployed, HtlcFunded, HtlcRedeemed, HtlcRefunded, Redeemed,
-                Refunded,
+                Deployed, Funded, HtlcConfirmed, HtlcDeployed, HtlcFunded, HtlcPending,
+                HtlcRedeemed, HtlcRefunded, Pending, Redeemed, Refunded,
             },
+            expiries::ExpiryMargins,
             state_store::{self, InMemoryStateStore, StateStore},
             ActorState, Ledger,
         },
         SwapId,
     },
+    wallet,
 };
 use async_trait::async_trait;
-use chrono::NaiveDateTime;
+use chrono::{NaiveDateTime, Utc};
 use futures_core::channel::oneshot::Sender;
 use impl_template::impl_template;
 use libp2p::{Multiaddr, PeerId};
 use libp2p_comit::frame::Response;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::Mutex;
+
+type BitcoinScanner = Scanner<btsieve::bitcoin::Cache<Failover<BitcoinBackend>>, ::bitcoin::Block>;
+
+type EthereumScanner = Scanner<
+    ethereum::Cache<Failover<Web3Connector>>,
+    crate::ethereum::Block<crate::ethereum::Transaction>,
+>;
+
+type BitcoinMempool = Mempool<Failover<BitcoinBackend>, ::bitcoin::Txid, ::bitcoin::Transaction>;
+
+type EthereumMempool =
+    Mempool<Failover<Web3Connector>, crate::ethereum::H256, crate::ethereum::Transaction>;
 
 /// This is a facade that implements all the required traits and forwards them
 /// to another implementation. This allows us to keep the number of arguments to
@@ -41,13 +70,73 @@ use std::sync::Arc;
 #[delegate(PendingRequestFor, target = "swarm")]
 #[delegate(Retrieve, target = "db")]
 #[delegate(DetermineTypes, target = "db")]
+#[delegate(ExecutedActions, target = "db")]
 pub struct Facade {
-    pub bitcoin_connector: btsieve::bitcoin::Cache<BitcoindConnector>,
-    pub ethereum_connector: ethereum::Cache<Web3Connector>,
+    pub bitcoin_scanner: BitcoinScanner,
+    /// One scanner per Ethereum chain cnd is connected to.
+    pub ethereum_scanners: HashMap<ChainId, EthereumScanner>,
+    /// The number of confirmations after which a Bitcoin transaction is
+    /// considered final.
+    pub bitcoin_confirmations: u32,
+    /// The number of confirmations after which an Ethereum transaction is
+    /// considered final.
+    pub ethereum_confirmations: u32,
+    /// Watches the mempool of bitcoind for HTLC transactions, if enabled.
+    pub bitcoin_mempool: Option<BitcoinMempool>,
+    /// Watches the pending transactions of each Ethereum chain for HTLC
+    /// transactions, empty if not enabled.
+    pub ethereum_mempools: HashMap<ChainId, EthereumMempool>,
+    /// Follows the chain of the litecoind node, if cnd is connected to one.
+    pub litecoin_scanner: Option<BitcoinScanner>,
+    /// The number of confirmations after which a Litecoin transaction is
+    /// considered final.
+    pub litecoin_confirmations: u32,
+    pub lnd_connector: Option<LndConnector>,
     pub state_store: Arc<InMemoryStateStore>,
     pub seed: RootSeed,
     pub swarm: Swarm,
     pub db: Sqlite,
+    pub connected_ledgers: ConnectedLedgers,
+    pub expiry_margins: ExpiryMargins,
+    pub bitcoin_wallet: Option<wallet::bitcoin::Wallet>,
+    pub ethereum_wallet: Option<wallet::ethereum::Wallet>,
+    /// Held while an action is executed with the built-in wallets, see
+    /// `http_api::action::execute_once`.
+    pub execution_lock: Arc<Mutex<()>>,
+}
+
+impl Facade {
+    /// The scanner that follows the chain of `ledger`.
+    pub fn ethereum_scanner(&self, ledger: Ethereum) -> anyhow::Result<&EthereumScanner> {
+        self.ethereum_scanners.get(&ledger.chain_id).ok_or_else(|| {
+            anyhow::Error::from(UnsupportedLedger {
+                ledger: ledger.into(),
+            })
+        })
+    }
+
+    /// The scanner that follows the chain of `ledger`.
+    pub fn litecoin_scanner(&self, ledger: Litecoin) -> anyhow::Result<&BitcoinScanner> {
+        self.litecoin_scanner
+            .as_ref()
+            .filter(|_| self.connected_ledgers.litecoin == Some(ledger.network))
+            .ok_or_else(|| {
+                anyhow::Error::from(UnsupportedLedger {
+                    ledger: ledger.into(),
+                })
+            })
+    }
+
+    pub fn lnd_connector(&self) -> anyhow::Result<&LndConnector> {
+        self.lnd_connector
+            .as_ref()
+            .ok_or_else(|| anyhow::Error::from(LndNotConnected))
+    }
+
+    /// Our identity on the Lightning Network, if we are connected to lnd.
+    pub fn lnd_identity(&self) -> Option<crate::lightning::PublicKey> {
+        self.lnd_connector.as_ref().map(LndApi::identity)
+    }
 }
 
 impl StateStore for Facade {
@@ -107,6 +196,21 @@ where
     }
 }
 
+#[async_trait]
+impl<E> SwapEvents<E> for Facade
+where
+    E: Send + 'static,
+    Sqlite: SwapEvents<E>,
+{
+    async fn save_swap_event(&self, swap_id: &SwapId, event: E) -> anyhow::Result<()> {
+        self.db.save_swap_event(swap_id, event).await
+    }
+
+    async fn load_swap_events(&self, swap_id: &SwapId) -> anyhow::Result<Vec<E>> {
+        self.db.load_swap_events(swap_id).await
+    }
+}
+
 #[impl_template]
 #[async_trait::async_trait]
 impl HtlcFunded<((bitcoin::Mainnet, bitcoin::Testnet, bitcoin::Regtest)), asset::Bitcoin>
@@ -118,7 +222,7 @@ impl HtlcFunded<((bitcoin::Mainnet, bitcoin::Testnet, bitcoin::Regtest)), asset:
         htlc_deployment: &Deployed<::bitcoin::Transaction, ::bitcoin::OutPoint>,
         start_of_swap: NaiveDateTime,
     ) -> anyhow::Result<Funded<::bitcoin::Transaction, asset::Bitcoin>> {
-        self.bitcoin_connector
+        self.bitcoin_scanner
             .htlc_funded(htlc_params, htlc_deployment, start_of_swap)
             .await
     }
@@ -134,7 +238,7 @@ impl HtlcDeployed<((bitcoin::Mainnet, bitcoin::Testnet, bitcoin::Regtest)), asse
         htlc_params: HtlcParams<__TYPE0__, asset::Bitcoin, crate::bitcoin::PublicKey>,
         start_of_swap: NaiveDateTime,
     ) -> anyhow::Result<Deployed<::bitcoin::Transaction, ::bitcoin::OutPoint>> {
-        self.bitcoin_connector
+        self.bitcoin_scanner
             .htlc_deployed(htlc_params, start_of_swap)
             .await
     }
@@ -151,7 +255,7 @@ impl HtlcRedeemed<((bitcoin::Mainnet, bitcoin::Testnet, bitcoin::Regtest)), asse
         htlc_deployment: &Deployed<::bitcoin::Transaction, ::bitcoin::OutPoint>,
         start_of_swap: NaiveDateTime,
     ) -> anyhow::Result<Redeemed<::bitcoin::Transaction>> {
-        self.bitcoin_connector
+        self.bitcoin_scanner
             .htlc_redeemed(htlc_params, htlc_deployment, start_of_swap)
             .await
     }
@@ -168,12 +272,50 @@ impl HtlcRefunded<((bitcoin::Mainnet, bitcoin::Testnet, bitcoin::Regtest)), asse
         htlc_deployment: &Deployed<::bitcoin::Transaction, ::bitcoin::OutPoint>,
         start_of_swap: NaiveDateTime,
     ) -> anyhow::Result<Refunded<::bitcoin::Transaction>> {
-        self.bitcoin_connector
+        self.bitcoin_scanner
             .htlc_refunded(htlc_params, htlc_deployment, start_of_swap)
             .await
     }
 }
 
+#[impl_template]
+#[async_trait::async_trait]
+impl HtlcConfirmed<((bitcoin::Mainnet, bitcoin::Testnet, bitcoin::Regtest))> for Facade {
+    async fn htlc_confirmed(
+        &self,
+        _ledger: __TYPE0__,
+        transaction: &::bitcoin::Transaction,
+        start_of_swap: NaiveDateTime,
+    ) -> anyhow::Result<Confirmation> {
+        let txid = transaction.txid();
+
+        self.bitcoin_scanner
+            .confirmation(start_of_swap, self.bitcoin_confirmations, move |block| {
+                block
+                    .txdata
+                    .iter()
+                    .any(|transaction| transaction.txid() == txid)
+            })
+            .await
+    }
+}
+
+#[impl_template]
+impl HtlcPending<((bitcoin::Mainnet, bitcoin::Testnet, bitcoin::Regtest)), asset::Bitcoin>
+    for Facade
+{
+    fn htlc_pending(
+        &self,
+        htlc_params: HtlcParams<__TYPE0__, asset::Bitcoin, crate::bitcoin::PublicKey>,
+        htlc_deployment: Option<&Deployed<::bitcoin::Transaction, ::bitcoin::OutPoint>>,
+    ) -> anyhow::Result<Watch<PendingChange<Pending<::bitcoin::Transaction>>>> {
+        match &self.bitcoin_mempool {
+            Some(mempool) => mempool.htlc_pending(htlc_params, htlc_deployment),
+            None => Ok(Watch::never()),
+        }
+    }
+}
+
 #[impl_template]
 #[async_trait::async_trait]
 impl HtlcFunded<Ethereum, ((asset::Ether, asset::Erc20))> for Facade {
@@ -183,7 +325,7 @@ impl HtlcFunded<Ethereum, ((asset::Ether, asset::Erc20))> for Facade {
         htlc_deployment: &Deployed<crate::ethereum::Transaction, crate::ethereum::Address>,
         start_of_swap: NaiveDateTime,
     ) -> anyhow::Result<Funded<crate::ethereum::Transaction, __TYPE0__>> {
-        self.ethereum_connector
+        self.ethereum_scanner(htlc_params.ledger)?
             .htlc_funded(htlc_params, htlc_deployment, start_of_swap)
             .await
     }
@@ -197,7 +339,7 @@ impl HtlcDeployed<Ethereum, ((asset::Ether, asset::Erc20))> for Facade {
         htlc_params: HtlcParams<Ethereum, __TYPE0__, crate::ethereum::Address>,
         start_of_swap: NaiveDateTime,
     ) -> anyhow::Result<Deployed<crate::ethereum::Transaction, crate::ethereum::Address>> {
-        self.ethereum_connector
+        self.ethereum_scanner(htlc_params.ledger)?
             .htlc_deployed(htlc_params, start_of_swap)
             .await
     }
@@ -212,7 +354,7 @@ impl HtlcRedeemed<Ethereum, ((asset::Ether, asset::Erc20))> for Facade {
         htlc_deployment: &Deployed<crate::ethereum::Transaction, crate::ethereum::Address>,
         start_of_swap: NaiveDateTime,
     ) -> anyhow::Result<Redeemed<crate::ethereum::Transaction>> {
-        self.ethereum_connector
+        self.ethereum_scanner(htlc_params.ledger)?
             .htlc_redeemed(htlc_params, htlc_deployment, start_of_swap)
             .await
     }
@@ -227,8 +369,213 @@ impl HtlcRefunded<Ethereum, ((asset::Ether, asset::Erc20))> for Facade {
         htlc_deployment: &Deployed<crate::ethereum::Transaction, crate::ethereum::Address>,
         start_of_swap: NaiveDateTime,
     ) -> anyhow::Result<Refunded<crate::ethereum::Transaction>> {
-        self.ethereum_connector
+        self.ethereum_scanner(htlc_params.ledger)?
+            .htlc_refunded(htlc_params, htlc_deployment, start_of_swap)
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl HtlcConfirmed<Ethereum> for Facade {
+    async fn htlc_confirmed(
+        &self,
+        ledger: Ethereum,
+        transaction: &crate::ethereum::Transaction,
+        start_of_swap: NaiveDateTime,
+    ) -> anyhow::Result<Confirmation> {
+        let hash = transaction.hash;
+
+        self.ethereum_scanner(ledger)?
+            .confirmation(start_of_swap, self.ethereum_confirmations, move |block| {
+                block
+                    .transactions
+                    .iter()
+                    .any(|transaction| transaction.hash == hash)
+            })
+            .await
+    }
+}
+
+#[impl_template]
+impl HtlcPending<Ethereum, ((asset::Ether, asset::Erc20))> for Facade {
+    fn htlc_pending(
+        &self,
+        htlc_params: HtlcParams<Ethereum, __TYPE0__, crate::ethereum::Address>,
+        htlc_deployment: Option<&Deployed<crate::ethereum::Transaction, crate::ethereum::Address>>,
+    ) -> anyhow::Result<Watch<PendingChange<Pending<crate::ethereum::Transaction>>>> {
+        match self.ethereum_mempools.get(&htlc_params.ledger.chain_id) {
+            Some(mempool) => mempool.htlc_pending(htlc_params, htlc_deployment),
+            None => Ok(Watch::never()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl HtlcFunded<Lightning, asset::Bitcoin> for Facade {
+    async fn htlc_funded(
+        &self,
+        htlc_params: HtlcParams<Lightning, asset::Bitcoin, crate::lightning::PublicKey>,
+        htlc_deployment: &Deployed<crate::lightning::Transaction, rfc003::SecretHash>,
+        start_of_swap: NaiveDateTime,
+    ) -> anyhow::Result<Funded<crate::lightning::Transaction, asset::Bitcoin>> {
+        self.lnd_connector()?
+            .htlc_funded(htlc_params, htlc_deployment, start_of_swap)
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl HtlcDeployed<Lightning, asset::Bitcoin> for Facade {
+    async fn htlc_deployed(
+        &self,
+        htlc_params: HtlcParams<Lightning, asset::Bitcoin, crate::lightning::PublicKey>,
+        start_of_swap: NaiveDateTime,
+    ) -> anyhow::Result<Deployed<crate::lightning::Transaction, rfc003::SecretHash>> {
+        self.lnd_connector()?
+            .htlc_deployed(htlc_params, start_of_swap)
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl HtlcRedeemed<Lightning, asset::Bitcoin> for Facade {
+    async fn htlc_redeemed(
+        &self,
+        htlc_params: HtlcParams<Lightning, asset::Bitcoin, crate::lightning::PublicKey>,
+        htlc_deployment: &Deployed<crate::lightning::Transaction, rfc003::SecretHash>,
+        start_of_swap: NaiveDateTime,
+    ) -> anyhow::Result<Redeemed<crate::lightning::Transaction>> {
+        self.lnd_connector()?
+            .htlc_redeemed(htlc_params, htlc_deployment, start_of_swap)
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl HtlcRefunded<Lightning, asset::Bitcoin> for Facade {
+    async fn htlc_refunded(
+        &self,
+        htlc_params: HtlcParams<Lightning, asset::Bitcoin, crate::lightning::PublicKey>,
+        htlc_deployment: &Deployed<crate::lightning::Transaction, rfc003::SecretHash>,
+        start_of_swap: NaiveDateTime,
+    ) -> anyhow::Result<Refunded<crate::lightning::Transaction>> {
+        self.lnd_connector()?
             .htlc_refunded(htlc_params, htlc_deployment, start_of_swap)
             .await
     }
 }
+
+#[async_trait::async_trait]
+impl HtlcConfirmed<Lightning> for Facade {
+    /// Settled and canceled invoices cannot be reverted, there is nothing to
+    /// wait for. There are no blocks either, the time of the check stands in
+    /// for the block time.
+    async fn htlc_confirmed(
+        &self,
+        _ledger: Lightning,
+        _transaction: &crate::lightning::Transaction,
+        _start_of_swap: NaiveDateTime,
+    ) -> anyhow::Result<Confirmation> {
+        Ok(Confirmation::Confirmed {
+            block_time: Utc::now().naive_utc(),
+        })
+    }
+}
+
+impl HtlcPending<Lightning, asset::Bitcoin> for Facade {
+    /// Invoices don't have transactions that wait to be mined.
+    fn htlc_pending(
+        &self,
+        _htlc_params: HtlcParams<Lightning, asset::Bitcoin, crate::lightning::PublicKey>,
+        _htlc_deployment: Option<&Deployed<crate::lightning::Transaction, rfc003::SecretHash>>,
+    ) -> anyhow::Result<Watch<PendingChange<Pending<crate::lightning::Transaction>>>> {
+        Ok(Watch::never())
+    }
+}
+
+#[async_trait::async_trait]
+impl HtlcFunded<Litecoin, asset::Litecoin> for Facade {
+    async fn htlc_funded(
+        &self,
+        htlc_params: HtlcParams<Litecoin, asset::Litecoin, crate::bitcoin::PublicKey>,
+        htlc_deployment: &Deployed<::bitcoin::Transaction, ::bitcoin::OutPoint>,
+        start_of_swap: NaiveDateTime,
+    ) -> anyhow::Result<Funded<::bitcoin::Transaction, asset::Litecoin>> {
+        self.litecoin_scanner(htlc_params.ledger)?
+            .htlc_funded(htlc_params, htlc_deployment, start_of_swap)
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl HtlcDeployed<Litecoin, asset::Litecoin> for Facade {
+    async fn htlc_deployed(
+        &self,
+        htlc_params: HtlcParams<Litecoin, asset::Litecoin, crate::bitcoin::PublicKey>,
+        start_of_swap: NaiveDateTime,
+    ) -> anyhow::Result<Deployed<::bitcoin::Transaction, ::bitcoin::OutPoint>> {
+        self.litecoin_scanner(htlc_params.ledger)?
+            .htlc_deployed(htlc_params, start_of_swap)
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl HtlcRedeemed<Litecoin, asset::Litecoin> for Facade {
+    async fn htlc_redeemed(
+        &self,
+        htlc_params: HtlcParams<Litecoin, asset::Litecoin, crate::bitcoin::PublicKey>,
+        htlc_deployment: &Deployed<::bitcoin::Transaction, ::bitcoin::OutPoint>,
+        start_of_swap: NaiveDateTime,
+    ) -> anyhow::Result<Redeemed<::bitcoin::Transaction>> {
+        self.litecoin_scanner(htlc_params.ledger)?
+            .htlc_redeemed(htlc_params, htlc_deployment, start_of_swap)
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl HtlcRefunded<Litecoin, asset::Litecoin> for Facade {
+    async fn htlc_refunded(
+        &self,
+        htlc_params: HtlcParams<Litecoin, asset::Litecoin, crate::bitcoin::PublicKey>,
+        htlc_deployment: &Deployed<::bitcoin::Transaction, ::bitcoin::OutPoint>,
+        start_of_swap: NaiveDateTime,
+    ) -> anyhow::Result<Refunded<::bitcoin::Transaction>> {
+        self.litecoin_scanner(htlc_params.ledger)?
+            .htlc_refunded(htlc_params, htlc_deployment, start_of_swap)
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl HtlcConfirmed<Litecoin> for Facade {
+    async fn htlc_confirmed(
+        &self,
+        ledger: Litecoin,
+        transaction: &::bitcoin::Transaction,
+        start_of_swap: NaiveDateTime,
+    ) -> anyhow::Result<Confirmation> {
+        let txid = transaction.txid();
+
+        self.litecoin_scanner(ledger)?
+            .confirmation(start_of_swap, self.litecoin_confirmations, move |block| {
+                block
+                    .txdata
+                    .iter()
+                    .any(|transaction| transaction.txid() == txid)
+            })
+            .await
+    }
+}
+
+impl HtlcPending<Litecoin, asset::Litecoin> for Facade {
+    /// cnd does not watch the mempool of litecoind.
+    fn htlc_pending(
+        &self,
+        _htlc_params: HtlcParams<Litecoin, asset::Litecoin, crate::bitcoin::PublicKey>,
+        _htlc_deployment: Option<&Deployed<::bitcoin::Transaction, ::bitcoin::OutPoint>>,
+    ) -> anyhow::Result<Watch<PendingChange<Pending<::bitcoin::Transaction>>>> {
+        Ok(Watch::never())
+    }
+}
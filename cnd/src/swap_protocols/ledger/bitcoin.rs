@@ -9,16 +9,27 @@ pub struct Testnet;
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Regtest;
 
+/// A ledger that uses Bitcoin's script and transaction format.
 pub trait Bitcoin:
     Sized + std::fmt::Debug + std::hash::Hash + Eq + Sync + Copy + Send + Into<LedgerKind> + 'static
 {
+    /// The Bitcoin network whose parameters represent outputs on this ledger
+    /// as a `::bitcoin::Address`.
+    ///
+    /// Only the script of such an address is meaningful on ledgers other than
+    /// Bitcoin.
+    fn bitcoin_network(&self) -> ::bitcoin::Network;
 }
 
 pub trait Network {
     fn network() -> ::bitcoin::Network;
 }
 
-impl Bitcoin for Mainnet {}
+impl Bitcoin for Mainnet {
+    fn bitcoin_network(&self) -> ::bitcoin::Network {
+        Self::network()
+    }
+}
 
 impl From<Mainnet> for LedgerKind {
     fn from(_: Mainnet) -> Self {
@@ -30,7 +41,11 @@ impl Network for Mainnet {
         ::bitcoin::Network::Bitcoin
     }
 }
-impl Bitcoin for Testnet {}
+impl Bitcoin for Testnet {
+    fn bitcoin_network(&self) -> ::bitcoin::Network {
+        Self::network()
+    }
+}
 
 impl From<Testnet> for LedgerKind {
     fn from(_: Testnet) -> Self {
@@ -43,7 +58,11 @@ impl Network for Testnet {
     }
 }
 
-impl Bitcoin for Regtest {}
+impl Bitcoin for Regtest {
+    fn bitcoin_network(&self) -> ::bitcoin::Network {
+        Self::network()
+    }
+}
 impl From<Regtest> for LedgerKind {
     fn from(_: Regtest) -> Self {
         LedgerKind::BitcoinRegtest
@@ -0,0 +1,28 @@
+use crate::comit_api::LedgerKind;
+use serde::{Deserialize, Serialize};
+
+/// The Lightning Network on top of the given Bitcoin network.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+pub struct Lightning {
+    pub network: ::bitcoin::Network,
+}
+
+impl Lightning {
+    pub fn new(network: ::bitcoin::Network) -> Self {
+        Lightning { network }
+    }
+}
+
+impl Default for Lightning {
+    fn default() -> Self {
+        Lightning {
+            network: ::bitcoin::Network::Regtest,
+        }
+    }
+}
+
+impl From<Lightning> for LedgerKind {
+    fn from(lightning: Lightning) -> Self {
+        LedgerKind::Lightning(lightning)
+    }
+}
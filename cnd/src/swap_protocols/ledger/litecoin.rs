@@ -0,0 +1,116 @@
+use crate::{comit_api::LedgerKind, swap_protocols::ledger};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// The networks of the Litecoin blockchain.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    Regtest,
+}
+
+impl Network {
+    /// The Bitcoin network Litecoin's network of the same kind corresponds
+    /// to.
+    ///
+    /// Both chains share their script and transaction format, only addresses
+    /// are encoded with different prefixes.
+    pub fn bitcoin_network(self) -> ::bitcoin::Network {
+        match self {
+            Network::Mainnet => ::bitcoin::Network::Bitcoin,
+            Network::Testnet => ::bitcoin::Network::Testnet,
+            Network::Regtest => ::bitcoin::Network::Regtest,
+        }
+    }
+
+    /// The version byte of base58 encoded pay-to-pubkey-hash addresses.
+    pub fn p2pkh_prefix(self) -> u8 {
+        match self {
+            Network::Mainnet => 0x30,
+            Network::Testnet | Network::Regtest => 0x6f,
+        }
+    }
+
+    /// The version byte of base58 encoded pay-to-script-hash addresses.
+    pub fn p2sh_prefix(self) -> u8 {
+        match self {
+            Network::Mainnet => 0x32,
+            Network::Testnet | Network::Regtest => 0x3a,
+        }
+    }
+
+    /// The human-readable part of bech32 encoded segwit addresses.
+    pub fn bech32_hrp(self) -> &'static str {
+        match self {
+            Network::Mainnet => "ltc",
+            Network::Testnet => "tltc",
+            Network::Regtest => "rltc",
+        }
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let network = match self {
+            Network::Mainnet => "mainnet",
+            Network::Testnet => "testnet",
+            Network::Regtest => "regtest",
+        };
+
+        f.write_str(network)
+    }
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
+#[error("unknown litecoin network: {0}")]
+pub struct UnknownNetwork(String);
+
+impl FromStr for Network {
+    type Err = UnknownNetwork;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mainnet" => Ok(Network::Mainnet),
+            "testnet" => Ok(Network::Testnet),
+            "regtest" => Ok(Network::Regtest),
+            other => Err(UnknownNetwork(other.to_owned())),
+        }
+    }
+}
+
+/// The Litecoin blockchain on the given network.
+///
+/// Litecoin is a fork of Bitcoin, swaps on it use the Bitcoin HTLC and are
+/// watched through the Bitcoin connectors.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+pub struct Litecoin {
+    pub network: Network,
+}
+
+impl Litecoin {
+    pub fn new(network: Network) -> Self {
+        Litecoin { network }
+    }
+}
+
+impl Default for Litecoin {
+    fn default() -> Self {
+        Litecoin {
+            network: Network::Regtest,
+        }
+    }
+}
+
+impl ledger::Bitcoin for Litecoin {
+    fn bitcoin_network(&self) -> ::bitcoin::Network {
+        self.network.bitcoin_network()
+    }
+}
+
+impl From<Litecoin> for LedgerKind {
+    fn from(litecoin: Litecoin) -> Self {
+        LedgerKind::Litecoin(litecoin)
+    }
+}
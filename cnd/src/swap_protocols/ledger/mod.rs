@@ -1,4 +1,152 @@
 pub mod bitcoin;
 pub mod ethereum;
+pub mod lightning;
+pub mod litecoin;
 
-pub use self::{bitcoin::Bitcoin, ethereum::Ethereum};
+pub use self::{bitcoin::Bitcoin, ethereum::Ethereum, lightning::Lightning, litecoin::Litecoin};
+use crate::comit_api::LedgerKind;
+
+/// The ledgers cnd has a connector for.
+///
+/// Swaps on any other ledger cannot be watched and must not be accepted or
+/// requested.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConnectedLedgers {
+    pub bitcoin: ::bitcoin::Network,
+    /// The chains of the Ethereum nodes, the one of the built-in wallet first.
+    pub ethereum: Vec<ethereum::ChainId>,
+    /// The network of the lnd node, if cnd is connected to one.
+    pub lightning: Option<::bitcoin::Network>,
+    /// The network of the litecoind node, if cnd is connected to one.
+    pub litecoin: Option<litecoin::Network>,
+}
+
+impl ConnectedLedgers {
+    pub fn ensure_connected(&self, ledger: LedgerKind) -> Result<(), UnsupportedLedger> {
+        let connected = match ledger {
+            LedgerKind::BitcoinMainnet => self.bitcoin == ::bitcoin::Network::Bitcoin,
+            LedgerKind::BitcoinTestnet => self.bitcoin == ::bitcoin::Network::Testnet,
+            LedgerKind::BitcoinRegtest => self.bitcoin == ::bitcoin::Network::Regtest,
+            LedgerKind::Ethereum(ethereum) => self.ethereum.contains(&ethereum.chain_id),
+            LedgerKind::Lightning(lightning) => self.lightning == Some(lightning.network),
+            LedgerKind::Litecoin(litecoin) => self.litecoin == Some(litecoin.network),
+        };
+
+        if connected {
+            Ok(())
+        } else {
+            Err(UnsupportedLedger { ledger })
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+#[error("cnd is not connected to ledger {ledger:?}")]
+pub struct UnsupportedLedger {
+    pub ledger: LedgerKind,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spectral::prelude::*;
+
+    fn regtest_ledgers() -> ConnectedLedgers {
+        ConnectedLedgers {
+            bitcoin: ::bitcoin::Network::Regtest,
+            ethereum: vec![ethereum::ChainId::regtest()],
+            lightning: None,
+            litecoin: None,
+        }
+    }
+
+    #[test]
+    fn accepts_connected_ledgers() {
+        let ledgers = regtest_ledgers();
+
+        assert_that(&ledgers.ensure_connected(LedgerKind::BitcoinRegtest)).is_ok();
+        assert_that(&ledgers.ensure_connected(LedgerKind::Ethereum(Ethereum::default()))).is_ok();
+    }
+
+    #[test]
+    fn rejects_other_ledgers() {
+        let ledgers = regtest_ledgers();
+
+        assert_that(&ledgers.ensure_connected(LedgerKind::BitcoinMainnet)).is_err();
+        assert_that(
+            &ledgers.ensure_connected(LedgerKind::Ethereum(Ethereum::new(
+                ethereum::ChainId::mainnet(),
+            ))),
+        )
+        .is_err();
+    }
+
+    #[test]
+    fn accepts_all_connected_ethereum_chains() {
+        let ledgers = ConnectedLedgers {
+            ethereum: vec![ethereum::ChainId::regtest(), ethereum::ChainId::ropsten()],
+            ..regtest_ledgers()
+        };
+
+        assert_that(&ledgers.ensure_connected(LedgerKind::Ethereum(Ethereum::default()))).is_ok();
+        assert_that(
+            &ledgers.ensure_connected(LedgerKind::Ethereum(Ethereum::new(
+                ethereum::ChainId::ropsten(),
+            ))),
+        )
+        .is_ok();
+        assert_that(
+            &ledgers.ensure_connected(LedgerKind::Ethereum(Ethereum::new(
+                ethereum::ChainId::mainnet(),
+            ))),
+        )
+        .is_err();
+    }
+
+    #[test]
+    fn rejects_lightning_without_lnd() {
+        let ledgers = regtest_ledgers();
+
+        assert_that(&ledgers.ensure_connected(LedgerKind::Lightning(Lightning::default())))
+            .is_err();
+    }
+
+    #[test]
+    fn accepts_lightning_on_the_network_of_lnd() {
+        let ledgers = ConnectedLedgers {
+            lightning: Some(::bitcoin::Network::Regtest),
+            ..regtest_ledgers()
+        };
+
+        assert_that(&ledgers.ensure_connected(LedgerKind::Lightning(Lightning::default()))).is_ok();
+        assert_that(
+            &ledgers.ensure_connected(LedgerKind::Lightning(Lightning::new(
+                ::bitcoin::Network::Bitcoin,
+            ))),
+        )
+        .is_err();
+    }
+
+    #[test]
+    fn rejects_litecoin_without_litecoind() {
+        let ledgers = regtest_ledgers();
+
+        assert_that(&ledgers.ensure_connected(LedgerKind::Litecoin(Litecoin::default()))).is_err();
+    }
+
+    #[test]
+    fn accepts_litecoin_on_the_network_of_litecoind() {
+        let ledgers = ConnectedLedgers {
+            litecoin: Some(litecoin::Network::Regtest),
+            ..regtest_ledgers()
+        };
+
+        assert_that(&ledgers.ensure_connected(LedgerKind::Litecoin(Litecoin::default()))).is_ok();
+        assert_that(
+            &ledgers.ensure_connected(LedgerKind::Litecoin(Litecoin::new(
+                litecoin::Network::Mainnet,
+            ))),
+        )
+        .is_err();
+    }
+}
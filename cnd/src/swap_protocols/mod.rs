@@ -1,6 +1,7 @@
 pub mod actions;
 mod facade;
 pub mod ledger;
+pub mod registry;
 pub mod rfc003;
 mod swap_id;
 
@@ -0,0 +1,484 @@
+//! The combinations of ledgers and assets cnd can swap.
+//!
+//! Ledgers and assets take part in dispatching by implementing
+//! `RegisteredLedger` and `RegisteredAsset`. A combination of them is supported
+//! once it is listed in `swap_registry!`; the network layer, the HTTP API and
+//! the database go from the runtime description of a swap to its types through
+//! that list only.
+
+use crate::{
+    asset::{self, Asset, AssetKind},
+    comit_api::LedgerKind,
+    db,
+    swap_protocols::{
+        ledger::{bitcoin, Ethereum, Lightning, Litecoin},
+        rfc003::Ledger,
+    },
+};
+
+/// Lists every supported combination to `$consumer` as
+/// `{ AlphaLedger, BetaLedger, AlphaAsset, BetaAsset, AcceptBody }`.
+///
+/// `AcceptBody` is what the HTTP API takes to accept a swap of the
+/// combination. Adding a line here is all it takes to dispatch to a new
+/// combination, as long as its ledgers, assets and messages implement the
+/// traits the consumers require.
+#[macro_export]
+macro_rules! swap_registry {
+    ($consumer:ident!($($args:tt)*)) => {
+        $crate::$consumer!(($($args)*) [
+            {
+                $crate::swap_protocols::ledger::bitcoin::Mainnet,
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::asset::Bitcoin,
+                $crate::asset::Ether,
+                $crate::http_api::routes::rfc003::accept::OnlyRefund<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::bitcoin::Testnet,
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::asset::Bitcoin,
+                $crate::asset::Ether,
+                $crate::http_api::routes::rfc003::accept::OnlyRefund<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::bitcoin::Regtest,
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::asset::Bitcoin,
+                $crate::asset::Ether,
+                $crate::http_api::routes::rfc003::accept::OnlyRefund<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::bitcoin::Mainnet,
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::asset::Bitcoin,
+                $crate::asset::Erc20,
+                $crate::http_api::routes::rfc003::accept::OnlyRefund<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::bitcoin::Testnet,
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::asset::Bitcoin,
+                $crate::asset::Erc20,
+                $crate::http_api::routes::rfc003::accept::OnlyRefund<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::bitcoin::Regtest,
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::asset::Bitcoin,
+                $crate::asset::Erc20,
+                $crate::http_api::routes::rfc003::accept::OnlyRefund<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::swap_protocols::ledger::bitcoin::Mainnet,
+                $crate::asset::Ether,
+                $crate::asset::Bitcoin,
+                $crate::http_api::routes::rfc003::accept::OnlyRedeem<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::swap_protocols::ledger::bitcoin::Testnet,
+                $crate::asset::Ether,
+                $crate::asset::Bitcoin,
+                $crate::http_api::routes::rfc003::accept::OnlyRedeem<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::swap_protocols::ledger::bitcoin::Regtest,
+                $crate::asset::Ether,
+                $crate::asset::Bitcoin,
+                $crate::http_api::routes::rfc003::accept::OnlyRedeem<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::swap_protocols::ledger::bitcoin::Mainnet,
+                $crate::asset::Erc20,
+                $crate::asset::Bitcoin,
+                $crate::http_api::routes::rfc003::accept::OnlyRedeem<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::swap_protocols::ledger::bitcoin::Testnet,
+                $crate::asset::Erc20,
+                $crate::asset::Bitcoin,
+                $crate::http_api::routes::rfc003::accept::OnlyRedeem<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::swap_protocols::ledger::bitcoin::Regtest,
+                $crate::asset::Erc20,
+                $crate::asset::Bitcoin,
+                $crate::http_api::routes::rfc003::accept::OnlyRedeem<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::Lightning,
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::asset::Bitcoin,
+                $crate::asset::Ether,
+                $crate::http_api::routes::rfc003::accept::OnlyRefund<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::Lightning,
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::asset::Bitcoin,
+                $crate::asset::Erc20,
+                $crate::http_api::routes::rfc003::accept::OnlyRefund<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::swap_protocols::ledger::Lightning,
+                $crate::asset::Ether,
+                $crate::asset::Bitcoin,
+                $crate::http_api::routes::rfc003::accept::OnlyRedeem<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::swap_protocols::ledger::Lightning,
+                $crate::asset::Erc20,
+                $crate::asset::Bitcoin,
+                $crate::http_api::routes::rfc003::accept::OnlyRedeem<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::asset::Ether,
+                $crate::asset::Erc20,
+                $crate::http_api::routes::rfc003::accept::RedeemAndRefund<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::asset::Erc20,
+                $crate::asset::Ether,
+                $crate::http_api::routes::rfc003::accept::RedeemAndRefund<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::asset::Erc20,
+                $crate::asset::Erc20,
+                $crate::http_api::routes::rfc003::accept::RedeemAndRefund<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::Litecoin,
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::asset::Litecoin,
+                $crate::asset::Ether,
+                $crate::http_api::routes::rfc003::accept::OnlyRefund<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::Litecoin,
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::asset::Litecoin,
+                $crate::asset::Erc20,
+                $crate::http_api::routes::rfc003::accept::OnlyRefund<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::swap_protocols::ledger::Litecoin,
+                $crate::asset::Ether,
+                $crate::asset::Litecoin,
+                $crate::http_api::routes::rfc003::accept::OnlyRedeem<$crate::ethereum::Address>
+            }
+            {
+                $crate::swap_protocols::ledger::Ethereum,
+                $crate::swap_protocols::ledger::Litecoin,
+                $crate::asset::Erc20,
+                $crate::asset::Litecoin,
+                $crate::http_api::routes::rfc003::accept::OnlyRedeem<$crate::ethereum::Address>
+            }
+        ])
+    };
+}
+
+/// Evaluates `$fn` with the values of a swap described by `$kinds` if its
+/// combination is registered, `$unsupported` otherwise.
+///
+/// `$kinds` is a tuple of the `comit_api::LedgerKind`s and `asset::AssetKind`s
+/// of the alpha and beta side. In `$fn`, the values are bound to the given
+/// identifiers and their types are available as `AL`, `BL`, `AA` and `BA`.
+#[macro_export]
+macro_rules! with_swap {
+    (
+        ($alpha_ledger:ident, $beta_ledger:ident, $alpha_asset:ident, $beta_asset:ident) =
+        $kinds:expr,
+        $fn:expr,
+        $unsupported:expr
+    ) => {{
+        let kinds: (
+            $crate::comit_api::LedgerKind,
+            $crate::comit_api::LedgerKind,
+            $crate::asset::AssetKind,
+            $crate::asset::AssetKind,
+        ) = $kinds;
+
+        $crate::swap_registry!(_with_swap!(
+            kinds,
+            ($alpha_ledger, $beta_ledger, $alpha_asset, $beta_asset),
+            $fn,
+            $unsupported
+        ))
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! _with_swap {
+    (($kinds:ident, ($alpha_ledger:ident, $beta_ledger:ident, $alpha_asset:ident, $beta_asset:ident), $fn:expr, $unsupported:expr) [$({ $al:ty, $bl:ty, $aa:ty, $ba:ty, $accept:ty })*]) => {{
+        let (alpha_ledger, beta_ledger, alpha_asset, beta_asset) = $kinds;
+
+        if $crate::swap_protocols::registry::swaps_asset_for_itself(
+            &alpha_ledger,
+            &beta_ledger,
+            &alpha_asset,
+            &beta_asset,
+        ) {
+            $unsupported
+        } else $(
+            if let (Some($alpha_ledger), Some($beta_ledger), Some($alpha_asset), Some($beta_asset)) = (
+                <$al as $crate::swap_protocols::registry::RegisteredLedger>::from_ledger_kind(alpha_ledger),
+                <$bl as $crate::swap_protocols::registry::RegisteredLedger>::from_ledger_kind(beta_ledger),
+                <$aa as $crate::swap_protocols::registry::RegisteredAsset>::from_asset_kind(&alpha_asset),
+                <$ba as $crate::swap_protocols::registry::RegisteredAsset>::from_asset_kind(&beta_asset),
+            ) {
+                #[allow(dead_code)]
+                type AL = $al;
+                #[allow(dead_code)]
+                type BL = $bl;
+                #[allow(dead_code)]
+                type AA = $aa;
+                #[allow(dead_code)]
+                type BA = $ba;
+
+                $fn
+            } else
+        )* {
+            $unsupported
+        }
+    }};
+}
+
+/// A ledger swaps can be dispatched to at runtime.
+pub trait RegisteredLedger: Ledger {
+    /// How the database refers to this ledger.
+    const KIND: db::LedgerKind;
+
+    /// `ledger` as this ledger, if it is one.
+    fn from_ledger_kind(ledger: LedgerKind) -> Option<Self>;
+}
+
+/// An asset swaps can be dispatched to at runtime.
+pub trait RegisteredAsset: Asset {
+    /// How the database refers to this asset.
+    const KIND: db::AssetKind;
+
+    /// `asset` as this asset, if it is one.
+    fn from_asset_kind(asset: &AssetKind) -> Option<Self>;
+}
+
+impl RegisteredLedger for bitcoin::Mainnet {
+    const KIND: db::LedgerKind = db::LedgerKind::Bitcoin(db::BitcoinLedgerKind::Mainnet);
+
+    fn from_ledger_kind(ledger: LedgerKind) -> Option<Self> {
+        match ledger {
+            LedgerKind::BitcoinMainnet => Some(bitcoin::Mainnet),
+            _ => None,
+        }
+    }
+}
+
+impl RegisteredLedger for bitcoin::Testnet {
+    const KIND: db::LedgerKind = db::LedgerKind::Bitcoin(db::BitcoinLedgerKind::Testnet);
+
+    fn from_ledger_kind(ledger: LedgerKind) -> Option<Self> {
+        match ledger {
+            LedgerKind::BitcoinTestnet => Some(bitcoin::Testnet),
+            _ => None,
+        }
+    }
+}
+
+impl RegisteredLedger for bitcoin::Regtest {
+    const KIND: db::LedgerKind = db::LedgerKind::Bitcoin(db::BitcoinLedgerKind::Regtest);
+
+    fn from_ledger_kind(ledger: LedgerKind) -> Option<Self> {
+        match ledger {
+            LedgerKind::BitcoinRegtest => Some(bitcoin::Regtest),
+            _ => None,
+        }
+    }
+}
+
+impl RegisteredLedger for Ethereum {
+    const KIND: db::LedgerKind = db::LedgerKind::Ethereum;
+
+    fn from_ledger_kind(ledger: LedgerKind) -> Option<Self> {
+        match ledger {
+            LedgerKind::Ethereum(ethereum) => Some(ethereum),
+            _ => None,
+        }
+    }
+}
+
+impl RegisteredLedger for Lightning {
+    const KIND: db::LedgerKind = db::LedgerKind::Lightning;
+
+    fn from_ledger_kind(ledger: LedgerKind) -> Option<Self> {
+        match ledger {
+            LedgerKind::Lightning(lightning) => Some(lightning),
+            _ => None,
+        }
+    }
+}
+
+impl RegisteredLedger for Litecoin {
+    const KIND: db::LedgerKind = db::LedgerKind::Litecoin;
+
+    fn from_ledger_kind(ledger: LedgerKind) -> Option<Self> {
+        match ledger {
+            LedgerKind::Litecoin(litecoin) => Some(litecoin),
+            _ => None,
+        }
+    }
+}
+
+impl RegisteredAsset for asset::Bitcoin {
+    const KIND: db::AssetKind = db::AssetKind::Bitcoin;
+
+    fn from_asset_kind(asset: &AssetKind) -> Option<Self> {
+        match asset {
+            AssetKind::Bitcoin(bitcoin) => Some(*bitcoin),
+            _ => None,
+        }
+    }
+}
+
+impl RegisteredAsset for asset::Ether {
+    const KIND: db::AssetKind = db::AssetKind::Ether;
+
+    fn from_asset_kind(asset: &AssetKind) -> Option<Self> {
+        match asset {
+            AssetKind::Ether(ether) => Some(ether.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl RegisteredAsset for asset::Erc20 {
+    const KIND: db::AssetKind = db::AssetKind::Erc20;
+
+    fn from_asset_kind(asset: &AssetKind) -> Option<Self> {
+        match asset {
+            AssetKind::Erc20(erc20) => Some(erc20.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl RegisteredAsset for asset::Litecoin {
+    const KIND: db::AssetKind = db::AssetKind::Litecoin;
+
+    fn from_asset_kind(asset: &AssetKind) -> Option<Self> {
+        match asset {
+            AssetKind::Litecoin(litecoin) => Some(*litecoin),
+            _ => None,
+        }
+    }
+}
+
+/// Whether a swap gives an asset for itself on the same ledger, which is
+/// pointless whether or not its combination is registered.
+pub fn swaps_asset_for_itself(
+    alpha_ledger: &LedgerKind,
+    beta_ledger: &LedgerKind,
+    alpha_asset: &AssetKind,
+    beta_asset: &AssetKind,
+) -> bool {
+    if alpha_ledger != beta_ledger {
+        return false;
+    }
+
+    match (alpha_asset, beta_asset) {
+        (AssetKind::Bitcoin(_), AssetKind::Bitcoin(_))
+        | (AssetKind::Ether(_), AssetKind::Ether(_))
+        | (AssetKind::Litecoin(_), AssetKind::Litecoin(_)) => true,
+        (AssetKind::Erc20(alpha), AssetKind::Erc20(beta)) => {
+            alpha.token_contract == beta.token_contract
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        asset::Erc20Quantity,
+        swap_protocols::ledger::{ethereum::ChainId, litecoin},
+    };
+    use spectral::prelude::*;
+
+    fn erc20(token_contract: u8) -> AssetKind {
+        AssetKind::Erc20(asset::Erc20::new(
+            crate::ethereum::Address::repeat_byte(token_contract),
+            Erc20Quantity::zero(),
+        ))
+    }
+
+    fn dispatch(kinds: (LedgerKind, LedgerKind, AssetKind, AssetKind)) -> Option<db::SwapTypes> {
+        with_swap!(
+            (_alpha_ledger, _beta_ledger, _alpha_asset, _beta_asset) = kinds,
+            Some(db::SwapTypes {
+                alpha_ledger: <AL as RegisteredLedger>::KIND,
+                beta_ledger: <BL as RegisteredLedger>::KIND,
+                alpha_asset: <AA as RegisteredAsset>::KIND,
+                beta_asset: <BA as RegisteredAsset>::KIND,
+                role: crate::swap_protocols::Role::Alice,
+            }),
+            None
+        )
+    }
+
+    #[test]
+    fn dispatches_to_the_types_of_a_registered_combination() {
+        let swap_types = dispatch((
+            LedgerKind::Litecoin(Litecoin::new(litecoin::Network::Regtest)),
+            LedgerKind::Ethereum(Ethereum::default()),
+            AssetKind::Litecoin(asset::Litecoin::from_litoshi(100_000)),
+            erc20(1),
+        ));
+
+        assert_that(&swap_types)
+            .is_some()
+            .is_equal_to(db::SwapTypes {
+                alpha_ledger: db::LedgerKind::Litecoin,
+                beta_ledger: db::LedgerKind::Ethereum,
+                alpha_asset: db::AssetKind::Litecoin,
+                beta_asset: db::AssetKind::Erc20,
+                role: crate::swap_protocols::Role::Alice,
+            });
+    }
+
+    #[test]
+    fn does_not_dispatch_unregistered_combinations() {
+        let swap_types = dispatch((
+            LedgerKind::BitcoinRegtest,
+            LedgerKind::Lightning(Lightning::default()),
+            AssetKind::Bitcoin(asset::Bitcoin::from_sat(100_000)),
+            AssetKind::Bitcoin(asset::Bitcoin::from_sat(100_000)),
+        ));
+
+        assert_that(&swap_types).is_none();
+    }
+
+    #[test]
+    fn does_not_dispatch_a_token_for_itself_on_the_same_chain() {
+        let ethereum = LedgerKind::Ethereum(Ethereum::default());
+        let ropsten = LedgerKind::Ethereum(Ethereum::new(ChainId::ropsten()));
+
+        assert_that(&dispatch((ethereum, ethereum, erc20(1), erc20(1)))).is_none();
+        assert_that(&dispatch((ethereum, ethereum, erc20(1), erc20(2)))).is_some();
+        assert_that(&dispatch((ethereum, ropsten, erc20(1), erc20(1)))).is_some();
+    }
+}
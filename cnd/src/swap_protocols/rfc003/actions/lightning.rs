@@ -0,0 +1,42 @@
+use crate::{
+    asset,
+    lightning::PublicKey,
+    swap_protocols::{
+        actions::lnd::{AddHoldInvoice, SendPayment, SettleInvoice},
+        ledger::Lightning,
+        rfc003::{create_swap::HtlcParams, Secret},
+    },
+};
+
+pub fn add_hold_invoice_action(
+    htlc_params: HtlcParams<Lightning, asset::Bitcoin, PublicKey>,
+) -> AddHoldInvoice {
+    AddHoldInvoice {
+        amount: htlc_params.asset,
+        secret_hash: htlc_params.secret_hash,
+        expiry: htlc_params.expiry,
+        network: htlc_params.ledger.network,
+    }
+}
+
+pub fn send_payment_action(
+    htlc_params: HtlcParams<Lightning, asset::Bitcoin, PublicKey>,
+) -> SendPayment {
+    SendPayment {
+        to_public_key: htlc_params.redeem_identity,
+        amount: htlc_params.asset,
+        secret_hash: htlc_params.secret_hash,
+        expiry: htlc_params.expiry,
+        network: htlc_params.ledger.network,
+    }
+}
+
+pub fn settle_invoice_action(
+    htlc_params: HtlcParams<Lightning, asset::Bitcoin, PublicKey>,
+    secret: Secret,
+) -> SettleInvoice {
+    SettleInvoice {
+        secret,
+        network: htlc_params.ledger.network,
+    }
+}
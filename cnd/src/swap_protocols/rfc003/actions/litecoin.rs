@@ -0,0 +1,81 @@
+use crate::{
+    asset, litecoin,
+    swap_protocols::{
+        actions::litecoin::{SendToAddress, SpendOutput},
+        ledger::Litecoin,
+        rfc003::{
+            actions::{FundAction, RedeemAction, RefundAction},
+            create_swap::HtlcParams,
+            DeriveIdentities, Secret,
+        },
+    },
+};
+use ::bitcoin::{Amount, OutPoint, Transaction};
+use blockchain_contracts::bitcoin::{rfc003::bitcoin_htlc::BitcoinHtlc, witness::PrimedInput};
+
+impl FundAction<Litecoin, asset::Litecoin> for (Litecoin, asset::Litecoin) {
+    type FundActionOutput = SendToAddress;
+
+    fn fund_action(
+        htlc_params: HtlcParams<Litecoin, asset::Litecoin, crate::bitcoin::PublicKey>,
+    ) -> Self::FundActionOutput {
+        let network = htlc_params.ledger.network;
+        let to =
+            litecoin::Address::from_script(htlc_params.compute_address().script_pubkey(), network)
+                .expect("the HTLC is a pay-to-witness-script-hash output");
+
+        SendToAddress {
+            to,
+            amount: htlc_params.asset,
+            network,
+        }
+    }
+}
+
+impl RefundAction<Litecoin, asset::Litecoin> for (Litecoin, asset::Litecoin) {
+    type RefundActionOutput = SpendOutput;
+
+    fn refund_action(
+        htlc_params: HtlcParams<Litecoin, asset::Litecoin, crate::bitcoin::PublicKey>,
+        htlc_location: OutPoint,
+        secret_source: &dyn DeriveIdentities,
+        fund_transaction: &Transaction,
+    ) -> Self::RefundActionOutput {
+        let htlc = BitcoinHtlc::from(htlc_params);
+
+        SpendOutput {
+            output: PrimedInput::new(
+                htlc_location,
+                Amount::from_sat(fund_transaction.output[htlc_location.vout as usize].value),
+                htlc.unlock_after_timeout(&*crate::SECP, secret_source.derive_refund_identity()),
+            ),
+            network: htlc_params.ledger.network,
+        }
+    }
+}
+
+impl RedeemAction<Litecoin, asset::Litecoin> for (Litecoin, asset::Litecoin) {
+    type RedeemActionOutput = SpendOutput;
+
+    fn redeem_action(
+        htlc_params: HtlcParams<Litecoin, asset::Litecoin, crate::bitcoin::PublicKey>,
+        htlc_location: OutPoint,
+        secret_source: &dyn DeriveIdentities,
+        secret: Secret,
+    ) -> Self::RedeemActionOutput {
+        let htlc = BitcoinHtlc::from(htlc_params);
+
+        SpendOutput {
+            output: PrimedInput::new(
+                htlc_location,
+                htlc_params.asset.into(),
+                htlc.unlock_with_secret(
+                    &*crate::SECP,
+                    secret_source.derive_redeem_identity(),
+                    secret.into_raw_secret(),
+                ),
+            ),
+            network: htlc_params.ledger.network,
+        }
+    }
+}
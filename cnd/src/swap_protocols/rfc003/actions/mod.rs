@@ -1,6 +1,8 @@
 pub mod bitcoin;
 pub mod erc20;
 pub mod ether;
+pub mod lightning;
+pub mod litecoin;
 
 use crate::{
     asset::Asset,
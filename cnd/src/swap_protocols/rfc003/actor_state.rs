@@ -1,6 +1,6 @@
 use crate::{
     asset::Asset,
-    swap_protocols::rfc003::{ledger_state::LedgerState, Ledger},
+    swap_protocols::rfc003::{events::Pending, ledger_state::LedgerState, Ledger, SwapOutcome},
 };
 use std::fmt::Debug;
 
@@ -13,6 +13,21 @@ pub trait ActorState: Debug + Clone + Send + Sync + 'static {
     fn expected_alpha_asset(&self) -> Self::AA;
     fn expected_beta_asset(&self) -> Self::BA;
 
+    fn alpha_ledger(
+        &self,
+    ) -> &LedgerState<
+        <Self::AL as Ledger>::HtlcLocation,
+        <Self::AL as Ledger>::Transaction,
+        Self::AA,
+    >;
+    fn beta_ledger(
+        &self,
+    ) -> &LedgerState<
+        <Self::BL as Ledger>::HtlcLocation,
+        <Self::BL as Ledger>::Transaction,
+        Self::BA,
+    >;
+
     fn alpha_ledger_mut(
         &mut self,
     ) -> &mut LedgerState<
@@ -28,6 +43,16 @@ pub trait ActorState: Debug + Clone + Send + Sync + 'static {
         Self::BA,
     >;
 
+    /// The transaction of the alpha HTLC that was broadcast but is not mined
+    /// yet.
+    fn alpha_pending(&self) -> Option<&Pending<<Self::AL as Ledger>::Transaction>>;
+    /// The transaction of the beta HTLC that was broadcast but is not mined
+    /// yet.
+    fn beta_pending(&self) -> Option<&Pending<<Self::BL as Ledger>::Transaction>>;
+
+    fn set_alpha_pending(&mut self, pending: Option<Pending<<Self::AL as Ledger>::Transaction>>);
+    fn set_beta_pending(&mut self, pending: Option<Pending<<Self::BL as Ledger>::Transaction>>);
+
     /// Returns true if the current swap failed at some stage.
     fn swap_failed(&self) -> bool;
 
@@ -35,4 +60,10 @@ pub trait ActorState: Debug + Clone + Send + Sync + 'static {
     /// specifically do not support setting this to `false` because currently a
     /// failed swap cannot be restarted.
     fn set_swap_failed(&mut self);
+
+    /// Returns the outcome of the swap once no more ledger events are
+    /// expected.
+    fn swap_outcome(&self) -> Option<SwapOutcome>;
+
+    fn set_swap_outcome(&mut self, outcome: SwapOutcome);
 }
@@ -0,0 +1,236 @@
+//! Alice only ever pays or receives through her own lnd node, hence none of
+//! the actions below are generic over the other ledger.
+//!
+//! As the receiver of a Lightning payment Alice has to add the hold invoice
+//! before she funds the alpha HTLC, otherwise Bob's payment would fail. As the
+//! payer she cannot see Bob's invoice, if it is not there yet her payment
+//! fails and the swap ends without anyone losing funds.
+
+use crate::{
+    asset,
+    swap_protocols::{
+        actions::{
+            ethereum,
+            lnd::{AddHoldInvoice, SendPayment, SettleInvoice},
+            Actions, DeployContractOrAddHoldInvoice,
+        },
+        ledger::{Ethereum, Lightning},
+        rfc003::{
+            actions::{
+                erc20, lightning, Accept, Action, Decline, FundAction, RedeemAction, RefundAction,
+            },
+            alice,
+            create_swap::HtlcParams,
+            DeriveSecret, LedgerState, SwapCommunication,
+        },
+    },
+};
+use std::convert::Infallible;
+
+impl Actions for alice::State<Lightning, Ethereum, asset::Bitcoin, asset::Ether> {
+    #[allow(clippy::type_complexity)]
+    type ActionKind = Action<
+        Accept<Lightning, Ethereum>,
+        Decline<Lightning, Ethereum>,
+        Infallible,
+        SendPayment,
+        ethereum::CallContract,
+        Infallible,
+    >;
+
+    fn actions(&self) -> Vec<Self::ActionKind> {
+        let (request, response) = match self.swap_communication {
+            SwapCommunication::Accepted {
+                ref request,
+                ref response,
+            } => (request, response),
+            _ => return vec![],
+        };
+        let alpha_state = &self.alpha_ledger_state;
+        let beta_state = &self.beta_ledger_state;
+
+        use self::LedgerState::*;
+
+        let mut actions = match alpha_state {
+            NotDeployed => vec![Action::Fund(lightning::send_payment_action(
+                HtlcParams::new_alpha_params(request, response),
+            ))],
+            _ => vec![],
+        };
+
+        if let Funded { htlc_location, .. } = beta_state {
+            actions.push(Action::Redeem(<(Ethereum, asset::Ether)>::redeem_action(
+                HtlcParams::new_beta_params(request, response),
+                *htlc_location,
+                &self.secret_source, // Derive identities with this.
+                self.secret_source.derive_secret(), // The secret used by Alice.
+            )));
+        }
+        actions
+    }
+}
+
+impl Actions for alice::State<Lightning, Ethereum, asset::Bitcoin, asset::Erc20> {
+    #[allow(clippy::type_complexity)]
+    type ActionKind = Action<
+        Accept<Lightning, Ethereum>,
+        Decline<Lightning, Ethereum>,
+        Infallible,
+        SendPayment,
+        ethereum::CallContract,
+        Infallible,
+    >;
+
+    fn actions(&self) -> Vec<Self::ActionKind> {
+        let (request, response) = match self.swap_communication {
+            SwapCommunication::Accepted {
+                ref request,
+                ref response,
+            } => (request, response),
+            _ => return vec![],
+        };
+        let alpha_state = &self.alpha_ledger_state;
+        let beta_state = &self.beta_ledger_state;
+
+        use self::LedgerState::*;
+
+        let mut actions = match alpha_state {
+            NotDeployed => vec![Action::Fund(lightning::send_payment_action(
+                HtlcParams::new_alpha_params(request, response),
+            ))],
+            _ => vec![],
+        };
+
+        if let Funded { htlc_location, .. } = beta_state {
+            actions.push(Action::Redeem(erc20::redeem_action(
+                *htlc_location,
+                self.secret_source.derive_secret(),
+                request.beta_ledger.chain_id,
+            )));
+        }
+        actions
+    }
+}
+
+impl Actions for alice::State<Ethereum, Lightning, asset::Ether, asset::Bitcoin> {
+    #[allow(clippy::type_complexity)]
+    type ActionKind = Action<
+        Accept<Ethereum, Lightning>,
+        Decline<Ethereum, Lightning>,
+        AddHoldInvoice,
+        ethereum::DeployContract,
+        SettleInvoice,
+        ethereum::CallContract,
+    >;
+
+    fn actions(&self) -> Vec<Self::ActionKind> {
+        let (request, response) = match self.swap_communication {
+            SwapCommunication::Accepted {
+                ref request,
+                ref response,
+            } => (request, response),
+            _ => return vec![],
+        };
+        let alpha_state = &self.alpha_ledger_state;
+        let beta_state = &self.beta_ledger_state;
+
+        use self::LedgerState::*;
+
+        let mut actions = match (alpha_state, beta_state) {
+            (NotDeployed, NotDeployed) => vec![Action::Deploy(lightning::add_hold_invoice_action(
+                HtlcParams::new_beta_params(request, response),
+            ))],
+            (NotDeployed, _) => vec![Action::Fund(<(Ethereum, asset::Ether)>::fund_action(
+                HtlcParams::new_alpha_params(request, response),
+            ))],
+            (
+                Funded {
+                    htlc_location,
+                    fund_transaction,
+                    ..
+                },
+                _,
+            )
+            | (
+                IncorrectlyFunded {
+                    htlc_location,
+                    fund_transaction,
+                    ..
+                },
+                _,
+            ) => vec![Action::Refund(<(Ethereum, asset::Ether)>::refund_action(
+                HtlcParams::new_alpha_params(request, response),
+                *htlc_location,
+                &self.secret_source,
+                fund_transaction,
+            ))],
+            _ => vec![],
+        };
+
+        if let Funded { .. } = beta_state {
+            actions.push(Action::Redeem(lightning::settle_invoice_action(
+                HtlcParams::new_beta_params(request, response),
+                self.secret_source.derive_secret(),
+            )));
+        }
+        actions
+    }
+}
+
+impl Actions for alice::State<Ethereum, Lightning, asset::Erc20, asset::Bitcoin> {
+    #[allow(clippy::type_complexity)]
+    type ActionKind = Action<
+        Accept<Ethereum, Lightning>,
+        Decline<Ethereum, Lightning>,
+        DeployContractOrAddHoldInvoice,
+        ethereum::CallContract,
+        SettleInvoice,
+        ethereum::CallContract,
+    >;
+
+    fn actions(&self) -> Vec<Self::ActionKind> {
+        let (request, response) = match self.swap_communication {
+            SwapCommunication::Accepted {
+                ref request,
+                ref response,
+            } => (request, response),
+            _ => return vec![],
+        };
+        let alpha_state = &self.alpha_ledger_state;
+        let beta_state = &self.beta_ledger_state;
+
+        use self::LedgerState::*;
+
+        let mut actions = match (alpha_state, beta_state) {
+            (NotDeployed, NotDeployed) => vec![Action::Deploy(
+                DeployContractOrAddHoldInvoice::AddHoldInvoice(lightning::add_hold_invoice_action(
+                    HtlcParams::new_beta_params(request, response),
+                )),
+            )],
+            (NotDeployed, _) => vec![Action::Deploy(
+                DeployContractOrAddHoldInvoice::DeployContract(erc20::deploy_action(
+                    HtlcParams::new_alpha_params(request, response),
+                )),
+            )],
+            (Deployed { htlc_location, .. }, _) => vec![Action::Fund(erc20::fund_action(
+                HtlcParams::new_alpha_params(request, response),
+                request.alpha_asset.token_contract,
+                *htlc_location,
+            ))],
+            (Funded { htlc_location, .. }, _) => vec![Action::Refund(erc20::refund_action(
+                request.alpha_ledger.chain_id,
+                request.alpha_expiry,
+                *htlc_location,
+            ))],
+            _ => vec![],
+        };
+
+        if let Funded { .. } = beta_state {
+            actions.push(Action::Redeem(lightning::settle_invoice_action(
+                HtlcParams::new_beta_params(request, response),
+                self.secret_source.derive_secret(),
+            )));
+        }
+        actions
+    }
+}
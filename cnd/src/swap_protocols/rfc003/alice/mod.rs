@@ -6,7 +6,8 @@ use crate::{
     asset::Asset,
     seed::SwapSeed,
     swap_protocols::rfc003::{
-        ledger::Ledger, ledger_state::LedgerState, messages, ActorState, SwapCommunication,
+        events::Pending, ledger::Ledger, ledger_state::LedgerState, messages, ActorState,
+        SwapCommunication, SwapOutcome,
     },
 };
 use derivative::Derivative;
@@ -17,9 +18,12 @@ pub struct State<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> {
     pub swap_communication: SwapCommunication<AL, BL, AA, BA>,
     pub alpha_ledger_state: LedgerState<AL::HtlcLocation, AL::Transaction, AA>,
     pub beta_ledger_state: LedgerState<BL::HtlcLocation, BL::Transaction, BA>,
+    pub alpha_pending: Option<Pending<AL::Transaction>>,
+    pub beta_pending: Option<Pending<BL::Transaction>>,
     #[derivative(Debug = "ignore", PartialEq = "ignore")]
     pub secret_source: SwapSeed, // Used to derive identities and also to generate the secret.
     pub failed: bool,
+    pub outcome: Option<SwapOutcome>,
 }
 
 impl<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> State<AL, BL, AA, BA> {
@@ -28,8 +32,11 @@ impl<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> State<AL, BL, AA, BA> {
             swap_communication: SwapCommunication::Proposed { request },
             alpha_ledger_state: LedgerState::NotDeployed,
             beta_ledger_state: LedgerState::NotDeployed,
+            alpha_pending: None,
+            beta_pending: None,
             secret_source,
             failed: false,
+            outcome: None,
         }
     }
 
@@ -42,8 +49,11 @@ impl<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> State<AL, BL, AA, BA> {
             swap_communication: SwapCommunication::Accepted { request, response },
             alpha_ledger_state: LedgerState::NotDeployed,
             beta_ledger_state: LedgerState::NotDeployed,
+            alpha_pending: None,
+            beta_pending: None,
             secret_source,
             failed: false,
+            outcome: None,
         }
     }
 
@@ -56,8 +66,11 @@ impl<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> State<AL, BL, AA, BA> {
             swap_communication: SwapCommunication::Declined { request, response },
             alpha_ledger_state: LedgerState::NotDeployed,
             beta_ledger_state: LedgerState::NotDeployed,
+            alpha_pending: None,
+            beta_pending: None,
             secret_source,
             failed: false,
+            outcome: None,
         }
     }
 
@@ -80,6 +93,14 @@ impl<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> ActorState for State<AL, BL,
         self.swap_communication.request().beta_asset.clone()
     }
 
+    fn alpha_ledger(&self) -> &LedgerState<AL::HtlcLocation, AL::Transaction, AA> {
+        &self.alpha_ledger_state
+    }
+
+    fn beta_ledger(&self) -> &LedgerState<BL::HtlcLocation, BL::Transaction, BA> {
+        &self.beta_ledger_state
+    }
+
     fn alpha_ledger_mut(&mut self) -> &mut LedgerState<AL::HtlcLocation, AL::Transaction, AA> {
         &mut self.alpha_ledger_state
     }
@@ -88,6 +109,22 @@ impl<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> ActorState for State<AL, BL,
         &mut self.beta_ledger_state
     }
 
+    fn alpha_pending(&self) -> Option<&Pending<AL::Transaction>> {
+        self.alpha_pending.as_ref()
+    }
+
+    fn beta_pending(&self) -> Option<&Pending<BL::Transaction>> {
+        self.beta_pending.as_ref()
+    }
+
+    fn set_alpha_pending(&mut self, pending: Option<Pending<AL::Transaction>>) {
+        self.alpha_pending = pending;
+    }
+
+    fn set_beta_pending(&mut self, pending: Option<Pending<BL::Transaction>>) {
+        self.beta_pending = pending;
+    }
+
     fn swap_failed(&self) -> bool {
         self.failed
     }
@@ -95,4 +132,12 @@ impl<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> ActorState for State<AL, BL,
     fn set_swap_failed(&mut self) {
         self.failed = true;
     }
+
+    fn swap_outcome(&self) -> Option<SwapOutcome> {
+        self.outcome
+    }
+
+    fn set_swap_outcome(&mut self, outcome: SwapOutcome) {
+        self.outcome = Some(outcome);
+    }
 }
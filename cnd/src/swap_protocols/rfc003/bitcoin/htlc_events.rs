@@ -1,7 +1,9 @@
 use crate::{
     asset,
-    btsieve::bitcoin::{
-        matching_transaction, BitcoindConnector, Cache, TransactionExt, TransactionPattern,
+    btsieve::{
+        bitcoin::{BitcoinBackend, Cache, TransactionExt, TransactionPattern},
+        failover::Failover,
+        Mempool, PendingChange, Scanner, Watch,
     },
     swap_protocols::{
         ledger::bitcoin,
@@ -9,8 +11,8 @@ use crate::{
             bitcoin::extract_secret::extract_secret,
             create_swap::HtlcParams,
             events::{
-                Deployed, Funded, HtlcDeployed, HtlcFunded, HtlcRedeemed, HtlcRefunded, Redeemed,
-                Refunded,
+                Deployed, Funded, HtlcDeployed, HtlcFunded, HtlcPending, HtlcRedeemed,
+                HtlcRefunded, Pending, PendingState, Redeemed, Refunded,
             },
         },
     },
@@ -20,18 +22,17 @@ use anyhow::Context;
 use chrono::NaiveDateTime;
 
 #[async_trait::async_trait]
-impl<Bitcoin: bitcoin::Bitcoin + bitcoin::Network> HtlcFunded<Bitcoin, asset::Bitcoin>
-    for Cache<BitcoindConnector>
+impl<Bitcoin: bitcoin::Bitcoin, A: asset::BitcoinLike> HtlcFunded<Bitcoin, A>
+    for Scanner<Cache<Failover<BitcoinBackend>>, ::bitcoin::Block>
 {
     async fn htlc_funded(
         &self,
-        _htlc_params: HtlcParams<Bitcoin, asset::Bitcoin, crate::bitcoin::PublicKey>,
+        _htlc_params: HtlcParams<Bitcoin, A, crate::bitcoin::PublicKey>,
         htlc_deployment: &Deployed<::bitcoin::Transaction, ::bitcoin::OutPoint>,
         _start_of_swap: NaiveDateTime,
-    ) -> anyhow::Result<Funded<::bitcoin::Transaction, asset::Bitcoin>> {
+    ) -> anyhow::Result<Funded<::bitcoin::Transaction, A>> {
         let tx = &htlc_deployment.transaction;
-        let asset =
-            asset::Bitcoin::from_sat(tx.output[htlc_deployment.location.vout as usize].value);
+        let asset = A::from_output_value(tx.output[htlc_deployment.location.vout as usize].value);
 
         Ok(Funded {
             transaction: tx.clone(),
@@ -41,22 +42,23 @@ impl<Bitcoin: bitcoin::Bitcoin + bitcoin::Network> HtlcFunded<Bitcoin, asset::Bi
 }
 
 #[async_trait::async_trait]
-impl<Bitcoin: bitcoin::Bitcoin + bitcoin::Network> HtlcDeployed<Bitcoin, asset::Bitcoin>
-    for Cache<BitcoindConnector>
+impl<Bitcoin: bitcoin::Bitcoin, A: asset::BitcoinLike> HtlcDeployed<Bitcoin, A>
+    for Scanner<Cache<Failover<BitcoinBackend>>, ::bitcoin::Block>
 {
     async fn htlc_deployed(
         &self,
-        htlc_params: HtlcParams<Bitcoin, asset::Bitcoin, crate::bitcoin::PublicKey>,
+        htlc_params: HtlcParams<Bitcoin, A, crate::bitcoin::PublicKey>,
         start_of_swap: NaiveDateTime,
     ) -> anyhow::Result<Deployed<::bitcoin::Transaction, ::bitcoin::OutPoint>> {
-        let connector = self.clone();
         let pattern = TransactionPattern {
             to_address: Some(htlc_params.compute_address()),
             from_outpoint: None,
+            from_outpoint_script: None,
             unlock_script: None,
         };
 
-        let transaction = matching_transaction(connector, pattern, start_of_swap)
+        let transaction = self
+            .matching_transaction(pattern, start_of_swap)
             .await
             .context("failed to find transaction to deploy htlc")?;
 
@@ -75,23 +77,24 @@ impl<Bitcoin: bitcoin::Bitcoin + bitcoin::Network> HtlcDeployed<Bitcoin, asset::
 }
 
 #[async_trait::async_trait]
-impl<Bitcoin: bitcoin::Bitcoin + bitcoin::Network> HtlcRedeemed<Bitcoin, asset::Bitcoin>
-    for Cache<BitcoindConnector>
+impl<Bitcoin: bitcoin::Bitcoin, A: asset::BitcoinLike> HtlcRedeemed<Bitcoin, A>
+    for Scanner<Cache<Failover<BitcoinBackend>>, ::bitcoin::Block>
 {
     async fn htlc_redeemed(
         &self,
-        htlc_params: HtlcParams<Bitcoin, asset::Bitcoin, crate::bitcoin::PublicKey>,
+        htlc_params: HtlcParams<Bitcoin, A, crate::bitcoin::PublicKey>,
         htlc_deployment: &Deployed<::bitcoin::Transaction, ::bitcoin::OutPoint>,
         start_of_swap: NaiveDateTime,
     ) -> anyhow::Result<Redeemed<::bitcoin::Transaction>> {
-        let connector = self.clone();
         let pattern = TransactionPattern {
             to_address: None,
             from_outpoint: Some(htlc_deployment.location),
+            from_outpoint_script: Some(htlc_params.compute_address().script_pubkey()),
             unlock_script: Some(vec![vec![1u8]]),
         };
 
-        let transaction = matching_transaction(connector, pattern, start_of_swap)
+        let transaction = self
+            .matching_transaction(pattern, start_of_swap)
             .await
             .context("failed to find transaction to redeem from htlc")?;
         let secret = extract_secret(&transaction, &htlc_params.secret_hash)
@@ -105,25 +108,71 @@ impl<Bitcoin: bitcoin::Bitcoin + bitcoin::Network> HtlcRedeemed<Bitcoin, asset::
 }
 
 #[async_trait::async_trait]
-impl<Bitcoin: bitcoin::Bitcoin + bitcoin::Network> HtlcRefunded<Bitcoin, asset::Bitcoin>
-    for Cache<BitcoindConnector>
+impl<Bitcoin: bitcoin::Bitcoin, A: asset::BitcoinLike> HtlcRefunded<Bitcoin, A>
+    for Scanner<Cache<Failover<BitcoinBackend>>, ::bitcoin::Block>
 {
     async fn htlc_refunded(
         &self,
-        _htlc_params: HtlcParams<Bitcoin, asset::Bitcoin, crate::bitcoin::PublicKey>,
+        htlc_params: HtlcParams<Bitcoin, A, crate::bitcoin::PublicKey>,
         htlc_deployment: &Deployed<::bitcoin::Transaction, ::bitcoin::OutPoint>,
         start_of_swap: NaiveDateTime,
     ) -> anyhow::Result<Refunded<::bitcoin::Transaction>> {
-        let connector = self.clone();
         let pattern = TransactionPattern {
             to_address: None,
             from_outpoint: Some(htlc_deployment.location),
+            from_outpoint_script: Some(htlc_params.compute_address().script_pubkey()),
             unlock_script: Some(vec![vec![]]),
         };
-        let transaction = matching_transaction(connector, pattern, start_of_swap)
+        let transaction = self
+            .matching_transaction(pattern, start_of_swap)
             .await
             .context("failed to find transaction to refund from htlc")?;
 
         Ok(Refunded { transaction })
     }
 }
+
+impl<Bitcoin: bitcoin::Bitcoin> HtlcPending<Bitcoin, asset::Bitcoin>
+    for Mempool<Failover<BitcoinBackend>, ::bitcoin::Txid, ::bitcoin::Transaction>
+{
+    fn htlc_pending(
+        &self,
+        htlc_params: HtlcParams<Bitcoin, asset::Bitcoin, crate::bitcoin::PublicKey>,
+        htlc_deployment: Option<&Deployed<::bitcoin::Transaction, ::bitcoin::OutPoint>>,
+    ) -> anyhow::Result<Watch<PendingChange<Pending<::bitcoin::Transaction>>>> {
+        // Deploying a Bitcoin HTLC funds it, there is no funding transaction
+        // once it is deployed.
+        let patterns = match htlc_deployment {
+            None => vec![(PendingState::Funding, TransactionPattern {
+                to_address: Some(htlc_params.compute_address()),
+                from_outpoint: None,
+                from_outpoint_script: None,
+                unlock_script: None,
+            })],
+            Some(htlc_deployment) => vec![
+                (PendingState::Redeeming, TransactionPattern {
+                    to_address: None,
+                    from_outpoint: Some(htlc_deployment.location),
+                    from_outpoint_script: Some(htlc_params.compute_address().script_pubkey()),
+                    unlock_script: Some(vec![vec![1u8]]),
+                }),
+                (PendingState::Refunding, TransactionPattern {
+                    to_address: None,
+                    from_outpoint: Some(htlc_deployment.location),
+                    from_outpoint_script: Some(htlc_params.compute_address().script_pubkey()),
+                    unlock_script: Some(vec![vec![]]),
+                }),
+            ],
+        };
+
+        Ok(self.watch(move |transaction: &::bitcoin::Transaction| {
+            patterns
+                .iter()
+                .find(|(_, pattern)| pattern.matches(transaction))
+                .map(|(state, _)| Pending {
+                    transaction: transaction.clone(),
+                    state: *state,
+                })
+        }))
+    }
+}
@@ -20,10 +20,10 @@ impl<B: ledger::Bitcoin> Ledger for B {
     type Transaction = Transaction;
 }
 
-impl<B: ledger::Bitcoin> From<HtlcParams<B, asset::Bitcoin, crate::bitcoin::PublicKey>>
+impl<B: ledger::Bitcoin, A: asset::BitcoinLike> From<HtlcParams<B, A, crate::bitcoin::PublicKey>>
     for BitcoinHtlc
 {
-    fn from(htlc_params: HtlcParams<B, asset::Bitcoin, crate::bitcoin::PublicKey>) -> Self {
+    fn from(htlc_params: HtlcParams<B, A, crate::bitcoin::PublicKey>) -> Self {
         let refund_public_key = ::bitcoin::PublicKey::from(htlc_params.refund_identity);
         let redeem_public_key = ::bitcoin::PublicKey::from(htlc_params.redeem_identity);
 
@@ -39,10 +39,8 @@ impl<B: ledger::Bitcoin> From<HtlcParams<B, asset::Bitcoin, crate::bitcoin::Publ
     }
 }
 
-impl<B: ledger::Bitcoin + ledger::bitcoin::Network>
-    HtlcParams<B, asset::Bitcoin, crate::bitcoin::PublicKey>
-{
+impl<B: ledger::Bitcoin, A: asset::BitcoinLike> HtlcParams<B, A, crate::bitcoin::PublicKey> {
     pub fn compute_address(&self) -> Address {
-        BitcoinHtlc::from(*self).compute_address(B::network())
+        BitcoinHtlc::from(*self).compute_address(self.ledger.bitcoin_network())
     }
 }
@@ -0,0 +1,255 @@
+//! Bob only ever pays or receives through his own lnd node, hence none of the
+//! actions below are generic over the other ledger.
+//!
+//! As the receiver of a Lightning payment Bob adds the hold invoice right
+//! after accepting the swap so that it is there by the time Alice pays.
+
+use crate::{
+    asset,
+    swap_protocols::{
+        actions::{
+            ethereum,
+            lnd::{AddHoldInvoice, SendPayment, SettleInvoice},
+            Actions, DeployContractOrAddHoldInvoice,
+        },
+        ledger::{Ethereum, Lightning},
+        rfc003::{
+            actions::{
+                erc20, lightning, Accept, Action, Decline, FundAction, RedeemAction, RefundAction,
+            },
+            bob,
+            create_swap::HtlcParams,
+            LedgerState, SwapCommunication,
+        },
+    },
+};
+use std::convert::Infallible;
+
+impl Actions for bob::State<Lightning, Ethereum, asset::Bitcoin, asset::Ether> {
+    #[allow(clippy::type_complexity)]
+    type ActionKind = Action<
+        Accept<Lightning, Ethereum>,
+        Decline<Lightning, Ethereum>,
+        AddHoldInvoice,
+        ethereum::DeployContract,
+        SettleInvoice,
+        ethereum::CallContract,
+    >;
+
+    fn actions(&self) -> Vec<Self::ActionKind> {
+        let (request, response) = match &self.swap_communication {
+            SwapCommunication::Proposed { .. } => {
+                return vec![
+                    Action::Accept(Accept::new()),
+                    Action::Decline(Decline::new()),
+                ];
+            }
+            SwapCommunication::Accepted {
+                ref request,
+                ref response,
+            } => (request, response),
+            _ => return vec![],
+        };
+
+        let alpha_state = &self.alpha_ledger_state;
+        let beta_state = &self.beta_ledger_state;
+
+        use self::LedgerState::*;
+        let mut actions = match (alpha_state, beta_state) {
+            (NotDeployed, _) => vec![Action::Deploy(lightning::add_hold_invoice_action(
+                HtlcParams::new_alpha_params(request, response),
+            ))],
+            (Funded { .. }, Redeemed { secret, .. }) => {
+                vec![Action::Redeem(lightning::settle_invoice_action(
+                    HtlcParams::new_alpha_params(request, response),
+                    *secret, // Bob uses the secret learned from Alice's redeem action.
+                ))]
+            }
+            (Funded { .. }, NotDeployed) => {
+                vec![Action::Fund(<(Ethereum, asset::Ether)>::fund_action(
+                    HtlcParams::new_beta_params(request, response),
+                ))]
+            }
+            _ => vec![],
+        };
+
+        if let Funded {
+            htlc_location,
+            fund_transaction,
+            ..
+        }
+        | IncorrectlyFunded {
+            htlc_location,
+            fund_transaction,
+            ..
+        } = beta_state
+        {
+            actions.push(Action::Refund(<(Ethereum, asset::Ether)>::refund_action(
+                HtlcParams::new_beta_params(request, response),
+                *htlc_location,
+                &*self.secret_source,
+                fund_transaction,
+            )))
+        }
+
+        actions
+    }
+}
+
+impl Actions for bob::State<Lightning, Ethereum, asset::Bitcoin, asset::Erc20> {
+    #[allow(clippy::type_complexity)]
+    type ActionKind = Action<
+        Accept<Lightning, Ethereum>,
+        Decline<Lightning, Ethereum>,
+        DeployContractOrAddHoldInvoice,
+        ethereum::CallContract,
+        SettleInvoice,
+        ethereum::CallContract,
+    >;
+
+    fn actions(&self) -> Vec<Self::ActionKind> {
+        let (request, response) = match &self.swap_communication {
+            SwapCommunication::Proposed { .. } => {
+                return vec![
+                    Action::Accept(Accept::new()),
+                    Action::Decline(Decline::new()),
+                ];
+            }
+            SwapCommunication::Accepted {
+                ref request,
+                ref response,
+            } => (request, response),
+            _ => return vec![],
+        };
+
+        let alpha_state = &self.alpha_ledger_state;
+        let beta_state = &self.beta_ledger_state;
+
+        use self::LedgerState::*;
+        let mut actions = match (alpha_state, beta_state) {
+            (NotDeployed, _) => vec![Action::Deploy(
+                DeployContractOrAddHoldInvoice::AddHoldInvoice(lightning::add_hold_invoice_action(
+                    HtlcParams::new_alpha_params(request, response),
+                )),
+            )],
+            (Funded { .. }, Redeemed { secret, .. }) => {
+                vec![Action::Redeem(lightning::settle_invoice_action(
+                    HtlcParams::new_alpha_params(request, response),
+                    *secret, // Bob uses the secret learned from Alice's redeem action.
+                ))]
+            }
+            (Funded { .. }, NotDeployed) => vec![Action::Deploy(
+                DeployContractOrAddHoldInvoice::DeployContract(erc20::deploy_action(
+                    HtlcParams::new_beta_params(request, response),
+                )),
+            )],
+            (Funded { .. }, Deployed { htlc_location, .. }) => {
+                vec![Action::Fund(erc20::fund_action(
+                    HtlcParams::new_beta_params(request, response),
+                    request.beta_asset.token_contract,
+                    *htlc_location,
+                ))]
+            }
+            _ => vec![],
+        };
+
+        if let Funded { htlc_location, .. } = beta_state {
+            actions.push(Action::Refund(erc20::refund_action(
+                request.beta_ledger.chain_id,
+                request.beta_expiry,
+                *htlc_location,
+            )));
+        }
+        actions
+    }
+}
+
+impl Actions for bob::State<Ethereum, Lightning, asset::Ether, asset::Bitcoin> {
+    #[allow(clippy::type_complexity)]
+    type ActionKind = Action<
+        Accept<Ethereum, Lightning>,
+        Decline<Ethereum, Lightning>,
+        Infallible,
+        SendPayment,
+        ethereum::CallContract,
+        Infallible,
+    >;
+
+    fn actions(&self) -> Vec<Self::ActionKind> {
+        let (request, response) = match &self.swap_communication {
+            SwapCommunication::Proposed { .. } => {
+                return vec![
+                    Action::Accept(Accept::new()),
+                    Action::Decline(Decline::new()),
+                ];
+            }
+            SwapCommunication::Accepted {
+                ref request,
+                ref response,
+            } => (request, response),
+            _ => return vec![],
+        };
+
+        let alpha_state = &self.alpha_ledger_state;
+        let beta_state = &self.beta_ledger_state;
+
+        use self::LedgerState::*;
+        match (alpha_state, beta_state) {
+            (Funded { htlc_location, .. }, Redeemed { secret, .. }) => {
+                vec![Action::Redeem(<(Ethereum, asset::Ether)>::redeem_action(
+                    HtlcParams::new_alpha_params(request, response),
+                    *htlc_location,
+                    &*self.secret_source, // Derive identities with this.
+                    *secret,              /* Bob uses the secret learned from Alice's redeem
+                                           * action. */
+                ))]
+            }
+            (Funded { .. }, NotDeployed) => vec![Action::Fund(lightning::send_payment_action(
+                HtlcParams::new_beta_params(request, response),
+            ))],
+            _ => vec![],
+        }
+    }
+}
+
+impl Actions for bob::State<Ethereum, Lightning, asset::Erc20, asset::Bitcoin> {
+    #[allow(clippy::type_complexity)]
+    type ActionKind = Action<
+        Accept<Ethereum, Lightning>,
+        Decline<Ethereum, Lightning>,
+        Infallible,
+        SendPayment,
+        ethereum::CallContract,
+        Infallible,
+    >;
+
+    fn actions(&self) -> Vec<Self::ActionKind> {
+        let (request, response) = match &self.swap_communication {
+            SwapCommunication::Proposed { .. } => {
+                return vec![
+                    Action::Accept(Accept::new()),
+                    Action::Decline(Decline::new()),
+                ];
+            }
+            SwapCommunication::Accepted {
+                ref request,
+                ref response,
+            } => (request, response),
+            _ => return vec![],
+        };
+
+        let alpha_state = &self.alpha_ledger_state;
+        let beta_state = &self.beta_ledger_state;
+
+        use self::LedgerState::*;
+        match (alpha_state, beta_state) {
+            (Funded { htlc_location, .. }, Redeemed { secret, .. }) => vec![Action::Redeem(
+                erc20::redeem_action(*htlc_location, *secret, request.alpha_ledger.chain_id),
+            )],
+            (Funded { .. }, NotDeployed) => vec![Action::Fund(lightning::send_payment_action(
+                HtlcParams::new_beta_params(request, response),
+            ))],
+            _ => vec![],
+        }
+    }
+}
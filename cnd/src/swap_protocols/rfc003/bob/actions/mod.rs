@@ -1,2 +1,3 @@
 mod erc20;
 mod generic_impl;
+mod lightning;
@@ -3,8 +3,8 @@ pub mod actions;
 use crate::{
     asset::Asset,
     swap_protocols::rfc003::{
-        self, ledger::Ledger, ledger_state::LedgerState, messages::Request, Accept, ActorState,
-        Decline, DeriveIdentities, SwapCommunication,
+        self, events::Pending, ledger::Ledger, ledger_state::LedgerState, messages::Request,
+        Accept, ActorState, Decline, DeriveIdentities, SwapCommunication, SwapOutcome,
     },
 };
 use derivative::Derivative;
@@ -21,9 +21,12 @@ pub struct State<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> {
     pub swap_communication: SwapCommunication<AL, BL, AA, BA>,
     pub alpha_ledger_state: LedgerState<AL::HtlcLocation, AL::Transaction, AA>,
     pub beta_ledger_state: LedgerState<BL::HtlcLocation, BL::Transaction, BA>,
+    pub alpha_pending: Option<Pending<AL::Transaction>>,
+    pub beta_pending: Option<Pending<BL::Transaction>>,
     #[derivative(Debug = "ignore")]
     pub secret_source: Arc<dyn DeriveIdentities>,
     pub failed: bool, // Gets set on any error during the execution of a swap.
+    pub outcome: Option<SwapOutcome>, // Gets set once no more ledger events are expected.
 }
 
 impl<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> State<AL, BL, AA, BA> {
@@ -35,8 +38,11 @@ impl<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> State<AL, BL, AA, BA> {
             swap_communication: SwapCommunication::Proposed { request },
             alpha_ledger_state: LedgerState::NotDeployed,
             beta_ledger_state: LedgerState::NotDeployed,
+            alpha_pending: None,
+            beta_pending: None,
             secret_source: Arc::new(secret_source),
             failed: false,
+            outcome: None,
         }
     }
 
@@ -49,8 +55,11 @@ impl<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> State<AL, BL, AA, BA> {
             swap_communication: SwapCommunication::Accepted { request, response },
             alpha_ledger_state: LedgerState::NotDeployed,
             beta_ledger_state: LedgerState::NotDeployed,
+            alpha_pending: None,
+            beta_pending: None,
             secret_source: Arc::new(secret_source),
             failed: false,
+            outcome: None,
         }
     }
 
@@ -63,8 +72,11 @@ impl<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> State<AL, BL, AA, BA> {
             swap_communication: SwapCommunication::Declined { request, response },
             alpha_ledger_state: LedgerState::NotDeployed,
             beta_ledger_state: LedgerState::NotDeployed,
+            alpha_pending: None,
+            beta_pending: None,
             secret_source: Arc::new(secret_source),
             failed: false,
+            outcome: None,
         }
     }
 
@@ -91,6 +103,14 @@ impl<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> ActorState for State<AL, BL,
         self.swap_communication.request().beta_asset.clone()
     }
 
+    fn alpha_ledger(&self) -> &LedgerState<AL::HtlcLocation, AL::Transaction, AA> {
+        &self.alpha_ledger_state
+    }
+
+    fn beta_ledger(&self) -> &LedgerState<BL::HtlcLocation, BL::Transaction, BA> {
+        &self.beta_ledger_state
+    }
+
     fn alpha_ledger_mut(&mut self) -> &mut LedgerState<AL::HtlcLocation, AL::Transaction, AA> {
         &mut self.alpha_ledger_state
     }
@@ -99,6 +119,22 @@ impl<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> ActorState for State<AL, BL,
         &mut self.beta_ledger_state
     }
 
+    fn alpha_pending(&self) -> Option<&Pending<AL::Transaction>> {
+        self.alpha_pending.as_ref()
+    }
+
+    fn beta_pending(&self) -> Option<&Pending<BL::Transaction>> {
+        self.beta_pending.as_ref()
+    }
+
+    fn set_alpha_pending(&mut self, pending: Option<Pending<AL::Transaction>>) {
+        self.alpha_pending = pending;
+    }
+
+    fn set_beta_pending(&mut self, pending: Option<Pending<BL::Transaction>>) {
+        self.beta_pending = pending;
+    }
+
     fn swap_failed(&self) -> bool {
         self.failed
     }
@@ -106,4 +142,12 @@ impl<AL: Ledger, BL: Ledger, AA: Asset, BA: Asset> ActorState for State<AL, BL,
     fn set_swap_failed(&mut self) {
         self.failed = true;
     }
+
+    fn swap_outcome(&self) -> Option<SwapOutcome> {
+        self.outcome
+    }
+
+    fn set_swap_outcome(&mut self, outcome: SwapOutcome) {
+        self.outcome = Some(outcome);
+    }
 }
@@ -1,18 +1,21 @@
 use crate::{
     asset::Asset,
-    db::AcceptedSwap,
+    btsieve::{Confirmation, PendingChange, Watch},
+    db::{AcceptedSwap, SwapEvents},
     swap_protocols::{
         rfc003::{
             self,
             events::{
-                Deployed, Funded, HtlcDeployed, HtlcFunded, HtlcRedeemed, HtlcRefunded, Redeemed,
-                Refunded,
+                Deployed, Funded, HtlcConfirmed, HtlcDeployed, HtlcFunded, HtlcPending,
+                HtlcRedeemed, HtlcRefunded, Pending, Redeemed, Refunded,
             },
             ledger::Ledger,
+            ledger_state::{HtlcState, LedgerState},
+            outcome::NEVER_FUNDED_GRACE_PERIOD_SECS,
             state_store::StateStore,
-            Accept, ActorState, Request, SecretHash,
+            Accept, ActorState, Request, SecretHash, SwapOutcome,
         },
-        HashFunction,
+        HashFunction, SwapId,
     },
     timestamp::Timestamp,
 };
@@ -22,23 +25,55 @@ use genawaiter::{
     sync::{Co, Gen},
     GeneratorState,
 };
+use serde::{Deserialize, Serialize};
+use std::{future::Future, time::Duration};
+
+/// How often recording an event is attempted before the swap is stopped.
+const SAVE_EVENT_ATTEMPTS: u32 = 5;
+
+/// The delay before the first retry of recording an event, doubled with every
+/// further attempt.
+const SAVE_EVENT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// How long before the block of the last confirmed transaction on a ledger we
+/// start looking for the next one when a swap is resumed.
+///
+/// The next transaction can only be in the same or a later block, but block
+/// times do not strictly increase, e.g. Bitcoin accepts blocks with a time as
+/// early as the median time of the previous 11 blocks.
+const RESUME_MARGIN_SECS: i64 = 2 * 60 * 60;
 
 /// Returns a future that tracks the swap negotiated from the given request and
 /// accept response on both ledgers.
 ///
-/// The current implementation is naive in the sense that it does not take into
-/// account situations where it is clear that no more events will happen even
-/// though in theory, there could. For example:
-/// - alpha funded
-/// - alpha refunded
+/// Every event is recorded before it is applied to the state store. Events
+/// recorded by a previous run are replayed first, hence the ledgers are only
+/// watched for the events that have not been observed yet, starting from the
+/// block of the last confirmed transaction on each ledger. If an event cannot
+/// be recorded after `SAVE_EVENT_ATTEMPTS`, the swap is stopped rather than
+/// continued with a state that a restart would not restore.
 ///
-/// It is highly unlikely for Bob to fund the HTLC now, yet the current
-/// implementation is still waiting for that.
+/// Every event is yielded as soon as its transaction is seen. The next event
+/// is only watched for once that transaction has been confirmed; if a reorg
+/// orphans it instead, the event is reverted and watched for again.
+///
+/// Transactions of the HTLCs that are seen before they are mined are yielded
+/// as pending events. They last until the next event on the same ledger or
+/// until the transaction leaves the mempool. Whether a replayed pending
+/// transaction is still in the mempool is not known, hence it is cleared until
+/// the mempool reports it again.
+///
+/// As soon as the state of both HTLCs together with the expiries decides the
+/// `SwapOutcome` (e.g. alpha refunded while beta was never funded), the
+/// outcome is recorded as the last event and the ledgers are no longer
+/// watched; a swap with an outcome is not resumed. HTLCs that are not funded
+/// are watched until `NEVER_FUNDED_GRACE_PERIOD_SECS` after their expiry.
 pub async fn create_swap<D, A: ActorState>(
     dependencies: D,
     accepted: AcceptedSwap<A::AL, A::BL, A::AA, A::BA>,
 ) where
     D: StateStore
+        + SwapEvents<SwapEventOnLedger<A::AL, A::BL, A::AA, A::BA>>
         + HtlcFunded<A::AL, A::AA>
         + HtlcFunded<A::BL, A::BA>
         + HtlcDeployed<A::AL, A::AA>
@@ -47,6 +82,10 @@ pub async fn create_swap<D, A: ActorState>(
         + HtlcRedeemed<A::BL, A::BA>
         + HtlcRefunded<A::AL, A::AA>
         + HtlcRefunded<A::BL, A::BA>
+        + HtlcConfirmed<A::AL>
+        + HtlcConfirmed<A::BL>
+        + HtlcPending<A::AL, A::AA>
+        + HtlcPending<A::BL, A::BA>
         + Clone,
 {
     let (request, accept, at) = accepted.clone();
@@ -54,6 +93,50 @@ pub async fn create_swap<D, A: ActorState>(
     let id = request.swap_id;
     let swap = OngoingSwap::new(request, accept);
 
+    let known_events = match dependencies.load_swap_events(&id).await {
+        Ok(known_events) => known_events,
+        Err(e) => {
+            tracing::error!("failed to load events of swap {}: {:?}", id, e);
+            return;
+        }
+    };
+
+    let mut alpha_confirmed_at = None;
+    let mut beta_confirmed_at = None;
+    for event in known_events {
+        tracing::debug!("swap {} replayed event {}", id, event);
+        match &event {
+            SwapEvent::AlphaConfirmed(block_time) => alpha_confirmed_at = Some(*block_time),
+            SwapEvent::BetaConfirmed(block_time) => beta_confirmed_at = Some(*block_time),
+            _ => {}
+        }
+        dependencies.update::<A>(&id, event);
+    }
+
+    let state = match dependencies.get::<A>(&id) {
+        Ok(Some(state)) => state,
+        Ok(None) | Err(_) => {
+            tracing::error!("state store did not contain an entry for {}", id);
+            return;
+        }
+    };
+    if let Some(outcome) = state.swap_outcome() {
+        tracing::info!("swap {} already finished with outcome {}", id, outcome);
+        return;
+    }
+    if state.alpha_pending().is_some() {
+        dependencies.update::<A>(&id, SwapEvent::AlphaPendingLeft);
+    }
+    if state.beta_pending().is_some() {
+        dependencies.update::<A>(&id, SwapEvent::BetaPendingLeft);
+    }
+
+    let (alpha_ledger_state, beta_ledger_state) =
+        (state.alpha_ledger().clone(), state.beta_ledger().clone());
+    let (alpha_expiry, beta_expiry) = (swap.alpha_expiry, swap.beta_expiry);
+    let alpha_watch_from = resume_from(at, alpha_confirmed_at);
+    let beta_watch_from = resume_from(at, beta_confirmed_at);
+
     // construct a generator that watches alpha and beta ledger concurrently
     let mut generator = Gen::new({
         let dependencies = dependencies.clone();
@@ -63,13 +146,15 @@ pub async fn create_swap<D, A: ActorState>(
                     &dependencies,
                     &co,
                     swap.alpha_htlc_params(),
-                    at,
+                    alpha_watch_from,
+                    alpha_ledger_state,
                 ),
                 watch_beta_ledger::<_, A::AL, _, A::BL, _>(
                     &dependencies,
                     &co,
                     swap.beta_htlc_params(),
-                    at,
+                    beta_watch_from,
+                    beta_ledger_state,
                 ),
             )
             .await
@@ -77,11 +162,57 @@ pub async fn create_swap<D, A: ActorState>(
     });
 
     loop {
+        let now = Timestamp::now();
+        if let Some(outcome) =
+            decide_outcome::<_, A>(&dependencies, &id, alpha_expiry, beta_expiry, now)
+        {
+            // The outcome follows from the recorded events, if it cannot be
+            // saved a restart decides it again.
+            let event = SwapEvent::Finished(outcome);
+            if let Err(e) = save_swap_event(&dependencies, &id, event.clone()).await {
+                tracing::error!("failed to save outcome of swap {}: {:?}", id, e);
+            }
+            dependencies.update::<A>(&id, event);
+
+            // dropping the generator cancels the remaining ledger watchers
+            tracing::info!("swap {} finished with outcome {}", id, outcome);
+            return;
+        }
+
+        // Giving up on an HTLC that was not funded can decide the outcome
+        // without any event, hence we also wake up when that happens next.
+        let next_deadline = [alpha_expiry, beta_expiry]
+            .iter()
+            .map(|expiry| expiry.plus(NEVER_FUNDED_GRACE_PERIOD_SECS))
+            .filter(|deadline| *deadline > now)
+            .min();
+        let resumed = match next_deadline {
+            Some(deadline) => {
+                let remaining = u32::from(deadline).saturating_sub(u32::from(now));
+                let delay = tokio::time::delay_for(Duration::from_secs(u64::from(remaining)));
+
+                match future::select(Box::pin(generator.async_resume()), delay).await {
+                    Either::Left((resumed, _)) => resumed,
+                    Either::Right(((), _)) => continue,
+                }
+            }
+            None => generator.async_resume().await,
+        };
+
         // wait for events to be emitted as the generator executes
-        match generator.async_resume().await {
-            // every event that is yielded is passed on
+        match resumed {
+            // every event that is yielded is recorded and passed on
             GeneratorState::Yielded(event) => {
                 tracing::info!("swap {} yielded event {}", id, event);
+                if let Err(e) = save_swap_event(&dependencies, &id, event.clone()).await {
+                    tracing::error!(
+                        "stopping swap {} because event {} could not be saved: {:?}",
+                        id,
+                        event,
+                        e
+                    );
+                    return;
+                }
                 dependencies.update::<A>(&id, event);
             }
             // the generator stopped executing, this means there are no more events that can be
@@ -98,98 +229,496 @@ pub async fn create_swap<D, A: ActorState>(
     }
 }
 
+/// Records `event`, retrying with an exponential backoff if the database fails.
+async fn save_swap_event<D, E>(dependencies: &D, id: &SwapId, event: E) -> anyhow::Result<()>
+where
+    D: SwapEvents<E>,
+    E: Clone,
+{
+    let mut backoff = SAVE_EVENT_BACKOFF;
+    let mut attempt = 1;
+
+    loop {
+        match dependencies.save_swap_event(id, event.clone()).await {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt < SAVE_EVENT_ATTEMPTS => {
+                tracing::warn!(
+                    "failed to save event of swap {} (attempt {}): {:?}",
+                    id,
+                    attempt,
+                    e
+                );
+                tokio::time::delay_for(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Determines the outcome of the swap from its current state.
+///
+/// Returns `None` if events on either ledger can still affect the swap. This
+/// includes events that are not confirmed yet as a reorg may revert them and
+/// transactions that are not mined yet.
+fn decide_outcome<D, A>(
+    dependencies: &D,
+    id: &SwapId,
+    alpha_expiry: Timestamp,
+    beta_expiry: Timestamp,
+    now: Timestamp,
+) -> Option<SwapOutcome>
+where
+    D: StateStore,
+    A: ActorState,
+{
+    let state = match dependencies.get::<A>(id) {
+        Ok(Some(state)) => state,
+        Ok(None) | Err(_) => {
+            tracing::error!("state store did not contain an entry for {}", id);
+            return None;
+        }
+    };
+
+    if !is_settled(state.alpha_ledger()) || !is_settled(state.beta_ledger()) {
+        return None;
+    }
+
+    // A pending transaction may still fund an HTLC that looks abandoned.
+    if state.alpha_pending().is_some() || state.beta_pending().is_some() {
+        return None;
+    }
+
+    SwapOutcome::determine(
+        HtlcState::from(state.alpha_ledger()),
+        alpha_expiry,
+        HtlcState::from(state.beta_ledger()),
+        beta_expiry,
+        now,
+    )
+}
+
+/// Where to start watching a ledger when a swap is resumed: the block of the
+/// last confirmed transaction on the ledger, less `RESUME_MARGIN_SECS`, or the
+/// start of the swap if there is none.
+fn resume_from(
+    start_of_swap: NaiveDateTime,
+    last_confirmed_at: Option<NaiveDateTime>,
+) -> NaiveDateTime {
+    match last_confirmed_at {
+        Some(block_time) => std::cmp::max(
+            start_of_swap,
+            block_time - chrono::Duration::seconds(RESUME_MARGIN_SECS),
+        ),
+        None => start_of_swap,
+    }
+}
+
+fn is_settled<H, T, A>(ledger_state: &LedgerState<H, T, A>) -> bool {
+    match ledger_state {
+        LedgerState::NotDeployed => true,
+        ledger_state => ledger_state.is_confirmed(),
+    }
+}
+
 /// Returns a future that waits for events on alpha ledger to happen.
 ///
 /// Each event is yielded through the controller handle (co) of the coroutine.
+/// Events that are already part of `known_state` are not watched for again,
+/// the ledger is only looked at from `watch_from` on.
 async fn watch_alpha_ledger<D, AL, AA, BL, BA>(
     dependencies: &D,
     co: &Co<SwapEventOnLedger<AL, BL, AA, BA>>,
     htlc_params: HtlcParams<AL, AA, AL::Identity>,
-    start_of_swap: NaiveDateTime,
+    watch_from: NaiveDateTime,
+    mut known_state: LedgerState<AL::HtlcLocation, AL::Transaction, AA>,
 ) -> anyhow::Result<()>
 where
     AL: Ledger,
     BL: Ledger,
     AA: Asset,
     BA: Asset,
-    D: HtlcFunded<AL, AA> + HtlcDeployed<AL, AA> + HtlcRedeemed<AL, AA> + HtlcRefunded<AL, AA>,
+    D: HtlcFunded<AL, AA>
+        + HtlcDeployed<AL, AA>
+        + HtlcRedeemed<AL, AA>
+        + HtlcRefunded<AL, AA>
+        + HtlcConfirmed<AL>
+        + HtlcPending<AL, AA>,
 {
-    let deployed = dependencies
-        .htlc_deployed(htlc_params.clone(), start_of_swap)
-        .await?;
-    co.yield_(SwapEvent::AlphaDeployed(deployed.clone())).await;
+    // The last event seen by a previous run may have been orphaned since.
+    if !known_state.is_confirmed() {
+        if let Some(transaction) = known_state.last_transaction() {
+            let confirmed = confirm::<_, AL, _>(
+                dependencies,
+                co,
+                htlc_params.ledger,
+                transaction,
+                watch_from,
+                SwapEvent::AlphaConfirmed,
+                SwapEvent::AlphaOrphaned,
+            )
+            .await?;
 
-    let funded = dependencies
-        .htlc_funded(htlc_params.clone(), &deployed, start_of_swap)
-        .await?;
-    co.yield_(SwapEvent::AlphaFunded(funded.clone())).await;
+            if confirmed {
+                known_state.transition_to_confirmed();
+            } else {
+                known_state.transition_to_orphaned(&htlc_params.asset);
+            }
+        }
+    }
 
-    let redeemed = dependencies.htlc_redeemed(htlc_params.clone(), &deployed, start_of_swap);
+    let known_htlc_state = HtlcState::from(&known_state);
 
-    let refunded = dependencies.htlc_refunded(htlc_params, &deployed, start_of_swap);
+    if let HtlcState::Redeemed | HtlcState::Refunded = known_htlc_state {
+        return Ok(());
+    }
 
-    match future::try_select(redeemed, refunded).await {
-        Ok(Either::Left((redeemed, _))) => {
-            co.yield_(SwapEvent::AlphaRedeemed(redeemed.clone())).await;
-        }
-        Ok(Either::Right((refunded, _))) => {
-            co.yield_(SwapEvent::AlphaRefunded(refunded.clone())).await;
+    let deployed = match known_state.deployed() {
+        Some(deployed) => deployed,
+        None => loop {
+            let deployed = or_pending(
+                co,
+                dependencies.htlc_deployed(htlc_params.clone(), watch_from),
+                dependencies.htlc_pending(htlc_params.clone(), None),
+                SwapEvent::AlphaPending,
+                || SwapEvent::AlphaPendingLeft,
+            )
+            .await?;
+            co.yield_(SwapEvent::AlphaDeployed(deployed.clone())).await;
+
+            if confirm::<_, AL, _>(
+                dependencies,
+                co,
+                htlc_params.ledger,
+                &deployed.transaction,
+                watch_from,
+                SwapEvent::AlphaConfirmed,
+                SwapEvent::AlphaOrphaned,
+            )
+            .await?
+            {
+                break deployed;
+            }
+        },
+    };
+
+    if let HtlcState::NotDeployed | HtlcState::Deployed = known_htlc_state {
+        loop {
+            let funded = or_pending(
+                co,
+                dependencies.htlc_funded(htlc_params.clone(), &deployed, watch_from),
+                dependencies.htlc_pending(htlc_params.clone(), Some(&deployed)),
+                SwapEvent::AlphaPending,
+                || SwapEvent::AlphaPendingLeft,
+            )
+            .await?;
+            co.yield_(SwapEvent::AlphaFunded(funded.clone())).await;
+
+            if confirm::<_, AL, _>(
+                dependencies,
+                co,
+                htlc_params.ledger,
+                &funded.transaction,
+                watch_from,
+                SwapEvent::AlphaConfirmed,
+                SwapEvent::AlphaOrphaned,
+            )
+            .await?
+            {
+                break;
+            }
         }
-        Err(either) => {
-            let (error, _other_future) = either.factor_first();
+    }
+
+    loop {
+        let redeemed = dependencies.htlc_redeemed(htlc_params.clone(), &deployed, watch_from);
+
+        let refunded = dependencies.htlc_refunded(htlc_params.clone(), &deployed, watch_from);
+
+        let redeemed_or_refunded = async {
+            match future::try_select(redeemed, refunded).await {
+                Ok(Either::Left((redeemed, _))) => Ok(Either::Left(redeemed)),
+                Ok(Either::Right((refunded, _))) => Ok(Either::Right(refunded)),
+                Err(either) => {
+                    let (error, _other_future) = either.factor_first();
+
+                    Err(error)
+                }
+            }
+        };
+
+        let transaction = match or_pending(
+            co,
+            redeemed_or_refunded,
+            dependencies.htlc_pending(htlc_params.clone(), Some(&deployed)),
+            SwapEvent::AlphaPending,
+            || SwapEvent::AlphaPendingLeft,
+        )
+        .await?
+        {
+            Either::Left(redeemed) => {
+                co.yield_(SwapEvent::AlphaRedeemed(redeemed.clone())).await;
+
+                redeemed.transaction
+            }
+            Either::Right(refunded) => {
+                co.yield_(SwapEvent::AlphaRefunded(refunded.clone())).await;
 
-            return Err(error);
+                refunded.transaction
+            }
+        };
+
+        if confirm::<_, AL, _>(
+            dependencies,
+            co,
+            htlc_params.ledger,
+            &transaction,
+            watch_from,
+            SwapEvent::AlphaConfirmed,
+            SwapEvent::AlphaOrphaned,
+        )
+        .await?
+        {
+            return Ok(());
         }
     }
-
-    Ok(())
 }
 
 /// Returns a future that waits for events on beta ledger to happen.
 ///
 /// Each event is yielded through the controller handle (co) of the coroutine.
+/// Events that are already part of `known_state` are not watched for again,
+/// the ledger is only looked at from `watch_from` on.
 async fn watch_beta_ledger<D, AL, AA, BL, BA>(
     dependencies: &D,
     co: &Co<SwapEventOnLedger<AL, BL, AA, BA>>,
     htlc_params: HtlcParams<BL, BA, BL::Identity>,
-    start_of_swap: NaiveDateTime,
+    watch_from: NaiveDateTime,
+    mut known_state: LedgerState<BL::HtlcLocation, BL::Transaction, BA>,
 ) -> anyhow::Result<()>
 where
     AL: Ledger,
     BL: Ledger,
     AA: Asset,
     BA: Asset,
-    D: HtlcFunded<BL, BA> + HtlcDeployed<BL, BA> + HtlcRedeemed<BL, BA> + HtlcRefunded<BL, BA>,
+    D: HtlcFunded<BL, BA>
+        + HtlcDeployed<BL, BA>
+        + HtlcRedeemed<BL, BA>
+        + HtlcRefunded<BL, BA>
+        + HtlcConfirmed<BL>
+        + HtlcPending<BL, BA>,
 {
-    let deployed = dependencies
-        .htlc_deployed(htlc_params.clone(), start_of_swap)
-        .await?;
-    co.yield_(SwapEvent::BetaDeployed(deployed.clone())).await;
+    // The last event seen by a previous run may have been orphaned since.
+    if !known_state.is_confirmed() {
+        if let Some(transaction) = known_state.last_transaction() {
+            let confirmed = confirm::<_, BL, _>(
+                dependencies,
+                co,
+                htlc_params.ledger,
+                transaction,
+                watch_from,
+                SwapEvent::BetaConfirmed,
+                SwapEvent::BetaOrphaned,
+            )
+            .await?;
+
+            if confirmed {
+                known_state.transition_to_confirmed();
+            } else {
+                known_state.transition_to_orphaned(&htlc_params.asset);
+            }
+        }
+    }
+
+    let known_htlc_state = HtlcState::from(&known_state);
+
+    if let HtlcState::Redeemed | HtlcState::Refunded = known_htlc_state {
+        return Ok(());
+    }
+
+    let deployed = match known_state.deployed() {
+        Some(deployed) => deployed,
+        None => loop {
+            let deployed = or_pending(
+                co,
+                dependencies.htlc_deployed(htlc_params.clone(), watch_from),
+                dependencies.htlc_pending(htlc_params.clone(), None),
+                SwapEvent::BetaPending,
+                || SwapEvent::BetaPendingLeft,
+            )
+            .await?;
+            co.yield_(SwapEvent::BetaDeployed(deployed.clone())).await;
+
+            if confirm::<_, BL, _>(
+                dependencies,
+                co,
+                htlc_params.ledger,
+                &deployed.transaction,
+                watch_from,
+                SwapEvent::BetaConfirmed,
+                SwapEvent::BetaOrphaned,
+            )
+            .await?
+            {
+                break deployed;
+            }
+        },
+    };
+
+    if let HtlcState::NotDeployed | HtlcState::Deployed = known_htlc_state {
+        loop {
+            let funded = or_pending(
+                co,
+                dependencies.htlc_funded(htlc_params.clone(), &deployed, watch_from),
+                dependencies.htlc_pending(htlc_params.clone(), Some(&deployed)),
+                SwapEvent::BetaPending,
+                || SwapEvent::BetaPendingLeft,
+            )
+            .await?;
+            co.yield_(SwapEvent::BetaFunded(funded.clone())).await;
+
+            if confirm::<_, BL, _>(
+                dependencies,
+                co,
+                htlc_params.ledger,
+                &funded.transaction,
+                watch_from,
+                SwapEvent::BetaConfirmed,
+                SwapEvent::BetaOrphaned,
+            )
+            .await?
+            {
+                break;
+            }
+        }
+    }
+
+    loop {
+        let redeemed = dependencies.htlc_redeemed(htlc_params.clone(), &deployed, watch_from);
 
-    let funded = dependencies
-        .htlc_funded(htlc_params.clone(), &deployed, start_of_swap)
-        .await?;
-    co.yield_(SwapEvent::BetaFunded(funded.clone())).await;
+        let refunded = dependencies.htlc_refunded(htlc_params.clone(), &deployed, watch_from);
 
-    let redeemed = dependencies.htlc_redeemed(htlc_params.clone(), &deployed, start_of_swap);
+        let redeemed_or_refunded = async {
+            match future::try_select(redeemed, refunded).await {
+                Ok(Either::Left((redeemed, _))) => Ok(Either::Left(redeemed)),
+                Ok(Either::Right((refunded, _))) => Ok(Either::Right(refunded)),
+                Err(either) => {
+                    let (error, _other_future) = either.factor_first();
 
-    let refunded = dependencies.htlc_refunded(htlc_params, &deployed, start_of_swap);
+                    Err(error)
+                }
+            }
+        };
+
+        let transaction = match or_pending(
+            co,
+            redeemed_or_refunded,
+            dependencies.htlc_pending(htlc_params.clone(), Some(&deployed)),
+            SwapEvent::BetaPending,
+            || SwapEvent::BetaPendingLeft,
+        )
+        .await?
+        {
+            Either::Left(redeemed) => {
+                co.yield_(SwapEvent::BetaRedeemed(redeemed.clone())).await;
+
+                redeemed.transaction
+            }
+            Either::Right(refunded) => {
+                co.yield_(SwapEvent::BetaRefunded(refunded.clone())).await;
 
-    match future::try_select(redeemed, refunded).await {
-        Ok(Either::Left((redeemed, _))) => {
-            co.yield_(SwapEvent::BetaRedeemed(redeemed.clone())).await;
+                refunded.transaction
+            }
+        };
+
+        if confirm::<_, BL, _>(
+            dependencies,
+            co,
+            htlc_params.ledger,
+            &transaction,
+            watch_from,
+            SwapEvent::BetaConfirmed,
+            SwapEvent::BetaOrphaned,
+        )
+        .await?
+        {
+            return Ok(());
         }
-        Ok(Either::Right((refunded, _))) => {
-            co.yield_(SwapEvent::BetaRefunded(refunded.clone())).await;
+    }
+}
+
+/// Waits for `event`, yielding the transactions of the HTLC that show up as
+/// pending before they are mined and `pending_left_event` once they leave the
+/// mempool, e.g. because they were replaced or evicted.
+///
+/// Watching pending transactions is best effort, if that fails we keep waiting
+/// for `event` only.
+async fn or_pending<E, R, T>(
+    co: &Co<E>,
+    event: impl Future<Output = anyhow::Result<R>>,
+    pending: anyhow::Result<Watch<PendingChange<Pending<T>>>>,
+    pending_event: fn(Pending<T>) -> E,
+    pending_left_event: fn() -> E,
+) -> anyhow::Result<R> {
+    let mut event = Box::pin(event);
+    let mut pending = match pending {
+        Ok(pending) => pending,
+        Err(e) => {
+            tracing::warn!("failed to watch pending transactions: {:?}", e);
+            return event.await;
         }
-        Err(either) => {
-            let (error, _other_future) = either.factor_first();
+    };
 
-            return Err(error);
+    loop {
+        match future::select(event, Box::pin(pending.next())).await {
+            Either::Left((event, _)) => return event,
+            Either::Right((Ok(change), unfinished)) => {
+                event = unfinished;
+
+                match change {
+                    PendingChange::Entered(pending) => co.yield_(pending_event(pending)).await,
+                    PendingChange::Left => co.yield_(pending_left_event()).await,
+                }
+            }
+            Either::Right((Err(e), event)) => {
+                tracing::warn!("failed to watch pending transactions: {:?}", e);
+                return event.await;
+            }
         }
     }
+}
 
-    Ok(())
+/// Waits until `transaction` is final and yields `confirmed` with the time of
+/// its block or `orphaned` accordingly.
+///
+/// Returns whether the transaction was confirmed.
+async fn confirm<D, L, E>(
+    dependencies: &D,
+    co: &Co<E>,
+    ledger: L,
+    transaction: &L::Transaction,
+    start_of_swap: NaiveDateTime,
+    confirmed: fn(NaiveDateTime) -> E,
+    orphaned: E,
+) -> anyhow::Result<bool>
+where
+    D: HtlcConfirmed<L>,
+    L: Ledger,
+{
+    match dependencies
+        .htlc_confirmed(ledger, transaction, start_of_swap)
+        .await?
+    {
+        Confirmation::Confirmed { block_time } => {
+            co.yield_(confirmed(block_time)).await;
+            Ok(true)
+        }
+        Confirmation::Orphaned => {
+            co.yield_(orphaned).await;
+            Ok(false)
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -307,7 +836,7 @@ pub type SwapEventOnLedger<AL, BL, AA, BA> = SwapEvent<
     BA,
 >;
 
-#[derive(Debug, Clone, PartialEq, strum_macros::Display)]
+#[derive(Debug, Clone, PartialEq, strum_macros::Display, Serialize, Deserialize)]
 pub enum SwapEvent<AH, AT, BH, BT, AA, BA>
 where
     AA: Asset,
@@ -317,11 +846,35 @@ where
     AlphaFunded(Funded<AT, AA>),
     AlphaRedeemed(Redeemed<AT>),
     AlphaRefunded(Refunded<AT>),
+    /// The transaction of the last alpha event reached the confirmation
+    /// threshold of the ledger in a block mined at the given time.
+    AlphaConfirmed(NaiveDateTime),
+    /// A reorg removed the transaction of the last alpha event from the
+    /// chain, the event is reverted.
+    AlphaOrphaned,
+    /// A transaction of the alpha HTLC was broadcast but is not mined yet.
+    AlphaPending(Pending<AT>),
+    /// The pending transaction of the alpha HTLC left the mempool.
+    AlphaPendingLeft,
 
     BetaDeployed(Deployed<BT, BH>),
     BetaFunded(Funded<BT, BA>),
     BetaRedeemed(Redeemed<BT>),
     BetaRefunded(Refunded<BT>),
+    /// The transaction of the last beta event reached the confirmation
+    /// threshold of the ledger in a block mined at the given time.
+    BetaConfirmed(NaiveDateTime),
+    /// A reorg removed the transaction of the last beta event from the chain,
+    /// the event is reverted.
+    BetaOrphaned,
+    /// A transaction of the beta HTLC was broadcast but is not mined yet.
+    BetaPending(Pending<BT>),
+    /// The pending transaction of the beta HTLC left the mempool.
+    BetaPendingLeft,
+
+    /// The state of both HTLCs decided the outcome of the swap, no more
+    /// events follow.
+    Finished(SwapOutcome),
 }
 
 #[cfg(test)]
@@ -347,4 +900,30 @@ mod tests {
 
         assert_eq!(formatted, "BetaDeployed")
     }
+
+    #[test]
+    fn given_no_confirmed_transaction_resumes_from_start_of_swap() {
+        let start_of_swap = NaiveDateTime::from_timestamp(1_000_000, 0);
+
+        assert_eq!(resume_from(start_of_swap, None), start_of_swap)
+    }
+
+    #[test]
+    fn given_confirmed_transaction_resumes_from_its_block_less_margin() {
+        let start_of_swap = NaiveDateTime::from_timestamp(1_000_000, 0);
+        let block_time = NaiveDateTime::from_timestamp(2_000_000, 0);
+
+        assert_eq!(
+            resume_from(start_of_swap, Some(block_time)),
+            NaiveDateTime::from_timestamp(2_000_000 - RESUME_MARGIN_SECS, 0)
+        )
+    }
+
+    #[test]
+    fn never_resumes_before_start_of_swap() {
+        let start_of_swap = NaiveDateTime::from_timestamp(1_000_000, 0);
+        let block_time = NaiveDateTime::from_timestamp(1_000_060, 0);
+
+        assert_eq!(resume_from(start_of_swap, Some(block_time)), start_of_swap)
+    }
 }
@@ -1,21 +1,24 @@
 use crate::{
     asset::{ethereum::FromWei, Erc20, Erc20Quantity, Ether},
-    btsieve::ethereum::{
-        watch_for_contract_creation, watch_for_event, Cache, Event, Topic, Web3Connector,
+    btsieve::{
+        ethereum::{Cache, Event, Topic, Web3Connector},
+        failover::Failover,
+        Mempool, PendingChange, Scanner, Watch,
     },
-    ethereum::{Address, Transaction, H256, U256},
+    ethereum::{Address, Block, Transaction, H256, U256},
     swap_protocols::{
         ledger::Ethereum,
         rfc003::{
             create_swap::HtlcParams,
             events::{
-                Deployed, Funded, HtlcDeployed, HtlcFunded, HtlcRedeemed, HtlcRefunded, Redeemed,
-                Refunded,
+                Deployed, Funded, HtlcDeployed, HtlcFunded, HtlcPending, HtlcRedeemed,
+                HtlcRefunded, Pending, PendingState, Redeemed, Refunded,
             },
             Secret,
         },
     },
 };
+use blockchain_contracts::ethereum::rfc003::erc20_htlc::Erc20Htlc;
 use chrono::NaiveDateTime;
 use tracing_futures::Instrument;
 
@@ -27,7 +30,7 @@ lazy_static::lazy_static! {
 }
 
 #[async_trait::async_trait]
-impl HtlcFunded<Ethereum, Ether> for Cache<Web3Connector> {
+impl HtlcFunded<Ethereum, Ether> for Scanner<Cache<Failover<Web3Connector>>, Block<Transaction>> {
     async fn htlc_funded(
         &self,
         _htlc_params: HtlcParams<Ethereum, Ether, Address>,
@@ -42,17 +45,16 @@ impl HtlcFunded<Ethereum, Ether> for Cache<Web3Connector> {
 }
 
 #[async_trait::async_trait]
-impl HtlcDeployed<Ethereum, Ether> for Cache<Web3Connector> {
+impl HtlcDeployed<Ethereum, Ether> for Scanner<Cache<Failover<Web3Connector>>, Block<Transaction>> {
     async fn htlc_deployed(
         &self,
         htlc_params: HtlcParams<Ethereum, Ether, Address>,
         start_of_swap: NaiveDateTime,
     ) -> anyhow::Result<Deployed<Transaction, Address>> {
-        let connector = self.clone();
-        let (transaction, location) =
-            watch_for_contract_creation(connector, start_of_swap, htlc_params.bytecode())
-                .instrument(tracing::info_span!("htlc_deployed"))
-                .await?;
+        let (transaction, location) = self
+            .watch_for_contract_creation(start_of_swap, htlc_params.bytecode())
+            .instrument(tracing::info_span!("htlc_deployed"))
+            .await?;
 
         Ok(Deployed {
             transaction,
@@ -62,20 +64,20 @@ impl HtlcDeployed<Ethereum, Ether> for Cache<Web3Connector> {
 }
 
 #[async_trait::async_trait]
-impl HtlcRedeemed<Ethereum, Ether> for Cache<Web3Connector> {
+impl HtlcRedeemed<Ethereum, Ether> for Scanner<Cache<Failover<Web3Connector>>, Block<Transaction>> {
     async fn htlc_redeemed(
         &self,
         _htlc_params: HtlcParams<Ethereum, Ether, Address>,
         htlc_deployment: &Deployed<Transaction, Address>,
         start_of_swap: NaiveDateTime,
     ) -> anyhow::Result<Redeemed<Transaction>> {
-        let connector = self.clone();
         let event = Event {
             address: htlc_deployment.location,
             topics: vec![Some(Topic(*REDEEM_LOG_MSG))],
         };
 
-        let (transaction, log) = watch_for_event(connector, start_of_swap, event)
+        let (transaction, log) = self
+            .watch_for_event(start_of_swap, event)
             .instrument(tracing::info_span!("htlc_redeemed"))
             .await?;
 
@@ -91,20 +93,20 @@ impl HtlcRedeemed<Ethereum, Ether> for Cache<Web3Connector> {
 }
 
 #[async_trait::async_trait]
-impl HtlcRefunded<Ethereum, Ether> for Cache<Web3Connector> {
+impl HtlcRefunded<Ethereum, Ether> for Scanner<Cache<Failover<Web3Connector>>, Block<Transaction>> {
     async fn htlc_refunded(
         &self,
         _htlc_params: HtlcParams<Ethereum, Ether, Address>,
         htlc_deployment: &Deployed<Transaction, Address>,
         start_of_swap: NaiveDateTime,
     ) -> anyhow::Result<Refunded<Transaction>> {
-        let connector = self.clone();
         let event = Event {
             address: htlc_deployment.location,
             topics: vec![Some(Topic(*REFUND_LOG_MSG))],
         };
 
-        let (transaction, _) = watch_for_event(connector, start_of_swap, event)
+        let (transaction, _) = self
+            .watch_for_event(start_of_swap, event)
             .instrument(tracing::info_span!("htlc_refunded"))
             .await?;
 
@@ -113,15 +115,13 @@ impl HtlcRefunded<Ethereum, Ether> for Cache<Web3Connector> {
 }
 
 #[async_trait::async_trait]
-impl HtlcFunded<Ethereum, Erc20> for Cache<Web3Connector> {
+impl HtlcFunded<Ethereum, Erc20> for Scanner<Cache<Failover<Web3Connector>>, Block<Transaction>> {
     async fn htlc_funded(
         &self,
         htlc_params: HtlcParams<Ethereum, Erc20, Address>,
         htlc_deployment: &Deployed<Transaction, Address>,
         start_of_swap: NaiveDateTime,
     ) -> anyhow::Result<Funded<Transaction, Erc20>> {
-        let connector = self.clone();
-
         let event = Event {
             address: htlc_params.asset.token_contract,
             topics: vec![
@@ -131,7 +131,8 @@ impl HtlcFunded<Ethereum, Erc20> for Cache<Web3Connector> {
             ],
         };
 
-        let (transaction, log) = watch_for_event(connector, start_of_swap, event)
+        let (transaction, log) = self
+            .watch_for_event(start_of_swap, event)
             .instrument(tracing::info_span!("htlc_funded"))
             .await?;
 
@@ -143,18 +144,16 @@ impl HtlcFunded<Ethereum, Erc20> for Cache<Web3Connector> {
 }
 
 #[async_trait::async_trait]
-impl HtlcDeployed<Ethereum, Erc20> for Cache<Web3Connector> {
+impl HtlcDeployed<Ethereum, Erc20> for Scanner<Cache<Failover<Web3Connector>>, Block<Transaction>> {
     async fn htlc_deployed(
         &self,
         htlc_params: HtlcParams<Ethereum, Erc20, Address>,
         start_of_swap: NaiveDateTime,
     ) -> anyhow::Result<Deployed<Transaction, Address>> {
-        let connector = self.clone();
-
-        let (transaction, location) =
-            watch_for_contract_creation(connector, start_of_swap, htlc_params.bytecode())
-                .instrument(tracing::info_span!("htlc_deployed"))
-                .await?;
+        let (transaction, location) = self
+            .watch_for_contract_creation(start_of_swap, htlc_params.bytecode())
+            .instrument(tracing::info_span!("htlc_deployed"))
+            .await?;
 
         Ok(Deployed {
             transaction,
@@ -164,20 +163,20 @@ impl HtlcDeployed<Ethereum, Erc20> for Cache<Web3Connector> {
 }
 
 #[async_trait::async_trait]
-impl HtlcRedeemed<Ethereum, Erc20> for Cache<Web3Connector> {
+impl HtlcRedeemed<Ethereum, Erc20> for Scanner<Cache<Failover<Web3Connector>>, Block<Transaction>> {
     async fn htlc_redeemed(
         &self,
         _htlc_params: HtlcParams<Ethereum, Erc20, Address>,
         htlc_deployment: &Deployed<Transaction, Address>,
         start_of_swap: NaiveDateTime,
     ) -> anyhow::Result<Redeemed<Transaction>> {
-        let connector = self.clone();
         let event = Event {
             address: htlc_deployment.location,
             topics: vec![Some(Topic(*REDEEM_LOG_MSG))],
         };
 
-        let (transaction, log) = watch_for_event(connector, start_of_swap, event)
+        let (transaction, log) = self
+            .watch_for_event(start_of_swap, event)
             .instrument(tracing::info_span!("htlc_redeemed"))
             .await?;
 
@@ -193,23 +192,144 @@ impl HtlcRedeemed<Ethereum, Erc20> for Cache<Web3Connector> {
 }
 
 #[async_trait::async_trait]
-impl HtlcRefunded<Ethereum, Erc20> for Cache<Web3Connector> {
+impl HtlcRefunded<Ethereum, Erc20> for Scanner<Cache<Failover<Web3Connector>>, Block<Transaction>> {
     async fn htlc_refunded(
         &self,
         _htlc_params: HtlcParams<Ethereum, Erc20, Address>,
         htlc_deployment: &Deployed<Transaction, Address>,
         start_of_swap: NaiveDateTime,
     ) -> anyhow::Result<Refunded<Transaction>> {
-        let connector = self.clone();
         let event = Event {
             address: htlc_deployment.location,
             topics: vec![Some(Topic(*REFUND_LOG_MSG))],
         };
 
-        let (transaction, _) = watch_for_event(connector, start_of_swap, event)
+        let (transaction, _) = self
+            .watch_for_event(start_of_swap, event)
             .instrument(tracing::info_span!("htlc_refunded"))
             .await?;
 
         Ok(Refunded { transaction })
     }
 }
+
+impl HtlcPending<Ethereum, Ether> for Mempool<Failover<Web3Connector>, H256, Transaction> {
+    fn htlc_pending(
+        &self,
+        htlc_params: HtlcParams<Ethereum, Ether, Address>,
+        htlc_deployment: Option<&Deployed<Transaction, Address>>,
+    ) -> anyhow::Result<Watch<PendingChange<Pending<Transaction>>>> {
+        let watch = match htlc_deployment {
+            // The Ether HTLC is funded with the value of the deployment.
+            None => {
+                let bytecode = htlc_params.bytecode();
+
+                self.watch(move |transaction: &Transaction| {
+                    if transaction.to.is_none() && transaction.input == bytecode {
+                        Some(Pending {
+                            transaction: transaction.clone(),
+                            state: PendingState::Funding,
+                        })
+                    } else {
+                        None
+                    }
+                })
+            }
+            Some(htlc_deployment) => {
+                let htlc = htlc_deployment.location;
+
+                self.watch(move |transaction: &Transaction| {
+                    redeem_or_refund(transaction, htlc).map(|state| Pending {
+                        transaction: transaction.clone(),
+                        state,
+                    })
+                })
+            }
+        };
+
+        Ok(watch)
+    }
+}
+
+impl HtlcPending<Ethereum, Erc20> for Mempool<Failover<Web3Connector>, H256, Transaction> {
+    fn htlc_pending(
+        &self,
+        htlc_params: HtlcParams<Ethereum, Erc20, Address>,
+        htlc_deployment: Option<&Deployed<Transaction, Address>>,
+    ) -> anyhow::Result<Watch<PendingChange<Pending<Transaction>>>> {
+        // The ERC20 HTLC is funded by a transfer after it is deployed.
+        let htlc = match htlc_deployment {
+            Some(htlc_deployment) => htlc_deployment.location,
+            None => return Ok(Watch::never()),
+        };
+
+        // The payload starts with the selector of `transfer` and the address of
+        // the HTLC, followed by the quantity that might differ from the
+        // expected one.
+        let transfer_to_htlc = Erc20Htlc::transfer_erc20_tx_payload(
+            htlc_params.asset.quantity.into(),
+            blockchain_contracts::ethereum::Address(htlc.into()),
+        )[..36]
+            .to_vec();
+        let token_contract = htlc_params.asset.token_contract;
+
+        Ok(self.watch(move |transaction: &Transaction| {
+            let state = if transaction.to == Some(token_contract)
+                && transaction.input.0.starts_with(&transfer_to_htlc)
+            {
+                Some(PendingState::Funding)
+            } else {
+                redeem_or_refund(transaction, htlc)
+            };
+
+            state.map(|state| Pending {
+                transaction: transaction.clone(),
+                state,
+            })
+        }))
+    }
+}
+
+/// Redeeming calls the HTLC with the secret, refunding calls it without any
+/// data.
+fn redeem_or_refund(transaction: &Transaction, htlc: Address) -> Option<PendingState> {
+    if transaction.to != Some(htlc) {
+        return None;
+    }
+
+    match transaction.input.0.len() {
+        0 => Some(PendingState::Refunding),
+        32 => Some(PendingState::Redeeming),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ethereum::Bytes;
+    use spectral::prelude::*;
+
+    fn call(to: Address, input: Vec<u8>) -> Transaction {
+        Transaction {
+            to: Some(to),
+            input: Bytes(input),
+            ..Transaction::default()
+        }
+    }
+
+    #[test]
+    fn calls_to_htlc_are_classified_by_their_data() {
+        let htlc = Address::repeat_byte(1);
+
+        assert_that(&redeem_or_refund(&call(htlc, vec![]), htlc))
+            .is_equal_to(Some(PendingState::Refunding));
+        assert_that(&redeem_or_refund(&call(htlc, vec![0u8; 32]), htlc))
+            .is_equal_to(Some(PendingState::Redeeming));
+        assert_that(&redeem_or_refund(
+            &call(Address::repeat_byte(2), vec![]),
+            htlc,
+        ))
+        .is_equal_to(None);
+    }
+}
@@ -4,6 +4,7 @@
 
 use crate::{
     asset::Asset,
+    btsieve::{Confirmation, PendingChange, Watch},
     swap_protocols::rfc003::{create_swap::HtlcParams, ledger::Ledger, Secret},
 };
 use chrono::NaiveDateTime;
@@ -32,6 +33,23 @@ pub struct Refunded<T> {
     pub transaction: T,
 }
 
+/// A transaction of the HTLC that was broadcast but is not included in a block
+/// yet.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Pending<T> {
+    pub transaction: T,
+    pub state: PendingState,
+}
+
+/// What the pending transaction is going to do to the HTLC once it is mined.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, strum_macros::Display)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PendingState {
+    Funding,
+    Redeeming,
+    Refunding,
+}
+
 #[async_trait::async_trait]
 pub trait HtlcFunded<L: Ledger, A: Asset>: Send + Sync + Sized + 'static {
     async fn htlc_funded(
@@ -70,3 +88,30 @@ pub trait HtlcRefunded<L: Ledger, A: Asset>: Send + Sync + Sized + 'static {
         start_of_swap: NaiveDateTime,
     ) -> anyhow::Result<Refunded<L::Transaction>>;
 }
+
+/// Waits for a transaction that caused an HTLC event on `ledger` to become
+/// final.
+#[async_trait::async_trait]
+pub trait HtlcConfirmed<L: Ledger>: Send + Sync + Sized + 'static {
+    async fn htlc_confirmed(
+        &self,
+        ledger: L,
+        transaction: &L::Transaction,
+        start_of_swap: NaiveDateTime,
+    ) -> anyhow::Result<Confirmation>;
+}
+
+/// Watches the transactions that are not included in a block yet for the
+/// transactions of an HTLC.
+///
+/// Without a deployment, we look for transactions that deploy and fund the
+/// HTLC at once. Otherwise we look for transactions that fund, redeem or
+/// refund the deployed HTLC. The watch never yields anything if pending
+/// transactions are not watched on the ledger.
+pub trait HtlcPending<L: Ledger, A: Asset>: Send + Sync + Sized + 'static {
+    fn htlc_pending(
+        &self,
+        htlc_params: HtlcParams<L, A, L::Identity>,
+        htlc_deployment: Option<&Deployed<L::Transaction, L::HtlcLocation>>,
+    ) -> anyhow::Result<Watch<PendingChange<Pending<L::Transaction>>>>;
+}
@@ -0,0 +1,127 @@
+use crate::timestamp::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Safety margins that the expiries of a swap have to respect.
+///
+/// Beta expires first: Alice has to redeem beta, and thereby reveal the
+/// secret, before Bob can refund. The gap between beta and alpha expiry is the
+/// time Bob has to redeem alpha with the revealed secret.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, derivative::Derivative)]
+#[derivative(Default)]
+#[serde(default)]
+pub struct ExpiryMargins {
+    #[derivative(Default(value = "60 * 60"))]
+    pub min_gap_secs: u32,
+    #[derivative(Default(value = "60 * 60"))]
+    pub min_time_to_expiry_secs: u32,
+    #[derivative(Default(value = "60 * 60 * 24 * 7"))]
+    pub max_duration_secs: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, thiserror::Error)]
+pub enum UnsafeExpiries {
+    #[error("alpha expiry {alpha_expiry} is less than {min_gap_secs} seconds after beta expiry {beta_expiry}")]
+    GapTooSmall {
+        alpha_expiry: u32,
+        beta_expiry: u32,
+        min_gap_secs: u32,
+    },
+    #[error(
+        "beta expiry {beta_expiry} is less than {min_time_to_expiry_secs} seconds in the future"
+    )]
+    ExpiresTooSoon {
+        beta_expiry: u32,
+        min_time_to_expiry_secs: u32,
+    },
+    #[error("alpha expiry {alpha_expiry} is more than {max_duration_secs} seconds in the future")]
+    LastsTooLong {
+        alpha_expiry: u32,
+        max_duration_secs: u32,
+    },
+}
+
+impl ExpiryMargins {
+    pub fn validate(
+        &self,
+        alpha_expiry: Timestamp,
+        beta_expiry: Timestamp,
+        now: Timestamp,
+    ) -> Result<(), UnsafeExpiries> {
+        if alpha_expiry < beta_expiry.plus(self.min_gap_secs) {
+            return Err(UnsafeExpiries::GapTooSmall {
+                alpha_expiry: alpha_expiry.into(),
+                beta_expiry: beta_expiry.into(),
+                min_gap_secs: self.min_gap_secs,
+            });
+        }
+
+        if beta_expiry < now.plus(self.min_time_to_expiry_secs) {
+            return Err(UnsafeExpiries::ExpiresTooSoon {
+                beta_expiry: beta_expiry.into(),
+                min_time_to_expiry_secs: self.min_time_to_expiry_secs,
+            });
+        }
+
+        if alpha_expiry > now.plus(self.max_duration_secs) {
+            return Err(UnsafeExpiries::LastsTooLong {
+                alpha_expiry: alpha_expiry.into(),
+                max_duration_secs: self.max_duration_secs,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spectral::prelude::*;
+
+    const NOW: u32 = 1_500_000_000;
+    const HOUR: u32 = 60 * 60;
+
+    fn validate(alpha_expiry: u32, beta_expiry: u32) -> Result<(), UnsafeExpiries> {
+        ExpiryMargins::default().validate(
+            Timestamp::from(alpha_expiry),
+            Timestamp::from(beta_expiry),
+            Timestamp::from(NOW),
+        )
+    }
+
+    #[test]
+    fn accepts_default_expiries_of_post_swap() {
+        assert_that(&validate(NOW + 24 * HOUR, NOW + 12 * HOUR)).is_ok();
+    }
+
+    #[test]
+    fn rejects_inverted_expiries() {
+        assert_that(&validate(NOW + 12 * HOUR, NOW + 24 * HOUR)).is_err_containing(
+            UnsafeExpiries::GapTooSmall {
+                alpha_expiry: NOW + 12 * HOUR,
+                beta_expiry: NOW + 24 * HOUR,
+                min_gap_secs: HOUR,
+            },
+        );
+    }
+
+    #[test]
+    fn rejects_expiries_in_the_past() {
+        assert_that(&validate(NOW + HOUR, NOW - HOUR)).is_err_containing(
+            UnsafeExpiries::ExpiresTooSoon {
+                beta_expiry: NOW - HOUR,
+                min_time_to_expiry_secs: HOUR,
+            },
+        );
+    }
+
+    #[test]
+    fn rejects_expiries_too_far_in_the_future() {
+        assert_that(&validate(NOW + 8 * 24 * HOUR, NOW + 24 * HOUR)).is_err_containing(
+            UnsafeExpiries::LastsTooLong {
+                alpha_expiry: NOW + 8 * 24 * HOUR,
+                max_duration_secs: 7 * 24 * HOUR,
+            },
+        );
+    }
+}
@@ -8,6 +8,11 @@ use crate::{
 use serde::Serialize;
 use strum_macros::EnumDiscriminants;
 
+/// The state of an HTLC as observed on its ledger.
+///
+/// `confirmed` tells whether the transaction that caused the last transition
+/// is buried under enough blocks to be considered final. Until then, a reorg
+/// may orphan it and roll the state back.
 #[derive(Clone, Debug, PartialEq, EnumDiscriminants)]
 #[strum_discriminants(
     name(HtlcState),
@@ -19,12 +24,14 @@ pub enum LedgerState<H, T, A> {
     Deployed {
         htlc_location: H,
         deploy_transaction: T,
+        confirmed: bool,
     },
     Funded {
         htlc_location: H,
         deploy_transaction: T,
         fund_transaction: T,
         asset: A,
+        confirmed: bool,
     },
     Redeemed {
         htlc_location: H,
@@ -33,6 +40,7 @@ pub enum LedgerState<H, T, A> {
         redeem_transaction: T,
         asset: A,
         secret: Secret,
+        confirmed: bool,
     },
     Refunded {
         htlc_location: H,
@@ -40,12 +48,14 @@ pub enum LedgerState<H, T, A> {
         fund_transaction: T,
         refund_transaction: T,
         asset: A,
+        confirmed: bool,
     },
     IncorrectlyFunded {
         htlc_location: H,
         deploy_transaction: T,
         fund_transaction: T,
         asset: A,
+        confirmed: bool,
     },
 }
 
@@ -61,6 +71,7 @@ impl<T, H, A: Asset> LedgerState<H, T, A> {
                 *self = LedgerState::Deployed {
                     deploy_transaction: transaction,
                     htlc_location: location,
+                    confirmed: false,
                 }
             }
             other => panic!("expected state NotDeployed, got {}", HtlcState::from(other)),
@@ -74,12 +85,14 @@ impl<T, H, A: Asset> LedgerState<H, T, A> {
             LedgerState::Deployed {
                 deploy_transaction,
                 htlc_location,
+                ..
             } => {
                 *self = LedgerState::Funded {
                     deploy_transaction,
                     htlc_location,
                     fund_transaction: transaction,
                     asset,
+                    confirmed: false,
                 }
             }
             other => panic!("expected state Deployed, got {}", HtlcState::from(other)),
@@ -93,12 +106,14 @@ impl<T, H, A: Asset> LedgerState<H, T, A> {
             LedgerState::Deployed {
                 deploy_transaction,
                 htlc_location,
+                ..
             } => {
                 *self = LedgerState::IncorrectlyFunded {
                     deploy_transaction,
                     htlc_location,
                     fund_transaction: transaction,
                     asset,
+                    confirmed: false,
                 }
             }
             other => panic!("expected state Deployed, got {}", HtlcState::from(other)),
@@ -117,6 +132,7 @@ impl<T, H, A: Asset> LedgerState<H, T, A> {
                 htlc_location,
                 asset,
                 fund_transaction,
+                ..
             } => {
                 *self = LedgerState::Redeemed {
                     deploy_transaction,
@@ -125,6 +141,7 @@ impl<T, H, A: Asset> LedgerState<H, T, A> {
                     redeem_transaction: transaction,
                     asset,
                     secret,
+                    confirmed: false,
                 }
             }
             other => panic!("expected state Funded, got {}", HtlcState::from(other)),
@@ -140,12 +157,14 @@ impl<T, H, A: Asset> LedgerState<H, T, A> {
                 htlc_location,
                 asset,
                 fund_transaction,
+                ..
             }
             | LedgerState::IncorrectlyFunded {
                 deploy_transaction,
                 htlc_location,
                 asset,
                 fund_transaction,
+                ..
             } => {
                 *self = LedgerState::Refunded {
                     deploy_transaction,
@@ -153,6 +172,7 @@ impl<T, H, A: Asset> LedgerState<H, T, A> {
                     fund_transaction,
                     refund_transaction: transaction,
                     asset,
+                    confirmed: false,
                 }
             }
             other => panic!(
@@ -161,6 +181,162 @@ impl<T, H, A: Asset> LedgerState<H, T, A> {
             ),
         }
     }
+
+    pub fn transition_to_confirmed(&mut self) {
+        match self {
+            LedgerState::NotDeployed => {
+                panic!("expected a state with a transaction, got NotDeployed")
+            }
+            LedgerState::Deployed { confirmed, .. }
+            | LedgerState::Funded { confirmed, .. }
+            | LedgerState::Redeemed { confirmed, .. }
+            | LedgerState::Refunded { confirmed, .. }
+            | LedgerState::IncorrectlyFunded { confirmed, .. } => *confirmed = true,
+        }
+    }
+
+    /// Reverts the last transition because a reorg orphaned its transaction.
+    ///
+    /// The state we go back to was confirmed before we made the transition.
+    /// A refunded HTLC goes back to being funded or incorrectly funded
+    /// depending on the `expected_asset`.
+    pub fn transition_to_orphaned(&mut self, expected_asset: &A) {
+        match std::mem::replace(self, LedgerState::NotDeployed) {
+            LedgerState::NotDeployed => {
+                panic!("expected a state with a transaction, got NotDeployed")
+            }
+            LedgerState::Deployed { .. } => {}
+            LedgerState::Funded {
+                deploy_transaction,
+                htlc_location,
+                ..
+            }
+            | LedgerState::IncorrectlyFunded {
+                deploy_transaction,
+                htlc_location,
+                ..
+            } => {
+                *self = LedgerState::Deployed {
+                    deploy_transaction,
+                    htlc_location,
+                    confirmed: true,
+                }
+            }
+            LedgerState::Redeemed {
+                deploy_transaction,
+                htlc_location,
+                fund_transaction,
+                asset,
+                ..
+            } => {
+                *self = LedgerState::Funded {
+                    deploy_transaction,
+                    htlc_location,
+                    fund_transaction,
+                    asset,
+                    confirmed: true,
+                }
+            }
+            LedgerState::Refunded {
+                deploy_transaction,
+                htlc_location,
+                fund_transaction,
+                asset,
+                ..
+            } => {
+                *self = if asset == *expected_asset {
+                    LedgerState::Funded {
+                        deploy_transaction,
+                        htlc_location,
+                        fund_transaction,
+                        asset,
+                        confirmed: true,
+                    }
+                } else {
+                    LedgerState::IncorrectlyFunded {
+                        deploy_transaction,
+                        htlc_location,
+                        fund_transaction,
+                        asset,
+                        confirmed: true,
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<H, T, A> LedgerState<H, T, A> {
+    /// Whether the transaction of the last transition is confirmed.
+    pub fn is_confirmed(&self) -> bool {
+        match self {
+            LedgerState::NotDeployed => false,
+            LedgerState::Deployed { confirmed, .. }
+            | LedgerState::Funded { confirmed, .. }
+            | LedgerState::Redeemed { confirmed, .. }
+            | LedgerState::Refunded { confirmed, .. }
+            | LedgerState::IncorrectlyFunded { confirmed, .. } => *confirmed,
+        }
+    }
+
+    /// Returns the transaction of the last transition.
+    pub fn last_transaction(&self) -> Option<&T> {
+        match self {
+            LedgerState::NotDeployed => None,
+            LedgerState::Deployed {
+                deploy_transaction, ..
+            } => Some(deploy_transaction),
+            LedgerState::Funded {
+                fund_transaction, ..
+            }
+            | LedgerState::IncorrectlyFunded {
+                fund_transaction, ..
+            } => Some(fund_transaction),
+            LedgerState::Redeemed {
+                redeem_transaction, ..
+            } => Some(redeem_transaction),
+            LedgerState::Refunded {
+                refund_transaction, ..
+            } => Some(refund_transaction),
+        }
+    }
+}
+
+impl<H: Clone, T: Clone, A> LedgerState<H, T, A> {
+    /// Returns the deployment of the HTLC if it has already been observed.
+    pub fn deployed(&self) -> Option<Deployed<T, H>> {
+        match self {
+            LedgerState::NotDeployed => None,
+            LedgerState::Deployed {
+                htlc_location,
+                deploy_transaction,
+                ..
+            }
+            | LedgerState::Funded {
+                htlc_location,
+                deploy_transaction,
+                ..
+            }
+            | LedgerState::Redeemed {
+                htlc_location,
+                deploy_transaction,
+                ..
+            }
+            | LedgerState::Refunded {
+                htlc_location,
+                deploy_transaction,
+                ..
+            }
+            | LedgerState::IncorrectlyFunded {
+                htlc_location,
+                deploy_transaction,
+                ..
+            } => Some(Deployed {
+                transaction: deploy_transaction.clone(),
+                location: htlc_location.clone(),
+            }),
+        }
+    }
 }
 
 impl Default for HtlcState {
@@ -187,10 +363,57 @@ impl quickcheck::Arbitrary for HtlcState {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::asset;
+    use spectral::prelude::*;
+
     #[test]
     fn not_deployed_serializes_correctly_to_json() {
         let state = HtlcState::NotDeployed;
         let serialized = serde_json::to_string(&state).unwrap();
         assert_eq!(serialized, r#""NOT_DEPLOYED""#);
     }
+
+    fn funded(sat: u64) -> LedgerState<u8, u8, asset::Bitcoin> {
+        LedgerState::Funded {
+            htlc_location: 0,
+            deploy_transaction: 1,
+            fund_transaction: 2,
+            asset: asset::Bitcoin::from_sat(sat),
+            confirmed: true,
+        }
+    }
+
+    #[test]
+    fn orphaned_redeem_transaction_goes_back_to_confirmed_funded() {
+        let mut state = funded(100);
+        state.transition_to_redeemed(Redeemed {
+            transaction: 3,
+            secret: Secret::from(*b"hello world, you are beautiful!!"),
+        });
+        assert_that(&state.is_confirmed()).is_false();
+
+        state.transition_to_orphaned(&asset::Bitcoin::from_sat(100));
+
+        assert_that(&state).is_equal_to(funded(100));
+    }
+
+    #[test]
+    fn orphaned_refund_of_incorrect_funding_goes_back_to_incorrectly_funded() {
+        let mut state = funded(90);
+        state.transition_to_refunded(Refunded { transaction: 3 });
+        state.transition_to_orphaned(&asset::Bitcoin::from_sat(100));
+
+        assert_that(&HtlcState::from(&state)).is_equal_to(HtlcState::IncorrectlyFunded);
+        assert_that(&state.is_confirmed()).is_true();
+    }
+
+    #[test]
+    fn confirming_marks_last_transaction_as_final() {
+        let mut state = funded(100);
+        state.transition_to_refunded(Refunded { transaction: 3 });
+        state.transition_to_confirmed();
+
+        assert_that(&state.is_confirmed()).is_true();
+        assert_that(&state.last_transaction()).is_equal_to(Some(&3));
+    }
 }
@@ -0,0 +1,381 @@
+//! The HTLC of a swap on the Lightning Network is a hold invoice on the node
+//! of the redeemer that is paid by the node of the funder.
+//!
+//! Which of the two we are is decided by comparing the identity of our lnd
+//! node with the identities of the swap: The redeemer watches the invoice, the
+//! funder watches the payment.
+
+use crate::{
+    asset,
+    btsieve::lightning::{
+        watch_for_invoice, watch_for_payment, InvoiceState, LndApi, LndConnector, PaymentStatus,
+    },
+    lightning::{PublicKey, Transaction},
+    swap_protocols::{
+        ledger::Lightning,
+        rfc003::{
+            create_swap::HtlcParams,
+            events::{
+                Deployed, Funded, HtlcDeployed, HtlcFunded, HtlcRedeemed, HtlcRefunded, Redeemed,
+                Refunded,
+            },
+            SecretHash,
+        },
+    },
+};
+use chrono::NaiveDateTime;
+use tracing_futures::Instrument;
+
+#[async_trait::async_trait]
+impl HtlcDeployed<Lightning, asset::Bitcoin> for LndConnector {
+    async fn htlc_deployed(
+        &self,
+        htlc_params: HtlcParams<Lightning, asset::Bitcoin, PublicKey>,
+        _start_of_swap: NaiveDateTime,
+    ) -> anyhow::Result<Deployed<Transaction, SecretHash>> {
+        deployed(self, htlc_params)
+            .instrument(tracing::info_span!("htlc_deployed"))
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl HtlcFunded<Lightning, asset::Bitcoin> for LndConnector {
+    async fn htlc_funded(
+        &self,
+        htlc_params: HtlcParams<Lightning, asset::Bitcoin, PublicKey>,
+        _htlc_deployment: &Deployed<Transaction, SecretHash>,
+        _start_of_swap: NaiveDateTime,
+    ) -> anyhow::Result<Funded<Transaction, asset::Bitcoin>> {
+        funded(self, htlc_params)
+            .instrument(tracing::info_span!("htlc_funded"))
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl HtlcRedeemed<Lightning, asset::Bitcoin> for LndConnector {
+    async fn htlc_redeemed(
+        &self,
+        htlc_params: HtlcParams<Lightning, asset::Bitcoin, PublicKey>,
+        _htlc_deployment: &Deployed<Transaction, SecretHash>,
+        _start_of_swap: NaiveDateTime,
+    ) -> anyhow::Result<Redeemed<Transaction>> {
+        redeemed(self, htlc_params)
+            .instrument(tracing::info_span!("htlc_redeemed"))
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl HtlcRefunded<Lightning, asset::Bitcoin> for LndConnector {
+    async fn htlc_refunded(
+        &self,
+        htlc_params: HtlcParams<Lightning, asset::Bitcoin, PublicKey>,
+        _htlc_deployment: &Deployed<Transaction, SecretHash>,
+        _start_of_swap: NaiveDateTime,
+    ) -> anyhow::Result<Refunded<Transaction>> {
+        refunded(self, htlc_params)
+            .instrument(tracing::info_span!("htlc_refunded"))
+            .await
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Side {
+    Redeemer,
+    Funder,
+}
+
+fn side<C: LndApi>(
+    connector: &C,
+    htlc_params: &HtlcParams<Lightning, asset::Bitcoin, PublicKey>,
+) -> anyhow::Result<Side> {
+    let identity = connector.identity();
+
+    if identity == htlc_params.redeem_identity {
+        Ok(Side::Redeemer)
+    } else if identity == htlc_params.refund_identity {
+        Ok(Side::Funder)
+    } else {
+        anyhow::bail!(
+            "lnd node {} is neither the redeemer nor the funder of the HTLC",
+            identity
+        )
+    }
+}
+
+async fn deployed<C: LndApi>(
+    connector: &C,
+    htlc_params: HtlcParams<Lightning, asset::Bitcoin, PublicKey>,
+) -> anyhow::Result<Deployed<Transaction, SecretHash>> {
+    let payment_hash = htlc_params.secret_hash;
+
+    let amount = match side(connector, &htlc_params)? {
+        Side::Redeemer => {
+            watch_for_invoice(connector, payment_hash, |_| true)
+                .await?
+                .amount_paid
+        }
+        Side::Funder => {
+            watch_for_payment(connector, payment_hash, |payment| {
+                payment.status != PaymentStatus::Failed
+            })
+            .await?
+            .amount
+        }
+    };
+
+    Ok(Deployed {
+        transaction: Transaction {
+            payment_hash,
+            amount,
+        },
+        location: payment_hash,
+    })
+}
+
+async fn funded<C: LndApi>(
+    connector: &C,
+    htlc_params: HtlcParams<Lightning, asset::Bitcoin, PublicKey>,
+) -> anyhow::Result<Funded<Transaction, asset::Bitcoin>> {
+    let payment_hash = htlc_params.secret_hash;
+
+    let amount = match side(connector, &htlc_params)? {
+        Side::Redeemer => {
+            watch_for_invoice(connector, payment_hash, |invoice| match invoice.state {
+                InvoiceState::Accepted | InvoiceState::Settled => true,
+                InvoiceState::Open | InvoiceState::Canceled => false,
+            })
+            .await?
+            .amount_paid
+        }
+        Side::Funder => {
+            watch_for_payment(connector, payment_hash, |payment| {
+                payment.status != PaymentStatus::Failed
+            })
+            .await?
+            .amount
+        }
+    };
+
+    Ok(Funded {
+        transaction: Transaction {
+            payment_hash,
+            amount,
+        },
+        asset: amount,
+    })
+}
+
+async fn redeemed<C: LndApi>(
+    connector: &C,
+    htlc_params: HtlcParams<Lightning, asset::Bitcoin, PublicKey>,
+) -> anyhow::Result<Redeemed<Transaction>> {
+    let payment_hash = htlc_params.secret_hash;
+
+    let (amount, preimage) = match side(connector, &htlc_params)? {
+        Side::Redeemer => {
+            let invoice = watch_for_invoice(connector, payment_hash, |invoice| {
+                invoice.state == InvoiceState::Settled
+            })
+            .await?;
+
+            (invoice.amount_paid, invoice.preimage)
+        }
+        Side::Funder => {
+            let payment = watch_for_payment(connector, payment_hash, |payment| {
+                payment.status == PaymentStatus::Succeeded
+            })
+            .await?;
+
+            (payment.amount, payment.preimage)
+        }
+    };
+
+    let secret = preimage
+        .ok_or_else(|| anyhow::anyhow!("lnd did not report the preimage of {}", payment_hash))?;
+
+    Ok(Redeemed {
+        transaction: Transaction {
+            payment_hash,
+            amount,
+        },
+        secret,
+    })
+}
+
+async fn refunded<C: LndApi>(
+    connector: &C,
+    htlc_params: HtlcParams<Lightning, asset::Bitcoin, PublicKey>,
+) -> anyhow::Result<Refunded<Transaction>> {
+    let payment_hash = htlc_params.secret_hash;
+
+    let amount = match side(connector, &htlc_params)? {
+        Side::Redeemer => {
+            watch_for_invoice(connector, payment_hash, |invoice| {
+                invoice.state == InvoiceState::Canceled
+            })
+            .await?
+            .amount_paid
+        }
+        Side::Funder => {
+            watch_for_payment(connector, payment_hash, |payment| {
+                payment.status == PaymentStatus::Failed
+            })
+            .await?
+            .amount
+        }
+    };
+
+    Ok(Refunded {
+        transaction: Transaction {
+            payment_hash,
+            amount,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        btsieve::lightning::{Invoice, Payment},
+        swap_protocols::rfc003::Secret,
+        timestamp::Timestamp,
+    };
+    use async_trait::async_trait;
+    use bitcoin::secp256k1::{self, SecretKey};
+    use spectral::prelude::*;
+
+    struct StaticLnd {
+        identity: PublicKey,
+        invoice: Option<Invoice>,
+        payment: Option<Payment>,
+    }
+
+    #[async_trait]
+    impl LndApi for StaticLnd {
+        fn identity(&self) -> PublicKey {
+            self.identity
+        }
+
+        async fn lookup_invoice(&self, _: SecretHash) -> anyhow::Result<Option<Invoice>> {
+            Ok(self.invoice)
+        }
+
+        async fn lookup_payment(&self, _: SecretHash) -> anyhow::Result<Option<Payment>> {
+            Ok(self.payment)
+        }
+    }
+
+    fn public_key(byte: u8) -> PublicKey {
+        let secret_key = SecretKey::from_slice(&[byte; 32]).unwrap();
+
+        secp256k1::PublicKey::from_secret_key(&*crate::SECP, &secret_key).into()
+    }
+
+    fn secret() -> Secret {
+        Secret::from(*b"hello world, you are beautiful!!")
+    }
+
+    fn htlc_params() -> HtlcParams<Lightning, asset::Bitcoin, PublicKey> {
+        HtlcParams {
+            asset: asset::Bitcoin::from_sat(10_000),
+            ledger: Lightning::default(),
+            redeem_identity: public_key(1),
+            refund_identity: public_key(2),
+            expiry: Timestamp::from(0),
+            secret_hash: secret().hash(),
+        }
+    }
+
+    fn settled_invoice() -> Invoice {
+        Invoice {
+            state: InvoiceState::Settled,
+            amount_paid: asset::Bitcoin::from_sat(10_000),
+            preimage: Some(secret()),
+        }
+    }
+
+    fn succeeded_payment() -> Payment {
+        Payment {
+            status: PaymentStatus::Succeeded,
+            amount: asset::Bitcoin::from_sat(10_000),
+            preimage: Some(secret()),
+        }
+    }
+
+    #[test]
+    fn redeemer_learns_secret_from_settled_invoice() {
+        let lnd = StaticLnd {
+            identity: public_key(1),
+            invoice: Some(settled_invoice()),
+            payment: None,
+        };
+
+        let redeemed = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(redeemed(&lnd, htlc_params()));
+
+        assert_that(&redeemed)
+            .is_ok()
+            .map(|redeemed| &redeemed.secret)
+            .is_equal_to(&secret());
+    }
+
+    #[test]
+    fn funder_learns_secret_from_succeeded_payment() {
+        let lnd = StaticLnd {
+            identity: public_key(2),
+            invoice: None,
+            payment: Some(succeeded_payment()),
+        };
+
+        let redeemed = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(redeemed(&lnd, htlc_params()));
+
+        assert_that(&redeemed)
+            .is_ok()
+            .map(|redeemed| &redeemed.secret)
+            .is_equal_to(&secret());
+    }
+
+    #[test]
+    fn accepted_invoice_funds_htlc_with_amount_paid() {
+        let lnd = StaticLnd {
+            identity: public_key(1),
+            invoice: Some(Invoice {
+                state: InvoiceState::Accepted,
+                amount_paid: asset::Bitcoin::from_sat(9_000),
+                preimage: None,
+            }),
+            payment: None,
+        };
+
+        let funded = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(funded(&lnd, htlc_params()));
+
+        assert_that(&funded)
+            .is_ok()
+            .map(|funded| &funded.asset)
+            .is_equal_to(&asset::Bitcoin::from_sat(9_000));
+    }
+
+    #[test]
+    fn node_that_is_not_part_of_the_swap_cannot_watch_htlc() {
+        let lnd = StaticLnd {
+            identity: public_key(3),
+            invoice: Some(settled_invoice()),
+            payment: Some(succeeded_payment()),
+        };
+
+        let deployed = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(deployed(&lnd, htlc_params()));
+
+        assert_that(&deployed).is_err();
+    }
+}
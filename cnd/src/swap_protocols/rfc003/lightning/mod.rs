@@ -0,0 +1,17 @@
+pub mod htlc_events;
+
+use crate::{
+    lightning::{PublicKey, Transaction},
+    swap_protocols::{
+        ledger::Lightning,
+        rfc003::{Ledger, SecretHash},
+    },
+};
+
+/// A hold invoice is looked up by its payment hash, hence the secret hash is
+/// all we need to find the HTLC.
+impl Ledger for Lightning {
+    type HtlcLocation = SecretHash;
+    type Identity = PublicKey;
+    type Transaction = Transaction;
+}
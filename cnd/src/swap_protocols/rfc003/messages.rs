@@ -1,5 +1,6 @@
 use crate::{
     asset::Asset,
+    lightning,
     swap_protocols::{
         rfc003::{DeriveIdentities, Ledger, SecretHash},
         HashFunction, SwapId,
@@ -82,18 +83,27 @@ pub struct DeclineResponseBody {
 #[serde(rename_all = "kebab-case")]
 pub enum SwapDeclineReason {
     UnsatisfactoryRate,
+    UnsatisfactoryQuantity,
     UnsupportedProtocol,
     UnsupportedSwap,
     MissingMandatoryHeader,
     BadJsonField,
+    UnsupportedAlphaLedger,
+    UnsupportedBetaLedger,
+    UnsafeExpiries,
 }
 
+/// Creates the accept message for a swap from the body the user sent.
+///
+/// Identities on the Lightning Network cannot be derived from the swap seed,
+/// they are the identity of our lnd node instead.
 pub trait IntoAcceptMessage<AI, BI> {
     fn into_accept_message(
         self,
         id: SwapId,
         secret_source: &dyn DeriveIdentities,
-    ) -> Accept<AI, BI>;
+        lnd_identity: Option<lightning::PublicKey>,
+    ) -> anyhow::Result<Accept<AI, BI>>;
 }
 
 #[cfg(test)]
@@ -122,6 +132,18 @@ mod tests {
         assert_eq!(response, expected_response);
     }
 
+    #[test]
+    fn serialize_decline_body_unsatisfactory_quantity() {
+        let decline_response_body = DeclineResponseBody {
+            reason: Some(SwapDeclineReason::UnsatisfactoryQuantity),
+        };
+
+        let response = serde_json::to_string(&decline_response_body).unwrap();
+        let expected_response = r#"{"reason":"unsatisfactory-quantity"}"#;
+
+        assert_eq!(response, expected_response);
+    }
+
     #[test]
     fn serialize_decline_body_unsupported_swap() {
         let decline_response_body = DeclineResponseBody {
@@ -157,4 +179,16 @@ mod tests {
 
         assert_eq!(response, expected_response);
     }
+
+    #[test]
+    fn serialize_decline_body_unsupported_alpha_ledger() {
+        let decline_response_body = DeclineResponseBody {
+            reason: Some(SwapDeclineReason::UnsupportedAlphaLedger),
+        };
+
+        let response = serde_json::to_string(&decline_response_body).unwrap();
+        let expected_response = r#"{"reason":"unsupported-alpha-ledger"}"#;
+
+        assert_eq!(response, expected_response);
+    }
 }
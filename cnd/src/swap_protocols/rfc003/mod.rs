@@ -7,8 +7,11 @@ pub mod bob;
 pub mod create_swap;
 pub mod ethereum;
 pub mod events;
+pub mod expiries;
 pub mod ledger_state;
+pub mod lightning;
 pub mod messages;
+pub mod outcome;
 pub mod state_store;
 
 pub mod actions;
@@ -21,6 +24,7 @@ pub use self::{
     create_swap::create_swap,
     ledger::Ledger,
     ledger_state::{HtlcState, LedgerState},
+    outcome::SwapOutcome,
     secret::{FromErr, Secret, SecretHash},
 };
 
@@ -0,0 +1,148 @@
+use crate::{swap_protocols::rfc003::ledger_state::HtlcState, timestamp::Timestamp};
+use serde::{Deserialize, Serialize};
+
+/// How long after its expiry an HTLC that was not funded is still watched.
+///
+/// Ledgers enforce expiries against the time of their blocks, which lags behind
+/// the local clock, and a funding transaction broadcast just before the expiry
+/// may only be mined well after it. Such an HTLC can still be refunded, hence
+/// we only give up on it once this much time has passed since its expiry.
+pub const NEVER_FUNDED_GRACE_PERIOD_SECS: u32 = 6 * 60 * 60;
+
+/// The final result of a swap.
+///
+/// Once a swap has an outcome, no more events are expected on either ledger.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, strum_macros::Display)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SwapOutcome {
+    /// Both HTLCs were redeemed.
+    Swapped,
+    /// Only the alpha HTLC was redeemed.
+    AlphaRedeemedOnly,
+    /// Only the beta HTLC was redeemed.
+    BetaRedeemedOnly,
+    /// Nothing was redeemed and all funded HTLCs were refunded.
+    Refunded,
+    /// Neither HTLC was funded before it expired.
+    Expired,
+}
+
+/// How a single HTLC ended up.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum HtlcOutcome {
+    Redeemed,
+    Refunded,
+    NeverFunded,
+}
+
+impl SwapOutcome {
+    /// Determines the outcome of a swap from the state of both HTLCs.
+    ///
+    /// Returns `None` as long as an event on either ledger can still change
+    /// the result. An HTLC that is not funded by its expiry is considered
+    /// abandoned, but only once `NEVER_FUNDED_GRACE_PERIOD_SECS` have passed
+    /// since so that a late funding is still seen.
+    pub fn determine(
+        alpha_state: HtlcState,
+        alpha_expiry: Timestamp,
+        beta_state: HtlcState,
+        beta_expiry: Timestamp,
+        now: Timestamp,
+    ) -> Option<Self> {
+        let alpha = HtlcOutcome::determine(alpha_state, alpha_expiry, now)?;
+        let beta = HtlcOutcome::determine(beta_state, beta_expiry, now)?;
+
+        let outcome = match (alpha, beta) {
+            (HtlcOutcome::Redeemed, HtlcOutcome::Redeemed) => SwapOutcome::Swapped,
+            (HtlcOutcome::Redeemed, _) => SwapOutcome::AlphaRedeemedOnly,
+            (_, HtlcOutcome::Redeemed) => SwapOutcome::BetaRedeemedOnly,
+            (HtlcOutcome::NeverFunded, HtlcOutcome::NeverFunded) => SwapOutcome::Expired,
+            _ => SwapOutcome::Refunded,
+        };
+
+        Some(outcome)
+    }
+}
+
+impl HtlcOutcome {
+    fn determine(state: HtlcState, expiry: Timestamp, now: Timestamp) -> Option<Self> {
+        match state {
+            HtlcState::Redeemed => Some(HtlcOutcome::Redeemed),
+            HtlcState::Refunded => Some(HtlcOutcome::Refunded),
+            HtlcState::NotDeployed | HtlcState::Deployed
+                if now >= expiry.plus(NEVER_FUNDED_GRACE_PERIOD_SECS) =>
+            {
+                Some(HtlcOutcome::NeverFunded)
+            }
+            HtlcState::NotDeployed
+            | HtlcState::Deployed
+            | HtlcState::Funded
+            | HtlcState::IncorrectlyFunded => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::swap_protocols::rfc003::ledger_state::HtlcState::*;
+    use spectral::prelude::*;
+
+    const ALPHA_EXPIRY: u32 = 2_000;
+    const BETA_EXPIRY: u32 = 1_000;
+    const ALPHA_GIVEN_UP: u32 = ALPHA_EXPIRY + NEVER_FUNDED_GRACE_PERIOD_SECS;
+    const BETA_GIVEN_UP: u32 = BETA_EXPIRY + NEVER_FUNDED_GRACE_PERIOD_SECS;
+
+    fn determine(alpha_state: HtlcState, beta_state: HtlcState, now: u32) -> Option<SwapOutcome> {
+        SwapOutcome::determine(
+            alpha_state,
+            Timestamp::from(ALPHA_EXPIRY),
+            beta_state,
+            Timestamp::from(BETA_EXPIRY),
+            Timestamp::from(now),
+        )
+    }
+
+    #[test]
+    fn given_both_htlcs_redeemed_then_swapped() {
+        assert_that(&determine(Redeemed, Redeemed, 0)).contains_value(SwapOutcome::Swapped);
+    }
+
+    #[test]
+    fn given_alpha_refunded_and_beta_never_funded_then_refunded() {
+        assert_that(&determine(Refunded, NotDeployed, ALPHA_GIVEN_UP))
+            .contains_value(SwapOutcome::Refunded);
+    }
+
+    #[test]
+    fn given_alpha_refunded_before_beta_expiry_then_still_waiting_for_beta() {
+        assert_that(&determine(Refunded, NotDeployed, BETA_EXPIRY - 1)).is_none();
+    }
+
+    #[test]
+    fn given_beta_not_funded_within_grace_period_then_still_waiting_for_beta() {
+        assert_that(&determine(Refunded, NotDeployed, BETA_EXPIRY)).is_none();
+        assert_that(&determine(Refunded, Deployed, BETA_GIVEN_UP - 1)).is_none();
+        assert_that(&determine(Refunded, NotDeployed, BETA_GIVEN_UP))
+            .contains_value(SwapOutcome::Refunded);
+    }
+
+    #[test]
+    fn given_beta_redeemed_and_alpha_refunded_then_beta_redeemed_only() {
+        assert_that(&determine(Refunded, Redeemed, ALPHA_EXPIRY))
+            .contains_value(SwapOutcome::BetaRedeemedOnly);
+    }
+
+    #[test]
+    fn given_funded_htlc_then_no_outcome_even_after_expiry() {
+        assert_that(&determine(Funded, NotDeployed, ALPHA_EXPIRY)).is_none();
+        assert_that(&determine(IncorrectlyFunded, Refunded, ALPHA_EXPIRY)).is_none();
+    }
+
+    #[test]
+    fn given_nothing_funded_after_both_expiries_then_expired() {
+        assert_that(&determine(NotDeployed, Deployed, ALPHA_GIVEN_UP))
+            .contains_value(SwapOutcome::Expired);
+        assert_that(&determine(NotDeployed, NotDeployed, ALPHA_GIVEN_UP - 1)).is_none();
+    }
+}
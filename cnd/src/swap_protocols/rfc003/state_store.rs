@@ -28,6 +28,17 @@ pub trait StateStore: Send + Sync + 'static {
     );
 }
 
+/// Holds the state of all swaps in memory.
+///
+/// This is a cache: the ledger events that lead to the current state are
+/// persisted through `db::SwapEvents` and replayed into the store when a swap
+/// is loaded after a restart.
+///
+/// The states themselves are not written to Sqlite. They are typed by the
+/// ledgers and assets of the swap and include the seed of Alice's secret,
+/// whereas the events are plain data that the `rfc003_swap_events` table can
+/// hold for every swap type. Replaying them yields the same state and keeps the
+/// `StateStore` synchronous for the ledger watchers.
 #[derive(Default, Debug)]
 pub struct InMemoryStateStore {
     states: Mutex<HashMap<SwapId, Box<dyn Any + Send + Sync>>>,
@@ -75,6 +86,27 @@ impl StateStore for InMemoryStateStore {
             }
         };
 
+        // A pending transaction is superseded by the next event on its ledger.
+        match &event {
+            SwapEvent::AlphaDeployed(_)
+            | SwapEvent::AlphaFunded(_)
+            | SwapEvent::AlphaRedeemed(_)
+            | SwapEvent::AlphaRefunded(_)
+            | SwapEvent::AlphaConfirmed(_)
+            | SwapEvent::AlphaOrphaned => actor_state.set_alpha_pending(None),
+            SwapEvent::BetaDeployed(_)
+            | SwapEvent::BetaFunded(_)
+            | SwapEvent::BetaRedeemed(_)
+            | SwapEvent::BetaRefunded(_)
+            | SwapEvent::BetaConfirmed(_)
+            | SwapEvent::BetaOrphaned => actor_state.set_beta_pending(None),
+            SwapEvent::AlphaPending(_)
+            | SwapEvent::AlphaPendingLeft
+            | SwapEvent::BetaPending(_)
+            | SwapEvent::BetaPendingLeft
+            | SwapEvent::Finished(_) => {}
+        }
+
         match event {
             SwapEvent::AlphaDeployed(deployed) => actor_state
                 .alpha_ledger_mut()
@@ -99,6 +131,18 @@ impl StateStore for InMemoryStateStore {
             SwapEvent::AlphaRefunded(refunded) => actor_state
                 .alpha_ledger_mut()
                 .transition_to_refunded(refunded),
+            SwapEvent::AlphaConfirmed(_) => {
+                actor_state.alpha_ledger_mut().transition_to_confirmed()
+            }
+            SwapEvent::AlphaOrphaned => {
+                let expected_asset = actor_state.expected_alpha_asset();
+
+                actor_state
+                    .alpha_ledger_mut()
+                    .transition_to_orphaned(&expected_asset)
+            }
+            SwapEvent::AlphaPending(pending) => actor_state.set_alpha_pending(Some(pending)),
+            SwapEvent::AlphaPendingLeft => actor_state.set_alpha_pending(None),
             SwapEvent::BetaDeployed(deployed) => actor_state
                 .beta_ledger_mut()
                 .transition_to_deployed(deployed),
@@ -120,6 +164,17 @@ impl StateStore for InMemoryStateStore {
             SwapEvent::BetaRefunded(refunded) => actor_state
                 .beta_ledger_mut()
                 .transition_to_refunded(refunded),
+            SwapEvent::BetaConfirmed(_) => actor_state.beta_ledger_mut().transition_to_confirmed(),
+            SwapEvent::BetaOrphaned => {
+                let expected_asset = actor_state.expected_beta_asset();
+
+                actor_state
+                    .beta_ledger_mut()
+                    .transition_to_orphaned(&expected_asset)
+            }
+            SwapEvent::BetaPending(pending) => actor_state.set_beta_pending(Some(pending)),
+            SwapEvent::BetaPendingLeft => actor_state.set_beta_pending(None),
+            SwapEvent::Finished(outcome) => actor_state.set_swap_outcome(outcome),
         }
 
         self.insert(key.clone(), actor_state)
@@ -136,17 +191,22 @@ mod tests {
         seed::{DeriveSwapSeed, RootSeed},
         swap_protocols::{
             ledger::{bitcoin, Ethereum},
-            rfc003::{alice, messages::Request, Accept, Secret},
+            rfc003::{
+                alice,
+                events::{Deployed, Pending, PendingState},
+                messages::Request,
+                Accept, Secret,
+            },
             HashFunction,
         },
         timestamp::Timestamp,
     };
+    use chrono::NaiveDateTime;
     use spectral::prelude::*;
 
-    #[test]
-    fn insert_and_get_state() {
-        let state_store = InMemoryStateStore::default();
+    type AliceState = alice::State<bitcoin::Regtest, Ethereum, asset::Bitcoin, asset::Ether>;
 
+    fn accepted_state() -> (SwapId, AliceState) {
         let bitcoin_pub_key = "02c2a8efce029526d364c2cf39d89e3cdda05e5df7b2cbfc098b4e3d02b70b5275"
             .parse()
             .unwrap();
@@ -174,7 +234,14 @@ mod tests {
         let id = SwapId::default();
         let seed = RootSeed::from(*b"hello world, you are beautiful!!");
         let secret_source = seed.derive_swap_seed(id);
-        let state = alice::State::accepted(request, accept, secret_source);
+
+        (id, alice::State::accepted(request, accept, secret_source))
+    }
+
+    #[test]
+    fn insert_and_get_state() {
+        let state_store = InMemoryStateStore::default();
+        let (id, state) = accepted_state();
 
         state_store
             .insert::<alice::State<bitcoin::Regtest, Ethereum, asset::Bitcoin, asset::Ether>>(
@@ -187,4 +254,57 @@ mod tests {
             .unwrap();
         assert_that(&res).contains_value(state);
     }
+    #[test]
+    fn pending_transaction_is_cleared_by_next_event_on_its_ledger() {
+        let state_store = InMemoryStateStore::default();
+        let (id, state) = accepted_state();
+        state_store.insert(id, state);
+
+        let transaction = crate::ethereum::Transaction::default();
+        state_store.update::<AliceState>(
+            &id,
+            SwapEvent::BetaPending(Pending {
+                transaction: transaction.clone(),
+                state: PendingState::Funding,
+            }),
+        );
+        state_store.update::<AliceState>(
+            &id,
+            SwapEvent::AlphaConfirmed(NaiveDateTime::from_timestamp(0, 0)),
+        );
+
+        let state = state_store.get::<AliceState>(&id).unwrap().unwrap();
+        assert_that(&state.beta_pending().map(|pending| pending.state))
+            .is_equal_to(Some(PendingState::Funding));
+
+        state_store.update::<AliceState>(
+            &id,
+            SwapEvent::BetaDeployed(Deployed {
+                transaction,
+                location: Address::default(),
+            }),
+        );
+
+        let state = state_store.get::<AliceState>(&id).unwrap().unwrap();
+        assert_that(&state.beta_pending()).is_none();
+    }
+
+    #[test]
+    fn pending_transaction_is_cleared_once_it_left_the_mempool() {
+        let state_store = InMemoryStateStore::default();
+        let (id, state) = accepted_state();
+        state_store.insert(id, state);
+
+        state_store.update::<AliceState>(
+            &id,
+            SwapEvent::BetaPending(Pending {
+                transaction: crate::ethereum::Transaction::default(),
+                state: PendingState::Funding,
+            }),
+        );
+        state_store.update::<AliceState>(&id, SwapEvent::BetaPendingLeft);
+
+        let state = state_store.get::<AliceState>(&id).unwrap().unwrap();
+        assert_that(&state.beta_pending()).is_none();
+    }
 }
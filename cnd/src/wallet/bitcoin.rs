@@ -0,0 +1,514 @@
+use crate::{
+    asset,
+    btsieve::bitcoin::{BitcoindRpcClient, RpcError},
+    seed::RootSeed,
+    timestamp::Timestamp,
+};
+use ::bitcoin::{
+    consensus::encode::{serialize, serialize_hex},
+    secp256k1::{Message, SecretKey},
+    util::bip143::SighashComponents,
+    Address, Amount, Network, OutPoint, PublicKey, Script, Transaction, TxIn, TxOut, Txid,
+};
+use anyhow::anyhow;
+use serde::Deserialize;
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+    sync::Arc,
+};
+use tokio::sync::Mutex;
+
+/// Outputs below this value are not relayed by bitcoind.
+const DUST_LIMIT: u64 = 546;
+
+/// The witness of a P2WPKH input consists of a signature and a compressed
+/// public key, each prefixed with its length, plus the number of items.
+const P2WPKH_WITNESS_WEIGHT: usize = 1 + (1 + 72) + (1 + 33);
+
+/// The segwit marker and flag are only present in the witness serialization.
+const SEGWIT_MARKER_WEIGHT: usize = 2;
+
+/// The number of blocks we ask bitcoind to estimate the fee rate for.
+const CONFIRMATION_TARGET: u32 = 6;
+
+const SIGHASH_ALL: u8 = 0x01;
+
+/// A single-key Bitcoin wallet derived from the `RootSeed`.
+///
+/// The wallet only knows about a single P2WPKH address. Its unspent outputs
+/// are looked up with `scantxoutset` and transactions are broadcast with
+/// `sendrawtransaction`, hence bitcoind's own wallet does not need to be
+/// enabled.
+///
+/// `scantxoutset` only sees confirmed outputs. To not spend an output twice,
+/// the wallet funds one transaction at a time and remembers the outputs it
+/// spent until the spending transaction is confirmed, or until it is no
+/// longer in the mempool because it was evicted or never relayed.
+#[derive(Clone, derivative::Derivative)]
+#[derivative(Debug)]
+pub struct Wallet {
+    #[derivative(Debug = "ignore")]
+    secret_key: SecretKey,
+    network: Network,
+    fallback_fee_per_wu: usize,
+    client: BitcoindRpcClient,
+    #[derivative(Debug = "ignore")]
+    spent_unconfirmed: Arc<Mutex<HashMap<OutPoint, Txid>>>,
+}
+
+impl Wallet {
+    pub fn new(
+        seed: RootSeed,
+        network: Network,
+        client: BitcoindRpcClient,
+        fallback_fee_per_wu: usize,
+    ) -> anyhow::Result<Self> {
+        let secret_key = SecretKey::from_slice(&seed.sha256_with_seed(&[b"BITCOIN_WALLET"]))?;
+
+        Ok(Self {
+            secret_key,
+            network,
+            fallback_fee_per_wu,
+            client,
+            spent_unconfirmed: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    pub fn address(&self) -> Address {
+        Address::p2wpkh(&self.public_key(), self.network)
+    }
+
+    fn public_key(&self) -> PublicKey {
+        crate::bitcoin::PublicKey::from_secret_key(&*crate::SECP, &self.secret_key).into()
+    }
+
+    /// Returns the fee rate estimated by bitcoind, falling back to the
+    /// configured fee rate if bitcoind does not have enough data.
+    pub async fn fee_per_wu(&self) -> usize {
+        match self.estimate_fee_per_wu().await {
+            Ok(Some(fee_per_wu)) => fee_per_wu,
+            Ok(None) => self.fallback_fee_per_wu,
+            Err(e) => {
+                tracing::warn!("failed to estimate fee, using fallback: {:?}", e);
+                self.fallback_fee_per_wu
+            }
+        }
+    }
+
+    async fn estimate_fee_per_wu(&self) -> anyhow::Result<Option<usize>> {
+        let estimate = self
+            .client
+            .call::<FeeEstimate>("estimatesmartfee", vec![CONFIRMATION_TARGET.into()])
+            .await?;
+
+        let feerate = match estimate.feerate {
+            Some(feerate) => feerate,
+            None => return Ok(None),
+        };
+
+        let sat_per_kvb = Amount::from_btc(feerate)
+            .map_err(|e| anyhow!("invalid fee rate {}: {}", feerate, e))?
+            .as_sat();
+        // One virtual byte equals four weight units.
+        let fee_per_wu = usize::try_from(sat_per_kvb / 4000)?;
+
+        Ok(Some(fee_per_wu.max(1)))
+    }
+
+    /// Returns the median time past of the latest block, which is what
+    /// bitcoind checks the time lock of a transaction against.
+    pub async fn median_time_past(&self) -> anyhow::Result<Timestamp> {
+        let info = self
+            .client
+            .call::<BlockchainInfo>("getblockchaininfo", vec![])
+            .await?;
+
+        Ok(Timestamp::from(info.mediantime))
+    }
+
+    /// Sends `amount` to `to` from the unspent outputs of the wallet. Change
+    /// is sent back to the address of the wallet.
+    pub async fn send_to_address(
+        &self,
+        to: &Address,
+        amount: asset::Bitcoin,
+    ) -> anyhow::Result<Txid> {
+        let mut spent_unconfirmed = self.spent_unconfirmed.lock().await;
+
+        let unspents = self.unspent_outputs().await?;
+        let dropped = self
+            .not_in_mempool(spent_unconfirmed.values().copied().collect())
+            .await?;
+        let unspents = available_outputs(unspents, &mut spent_unconfirmed, &dropped);
+        let fee_per_wu = self.fee_per_wu().await;
+
+        let (transaction, input_values) =
+            build_funding_transaction(unspents, to, amount.as_sat(), &self.address(), fee_per_wu)?;
+        let transaction = self.sign(transaction, &input_values)?;
+
+        let txid = self.broadcast(&transaction).await?;
+        spent_unconfirmed.extend(
+            transaction
+                .input
+                .iter()
+                .map(|input| (input.previous_output, txid)),
+        );
+
+        Ok(txid)
+    }
+
+    pub async fn broadcast(&self, transaction: &Transaction) -> anyhow::Result<Txid> {
+        let txid = self
+            .client
+            .call::<Txid>(
+                "sendrawtransaction",
+                vec![serialize_hex(transaction).into()],
+            )
+            .await?;
+
+        tracing::info!("broadcast bitcoin transaction {}", txid);
+
+        Ok(txid)
+    }
+
+    /// Returns the transactions out of `txids` that are not in the mempool of
+    /// bitcoind.
+    async fn not_in_mempool(&self, txids: HashSet<Txid>) -> anyhow::Result<HashSet<Txid>> {
+        let mut not_in_mempool = HashSet::new();
+
+        for txid in txids {
+            let entry = self
+                .client
+                .call::<serde_json::Value>("getmempoolentry", vec![txid.to_string().into()])
+                .await;
+
+            match entry {
+                Ok(_) => {}
+                Err(e) => match e.downcast_ref::<RpcError>() {
+                    Some(error) if error.is_not_found() => {
+                        not_in_mempool.insert(txid);
+                    }
+                    _ => return Err(e),
+                },
+            }
+        }
+
+        Ok(not_in_mempool)
+    }
+
+    async fn unspent_outputs(&self) -> anyhow::Result<Vec<(OutPoint, u64)>> {
+        let descriptor = format!("addr({})", self.address());
+        let scan = self
+            .client
+            .call::<TxOutSetScan>("scantxoutset", vec![
+                "start".into(),
+                vec![descriptor].into(),
+            ])
+            .await?;
+
+        scan.unspents
+            .into_iter()
+            .map(|unspent| {
+                let value = Amount::from_btc(unspent.amount)
+                    .map_err(|e| anyhow!("invalid amount {}: {}", unspent.amount, e))?;
+
+                Ok((
+                    OutPoint {
+                        txid: unspent.txid,
+                        vout: unspent.vout,
+                    },
+                    value.as_sat(),
+                ))
+            })
+            .collect()
+    }
+
+    fn sign(
+        &self,
+        mut transaction: Transaction,
+        input_values: &[u64],
+    ) -> anyhow::Result<Transaction> {
+        let public_key = self.public_key();
+        let script_code = Address::p2pkh(&public_key, self.network).script_pubkey();
+        let components = SighashComponents::new(&transaction);
+
+        for (input, value) in transaction.input.iter_mut().zip(input_values) {
+            let sighash = components.sighash_all(input, &script_code, *value);
+            let message = Message::from_slice(&sighash[..])?;
+
+            let mut signature = crate::SECP
+                .sign(&message, &self.secret_key)
+                .serialize_der()
+                .to_vec();
+            signature.push(SIGHASH_ALL);
+
+            input.witness = vec![signature, public_key.to_bytes()];
+        }
+
+        Ok(transaction)
+    }
+}
+
+/// Removes the outputs we already spent in unconfirmed transactions from
+/// `unspents`.
+///
+/// Outputs that are no longer part of the confirmed UTXO set were spent in a
+/// confirmed transaction and are forgotten. So are outputs whose spending
+/// transaction is `dropped` from the mempool, they can be spent again.
+fn available_outputs(
+    unspents: Vec<(OutPoint, u64)>,
+    spent_unconfirmed: &mut HashMap<OutPoint, Txid>,
+    dropped: &HashSet<Txid>,
+) -> Vec<(OutPoint, u64)> {
+    let confirmed = unspents
+        .iter()
+        .map(|(outpoint, _)| *outpoint)
+        .collect::<HashSet<_>>();
+    spent_unconfirmed
+        .retain(|outpoint, txid| confirmed.contains(outpoint) && !dropped.contains(txid));
+
+    unspents
+        .into_iter()
+        .filter(|(outpoint, _)| !spent_unconfirmed.contains_key(outpoint))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, thiserror::Error, PartialEq)]
+#[error("wallet holds {available} satoshi but {required} satoshi are required")]
+pub struct InsufficientFunds {
+    pub available: u64,
+    pub required: u64,
+}
+
+/// Builds an unsigned transaction paying `amount` to `to`, selecting the
+/// largest unspent outputs first.
+///
+/// Returns the transaction together with the values of its inputs, which are
+/// needed for signing.
+fn build_funding_transaction(
+    mut unspents: Vec<(OutPoint, u64)>,
+    to: &Address,
+    amount: u64,
+    change_address: &Address,
+    fee_per_wu: usize,
+) -> Result<(Transaction, Vec<u64>), InsufficientFunds> {
+    unspents.sort_by(|(_, left), (_, right)| right.cmp(left));
+
+    let mut selected = Vec::new();
+    let mut available = 0;
+
+    for (outpoint, value) in unspents {
+        selected.push((outpoint, value));
+        available += value;
+
+        let mut outputs = vec![
+            TxOut {
+                value: amount,
+                script_pubkey: to.script_pubkey(),
+            },
+            TxOut {
+                value: 0,
+                script_pubkey: change_address.script_pubkey(),
+            },
+        ];
+        let fee = estimate_fee(
+            &unsigned_transaction(&selected, outputs.clone()),
+            fee_per_wu,
+        );
+
+        if available < amount + fee {
+            continue;
+        }
+
+        let change = available - amount - fee;
+        if change < DUST_LIMIT {
+            // the remainder is not worth an output, leave it to the miners
+            outputs.pop();
+        } else {
+            outputs[1].value = change;
+        }
+
+        let input_values = selected.iter().map(|(_, value)| *value).collect();
+
+        return Ok((unsigned_transaction(&selected, outputs), input_values));
+    }
+
+    Err(InsufficientFunds {
+        available,
+        required: amount,
+    })
+}
+
+fn unsigned_transaction(inputs: &[(OutPoint, u64)], outputs: Vec<TxOut>) -> Transaction {
+    Transaction {
+        version: 2,
+        lock_time: 0,
+        input: inputs
+            .iter()
+            .map(|(outpoint, _)| TxIn {
+                previous_output: *outpoint,
+                script_sig: Script::new(),
+                sequence: 0xFFFF_FFFF,
+                witness: Vec::new(),
+            })
+            .collect(),
+        output: outputs,
+    }
+}
+
+/// Estimates the fee of a transaction that spends P2WPKH outputs only.
+fn estimate_fee(transaction: &Transaction, fee_per_wu: usize) -> u64 {
+    let base_weight = serialize(transaction).len() * 4;
+    let witness_weight = SEGWIT_MARKER_WEIGHT + transaction.input.len() * P2WPKH_WITNESS_WEIGHT;
+
+    ((base_weight + witness_weight) * fee_per_wu) as u64
+}
+
+#[derive(Deserialize)]
+struct FeeEstimate {
+    feerate: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct BlockchainInfo {
+    mediantime: u32,
+}
+
+#[derive(Deserialize)]
+struct TxOutSetScan {
+    unspents: Vec<Unspent>,
+}
+
+#[derive(Deserialize)]
+struct Unspent {
+    txid: Txid,
+    vout: u32,
+    amount: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::bitcoin::hashes::Hash;
+    use spectral::prelude::*;
+
+    fn address() -> Address {
+        "bcrt1q6rhpng9evdsfnn833a4f4vej0asu6dk5srld6x"
+            .parse()
+            .unwrap()
+    }
+
+    fn change_address() -> Address {
+        "bcrt1qs2aderg3whgu0m8uadn6dwxjf7j3wx97kk2qqtrum89pmfcxknhsf89pj0"
+            .parse()
+            .unwrap()
+    }
+
+    fn unspent(vout: u32, value: u64) -> (OutPoint, u64) {
+        (
+            OutPoint {
+                txid: Txid::from_slice(&[1u8; 32]).unwrap(),
+                vout,
+            },
+            value,
+        )
+    }
+
+    fn spending_txid() -> Txid {
+        Txid::from_slice(&[2u8; 32]).unwrap()
+    }
+
+    #[test]
+    fn given_enough_funds_selects_largest_output_and_returns_change() {
+        let unspents = vec![unspent(0, 50_000), unspent(1, 2_000_000)];
+
+        let (transaction, input_values) =
+            build_funding_transaction(unspents, &address(), 1_000_000, &change_address(), 2)
+                .unwrap();
+
+        assert_that(&input_values).is_equal_to(vec![2_000_000]);
+        assert_that(&transaction.output).has_length(2);
+        assert_that(&transaction.output[0].value).is_equal_to(1_000_000);
+
+        let fee = 2_000_000 - 1_000_000 - transaction.output[1].value;
+        assert_that(&fee).is_equal_to(estimate_fee(&transaction, 2));
+    }
+
+    #[test]
+    fn given_change_below_dust_limit_omits_change_output() {
+        let unspents = vec![unspent(0, 1_001_000)];
+
+        let (transaction, _) =
+            build_funding_transaction(unspents, &address(), 1_000_000, &change_address(), 1)
+                .unwrap();
+
+        assert_that(&transaction.output).has_length(1);
+    }
+
+    #[test]
+    fn given_insufficient_funds_returns_error() {
+        let unspents = vec![unspent(0, 400_000), unspent(1, 500_000)];
+
+        let result =
+            build_funding_transaction(unspents, &address(), 1_000_000, &change_address(), 1);
+
+        assert_that(&result)
+            .is_err()
+            .is_equal_to(InsufficientFunds {
+                available: 900_000,
+                required: 1_000_000,
+            });
+    }
+
+    #[test]
+    fn given_output_spent_in_unconfirmed_transaction_it_is_not_available() {
+        let mut spent_unconfirmed = HashMap::new();
+        spent_unconfirmed.insert(unspent(0, 0).0, spending_txid());
+
+        let available = available_outputs(
+            vec![unspent(0, 400_000), unspent(1, 500_000)],
+            &mut spent_unconfirmed,
+            &HashSet::new(),
+        );
+
+        assert_that(&available).is_equal_to(vec![unspent(1, 500_000)]);
+        assert_that(&spent_unconfirmed.len()).is_equal_to(1);
+    }
+
+    #[test]
+    fn given_spending_transaction_confirmed_forgets_spent_output() {
+        let mut spent_unconfirmed = HashMap::new();
+        spent_unconfirmed.insert(unspent(0, 0).0, spending_txid());
+
+        let available = available_outputs(
+            vec![unspent(1, 500_000)],
+            &mut spent_unconfirmed,
+            &HashSet::new(),
+        );
+
+        assert_that(&available).is_equal_to(vec![unspent(1, 500_000)]);
+        assert_that(&spent_unconfirmed.is_empty()).is_true();
+    }
+
+    #[test]
+    fn given_spending_transaction_dropped_from_mempool_output_is_available_again() {
+        let mut spent_unconfirmed = HashMap::new();
+        spent_unconfirmed.insert(unspent(0, 0).0, spending_txid());
+        let mut dropped = HashSet::new();
+        dropped.insert(spending_txid());
+
+        let available = available_outputs(
+            vec![unspent(0, 400_000), unspent(1, 500_000)],
+            &mut spent_unconfirmed,
+            &dropped,
+        );
+
+        assert_that(&available).is_equal_to(vec![unspent(0, 400_000), unspent(1, 500_000)]);
+        assert_that(&spent_unconfirmed.is_empty()).is_true();
+    }
+}
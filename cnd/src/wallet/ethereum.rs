@@ -0,0 +1,267 @@
+use crate::{
+    btsieve::{ethereum::Web3Connector, LatestBlock},
+    ethereum::{Address, Bytes, H256, U256},
+    seed::RootSeed,
+    swap_protocols::{
+        actions::ethereum::{CallContract, DeployContract},
+        ledger::ethereum::ChainId,
+    },
+    wallet::ChainTimeNotReached,
+};
+use futures_core::compat::Future01CompatExt;
+use rlp::RlpStream;
+use secp256k1::{Message, PublicKey, SecretKey};
+use std::{convert::TryFrom, sync::Arc};
+use tiny_keccak::{Hasher, Keccak};
+use tokio::sync::Mutex;
+
+/// An Ethereum account that signs transactions itself and broadcasts them
+/// through the configured node.
+///
+/// The account key is derived from the `RootSeed` unless one is configured
+/// explicitly.
+///
+/// The nonce of a transaction is the number of transactions the node knows
+/// about for the account, including pending ones. To not use a nonce twice,
+/// the wallet sends one transaction at a time.
+#[derive(Clone, derivative::Derivative)]
+#[derivative(Debug)]
+pub struct Wallet {
+    #[derivative(Debug = "ignore")]
+    secret_key: SecretKey,
+    chain_id: ChainId,
+    connector: Web3Connector,
+    #[derivative(Debug = "ignore")]
+    send_lock: Arc<Mutex<()>>,
+}
+
+impl Wallet {
+    pub fn new(secret_key: SecretKey, chain_id: ChainId, connector: Web3Connector) -> Self {
+        Self {
+            secret_key,
+            chain_id,
+            connector,
+            send_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    pub fn from_seed(
+        seed: RootSeed,
+        chain_id: ChainId,
+        connector: Web3Connector,
+    ) -> anyhow::Result<Self> {
+        let secret_key = SecretKey::from_slice(&seed.sha256_with_seed(&[b"ETHEREUM_WALLET"]))?;
+
+        Ok(Self::new(secret_key, chain_id, connector))
+    }
+
+    pub fn chain_id(&self) -> ChainId {
+        self.chain_id
+    }
+
+    pub fn address(&self) -> Address {
+        address_from_secret_key(&self.secret_key)
+    }
+
+    pub async fn deploy_contract(&self, action: DeployContract) -> anyhow::Result<H256> {
+        let DeployContract {
+            data,
+            amount,
+            gas_limit,
+            chain_id,
+        } = action;
+
+        self.sign_and_send(chain_id, None, amount.to_u256(), data, gas_limit)
+            .await
+    }
+
+    pub async fn call_contract(&self, action: CallContract) -> anyhow::Result<H256> {
+        let CallContract {
+            to,
+            data,
+            gas_limit,
+            chain_id,
+            min_block_timestamp,
+        } = action;
+
+        if let Some(min_block_timestamp) = min_block_timestamp {
+            let block = self
+                .connector
+                .clone()
+                .latest_block()
+                .compat()
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("failed to fetch latest block"))?;
+            let min_time = u32::from(min_block_timestamp);
+
+            // The HTLC would revert the transaction and we would pay for the gas anyway.
+            if block.timestamp < U256::from(min_time) {
+                return Err(anyhow::Error::from(ChainTimeNotReached {
+                    ledger: "ethereum",
+                    min_time,
+                }));
+            }
+        }
+
+        self.sign_and_send(
+            chain_id,
+            Some(to),
+            U256::zero(),
+            data.unwrap_or_else(|| Bytes(Vec::new())),
+            gas_limit,
+        )
+        .await
+    }
+
+    async fn sign_and_send(
+        &self,
+        chain_id: ChainId,
+        to: Option<Address>,
+        value: U256,
+        data: Bytes,
+        gas_limit: U256,
+    ) -> anyhow::Result<H256> {
+        if chain_id != self.chain_id {
+            anyhow::bail!(
+                "action is for chain id {} but the wallet uses {}",
+                u32::from(chain_id),
+                u32::from(self.chain_id)
+            )
+        }
+
+        let _guard = self.send_lock.lock().await;
+
+        let nonce = self.connector.transaction_count(self.address()).await?;
+        let gas_price = self.connector.gas_price().await?;
+
+        let transaction = UnsignedTransaction {
+            nonce,
+            gas_price,
+            gas_limit,
+            to,
+            value,
+            data,
+        };
+        let signed_transaction = transaction.sign(&self.secret_key, chain_id)?;
+
+        let transaction_hash = self
+            .connector
+            .send_raw_transaction(Bytes(signed_transaction))
+            .await?;
+
+        tracing::info!("sent ethereum transaction {:x}", transaction_hash);
+
+        Ok(transaction_hash)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct UnsignedTransaction {
+    nonce: U256,
+    gas_price: U256,
+    gas_limit: U256,
+    to: Option<Address>,
+    value: U256,
+    data: Bytes,
+}
+
+impl UnsignedTransaction {
+    /// Signs the transaction with replay protection as specified in EIP-155
+    /// and returns its RLP encoding.
+    fn sign(&self, secret_key: &SecretKey, chain_id: ChainId) -> anyhow::Result<Vec<u8>> {
+        let chain_id = u64::from(u32::from(chain_id));
+
+        let mut stream = self.rlp_stream();
+        stream.append(&chain_id).append(&0u64).append(&0u64);
+        let message = Message::from_slice(&keccak256(&stream.out()))?;
+
+        let signature = crate::SECP.sign_recoverable(&message, secret_key);
+        let (recovery_id, signature) = signature.serialize_compact();
+        let v = u64::try_from(recovery_id.to_i32())? + chain_id * 2 + 35;
+
+        let mut stream = self.rlp_stream();
+        stream
+            .append(&v)
+            .append(&U256::from_big_endian(&signature[..32]))
+            .append(&U256::from_big_endian(&signature[32..]));
+
+        Ok(stream.out())
+    }
+
+    /// Starts the list of transaction fields, leaving room for the three
+    /// signature fields.
+    fn rlp_stream(&self) -> RlpStream {
+        let mut stream = RlpStream::new_list(9);
+        stream
+            .append(&self.nonce)
+            .append(&self.gas_price)
+            .append(&self.gas_limit);
+        match self.to {
+            Some(to) => stream.append(&to),
+            None => stream.append_empty_data(),
+        };
+        stream.append(&self.value).append(&self.data.0);
+
+        stream
+    }
+}
+
+fn address_from_secret_key(secret_key: &SecretKey) -> Address {
+    let public_key = PublicKey::from_secret_key(&*crate::SECP, secret_key).serialize_uncompressed();
+    // The first byte only tags the key as uncompressed.
+    let hash = keccak256(&public_key[1..]);
+
+    Address::from_slice(&hash[12..])
+}
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut keccak = Keccak::v256();
+    keccak.update(data);
+
+    let mut hash = [0u8; 32];
+    keccak.finalize(&mut hash);
+
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spectral::prelude::*;
+
+    // Example from https://eips.ethereum.org/EIPS/eip-155
+    fn eip155_secret_key() -> SecretKey {
+        SecretKey::from_slice(&[0x46; 32]).unwrap()
+    }
+
+    fn eip155_transaction() -> UnsignedTransaction {
+        UnsignedTransaction {
+            nonce: U256::from(9),
+            gas_price: U256::from(20_000_000_000u64),
+            gas_limit: U256::from(21_000),
+            to: Some(Address::from_slice(&[0x35; 20])),
+            value: U256::from(1_000_000_000_000_000_000u64),
+            data: Bytes(Vec::new()),
+        }
+    }
+
+    #[test]
+    fn signs_eip155_example_transaction() {
+        let signed = eip155_transaction()
+            .sign(&eip155_secret_key(), ChainId::mainnet())
+            .unwrap();
+
+        assert_that(&hex::encode(signed)).is_equal_to(
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83".to_owned(),
+        );
+    }
+
+    #[test]
+    fn derives_address_from_secret_key() {
+        let address = address_from_secret_key(&eip155_secret_key());
+
+        assert_that(&address).is_equal_to(Address::from_slice(
+            &hex::decode("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f").unwrap(),
+        ));
+    }
+}
@@ -0,0 +1,26 @@
+//! Built-in wallets that allow cnd to execute swap actions itself instead of
+//! handing them to the client.
+//!
+//! Wallets are optional and only used if an action is invoked in `execute`
+//! mode through the HTTP API.
+
+pub mod bitcoin;
+pub mod ethereum;
+
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+#[error("no built-in wallet is configured for {ledger}")]
+pub struct WalletNotConfigured {
+    pub ledger: &'static str,
+}
+
+/// The transaction of an action is not valid before the chain reaches
+/// `min_time`, e.g. the refund of an HTLC that did not expire yet.
+///
+/// Nothing was sent, the action can be executed again later.
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+#[error("the transaction is not valid on {ledger} before the chain time reaches {min_time}")]
+pub struct ChainTimeNotReached {
+    pub ledger: &'static str,
+    /// The earliest chain time as a UNIX timestamp.
+    pub min_time: u32,
+}
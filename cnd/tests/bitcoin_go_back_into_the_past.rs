@@ -3,7 +3,7 @@ pub mod bitcoin_helper;
 use bitcoin::Address;
 use bitcoin_helper::BitcoinConnectorMock;
 use chrono::NaiveDateTime;
-use cnd::btsieve::bitcoin::{matching_transaction, TransactionPattern};
+use cnd::btsieve::{bitcoin::TransactionPattern, Scanner};
 use std::str::FromStr;
 
 #[tokio::test]
@@ -35,11 +35,13 @@ async fn find_transaction_go_back_into_the_past() {
             .unwrap(),
         ),
         from_outpoint: None,
+        from_outpoint_script: None,
         unlock_script: None,
     };
     let start_of_swap =
         NaiveDateTime::from_timestamp(block1_with_transaction.header.time as i64, 0);
-    let expected_transaction = matching_transaction(connector, pattern, start_of_swap)
+    let expected_transaction = Scanner::new(connector)
+        .matching_transaction(pattern, start_of_swap)
         .await
         .unwrap();
 
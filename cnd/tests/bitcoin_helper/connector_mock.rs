@@ -1,5 +1,12 @@
-use bitcoin::{hashes::sha256d, util::hash::BitcoinHash, BlockHash};
-use cnd::btsieve::{BlockByHash, LatestBlock};
+use bitcoin::{
+    hashes::sha256d,
+    util::{bip158::BlockFilter, hash::BitcoinHash},
+    BlockHash, BlockHeader, Script,
+};
+use cnd::btsieve::{
+    bitcoin::{BlockFilterByHash, MatchingTransactions, TransactionPattern},
+    BlockByHash, LatestBlock,
+};
 use futures::{future::IntoFuture, Future};
 use std::{
     collections::HashMap,
@@ -12,6 +19,7 @@ pub struct BitcoinConnectorMock {
     latest_blocks: Vec<bitcoin::Block>,
     latest_time_return_block: Instant,
     current_latest_block_index: usize,
+    block_filters: Option<HashMap<BlockHash, (BlockHeader, Vec<u8>)>>,
 }
 
 impl BitcoinConnectorMock {
@@ -29,6 +37,29 @@ impl BitcoinConnectorMock {
             latest_blocks: latest_blocks.into_iter().collect(),
             latest_time_return_block: Instant::now(),
             current_latest_block_index: 0,
+            block_filters: None,
+        }
+    }
+
+    /// Serves the filters of `blocks`, these blocks don't have to be known
+    /// otherwise.
+    ///
+    /// The scripts of the spent outputs are not known, hence the filters only
+    /// contain the scripts of the outputs.
+    pub fn with_block_filters(self, blocks: impl IntoIterator<Item = bitcoin::Block>) -> Self {
+        let block_filters = blocks
+            .into_iter()
+            .map(|block| {
+                let filter = BlockFilter::new_script_filter(&block, |_| Ok(Script::new()))
+                    .expect("filter of test block");
+
+                (block.bitcoin_hash(), (block.header, filter.content))
+            })
+            .collect();
+
+        BitcoinConnectorMock {
+            block_filters: Some(block_filters),
+            ..self
         }
     }
 }
@@ -77,6 +108,40 @@ impl BlockByHash for BitcoinConnectorMock {
     }
 }
 
+impl MatchingTransactions for BitcoinConnectorMock {
+    fn matching_transactions(
+        &self,
+        _pattern: TransactionPattern,
+    ) -> Box<
+        dyn Future<Item = Option<Vec<bitcoin::Transaction>>, Error = anyhow::Error>
+            + Send
+            + 'static,
+    > {
+        Box::new(Ok(None).into_future())
+    }
+}
+
+impl BlockFilterByHash for BitcoinConnectorMock {
+    fn block_filter_by_hash(
+        &self,
+        block_hash: BlockHash,
+    ) -> Box<
+        dyn Future<Item = Option<(BlockHeader, BlockFilter)>, Error = anyhow::Error>
+            + Send
+            + 'static,
+    > {
+        let header_and_filter = match &self.block_filters {
+            Some(block_filters) => match block_filters.get(&block_hash) {
+                Some((header, filter)) => Ok(Some((*header, BlockFilter::new(filter)))),
+                None => Err(anyhow::Error::from(Error::UnknownHash(block_hash))),
+            },
+            None => Ok(None),
+        };
+
+        Box::new(header_and_filter.into_future())
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("ran out of blocks in chain")]
@@ -1,4 +1,5 @@
 pub mod connector_mock;
 pub mod include_hex;
+pub mod zmq_publisher_mock;
 
-pub use self::{connector_mock::*, include_hex::*};
+pub use self::{connector_mock::*, include_hex::*, zmq_publisher_mock::*};
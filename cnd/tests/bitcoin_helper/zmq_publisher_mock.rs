@@ -0,0 +1,49 @@
+use bitcoin::{consensus::encode::serialize, hashes::Hash, BlockHash};
+
+/// Publishes block notifications the way bitcoind does with
+/// `-zmqpubrawblock` and `-zmqpubhashblock`.
+pub struct ZmqPublisherMock {
+    socket: zmq::Socket,
+    endpoint: String,
+    sequence: u32,
+}
+
+impl ZmqPublisherMock {
+    /// Binds to a random local port.
+    pub fn bind() -> Self {
+        let socket = zmq::Context::new().socket(zmq::PUB).unwrap();
+        socket.bind("tcp://127.0.0.1:*").unwrap();
+        let endpoint = socket.get_last_endpoint().unwrap().unwrap();
+
+        ZmqPublisherMock {
+            socket,
+            endpoint,
+            sequence: 0,
+        }
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    pub fn publish_raw_block(&mut self, block: &bitcoin::Block) {
+        self.publish(b"rawblock", serialize(block));
+    }
+
+    pub fn publish_hash_block(&mut self, block_hash: BlockHash) {
+        // bitcoind publishes the hash in the byte order it is displayed in.
+        let mut bytes = block_hash.into_inner().to_vec();
+        bytes.reverse();
+
+        self.publish(b"hashblock", bytes);
+    }
+
+    fn publish(&mut self, topic: &[u8], body: Vec<u8>) {
+        let sequence = self.sequence.to_le_bytes().to_vec();
+        self.sequence += 1;
+
+        self.socket
+            .send_multipart(vec![topic.to_vec(), body, sequence], 0)
+            .unwrap();
+    }
+}
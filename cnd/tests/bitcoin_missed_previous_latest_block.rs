@@ -3,7 +3,7 @@ pub mod bitcoin_helper;
 use bitcoin::Address;
 use bitcoin_helper::BitcoinConnectorMock;
 use chrono::offset::Utc;
-use cnd::btsieve::bitcoin::{matching_transaction, TransactionPattern};
+use cnd::btsieve::{bitcoin::TransactionPattern, Scanner};
 use std::str::FromStr;
 
 #[tokio::test]
@@ -33,10 +33,12 @@ async fn find_transaction_missed_previous_latest_block() {
             .unwrap(),
         ),
         from_outpoint: None,
+        from_outpoint_script: None,
         unlock_script: None,
     };
     let start_of_swap = Utc::now().naive_local();
-    let expected_transaction = matching_transaction(connector, pattern, start_of_swap)
+    let expected_transaction = Scanner::new(connector)
+        .matching_transaction(pattern, start_of_swap)
         .await
         .unwrap();
 
@@ -78,10 +80,12 @@ async fn find_transaction_missed_previous_latest_block_with_big_gap() {
             .unwrap(),
         ),
         from_outpoint: None,
+        from_outpoint_script: None,
         unlock_script: None,
     };
     let start_of_swap = Utc::now().naive_local();
-    let expected_transaction = matching_transaction(connector, pattern, start_of_swap)
+    let expected_transaction = Scanner::new(connector)
+        .matching_transaction(pattern, start_of_swap)
         .await
         .unwrap();
 
@@ -119,10 +123,12 @@ async fn find_transaction_if_blockchain_reorganisation() {
             .unwrap(),
         ),
         from_outpoint: None,
+        from_outpoint_script: None,
         unlock_script: None,
     };
     let start_of_swap = Utc::now().naive_local();
-    let expected_transaction = matching_transaction(connector, pattern, start_of_swap)
+    let expected_transaction = Scanner::new(connector)
+        .matching_transaction(pattern, start_of_swap)
         .await
         .unwrap();
 
@@ -163,10 +169,12 @@ async fn find_transaction_if_blockchain_reorganisation_with_long_chain() {
                 .unwrap(),
         ),
         from_outpoint: None,
+        from_outpoint_script: None,
         unlock_script: None,
     };
     let start_of_swap = Utc::now().naive_local();
-    let expected_transaction = matching_transaction(connector, pattern, start_of_swap)
+    let expected_transaction = Scanner::new(connector)
+        .matching_transaction(pattern, start_of_swap)
         .await
         .unwrap();
 
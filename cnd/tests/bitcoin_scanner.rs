@@ -0,0 +1,142 @@
+pub mod bitcoin_helper;
+
+use bitcoin::Address;
+use bitcoin_helper::BitcoinConnectorMock;
+use chrono::{offset::Utc, NaiveDateTime};
+use cnd::btsieve::{bitcoin::TransactionPattern, Scanner};
+use std::str::FromStr;
+
+#[tokio::test]
+async fn scanner_finds_transaction_in_the_past() {
+    let block1_with_transaction: bitcoin::Block = include_hex!(
+        "./test_data/bitcoin/find_transaction_go_back_into_the_past/block1_with_transaction.hex"
+    );
+    let connector = BitcoinConnectorMock::new(
+        vec![
+            include_hex!("./test_data/bitcoin/find_transaction_go_back_into_the_past/block3.hex"),
+            include_hex!("./test_data/bitcoin/find_transaction_go_back_into_the_past/block4.hex"),
+            include_hex!("./test_data/bitcoin/find_transaction_go_back_into_the_past/block5.hex"),
+        ],
+        vec![
+            block1_with_transaction.clone(),
+            include_hex!("./test_data/bitcoin/find_transaction_go_back_into_the_past/block2.hex"),
+            include_hex!("./test_data/bitcoin/find_transaction_go_back_into_the_past/block3.hex"),
+            include_hex!("./test_data/bitcoin/find_transaction_go_back_into_the_past/block4.hex"),
+            include_hex!("./test_data/bitcoin/find_transaction_go_back_into_the_past/block5.hex"),
+        ],
+    );
+    let scanner = Scanner::new(connector);
+
+    let pattern = TransactionPattern {
+        to_address: Some(
+            Address::from_str(
+                include_str!("test_data/bitcoin/find_transaction_go_back_into_the_past/address")
+                    .trim(),
+            )
+            .unwrap(),
+        ),
+        from_outpoint: None,
+        from_outpoint_script: None,
+        unlock_script: None,
+    };
+    let start_of_swap =
+        NaiveDateTime::from_timestamp(block1_with_transaction.header.time as i64, 0);
+    let expected_transaction = scanner
+        .matching_transaction(pattern, start_of_swap)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        expected_transaction,
+        include_hex!("./test_data/bitcoin/find_transaction_go_back_into_the_past/transaction.hex")
+    );
+}
+
+#[tokio::test]
+async fn scanner_only_downloads_blocks_whose_filter_matches() {
+    let block1_with_transaction: bitcoin::Block = include_hex!(
+        "./test_data/bitcoin/find_transaction_go_back_into_the_past/block1_with_transaction.hex"
+    );
+    let block2: bitcoin::Block =
+        include_hex!("./test_data/bitcoin/find_transaction_go_back_into_the_past/block2.hex");
+    let block3: bitcoin::Block =
+        include_hex!("./test_data/bitcoin/find_transaction_go_back_into_the_past/block3.hex");
+    // Downloading block2 fails, its filter has to tell us it is of no
+    // interest.
+    let connector = BitcoinConnectorMock::new(vec![block3.clone()], vec![
+        block1_with_transaction.clone(),
+        block3.clone(),
+    ])
+    .with_block_filters(vec![block1_with_transaction.clone(), block2, block3]);
+    let scanner = Scanner::new(connector);
+
+    let pattern = TransactionPattern {
+        to_address: Some(
+            Address::from_str(
+                include_str!("test_data/bitcoin/find_transaction_go_back_into_the_past/address")
+                    .trim(),
+            )
+            .unwrap(),
+        ),
+        from_outpoint: None,
+        from_outpoint_script: None,
+        unlock_script: None,
+    };
+    let start_of_swap =
+        NaiveDateTime::from_timestamp(block1_with_transaction.header.time as i64, 0);
+    let expected_transaction = scanner
+        .matching_transaction(pattern, start_of_swap)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        expected_transaction,
+        include_hex!("./test_data/bitcoin/find_transaction_go_back_into_the_past/transaction.hex")
+    );
+}
+
+#[tokio::test]
+async fn scanner_serves_all_watches_from_missed_blocks() {
+    let connector = BitcoinConnectorMock::new(
+        vec![
+            include_hex!("./test_data/bitcoin/find_transaction_missed_previous_latest_block/block1.hex"),
+            include_hex!("./test_data/bitcoin/find_transaction_missed_previous_latest_block/block3.hex"),
+        ],
+        vec![
+            include_hex!("./test_data/bitcoin/find_transaction_missed_previous_latest_block/block1.hex"),
+            include_hex!(
+                "./test_data/bitcoin/find_transaction_missed_previous_latest_block/block2_with_transaction.hex"
+            ),
+            include_hex!("./test_data/bitcoin/find_transaction_missed_previous_latest_block/block3.hex"),
+        ],
+    );
+    let scanner = Scanner::new(connector);
+
+    let pattern = TransactionPattern {
+        to_address: Some(
+            Address::from_str(
+                include_str!(
+                    "test_data/bitcoin/find_transaction_missed_previous_latest_block/address"
+                )
+                .trim(),
+            )
+            .unwrap(),
+        ),
+        from_outpoint: None,
+        from_outpoint_script: None,
+        unlock_script: None,
+    };
+    let start_of_swap = Utc::now().naive_local();
+
+    let (first, second) = futures_core::future::join(
+        scanner.matching_transaction(pattern.clone(), start_of_swap),
+        scanner.matching_transaction(pattern, start_of_swap),
+    )
+    .await;
+
+    let expected_transaction: bitcoin::Transaction = include_hex!(
+        "./test_data/bitcoin/find_transaction_missed_previous_latest_block/transaction.hex"
+    );
+    assert_eq!(first.unwrap(), expected_transaction);
+    assert_eq!(second.unwrap(), expected_transaction);
+}
@@ -34,6 +34,7 @@ async fn bitcoin_transaction_pattern_e2e_test() {
     let pattern = TransactionPattern {
         to_address: Some(target_address.clone()),
         from_outpoint: None,
+        from_outpoint_script: None,
         unlock_script: None,
     };
 
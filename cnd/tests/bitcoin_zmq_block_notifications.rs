@@ -0,0 +1,73 @@
+pub mod bitcoin_helper;
+
+use bitcoin::{util::hash::BitcoinHash, Network};
+use bitcoin_helper::ZmqPublisherMock;
+use cnd::btsieve::{
+    bitcoin::{subscribe_zmq_blocks, BitcoindConnector},
+    LatestBlock,
+};
+use futures_core::compat::Future01CompatExt;
+use std::time::Duration;
+
+/// The REST interface is never reached in these tests, all blocks come from
+/// the notifications.
+fn connector_without_rest_interface(zmq_endpoint: &str) -> BitcoindConnector {
+    let new_blocks = subscribe_zmq_blocks(zmq_endpoint).unwrap();
+
+    BitcoindConnector::new("http://localhost:0".parse().unwrap(), Network::Regtest)
+        .unwrap()
+        .with_new_blocks(new_blocks)
+}
+
+/// ZMQ drops messages published before the subscription is established,
+/// hence we publish until the connector has seen the block.
+async fn publish_until_latest(
+    publisher: &mut ZmqPublisherMock,
+    connector: &mut BitcoindConnector,
+    block: &bitcoin::Block,
+) {
+    for _ in 0..50 {
+        publisher.publish_raw_block(block);
+        tokio::time::delay_for(Duration::from_millis(100)).await;
+
+        if let Ok(latest_block) = connector.latest_block().compat().await {
+            if latest_block.bitcoin_hash() == block.bitcoin_hash() {
+                return;
+            }
+        }
+    }
+
+    panic!("connector did not receive block {}", block.bitcoin_hash());
+}
+
+#[tokio::test]
+async fn latest_block_is_taken_from_rawblock_notification() {
+    let block: bitcoin::Block = include_hex!(
+        "./test_data/bitcoin/find_transaction_go_back_into_the_past/block1_with_transaction.hex"
+    );
+    let mut publisher = ZmqPublisherMock::bind();
+    let mut connector = connector_without_rest_interface(publisher.endpoint());
+
+    publish_until_latest(&mut publisher, &mut connector, &block).await;
+
+    let latest_block = connector.latest_block().compat().await.unwrap();
+    assert_eq!(latest_block, block);
+}
+
+#[tokio::test]
+async fn hashblock_notification_wakes_up_watchers() {
+    let block1: bitcoin::Block = include_hex!(
+        "./test_data/bitcoin/find_transaction_go_back_into_the_past/block1_with_transaction.hex"
+    );
+    let block2: bitcoin::Block =
+        include_hex!("./test_data/bitcoin/find_transaction_go_back_into_the_past/block2.hex");
+    let mut publisher = ZmqPublisherMock::bind();
+    let mut connector = connector_without_rest_interface(publisher.endpoint());
+    publish_until_latest(&mut publisher, &mut connector, &block1).await;
+
+    let new_block = connector.wait_for_new_block().compat();
+    publisher.publish_hash_block(block2.bitcoin_hash());
+
+    let woken_up = tokio::time::timeout(Duration::from_secs(5), new_block).await;
+    assert!(woken_up.is_ok(), "watchers were not woken up by hashblock");
+}
@@ -0,0 +1,74 @@
+use bitcoin::{Amount, BitcoinHash};
+use bitcoincore_rpc::RpcApi;
+use cnd::btsieve::{
+    bitcoin::{BitcoindRpcClient, BitcoindRpcConnector, RpcAuth},
+    BlockByHash, LatestBlock, PendingTransactions,
+};
+use futures_core::compat::Future01CompatExt;
+use images::coblox_bitcoincore::BitcoinCore;
+use reqwest::Url;
+use testcontainers::*;
+
+/// Verifies that the JSON-RPC connector fetches the same blocks and mempool
+/// transactions bitcoind reports through its own client.
+#[tokio::test]
+async fn bitcoind_rpc_connector_e2e_test() {
+    let cli = clients::Cli::default();
+    let container = cli.run(BitcoinCore::default());
+    let port = container.get_host_port(18443).unwrap();
+    let auth = container.image().auth();
+
+    let client = bitcoincore_rpc::Client::new(
+        format!("http://localhost:{}", port),
+        bitcoincore_rpc::Auth::UserPass(auth.username().to_owned(), auth.password().to_owned()),
+    )
+    .unwrap();
+
+    let url = Url::parse(&format!("http://localhost:{}", port)).unwrap();
+    let mut connector =
+        BitcoindRpcConnector::new(BitcoindRpcClient::new(url, RpcAuth::UserPassword {
+            user: auth.username().to_owned(),
+            password: auth.password().to_owned(),
+        }));
+
+    client.generate(101, None).unwrap();
+
+    let latest_block = connector.latest_block().compat().await.unwrap();
+    assert_eq!(
+        latest_block.bitcoin_hash(),
+        client.get_best_block_hash().unwrap()
+    );
+
+    let previous_block = connector
+        .block_by_hash(latest_block.header.prev_blockhash)
+        .compat()
+        .await
+        .unwrap();
+    assert_eq!(
+        previous_block.bitcoin_hash(),
+        latest_block.header.prev_blockhash
+    );
+
+    let address = client.get_new_address(None, None).unwrap();
+    let txid = client
+        .send_to_address(
+            &address,
+            Amount::from_sat(100_000_000),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+    let pending = connector.pending_transaction_ids().compat().await.unwrap();
+    assert!(pending.contains(&txid));
+
+    let transaction = connector.pending_transaction(txid).compat().await.unwrap();
+    assert_eq!(
+        transaction.map(|transaction| transaction.txid()),
+        Some(txid)
+    );
+}
@@ -0,0 +1,134 @@
+pub mod bitcoin_helper;
+
+use bitcoin::{consensus::encode::serialize, Address, BitcoinHash};
+use chrono::offset::Utc;
+use cnd::btsieve::{
+    bitcoin::{EsploraConnector, MatchingTransactions, TransactionPattern},
+    Scanner,
+};
+use futures_core::compat::Future01CompatExt;
+use mockito::{mock, server_url};
+use serde_json::json;
+use std::str::FromStr;
+
+fn esplora_connector() -> EsploraConnector {
+    EsploraConnector::new(server_url().parse().unwrap())
+}
+
+#[tokio::test]
+async fn scanner_finds_transaction_through_the_address_index() {
+    let transaction: bitcoin::Transaction =
+        include_hex!("./test_data/bitcoin/find_transaction_go_back_into_the_past/transaction.hex");
+    let tip: bitcoin::Block =
+        include_hex!("./test_data/bitcoin/find_transaction_go_back_into_the_past/block5.hex");
+    let address = Address::from_str(
+        include_str!("test_data/bitcoin/find_transaction_go_back_into_the_past/address").trim(),
+    )
+    .unwrap();
+
+    let _tip_hash = mock("GET", "/blocks/tip/hash")
+        .with_body(tip.bitcoin_hash().to_string())
+        .create();
+    let _tip = mock("GET", format!("/block/{}/raw", tip.bitcoin_hash()).as_str())
+        .with_body(serialize(&tip))
+        .create();
+    let _address_transactions = mock("GET", format!("/address/{}/txs", address).as_str())
+        .with_body(
+            json!([{ "txid": transaction.txid().to_string(), "status": { "confirmed": true } }])
+                .to_string(),
+        )
+        .create();
+    let _transaction = mock("GET", format!("/tx/{}/hex", transaction.txid()).as_str())
+        .with_body(hex::encode(serialize(&transaction)))
+        .create();
+
+    let scanner = Scanner::new(esplora_connector());
+    let pattern = TransactionPattern {
+        to_address: Some(address),
+        from_outpoint: None,
+        from_outpoint_script: None,
+        unlock_script: None,
+    };
+    // None of the blocks the scanner can fetch contain the transaction, only
+    // the index knows about it.
+    let start_of_swap = Utc::now().naive_local();
+
+    let actual_transaction = scanner
+        .matching_transaction(pattern, start_of_swap)
+        .await
+        .unwrap();
+
+    assert_eq!(actual_transaction, transaction);
+}
+
+#[tokio::test]
+async fn only_confirmed_spends_of_an_outpoint_match() {
+    let transaction: bitcoin::Transaction =
+        include_hex!("./test_data/bitcoin/find_transaction_go_back_into_the_past/transaction.hex");
+    let outpoint = transaction.input[0].previous_output;
+    let outspend_path = format!("/tx/{}/outspend/{}", outpoint.txid, outpoint.vout);
+    let outspend = |confirmed: bool| {
+        json!({
+            "spent": true,
+            "txid": transaction.txid().to_string(),
+            "vin": 0,
+            "status": { "confirmed": confirmed }
+        })
+        .to_string()
+    };
+
+    let connector = esplora_connector();
+    let pattern = TransactionPattern {
+        to_address: None,
+        from_outpoint: Some(outpoint),
+        from_outpoint_script: None,
+        unlock_script: None,
+    };
+
+    {
+        let _outspend = mock("GET", outspend_path.as_str())
+            .with_body(outspend(false))
+            .create();
+
+        let transactions = connector
+            .matching_transactions(pattern.clone())
+            .compat()
+            .await
+            .unwrap();
+
+        assert_eq!(transactions, Some(vec![]));
+    }
+
+    let _outspend = mock("GET", outspend_path.as_str())
+        .with_body(outspend(true))
+        .create();
+    let _transaction = mock("GET", format!("/tx/{}/hex", transaction.txid()).as_str())
+        .with_body(hex::encode(serialize(&transaction)))
+        .create();
+
+    let transactions = connector
+        .matching_transactions(pattern)
+        .compat()
+        .await
+        .unwrap();
+
+    assert_eq!(transactions, Some(vec![transaction]));
+}
+
+#[tokio::test]
+async fn unlock_scripts_are_not_indexed() {
+    let pattern = TransactionPattern {
+        to_address: None,
+        from_outpoint: None,
+        from_outpoint_script: None,
+        unlock_script: Some(vec![vec![1u8]]),
+    };
+
+    let transactions = esplora_connector()
+        .matching_transactions(pattern)
+        .compat()
+        .await
+        .unwrap();
+
+    assert_eq!(transactions, None);
+}
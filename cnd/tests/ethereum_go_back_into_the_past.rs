@@ -2,10 +2,11 @@ pub mod ethereum_helper;
 
 use chrono::NaiveDateTime;
 use cnd::{
-    btsieve::ethereum::matching_transaction_and_receipt,
+    btsieve::{ReceiptByHash, Scanner},
     ethereum::{Block, Transaction, TransactionReceipt},
 };
 use ethereum_helper::EthereumConnectorMock;
+use futures_core::compat::Future01CompatExt;
 
 #[tokio::test]
 async fn find_transaction_go_back_into_the_past() {
@@ -48,12 +49,27 @@ async fn find_transaction_go_back_into_the_past() {
     let start_of_swap =
         NaiveDateTime::from_timestamp(block1_with_transaction.timestamp.low_u32() as i64, 0);
 
-    let (got_transaction, got_receipt) =
-        matching_transaction_and_receipt(connector, start_of_swap, {
-            |transaction| transaction.to == want_transaction.to
+    let scanner = Scanner::new(connector);
+    let to = want_transaction.to;
+
+    let got_transaction = scanner
+        .watch(Some(start_of_swap), move |block: &Block<Transaction>| {
+            block
+                .transactions
+                .iter()
+                .find(|transaction| transaction.to == to)
+                .cloned()
         })
+        .next()
+        .await
+        .expect("failed to get the transaction");
+    let got_receipt = scanner
+        .connector()
+        .receipt_by_hash(got_transaction.hash)
+        .compat()
         .await
-        .expect("failed to get the transaction and receipt");
+        .expect("failed to get the receipt")
+        .expect("receipt of the transaction is unknown");
 
     assert_eq!(
         (got_transaction, got_receipt),
@@ -1,6 +1,8 @@
 use cnd::{
-    btsieve::{BlockByHash, LatestBlock, ReceiptByHash},
-    ethereum::{Block, Transaction, TransactionReceipt, H256},
+    btsieve::{
+        ethereum::Event, BlockByHash, BlockByNumber, LatestBlock, LogsInRange, ReceiptByHash,
+    },
+    ethereum::{Block, Log, Transaction, TransactionReceipt, H256},
 };
 use futures::{future::IntoFuture, Future};
 use std::{
@@ -13,6 +15,7 @@ pub struct EthereumConnectorMock {
     all_blocks: HashMap<H256, Block<Transaction>>,
     latest_blocks: Vec<Block<Transaction>>,
     receipts: HashMap<H256, TransactionReceipt>,
+    logs: Option<Vec<Log>>,
     latest_time_return_block: Instant,
     current_latest_block_index: usize,
 }
@@ -38,6 +41,16 @@ impl EthereumConnectorMock {
             latest_time_return_block: Instant::now(),
             current_latest_block_index: 0,
             receipts: receipts.into_iter().collect(),
+            logs: None,
+        }
+    }
+
+    /// Makes the mock answer log queries with the given logs, without them it
+    /// behaves like a node that does not index logs.
+    pub fn with_logs(self, logs: Vec<Log>) -> Self {
+        EthereumConnectorMock {
+            logs: Some(logs),
+            ..self
         }
     }
 }
@@ -80,6 +93,57 @@ impl BlockByHash for EthereumConnectorMock {
     }
 }
 
+impl BlockByNumber for EthereumConnectorMock {
+    type Block = Option<Block<Transaction>>;
+    type BlockNumber = u64;
+
+    /// The mock only knows a few blocks of the chain, any other block is
+    /// answered with the closest known block before it, or the first known
+    /// block if there is none.
+    fn block_by_number(
+        &self,
+        block_number: Self::BlockNumber,
+    ) -> Box<dyn Future<Item = Self::Block, Error = anyhow::Error> + Send + 'static> {
+        let number = |block: &&Block<Transaction>| block.number.unwrap().as_u64();
+
+        let block = self
+            .all_blocks
+            .values()
+            .filter(|block| number(block) <= block_number)
+            .max_by_key(number)
+            .or_else(|| self.all_blocks.values().min_by_key(number))
+            .cloned();
+
+        Box::new(Ok(block).into_future())
+    }
+}
+
+impl LogsInRange for EthereumConnectorMock {
+    type Filter = Event;
+    type Log = Log;
+    type BlockNumber = u64;
+
+    fn logs_in_range(
+        &self,
+        _filter: Self::Filter,
+        from_block: Self::BlockNumber,
+        to_block: Self::BlockNumber,
+    ) -> Box<dyn Future<Item = Option<Vec<Self::Log>>, Error = anyhow::Error> + Send + 'static>
+    {
+        let logs = self.logs.as_ref().map(|logs| {
+            logs.iter()
+                .filter(|log| {
+                    let number = log.block_number.unwrap().as_u64();
+                    from_block <= number && number <= to_block
+                })
+                .cloned()
+                .collect()
+        });
+
+        Box::new(Ok(logs).into_future())
+    }
+}
+
 impl ReceiptByHash for EthereumConnectorMock {
     type Receipt = Option<TransactionReceipt>;
     type TransactionHash = H256;
@@ -0,0 +1,141 @@
+pub mod ethereum_helper;
+
+use cnd::{
+    btsieve::{ethereum::Web3Connector, BlockByHash, LatestBlock, ReceiptByHash},
+    ethereum::{Block, Transaction, TransactionReceipt},
+};
+use futures_core::compat::Future01CompatExt;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{UnixListener, UnixStream},
+};
+
+/// Answers JSON-RPC requests on a Unix socket the way an Ethereum node does
+/// on its IPC socket, with the result `answer` returns for the method and
+/// params of a request.
+fn spawn_node_stub<F>(path: &Path, answer: F)
+where
+    F: Fn(&str, &Value) -> Value + Send + Sync + 'static,
+{
+    let mut listener = UnixListener::bind(path).unwrap();
+
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            respond(stream, &answer).await;
+        }
+    });
+}
+
+async fn respond<F>(mut stream: UnixStream, answer: &F)
+where
+    F: Fn(&str, &Value) -> Value,
+{
+    let mut request = Vec::new();
+    let request = loop {
+        let mut buffer = [0u8; 1024];
+        let read = stream.read(&mut buffer).await.unwrap();
+        request.extend_from_slice(&buffer[..read]);
+
+        if let Ok(request) = serde_json::from_slice::<Value>(&request) {
+            break request;
+        }
+    };
+
+    let result = answer(request["method"].as_str().unwrap(), &request["params"]);
+    let response = serde_json::to_vec(&json!({
+        "jsonrpc": "2.0",
+        "id": request["id"],
+        "result": result,
+    }))
+    .unwrap();
+
+    // The response may arrive in several pieces.
+    let (head, tail) = response.split_at(response.len() / 2);
+    stream.write_all(head).await.unwrap();
+    stream.flush().await.unwrap();
+    stream.write_all(tail).await.unwrap();
+}
+
+fn socket_path(dir: &TempDir) -> PathBuf {
+    dir.path().join("geth.ipc")
+}
+
+#[tokio::test]
+async fn latest_block_is_fetched_over_ipc() {
+    let block: Value = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_go_back_into_the_past/block1_with_transaction.json"
+    );
+    let want_block: Block<Transaction> = serde_json::from_value(block.clone()).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    spawn_node_stub(&socket_path(&dir), move |method, params| {
+        assert_eq!(method, "eth_getBlockByNumber");
+        assert_eq!(params, &json!(["latest", true]));
+
+        block.clone()
+    });
+
+    let mut connector = Web3Connector::with_ipc_path(socket_path(&dir));
+    let actual_block = connector.latest_block().compat().await.unwrap();
+
+    assert_eq!(actual_block, Some(want_block));
+}
+
+#[tokio::test]
+async fn block_by_hash_is_fetched_over_ipc() {
+    let block: Value = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_go_back_into_the_past/block1_with_transaction.json"
+    );
+    let want_block: Block<Transaction> = serde_json::from_value(block.clone()).unwrap();
+    let block_hash = want_block.hash.unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    spawn_node_stub(&socket_path(&dir), move |method, params| {
+        assert_eq!(method, "eth_getBlockByHash");
+        assert_eq!(params, &json!([block_hash, true]));
+
+        block.clone()
+    });
+
+    let connector = Web3Connector::with_ipc_path(socket_path(&dir));
+    let actual_block = connector.block_by_hash(block_hash).compat().await.unwrap();
+
+    assert_eq!(actual_block, Some(want_block));
+}
+
+#[tokio::test]
+async fn receipt_by_hash_is_fetched_over_ipc() {
+    let receipt: Value = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_go_back_into_the_past/receipt.json"
+    );
+    let want_receipt: TransactionReceipt = serde_json::from_value(receipt.clone()).unwrap();
+    let transaction_hash = want_receipt.transaction_hash;
+    let dir = tempfile::tempdir().unwrap();
+    spawn_node_stub(&socket_path(&dir), move |method, params| {
+        assert_eq!(method, "eth_getTransactionReceipt");
+        assert_eq!(params, &json!([transaction_hash]));
+
+        receipt.clone()
+    });
+
+    let connector = Web3Connector::with_ipc_path(socket_path(&dir));
+    let actual_receipt = connector
+        .receipt_by_hash(transaction_hash)
+        .compat()
+        .await
+        .unwrap();
+
+    assert_eq!(actual_receipt, Some(want_receipt));
+}
+
+#[tokio::test]
+async fn connecting_to_a_missing_socket_fails() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let mut connector = Web3Connector::with_ipc_path(socket_path(&dir));
+    let result = connector.latest_block().compat().await;
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,71 @@
+pub mod ethereum_helper;
+
+use chrono::NaiveDateTime;
+use cnd::{
+    btsieve::{
+        ethereum::{Event, Topic},
+        Scanner,
+    },
+    ethereum::{Block, Transaction, TransactionReceipt},
+};
+use ethereum_helper::EthereumConnectorMock;
+
+#[tokio::test]
+async fn scanner_finds_event_in_the_past_through_log_queries() {
+    let block1_with_transaction: Block<Transaction> = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_go_back_into_the_past/block1_with_transaction.json"
+    );
+    let want_transaction: Transaction = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_go_back_into_the_past/transaction.json"
+    );
+    let receipt: TransactionReceipt = include_json_test_data!(
+        "./test_data/ethereum/find_transaction_go_back_into_the_past/receipt.json"
+    );
+    let want_log = receipt.logs[0].clone();
+
+    // The mock does not know any receipts, hence the log can only be found
+    // through log queries.
+    let connector = EthereumConnectorMock::new(
+        vec![
+            include_json_test_data!(
+                "./test_data/ethereum/find_transaction_go_back_into_the_past/block4.json"
+            ),
+            include_json_test_data!(
+                "./test_data/ethereum/find_transaction_go_back_into_the_past/block5.json"
+            ),
+        ],
+        vec![
+            block1_with_transaction.clone(),
+            include_json_test_data!(
+                "./test_data/ethereum/find_transaction_go_back_into_the_past/block2.json"
+            ),
+            include_json_test_data!(
+                "./test_data/ethereum/find_transaction_go_back_into_the_past/block3.json"
+            ),
+            include_json_test_data!(
+                "./test_data/ethereum/find_transaction_go_back_into_the_past/block4.json"
+            ),
+            include_json_test_data!(
+                "./test_data/ethereum/find_transaction_go_back_into_the_past/block5.json"
+            ),
+        ],
+        vec![],
+    )
+    .with_logs(receipt.logs.clone());
+
+    let start_of_swap =
+        NaiveDateTime::from_timestamp(block1_with_transaction.timestamp.low_u32() as i64, 0);
+    let event = Event {
+        address: want_log.address,
+        topics: vec![Some(Topic(want_log.topics[0])), None, None, None],
+    };
+
+    let scanner = Scanner::new(connector);
+
+    let (got_transaction, got_log) = scanner
+        .watch_for_event(start_of_swap, event)
+        .await
+        .expect("failed to get the transaction and log");
+
+    assert_eq!((got_transaction, got_log), (want_transaction, want_log));
+}
@@ -1,7 +1,7 @@
 use chrono::offset::Utc;
 use cnd::{
-    btsieve::ethereum::{matching_transaction_and_receipt, Web3Connector},
-    ethereum::{TransactionRequest, U256},
+    btsieve::{ethereum::Web3Connector, Scanner},
+    ethereum::{Block, Transaction, TransactionRequest, U256},
 };
 use futures_core::compat::Future01CompatExt;
 use reqwest::Url;
@@ -35,7 +35,7 @@ async fn ethereum_transaction_matching_smoke_test() {
     ))
     .unwrap();
 
-    let connector = Web3Connector::new(url);
+    let scanner = Scanner::new(Web3Connector::new(url));
 
     let accounts = client
         .eth()
@@ -73,15 +73,17 @@ async fn ethereum_transaction_matching_smoke_test() {
         .await
         .expect("failed to send money to address");
 
-    let (matched_transaction, _receipt) = tokio::time::timeout(
-        Duration::from_secs(5),
-        matching_transaction_and_receipt(connector, start_of_swap, |transaction| {
-            transaction.to == Some(target_address)
-        }),
-    )
-    .await
-    .expect("failed to timeout")
-    .expect("failed to get the actual transaction and receipt");
+    let mut watch = scanner.watch(Some(start_of_swap), move |block: &Block<Transaction>| {
+        block
+            .transactions
+            .iter()
+            .find(|transaction| transaction.to == Some(target_address))
+            .cloned()
+    });
+    let matched_transaction = tokio::time::timeout(Duration::from_secs(5), watch.next())
+        .await
+        .expect("failed to timeout")
+        .expect("failed to get the actual transaction");
 
     assert_eq!(matched_transaction.hash, transaction)
 }